@@ -0,0 +1,151 @@
+use crate::{Context, OckamError};
+use ockam_core::compat::{boxed::Box, vec::Vec};
+use ockam_core::{Address, Any, Result, Route, Routed, Worker};
+use tracing::debug;
+
+/// Picks items in turn, each as often as its weight.
+///
+/// This is the smooth weighted round-robin of nginx: an item of weight 3
+/// among items of weight 1 is picked every other time rather than three
+/// times in a row. Items of weight 0 are never picked.
+#[derive(Debug, Clone)]
+pub struct WeightedRoundRobin<T> {
+    items: Vec<(T, i64)>,
+    current: Vec<i64>,
+    total: i64,
+}
+
+impl<T> WeightedRoundRobin<T> {
+    /// Constructor, failing when no item has a positive weight.
+    pub fn new(items: impl IntoIterator<Item = (T, u32)>) -> Result<Self> {
+        let items: Vec<(T, i64)> = items
+            .into_iter()
+            .filter(|(_, weight)| *weight > 0)
+            .map(|(item, weight)| (item, weight as i64))
+            .collect();
+        if items.is_empty() {
+            return Err(OckamError::InvalidParameter.into());
+        }
+        let total = items.iter().map(|(_, weight)| weight).sum();
+        Ok(Self {
+            current: vec![0; items.len()],
+            items,
+            total,
+        })
+    }
+
+    /// The next item.
+    pub fn next_item(&mut self) -> &T {
+        let mut picked = 0;
+        for (i, (_, weight)) in self.items.iter().enumerate() {
+            self.current[i] += weight;
+            if self.current[i] > self.current[picked] {
+                picked = i;
+            }
+        }
+        self.current[picked] -= self.total;
+        &self.items[picked].0
+    }
+}
+
+/// Worker relaying each message it receives on one of several upstream
+/// routes, in turn by weighted round-robin.
+///
+/// A message sent to `balancer => service` goes on to `upstream => service`,
+/// where `upstream` is the route picked for it.
+pub struct Balancer {
+    upstreams: WeightedRoundRobin<Route>,
+}
+
+impl Balancer {
+    /// Start a balancer at a random address, returned, between the given
+    /// upstream routes and their weights.
+    pub async fn create(
+        ctx: &Context,
+        upstreams: impl IntoIterator<Item = (Route, u32)>,
+    ) -> Result<Address> {
+        let upstreams = WeightedRoundRobin::new(upstreams)?;
+        let address = Address::random_local();
+        debug!("Starting balancer at {}", address);
+        ctx.start_worker(address.clone(), Self { upstreams })
+            .await?;
+        Ok(address)
+    }
+}
+
+#[crate::worker]
+impl Worker for Balancer {
+    type Context = Context;
+    type Message = Any;
+
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Self::Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        let mut message = msg.into_local_message();
+        let transport_message = message.transport_mut();
+
+        // Replace my address with the route to the next upstream
+        transport_message.onward_route.step()?;
+        transport_message
+            .onward_route
+            .modify()
+            .prepend_route(self.upstreams.next_item().clone());
+
+        ctx.forward(message).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ockam_core::route;
+    use std::string::{String, ToString};
+
+    #[test]
+    fn weighted_round_robin() {
+        let mut wrr = WeightedRoundRobin::new([("a", 3), ("b", 1), ("c", 0)]).unwrap();
+        let picked: Vec<_> = (0..8).map(|_| *wrr.next_item()).collect();
+        assert_eq!(picked, ["a", "a", "b", "a", "a", "a", "b", "a"]);
+
+        let mut wrr = WeightedRoundRobin::new([("a", 1), ("b", 1)]).unwrap();
+        let picked: Vec<_> = (0..4).map(|_| *wrr.next_item()).collect();
+        assert_eq!(picked, ["a", "b", "a", "b"]);
+
+        assert!(WeightedRoundRobin::new([("a", 0)]).is_err());
+        assert!(WeightedRoundRobin::<&str>::new([]).is_err());
+    }
+
+    /// Replies with the address it was started at.
+    struct Upstream;
+
+    #[crate::worker]
+    impl Worker for Upstream {
+        type Context = Context;
+        type Message = String;
+
+        async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+            ctx.send(msg.return_route(), ctx.address().address().to_string())
+                .await
+        }
+    }
+
+    #[ockam_macros::test]
+    async fn balancer_relays_to_upstreams(ctx: &mut Context) -> Result<()> {
+        ctx.start_worker("up_a", Upstream).await?;
+        ctx.start_worker("up_b", Upstream).await?;
+        let balancer = Balancer::create(ctx, [(route!["up_a"], 2), (route!["up_b"], 1)]).await?;
+
+        let mut replies = Vec::new();
+        for _ in 0..3 {
+            let reply: String = ctx
+                .send_and_receive(route![balancer.clone()], "hello".to_string())
+                .await?;
+            replies.push(reply);
+        }
+        assert_eq!(replies, ["up_a", "up_b", "up_a"]);
+
+        ctx.stop().await
+    }
+}
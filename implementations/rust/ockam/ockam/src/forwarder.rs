@@ -1,22 +1,48 @@
 use crate::Context;
 use core::str::from_utf8;
-use ockam_core::compat::{boxed::Box, vec::Vec};
-use ockam_core::{Address, Any, LocalMessage, Result, Route, Routed, TransportMessage, Worker};
-use tracing::info;
+use ockam_core::compat::{
+    boxed::Box,
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+    vec::Vec,
+};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{
+    Address, Any, Error, LocalMessage, Result, Route, Routed, TransportMessage, Worker,
+};
+use tracing::{info, warn};
 
 /// Alias worker to register remote workers under local names.
 ///
 /// To talk with this worker, you can use the
 /// [`RemoteForwarder`](crate::remote::RemoteForwarder) which is a
 /// compatible client for this server.
+///
+/// An alias stays registered to the worker which registered it first,
+/// except that the same remote node may register it again through the same
+/// route, e.g. to replace its forwarder by a new one: the messages for the
+/// alias are then forwarded to the worker which registered it last.
 #[non_exhaustive]
-pub struct ForwardingService;
+pub struct ForwardingService {
+    // forward routes of the forwarders registered under an alias
+    aliases: BTreeMap<Address, Arc<RwLock<Route>>>,
+}
 
 impl ForwardingService {
+    /// Alias registering the wildcard forwarder of this node, which receives
+    /// the messages for all the addresses no worker uses on the node.
+    ///
+    /// It is registered under a random address. There is at most one
+    /// wildcard forwarder per node, registering another one fails.
+    pub const WILDCARD_ALIAS: &'static str = "*";
+
     /// Start a forwarding service. The address of the forwarding service will be
     /// `"forwarding_service"`.
     pub async fn create(ctx: &Context) -> Result<()> {
-        ctx.start_worker("forwarding_service", Self).await?;
+        let service = Self {
+            aliases: BTreeMap::new(),
+        };
+        ctx.start_worker("forwarding_service", service).await?;
         Ok(())
     }
 }
@@ -33,14 +59,17 @@ impl Worker for ForwardingService {
     ) -> Result<()> {
         let forward_route = msg.return_route();
         let payload = msg.into_transport_message().payload;
-        Forwarder::create(ctx, forward_route, payload).await?;
+        Forwarder::create(ctx, forward_route, payload, &mut self.aliases).await?;
 
         Ok(())
     }
 }
 
 struct Forwarder {
-    forward_route: Route,
+    forward_route: Arc<RwLock<Route>>,
+    // messages to unknown addresses are forwarded with their onward route
+    // unchanged, for the other end to resolve them
+    wildcard: bool,
     // this option will be `None` after this worker is initialized, because
     // while initializing, the worker will send the payload contained in this
     // field to the `forward_route`, to indicate a successful connection
@@ -52,25 +81,40 @@ impl Forwarder {
         ctx: &Context,
         forward_route: Route,
         registration_payload: Vec<u8>,
+        aliases: &mut BTreeMap<Address, Arc<RwLock<Route>>>,
     ) -> Result<()> {
         let random_address = Address::random_local();
 
         // TODO: assume that the first byte is length, ignore it.
         // We have to improve this actually parse the payload.
-        let address = match registration_payload.get(1..) {
-            Some(address) => match from_utf8(address) {
-                Ok(v) => Address::from_string(v),
-                Err(_e) => random_address,
-            },
-            None => random_address,
+        let alias = registration_payload
+            .get(1..)
+            .and_then(|a| from_utf8(a).ok());
+        let wildcard = alias == Some(ForwardingService::WILDCARD_ALIAS);
+        let (address, is_alias) = match alias {
+            Some(v) if !wildcard => (Address::from_string(v), true),
+            _ => (random_address, false),
         };
+
+        if let Some(route) = aliases.get(&address) {
+            if update_route(route, &forward_route)? {
+                info!("Updated alias {} for {}", address, forward_route);
+                let msg = TransportMessage::v1(forward_route, address, registration_payload);
+                return ctx.forward(LocalMessage::new(msg, Vec::new())).await;
+            }
+        }
         info!("Created new alias for {}", forward_route);
 
+        let route = Arc::new(RwLock::new(forward_route));
         let forwarder = Self {
-            forward_route,
+            forward_route: route.clone(),
+            wildcard,
             payload: Some(registration_payload.clone()),
         };
-        ctx.start_worker(address, forwarder).await?;
+        ctx.start_worker(address.clone(), forwarder).await?;
+        if is_alias {
+            aliases.insert(address, route);
+        }
 
         Ok(())
     }
@@ -86,7 +130,14 @@ impl Worker for Forwarder {
             .payload
             .take()
             .expect("payload must be available on init");
-        let msg = TransportMessage::v1(self.forward_route.clone(), ctx.address(), payload);
+        let forward_route = self.forward_route()?;
+        if self.wildcard {
+            if let Err(e) = ctx.set_fallback_address(ctx.address()).await {
+                warn!("Rejected wildcard forwarder for {}: {}", forward_route, e);
+                return ctx.stop_worker(ctx.address()).await;
+            }
+        }
+        let msg = TransportMessage::v1(forward_route, ctx.address(), payload);
 
         ctx.forward(LocalMessage::new(msg, Vec::new())).await?;
         Ok(())
@@ -100,15 +151,89 @@ impl Worker for Forwarder {
         let mut message = msg.into_local_message();
         let transport_message = message.transport_mut();
 
-        // Remove my address from the onward_route
-        transport_message.onward_route.step()?;
+        // Remove my address from the onward_route, unless the message is
+        // for an unknown address relayed to the wildcard forwarder
+        if !self.wildcard || transport_message.onward_route.next()? == &ctx.address() {
+            transport_message.onward_route.step()?;
+        }
 
         // Prepend forward route
         transport_message
             .onward_route
             .modify()
-            .prepend_route(self.forward_route.clone());
+            .prepend_route(self.forward_route()?);
 
         ctx.forward(message).await
     }
 }
+
+impl Forwarder {
+    fn forward_route(&self) -> Result<Route> {
+        let route = self.forward_route.read().map_err(|_| lock_error())?;
+        Ok(route.clone())
+    }
+}
+
+fn lock_error() -> Error {
+    Error::new_without_cause(Origin::Core, Kind::Internal)
+}
+
+/// Replace the forward route of an alias by `new`, when both lead to the
+/// same remote node.
+fn update_route(route: &RwLock<Route>, new: &Route) -> Result<bool> {
+    let mut route = route.write().map_err(|_| lock_error())?;
+    if same_remote(&route, new) {
+        *route = new.clone();
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+/// Whether two forward routes lead to the same remote node, differing at
+/// most by the address of the worker registering the alias.
+fn same_remote(a: &Route, b: &Route) -> bool {
+    let a: Vec<_> = a.iter().collect();
+    let b: Vec<_> = b.iter().collect();
+    a.split_last().map(|(_, r)| r) == b.split_last().map(|(_, r)| r)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::remote::RemoteForwarder;
+    use crate::workers::Echoer;
+    use ockam_core::compat::string::{String, ToString};
+    use ockam_core::route;
+
+    #[test]
+    fn same_remote_ignores_the_registering_worker() {
+        assert!(same_remote(&route!["tcp", "a"], &route!["tcp", "b"]));
+        assert!(same_remote(&route!["a"], &route!["b"]));
+        assert!(!same_remote(&route!["tcp", "a"], &route!["other", "a"]));
+        assert!(!same_remote(&route!["tcp", "a"], &route!["a"]));
+    }
+
+    #[allow(non_snake_case)]
+    #[ockam_macros::test]
+    async fn forwarding__registering_alias_again__should_forward_to_new_worker(
+        ctx: &mut Context,
+    ) -> Result<()> {
+        ForwardingService::create(ctx).await?;
+        ctx.start_worker("echoer", Echoer).await?;
+
+        let first =
+            RemoteForwarder::create_static_without_heartbeats(ctx, route![], "alias").await?;
+        let second =
+            RemoteForwarder::create_static_without_heartbeats(ctx, route![], "alias").await?;
+        assert_eq!(first.remote_address(), second.remote_address());
+        ctx.stop_worker(first.worker_address().clone()).await?;
+
+        let resp = ctx
+            .send_and_receive::<_, _, String>(route!["alias", "echoer"], "Hello".to_string())
+            .await?;
+        assert_eq!(resp, "Hello");
+
+        ctx.stop().await
+    }
+}
@@ -29,6 +29,7 @@ pub use ockam_macros::{node, test};
 pub use ockam_node::{Context, DelayedEvent, Executor, NodeBuilder, WorkerBuilder};
 // ---
 
+mod balancer;
 mod delay;
 mod error;
 mod forwarder;
@@ -37,6 +38,7 @@ mod monotonic;
 mod system;
 mod unique;
 
+pub use balancer::{Balancer, WeightedRoundRobin};
 pub use error::OckamError;
 pub use forwarder::ForwardingService;
 pub use metadata::OckamMessage;
@@ -7,10 +7,14 @@ use ockam_core::compat::rand::random;
 use ockam_core::compat::{
     boxed::Box,
     string::{String, ToString},
+    sync::Arc,
     vec::Vec,
 };
-use ockam_core::{Address, AddressSet, Any, Decodable, Result, Route, Routed, Worker};
-use ockam_node::DelayedEvent;
+use ockam_core::{
+    async_trait, AccessControl, Address, AddressSet, Any, CancellationToken, DecisionTrace,
+    Decodable, LocalMessage, Mailbox, Mailboxes, Result, Route, Routed, TraceOutcome, Worker,
+};
+use ockam_node::{DelayedEvent, WorkerBuilder};
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
@@ -22,6 +26,7 @@ pub struct RemoteForwarderInfo {
     forwarding_route: Route,
     remote_address: String,
     worker_address: Address,
+    control_address: Address,
 }
 
 impl RemoteForwarderInfo {
@@ -37,6 +42,46 @@ impl RemoteForwarderInfo {
     pub fn worker_address(&self) -> &Address {
         &self.worker_address
     }
+    /// Returns the address the worker takes [`RemoteForwarderRequest`]s at.
+    pub fn control_address(&self) -> &Address {
+        &self.control_address
+    }
+}
+
+/// Requests a `RemoteForwarder` handles at its control address, answered
+/// with a [`RemoteForwarderStatus`]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Message)]
+pub enum RemoteForwarderRequest {
+    /// Stop refreshing the registration, to let the messages still on their
+    /// way arrive before the forwarder is stopped
+    ///
+    /// Draining forwarders keep forwarding every message they receive, but
+    /// no longer send heartbeats, so that the registration of a static
+    /// forwarder at a project expires. Draining twice is the same as once.
+    Drain,
+    /// Only report the status
+    Status,
+}
+
+/// State of a `RemoteForwarder`
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Message)]
+pub struct RemoteForwarderStatus {
+    draining: bool,
+    forwarded: u64,
+}
+
+impl RemoteForwarderStatus {
+    /// Whether the forwarder is draining
+    pub fn draining(&self) -> bool {
+        self.draining
+    }
+    /// Number of messages forwarded since the forwarder was started
+    ///
+    /// A draining forwarder whose count stays the same for a while has no
+    /// more messages in flight.
+    pub fn forwarded(&self) -> u64 {
+        self.forwarded
+    }
 }
 
 /// All addresses `RemoteForwarder` is registered for
@@ -46,6 +91,8 @@ struct Addresses {
     main_address: Address,
     /// Address used for heartbeat messages
     heartbeat_address: Address,
+    /// Address used for [`RemoteForwarderRequest`]s from this node
+    control_address: Address,
 }
 
 impl Distribution<Addresses> for Standard {
@@ -53,13 +100,79 @@ impl Distribution<Addresses> for Standard {
         Addresses {
             main_address: rng.gen(),
             heartbeat_address: rng.gen(),
+            control_address: rng.gen(),
         }
     }
 }
 
 impl Addresses {
     fn into_set(self) -> AddressSet {
-        vec![self.main_address, self.heartbeat_address].into()
+        vec![
+            self.main_address,
+            self.heartbeat_address,
+            self.control_address,
+        ]
+        .into()
+    }
+}
+
+/// Checks the messages a `RemoteForwarder` forwards with the given
+/// AccessControl, letting through the responses to its registration
+///
+/// Registration responses end at the forwarder, while the messages it
+/// forwards go on to a local worker.
+#[derive(Debug)]
+struct ForwardedMessagesAccessControl {
+    main_address: Address,
+    access_control: Arc<dyn AccessControl>,
+}
+
+impl ForwardedMessagesAccessControl {
+    fn is_registration_response(&self, local_msg: &LocalMessage) -> bool {
+        local_msg.transport().onward_route.recipient() == self.main_address
+    }
+}
+
+#[async_trait]
+impl AccessControl for ForwardedMessagesAccessControl {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        self.is_authorized_with_ctx(local_msg, None).await
+    }
+
+    async fn is_authorized_with_ctx(
+        &self,
+        local_msg: &LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<bool> {
+        if self.is_registration_response(local_msg) {
+            return Ok(true);
+        }
+        self.access_control
+            .is_authorized_with_ctx(local_msg, token)
+            .await
+    }
+
+    async fn authorize(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<LocalMessage>> {
+        if self.is_registration_response(&local_msg) {
+            return Ok(Some(local_msg));
+        }
+        self.access_control.authorize(local_msg, token).await
+    }
+
+    async fn trace_decision(&self, local_msg: &LocalMessage) -> DecisionTrace {
+        if self.is_registration_response(local_msg) {
+            return DecisionTrace::new(
+                "ForwardedMessages(registration)",
+                TraceOutcome::Allowed,
+                vec![],
+            );
+        }
+        let inner = self.access_control.trace_decision(local_msg).await;
+        DecisionTrace::new("ForwardedMessages", inner.outcome.clone(), vec![inner])
     }
 }
 
@@ -72,6 +185,8 @@ pub struct RemoteForwarder {
     // We only use Heartbeat for static RemoteForwarder
     heartbeat: Option<DelayedEvent<Vec<u8>>>,
     heartbeat_interval: Duration,
+    draining: bool,
+    forwarded: u64,
 }
 
 impl RemoteForwarder {
@@ -90,7 +205,57 @@ impl RemoteForwarder {
             callback_address: Some(callback_address),
             heartbeat,
             heartbeat_interval,
+            draining: false,
+            forwarded: 0,
+        }
+    }
+
+    /// Start `self` with the AccessControl inherited from `ctx`, or with
+    /// `access_control` for the messages it forwards when given
+    ///
+    /// The worker is started with mailboxes of its own either way, so that
+    /// the default AccessControl of the node only applies to the workers the
+    /// messages are forwarded to.
+    async fn start(
+        self,
+        ctx: &Context,
+        access_control: Option<Arc<dyn AccessControl>>,
+    ) -> Result<()> {
+        let addresses = self.addresses.clone();
+        let heartbeats = self.heartbeat.is_some();
+        let inherited = ctx.mailboxes().main_mailbox().access_control().clone();
+        let access_control = match access_control {
+            Some(access_control) => access_control,
+            None => {
+                let addresses = if heartbeats {
+                    addresses.into_set()
+                } else {
+                    vec![addresses.main_address, addresses.control_address].into()
+                };
+                let mailboxes = Mailboxes::from_address_set(addresses, inherited);
+                WorkerBuilder::with_mailboxes(mailboxes, self)
+                    .start(ctx)
+                    .await?;
+                return Ok(());
+            }
+        };
+
+        let main = Mailbox::new(
+            addresses.main_address.clone(),
+            Arc::new(ForwardedMessagesAccessControl {
+                main_address: addresses.main_address,
+                access_control,
+            }),
+        );
+        // Heartbeats and requests are sent by this node
+        let mut additional = vec![Mailbox::new(addresses.control_address, inherited.clone())];
+        if heartbeats {
+            additional.push(Mailbox::new(addresses.heartbeat_address, inherited));
         }
+        WorkerBuilder::with_mailboxes(Mailboxes::new(main, additional), self)
+            .start(ctx)
+            .await?;
+        Ok(())
     }
 
     /// Create and start static RemoteForwarder at predefined address with given Ockam Hub route
@@ -98,6 +263,26 @@ impl RemoteForwarder {
         ctx: &Context,
         hub_route: impl Into<Route>,
         alias: impl Into<String>,
+    ) -> Result<RemoteForwarderInfo> {
+        Self::create_static_impl(ctx, hub_route.into(), alias.into(), None).await
+    }
+
+    /// Like [`create_static`](Self::create_static), only forwarding the
+    /// messages allowed by `access_control`
+    pub async fn create_static_with_access_control(
+        ctx: &Context,
+        hub_route: impl Into<Route>,
+        alias: impl Into<String>,
+        access_control: Arc<dyn AccessControl>,
+    ) -> Result<RemoteForwarderInfo> {
+        Self::create_static_impl(ctx, hub_route.into(), alias.into(), Some(access_control)).await
+    }
+
+    async fn create_static_impl(
+        ctx: &Context,
+        mut hub_route: Route,
+        alias: String,
+        access_control: Option<Arc<dyn AccessControl>>,
     ) -> Result<RemoteForwarderInfo> {
         let address: Address = random();
         let mut child_ctx = ctx.new_detached(address).await?;
@@ -105,7 +290,6 @@ impl RemoteForwarder {
         let addresses: Addresses = random();
 
         let registration_route = hub_route
-            .into()
             .modify()
             .append("static_forwarding_service")
             .into();
@@ -115,7 +299,7 @@ impl RemoteForwarder {
         let forwarder = Self::new(
             addresses.clone(),
             registration_route,
-            alias.into(),
+            alias,
             child_ctx.address(),
             Some(heartbeat),
             Duration::from_secs(5),
@@ -125,7 +309,7 @@ impl RemoteForwarder {
             "Starting static RemoteForwarder at {}",
             &addresses.heartbeat_address
         );
-        ctx.start_worker(addresses.into_set(), forwarder).await?;
+        forwarder.start(ctx, access_control).await?;
 
         let resp = child_ctx
             .receive::<RemoteForwarderInfo>()
@@ -138,16 +322,30 @@ impl RemoteForwarder {
 
     /// Create and start new ephemeral RemoteForwarder at random address with given Ockam Hub route
     pub async fn create(ctx: &Context, hub_route: impl Into<Route>) -> Result<RemoteForwarderInfo> {
+        Self::create_impl(ctx, hub_route.into(), None).await
+    }
+
+    /// Like [`create`](Self::create), only forwarding the messages allowed
+    /// by `access_control`
+    pub async fn create_with_access_control(
+        ctx: &Context,
+        hub_route: impl Into<Route>,
+        access_control: Arc<dyn AccessControl>,
+    ) -> Result<RemoteForwarderInfo> {
+        Self::create_impl(ctx, hub_route.into(), Some(access_control)).await
+    }
+
+    async fn create_impl(
+        ctx: &Context,
+        mut hub_route: Route,
+        access_control: Option<Arc<dyn AccessControl>>,
+    ) -> Result<RemoteForwarderInfo> {
         let address: Address = random();
         let mut child_ctx = ctx.new_detached(address).await?;
 
         let addresses: Addresses = random();
 
-        let registration_route = hub_route
-            .into()
-            .modify()
-            .append("forwarding_service")
-            .into();
+        let registration_route = hub_route.modify().append("forwarding_service").into();
 
         let forwarder = Self::new(
             addresses.clone(),
@@ -162,7 +360,7 @@ impl RemoteForwarder {
             "Starting ephemeral RemoteForwarder at {}",
             &addresses.main_address
         );
-        ctx.start_worker(addresses.main_address, forwarder).await?;
+        forwarder.start(ctx, access_control).await?;
 
         let resp = child_ctx
             .receive::<RemoteForwarderInfo>()
@@ -181,23 +379,45 @@ impl RemoteForwarder {
         ctx: &Context,
         hub_route: impl Into<Route>,
         alias: impl Into<String>,
+    ) -> Result<RemoteForwarderInfo> {
+        Self::create_static_without_heartbeats_impl(ctx, hub_route.into(), alias.into(), None).await
+    }
+
+    /// Like [`create_static_without_heartbeats`](Self::create_static_without_heartbeats),
+    /// only forwarding the messages allowed by `access_control`
+    pub async fn create_static_without_heartbeats_with_access_control(
+        ctx: &Context,
+        hub_route: impl Into<Route>,
+        alias: impl Into<String>,
+        access_control: Arc<dyn AccessControl>,
+    ) -> Result<RemoteForwarderInfo> {
+        Self::create_static_without_heartbeats_impl(
+            ctx,
+            hub_route.into(),
+            alias.into(),
+            Some(access_control),
+        )
+        .await
+    }
+
+    async fn create_static_without_heartbeats_impl(
+        ctx: &Context,
+        mut hub_route: Route,
+        alias: String,
+        access_control: Option<Arc<dyn AccessControl>>,
     ) -> Result<RemoteForwarderInfo> {
         let address: Address = random();
         let mut child_ctx = ctx.new_detached(address).await?;
 
         let addresses: Addresses = random();
 
-        let registration_route = hub_route
-            .into()
-            .modify()
-            .append("forwarding_service")
-            .into();
+        let registration_route = hub_route.modify().append("forwarding_service").into();
 
         // let remote_address = Address::random_local().without_type().to_string();
         let forwarder = Self::new(
             addresses.clone(),
             registration_route,
-            alias.into(),
+            alias,
             child_ctx.address(),
             None,
             Duration::from_secs(10),
@@ -207,7 +427,7 @@ impl RemoteForwarder {
             "Starting static RemoteForwarder without heartbeats at {}",
             &addresses.main_address
         );
-        ctx.start_worker(addresses.main_address, forwarder).await?;
+        forwarder.start(ctx, access_control).await?;
 
         let resp = child_ctx
             .receive::<RemoteForwarderInfo>()
@@ -217,6 +437,16 @@ impl RemoteForwarder {
 
         Ok(resp)
     }
+
+    /// Send `req` to the forwarder described by `info`, returning its status
+    pub async fn request(
+        ctx: &Context,
+        info: &RemoteForwarderInfo,
+        req: RemoteForwarderRequest,
+    ) -> Result<RemoteForwarderStatus> {
+        ctx.send_and_receive(info.control_address.clone(), req)
+            .await
+    }
 }
 
 #[crate::worker]
@@ -242,6 +472,21 @@ impl Worker for RemoteForwarder {
         ctx: &mut Context,
         msg: Routed<Self::Message>,
     ) -> Result<()> {
+        if msg.msg_addr() == self.addresses.control_address {
+            let req = RemoteForwarderRequest::decode(msg.payload())?;
+            if req == RemoteForwarderRequest::Drain && !self.draining {
+                debug!("RemoteForwarder draining");
+                self.draining = true;
+                // Dropping the event cancels the next heartbeat
+                self.heartbeat = None;
+            }
+            let status = RemoteForwarderStatus {
+                draining: self.draining,
+                forwarded: self.forwarded,
+            };
+            return ctx.send(msg.return_route(), status).await;
+        }
+
         // Heartbeat message, send registration message
         if msg.msg_addr() == self.addresses.heartbeat_address {
             ctx.send_from_address(
@@ -284,6 +529,7 @@ impl Worker for RemoteForwarder {
                         forwarding_route: route,
                         remote_address: address,
                         worker_address: ctx.address(),
+                        control_address: self.addresses.control_address.clone(),
                     },
                 )
                 .await?;
@@ -303,6 +549,7 @@ impl Worker for RemoteForwarder {
 
             // Send the message on its onward_route
             ctx.forward(message).await?;
+            self.forwarded += 1;
 
             // We received message from the other node, our registration is still alive, let's reset
             // heartbeat timer
@@ -392,4 +639,75 @@ mod test {
 
         ctx.stop().await
     }
+
+    #[ockam_macros::test]
+    async fn forwarding_with_access_control(ctx: &mut Context) -> Result<()> {
+        crate::ForwardingService::create(ctx).await?;
+        ctx.start_worker("echoer", Echoer).await?;
+
+        // Registration responses are let through
+        let allowed = RemoteForwarder::create_static_without_heartbeats_with_access_control(
+            ctx,
+            route![],
+            "allowed",
+            Arc::new(ockam_core::AllowAll),
+        )
+        .await?;
+        let denied = RemoteForwarder::create_static_without_heartbeats_with_access_control(
+            ctx,
+            route![],
+            "denied",
+            Arc::new(ockam_core::DenyAll),
+        )
+        .await?;
+
+        let resp = ctx
+            .send_and_receive::<_, _, String>(
+                route![allowed.remote_address(), "echoer"],
+                "Hello".to_string(),
+            )
+            .await?;
+        assert_eq!(resp, "Hello");
+
+        ctx.send(
+            route![denied.remote_address(), "echoer"],
+            "Hello".to_string(),
+        )
+        .await?;
+        assert!(ctx
+            .receive_duration_timeout::<String>(Duration::from_millis(200))
+            .await
+            .is_err());
+
+        ctx.stop().await
+    }
+
+    #[ockam_macros::test]
+    async fn draining_keeps_forwarding(ctx: &mut Context) -> Result<()> {
+        crate::ForwardingService::create(ctx).await?;
+        ctx.start_worker("echoer", Echoer).await?;
+
+        let info =
+            RemoteForwarder::create_static_without_heartbeats(ctx, route![], "drained").await?;
+        let to_echoer = route![info.remote_address(), "echoer"];
+        let resp: String = ctx
+            .send_and_receive(to_echoer.clone(), "Hello".to_string())
+            .await?;
+        assert_eq!(resp, "Hello");
+
+        let status = RemoteForwarder::request(ctx, &info, RemoteForwarderRequest::Drain).await?;
+        assert!(status.draining());
+        assert_eq!(status.forwarded(), 1);
+
+        // Messages arriving while draining are not lost
+        let resp: String = ctx
+            .send_and_receive(to_echoer.clone(), "Hello".to_string())
+            .await?;
+        assert_eq!(resp, "Hello");
+        let status = RemoteForwarder::request(ctx, &info, RemoteForwarderRequest::Status).await?;
+        assert!(status.draining());
+        assert_eq!(status.forwarded(), 2);
+
+        ctx.stop().await
+    }
 }
@@ -20,5 +20,8 @@ pub use eval::eval;
 pub use expr::Expr;
 pub use parser::parse;
 pub use policy::PolicyAccessControl;
+
+#[cfg(feature = "std")]
+pub use policy::policy_file_loader;
 pub use traits::PolicyStorage;
 pub use types::{Action, Resource, Subject};
@@ -2,7 +2,11 @@ use core::{fmt, str};
 use ockam_core::async_trait;
 use ockam_core::compat::boxed::Box;
 use ockam_core::compat::format;
-use ockam_core::compat::string::ToString;
+use ockam_core::compat::string::{String, ToString};
+#[cfg(feature = "std")]
+use ockam_core::compat::sync::Arc;
+#[cfg(feature = "std")]
+use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{AccessControl, LocalMessage, Result};
 use ockam_identity::authenticated_storage::AuthenticatedStorage;
 use ockam_identity::{credential::AttributesStorageUtils, IdentitySecureChannelLocalInfo};
@@ -98,4 +102,68 @@ where
             }
         }
     }
+
+    fn describe(&self) -> String {
+        format!("Policy({})", self.expression)
+    }
+}
+
+/// A loader for a [`ReloadableAccessControl`](ockam_core::ReloadableAccessControl),
+/// reading the policy expression from the file at `path` on every load.
+///
+/// Every policy is a [`PolicyAccessControl`] for `store` and `env`.
+#[cfg(feature = "std")]
+pub fn policy_file_loader<S>(
+    path: impl Into<std::path::PathBuf>,
+    store: S,
+    env: Env,
+) -> impl Fn() -> Result<Arc<dyn AccessControl>> + Send + Sync + 'static
+where
+    S: AuthenticatedStorage + fmt::Debug + Clone + 'static,
+{
+    let path = path.into();
+    move || {
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| ockam_core::Error::new(Origin::Application, Kind::Io, e))?;
+        let expr = Expr::try_from(text.as_str())?;
+        log::debug!(path = %path.display(), %expr, "loaded policy");
+        Ok(Arc::new(PolicyAccessControl::new(
+            expr,
+            store.clone(),
+            env.clone(),
+        )))
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg(test)]
+mod tests {
+    use ockam_core::ReloadableAccessControl;
+    use ockam_identity::authenticated_storage::mem::InMemoryStorage;
+
+    use super::policy_file_loader;
+    use crate::Env;
+
+    #[test]
+    fn reload_policy_file() {
+        let path = std::env::temp_dir().join(format!("policy-{}", rand_suffix()));
+        std::fs::write(&path, "(= subject.role \"member\")").unwrap();
+        let loader = policy_file_loader(&path, InMemoryStorage::new(), Env::new());
+        let access_control = ReloadableAccessControl::new(loader).unwrap();
+
+        std::fs::write(&path, "(= subject.role").unwrap();
+        assert!(access_control.reload().is_err());
+        std::fs::write(&path, "(= subject.role \"admin\")").unwrap();
+        assert!(access_control.reload().is_ok());
+        std::fs::remove_file(&path).unwrap();
+        assert!(access_control.reload().is_err());
+        assert!(format!("{access_control:?}").contains("admin"));
+    }
+
+    fn rand_suffix() -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64
+    }
 }
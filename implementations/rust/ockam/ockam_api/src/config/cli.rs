@@ -113,6 +113,9 @@ pub struct NodeConfigOld {
     verbose: u8,
     pub pid: Option<i32>,
     state_dir: Option<PathBuf>,
+    /// `KEY=VALUE` tags given when creating the node, e.g. `env=prod`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
 }
 
 fn default_name() -> String {
@@ -144,6 +147,7 @@ impl NodeConfigOld {
             verbose,
             pid,
             state_dir,
+            tags: BTreeMap::new(),
         }
     }
 
@@ -170,6 +174,10 @@ impl NodeConfigOld {
     pub fn state_dir(&self) -> Option<&Path> {
         self.state_dir.as_deref()
     }
+
+    pub fn tags(&self) -> &BTreeMap<String, String> {
+        &self.tags
+    }
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -111,6 +111,25 @@ impl ConfigLookup {
         self.map.retain(|k, _| !k.starts_with("/project/"));
     }
 
+    /// Store a route under an alias, referenced as `@<alias>`
+    pub fn set_route(&mut self, alias: &str, route: MultiAddr) {
+        self.map
+            .insert(format!("/route/{}", alias), LookupValue::Route(route));
+    }
+
+    pub fn get_route(&self, alias: &str) -> Option<&MultiAddr> {
+        self.map
+            .get(&format!("/route/{}", alias))
+            .and_then(|value| match value {
+                LookupValue::Route(route) => Some(route),
+                _ => None,
+            })
+    }
+
+    pub fn remove_route(&mut self, alias: &str) -> Option<LookupValue> {
+        self.map.remove(&format!("/route/{}", alias))
+    }
+
     pub fn has_unresolved_projects(&self, meta: &LookupMeta) -> bool {
         meta.project
             .iter()
@@ -147,6 +166,7 @@ pub enum LookupValue {
     Address(InternetAddress),
     Space(SpaceLookup),
     Project(ProjectLookup),
+    Route(MultiAddr),
 }
 
 /// An internet address abstraction (v6/v4/dns)
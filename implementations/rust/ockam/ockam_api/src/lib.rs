@@ -7,6 +7,7 @@ pub mod error;
 pub mod identity;
 pub mod nodes;
 pub mod okta;
+pub mod opa;
 pub mod uppercase;
 pub mod vault;
 pub mod verifier;
@@ -43,6 +44,8 @@ pub mod actions {
 
 pub mod resources {
     use ockam_abac::Resource;
+    pub const FORWARDER: Resource = Resource::assert_inline("forwarder");
+    pub const NODE: Resource = Resource::assert_inline("node");
     pub const INLET: Resource = Resource::assert_inline("inlet");
     pub const OUTLET: Resource = Resource::assert_inline("outlet");
 }
@@ -4,6 +4,10 @@
 
 use minicbor::{Decode, Encode};
 use ockam_core::compat::borrow::Cow;
+use ockam_core::{
+    Address, AddressParseError, CowBytes, CowStr, DecisionTrace, LocalInfo, LocalMessage, Route,
+    TraceOutcome, TransportMessage,
+};
 
 #[cfg(feature = "tag")]
 use ockam_core::TypeTag;
@@ -12,6 +16,13 @@ use ockam_core::TypeTag;
 
 ///////////////////-!  RESPONSE BODIES
 
+/// Version of the API of this node.
+///
+/// It is increased whenever a request gains fields which older nodes would
+/// ignore, so that clients can tell beforehand whether a node supports them.
+/// Nodes without a version, which predate `/node/version`, are at version 0.
+pub const API_VERSION: u32 = 2;
+
 /// Response body for a node status
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
@@ -45,3 +56,266 @@ impl<'a> NodeStatus<'a> {
         }
     }
 }
+
+/// Response body for the version of a node
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct NodeVersion<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4818739>,
+    /// Version of the node software, e.g. 0.19.0
+    #[b(1)] pub version: Cow<'a, str>,
+    /// See [`API_VERSION`]
+    #[n(2)] pub api: u32,
+}
+
+impl<'a> NodeVersion<'a> {
+    pub fn new(version: impl Into<Cow<'a, str>>, api: u32) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            version: version.into(),
+            api,
+        }
+    }
+}
+
+/// How many messages the access control of a worker allowed and denied
+#[derive(Debug, Clone, Decode, Encode, serde::Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct WorkerAuthorizationStats<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<1706354>,
+    #[b(1)] pub address: Cow<'a, str>,
+    #[n(2)] pub allowed: u64,
+    #[n(3)] pub denied: u64,
+}
+
+impl<'a> WorkerAuthorizationStats<'a> {
+    pub fn new(address: impl Into<Cow<'a, str>>, allowed: u64, denied: u64) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            address: address.into(),
+            allowed,
+            denied,
+        }
+    }
+}
+
+/// Response body for the authorization stats of the workers of a node
+#[derive(Debug, Clone, Decode, Encode, serde::Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AuthorizationStatsList<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<3320667>,
+    #[b(1)] pub list: Vec<WorkerAuthorizationStats<'a>>,
+}
+
+impl<'a> AuthorizationStatsList<'a> {
+    pub fn new(list: Vec<WorkerAuthorizationStats<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            list,
+        }
+    }
+}
+
+/// Response body describing the access control of a worker
+#[derive(Debug, Clone, Decode, Encode, serde::Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AccessControlDescription<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<2915034>,
+    #[b(1)] pub address: Cow<'a, str>,
+    #[b(2)] pub description: Cow<'a, str>,
+}
+
+impl<'a> AccessControlDescription<'a> {
+    pub fn new(address: impl Into<Cow<'a, str>>, description: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            address: address.into(),
+            description: description.into(),
+        }
+    }
+}
+
+/// Request body for tracing how the access control of a worker decides
+/// about a message
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TraceAccessControl<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4075183>,
+    #[b(1)] pub onward_route: Vec<CowStr<'a>>,
+    #[b(2)] pub return_route: Vec<CowStr<'a>>,
+    #[b(3)] pub payload: CowBytes<'a>,
+    #[b(4)] pub local_info: Vec<TracedLocalInfo<'a>>,
+}
+
+/// Local info of the message of a [`TraceAccessControl`]
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TracedLocalInfo<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<1906422>,
+    #[b(1)] pub type_identifier: CowStr<'a>,
+    #[b(2)] pub data: CowBytes<'a>,
+}
+
+impl<'a> TracedLocalInfo<'a> {
+    pub fn new(type_identifier: impl Into<CowStr<'a>>, data: impl Into<CowBytes<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            type_identifier: type_identifier.into(),
+            data: data.into(),
+        }
+    }
+}
+
+impl<'a> TraceAccessControl<'a> {
+    pub fn new(
+        onward_route: Vec<CowStr<'a>>,
+        return_route: Vec<CowStr<'a>>,
+        payload: impl Into<CowBytes<'a>>,
+        local_info: Vec<TracedLocalInfo<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            onward_route,
+            return_route,
+            payload: payload.into(),
+            local_info,
+        }
+    }
+
+    /// The message to trace, failing on addresses which can't be parsed
+    pub fn local_message(&self) -> Result<LocalMessage, AddressParseError> {
+        let route = |addresses: &[CowStr<'a>]| -> Result<Route, AddressParseError> {
+            let addresses = addresses
+                .iter()
+                .map(|a| a.parse::<Address>())
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Route::create(addresses))
+        };
+        let local_info = self
+            .local_info
+            .iter()
+            .map(|i| LocalInfo::new(i.type_identifier.to_string(), i.data.to_vec()))
+            .collect();
+        Ok(LocalMessage::new(
+            TransportMessage::v1(
+                route(&self.onward_route)?,
+                route(&self.return_route)?,
+                self.payload.to_vec(),
+            ),
+            local_info,
+        ))
+    }
+}
+
+/// Response body tracing how the access control of a worker decided about a
+/// message, see [`DecisionTrace`]
+#[derive(Debug, Clone, PartialEq, Eq, Decode, Encode, serde::Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AccessControlTrace {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<2480976>,
+    #[n(1)] pub name: String,
+    /// `allowed`, `denied` or `failed`
+    #[n(2)] pub outcome: String,
+    #[n(3)] pub error: Option<String>,
+    #[n(4)] pub children: Vec<AccessControlTrace>,
+}
+
+impl From<&DecisionTrace> for AccessControlTrace {
+    fn from(trace: &DecisionTrace) -> Self {
+        let (outcome, error) = match &trace.outcome {
+            TraceOutcome::Allowed => ("allowed", None),
+            TraceOutcome::Denied => ("denied", None),
+            TraceOutcome::Failed(e) => ("failed", Some(e.clone())),
+        };
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            name: trace.name.clone(),
+            outcome: outcome.to_string(),
+            error,
+            children: trace.children.iter().map(Self::from).collect(),
+        }
+    }
+}
+
+impl From<&AccessControlTrace> for DecisionTrace {
+    fn from(trace: &AccessControlTrace) -> Self {
+        let outcome = match (trace.outcome.as_str(), &trace.error) {
+            ("allowed", _) => TraceOutcome::Allowed,
+            ("denied", _) => TraceOutcome::Denied,
+            (_, Some(e)) => TraceOutcome::Failed(e.clone()),
+            (other, None) => TraceOutcome::Failed(other.to_string()),
+        };
+        DecisionTrace::new(
+            trace.name.clone(),
+            outcome,
+            trace.children.iter().map(Self::from).collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::route;
+
+    #[test]
+    fn trace_round_trip() {
+        let trace = DecisionTrace::new(
+            "All",
+            TraceOutcome::Denied,
+            vec![
+                DecisionTrace::leaf("AllowAll", &Ok(true)),
+                DecisionTrace::new(
+                    "Policy",
+                    TraceOutcome::Failed("no credential".into()),
+                    vec![],
+                ),
+            ],
+        );
+        let bytes = minicbor::to_vec(AccessControlTrace::from(&trace)).unwrap();
+        let decoded: AccessControlTrace = minicbor::decode(&bytes).unwrap();
+        assert_eq!(DecisionTrace::from(&decoded), trace);
+    }
+
+    #[test]
+    fn traced_message() {
+        let body = TraceAccessControl::new(
+            vec!["uppercase".into()],
+            vec!["1#127.0.0.1:4000".into(), "app".into()],
+            b"hello".to_vec(),
+            vec![TracedLocalInfo::new("credential", vec![1, 2])],
+        );
+        let local_msg = body.local_message().unwrap();
+        assert_eq!(local_msg.transport().onward_route, route!["uppercase"]);
+        assert_eq!(local_msg.transport().return_route.iter().count(), 2);
+        assert_eq!(local_msg.local_info().len(), 1);
+
+        let body = TraceAccessControl::new(vec!["1#a#b".into()], vec![], vec![], vec![]);
+        assert!(body.local_message().is_err());
+    }
+}
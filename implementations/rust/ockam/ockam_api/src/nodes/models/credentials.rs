@@ -46,3 +46,30 @@ impl<'a> PresentCredentialRequest<'a> {
         }
     }
 }
+
+/// Response body for the credential of a node
+#[derive(Clone, Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CredentialStatus {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5160843>,
+    /// Whether the node presents its credential over the secure channels it creates
+    #[n(1)] pub checks: bool,
+    /// Whether the node holds a credential
+    #[n(2)] pub credential: bool,
+    /// Number of authorities the node can ask for a credential
+    #[n(3)] pub authorities: u32,
+}
+
+impl CredentialStatus {
+    pub fn new(checks: bool, credential: bool, authorities: u32) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            checks,
+            credential,
+            authorities,
+        }
+    }
+}
@@ -1,14 +1,28 @@
+use std::ops::Deref;
+use std::str::FromStr;
+use std::time::Duration;
+
 use minicbor::{Decode, Encode};
 
 use ockam::remote::RemoteForwarderInfo;
-use ockam_core::CowStr;
+use ockam_abac::Expr;
+use ockam_core::{CowStr, Route};
 use ockam_identity::IdentityIdentifier;
+use ockam_multiaddr::proto::Service;
 use ockam_multiaddr::MultiAddr;
 
+use crate::nodes::models::base::API_VERSION;
+
 #[cfg(feature = "tag")]
 use ockam_core::TypeTag;
 
 /// Request body when instructing a node to create a forwarder
+///
+/// Nodes and clients of other versions interoperate as long as fields are
+/// only ever added, optional and under new indexes: the fields a node
+/// doesn't know of are skipped when decoding, and those missing from the
+/// requests of older clients are `None`. `version` tells which of the
+/// fields the client knew of.
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
@@ -24,7 +38,27 @@ pub struct CreateForwarder<'a> {
     /// An authorised identity for secure channels.
     /// Only set for non-project addresses as for projects the project's
     /// authorised identity will be used.
-    #[n(4)] authorized: Option<IdentityIdentifier>
+    #[n(4)] authorized: Option<IdentityIdentifier>,
+    /// Seconds after which the node deletes the forwarder.
+    #[n(5)] expires_in: Option<u64>,
+    /// Create the wildcard forwarder of the node, see
+    /// [`ForwardingService::WILDCARD_ALIAS`](ockam::ForwardingService::WILDCARD_ALIAS).
+    #[n(6)] wildcard: Option<bool>,
+    /// Routes to balance the messages across, `address` being the first of
+    /// them. Missing for the forwarders created at a single node.
+    #[n(7)] upstreams: Option<Vec<Upstream>>,
+    /// Policy the messages going through the forwarder must satisfy.
+    /// Missing for the forwarders letting all messages through.
+    #[n(8)] access_policy: Option<Expr>,
+    /// Idle seconds after which TCP keepalive probes the connection to the
+    /// node the forwarder is created at. Missing to leave keepalive off.
+    #[n(9)] keepalive: Option<u64>,
+    /// Key under which the node remembers the creation for a while, to
+    /// answer the retries of this request with the forwarder it created.
+    #[b(10)] idempotency_key: Option<CowStr<'a>>,
+    /// [`API_VERSION`] of the client which made the request. Missing from
+    /// the requests of clients predating it.
+    #[n(11)] version: Option<u32>,
 }
 
 impl<'a> CreateForwarder<'a> {
@@ -36,6 +70,13 @@ impl<'a> CreateForwarder<'a> {
             alias: alias.map(|s| s.into()),
             at_rust_node: false,
             authorized: None,
+            expires_in: None,
+            wildcard: None,
+            upstreams: None,
+            access_policy: None,
+            keepalive: None,
+            idempotency_key: None,
+            version: Some(API_VERSION),
         }
     }
 
@@ -52,6 +93,13 @@ impl<'a> CreateForwarder<'a> {
             alias: alias.map(|s| s.into()),
             at_rust_node,
             authorized: auth,
+            expires_in: None,
+            wildcard: None,
+            upstreams: None,
+            access_policy: None,
+            keepalive: None,
+            idempotency_key: None,
+            version: Some(API_VERSION),
         }
     }
 
@@ -70,10 +118,154 @@ impl<'a> CreateForwarder<'a> {
     pub fn authorized(&self) -> Option<IdentityIdentifier> {
         self.authorized.clone()
     }
+
+    pub fn set_expires_in(&mut self, ttl: Option<Duration>) {
+        self.expires_in = ttl.map(|d| d.as_secs())
+    }
+
+    pub fn expires_in(&self) -> Option<Duration> {
+        self.expires_in.map(Duration::from_secs)
+    }
+
+    pub fn set_wildcard(&mut self, wildcard: bool) {
+        self.wildcard = wildcard.then_some(true)
+    }
+
+    pub fn wildcard(&self) -> bool {
+        self.wildcard.unwrap_or(false)
+    }
+
+    pub fn set_upstreams(&mut self, upstreams: Vec<Upstream>) {
+        self.upstreams = (!upstreams.is_empty()).then_some(upstreams)
+    }
+
+    pub fn upstreams(&self) -> &[Upstream] {
+        self.upstreams.as_deref().unwrap_or_default()
+    }
+
+    pub fn set_access_policy(&mut self, policy: Option<Expr>) {
+        self.access_policy = policy
+    }
+
+    pub fn access_policy(&self) -> Option<&Expr> {
+        self.access_policy.as_ref()
+    }
+
+    pub fn set_keepalive(&mut self, keepalive: Option<Duration>) {
+        self.keepalive = keepalive.map(|d| d.as_secs())
+    }
+
+    pub fn keepalive(&self) -> Option<Duration> {
+        self.keepalive.map(Duration::from_secs)
+    }
+
+    pub fn set_idempotency_key(&mut self, key: Option<String>) {
+        self.idempotency_key = key.map(|k| k.into())
+    }
+
+    pub fn idempotency_key(&self) -> Option<&str> {
+        self.idempotency_key.as_deref()
+    }
+
+    /// [`API_VERSION`] of the client which made the request, 0 for clients
+    /// predating it.
+    pub fn version(&self) -> u32 {
+        self.version.unwrap_or(0)
+    }
+}
+
+/// One of the routes a forwarder balances messages across, see
+/// [`Balancer`](ockam::Balancer).
+#[derive(Debug, Clone, PartialEq, Eq, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct Upstream {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6738014>,
+    #[n(1)] address: MultiAddr,
+    /// How many messages go to this route for each message going to a
+    /// route of weight 1.
+    #[n(2)] weight: u32,
+}
+
+impl Upstream {
+    pub fn new(address: MultiAddr, weight: u32) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: Default::default(),
+            address,
+            weight,
+        }
+    }
+
+    pub fn address(&self) -> &MultiAddr {
+        &self.address
+    }
+
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+}
+
+/// How the remote address of a forwarder was chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Decode, Encode, serde::Serialize, serde::Deserialize)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+#[serde(rename_all = "lowercase")]
+pub enum ForwarderKind {
+    /// Registered under the name it was created with. The address stays
+    /// the same when the forwarder is recreated, e.g. after the node it
+    /// was created at restarted.
+    #[n(0)] Static,
+    /// Registered under a random address, which changes whenever the
+    /// forwarder is recreated.
+    #[n(1)] Ephemeral,
+    /// Receiving the messages for all the addresses no worker uses at the
+    /// node it was created at, under a random address.
+    #[n(2)] Wildcard,
+}
+
+impl ForwarderKind {
+    /// The kind of the forwarders created with, or without, an alias.
+    pub fn of_alias(alias: Option<&str>) -> Self {
+        match alias {
+            Some(_) => ForwarderKind::Static,
+            None => ForwarderKind::Ephemeral,
+        }
+    }
+}
+
+/// Request body when instructing a node to rename a forwarder
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RenameForwarder<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6180531>,
+    /// New alias of the forwarder.
+    #[b(1)] alias: CowStr<'a>,
+}
+
+impl<'a> RenameForwarder<'a> {
+    pub fn new(alias: impl Into<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: Default::default(),
+            alias: alias.into(),
+        }
+    }
+
+    pub fn alias(&self) -> &str {
+        &self.alias
+    }
 }
 
 /// Response body when creating a forwarder
-#[derive(Debug, Clone, Decode, Encode, serde::Serialize)]
+///
+/// Equality and hashing compare the addresses only, whether they are
+/// borrowed or owned. Use [`ForwarderInfo::to_owned`] to keep a value
+/// around after the buffer it was decoded from is gone.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Decode, Encode, serde::Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct ForwarderInfo<'a> {
@@ -83,16 +275,72 @@ pub struct ForwarderInfo<'a> {
     #[b(1)] forwarding_route: CowStr<'a>,
     #[b(2)] remote_address: CowStr<'a>,
     #[b(3)] worker_address: CowStr<'a>,
+    /// Missing from the responses of nodes predating it.
+    #[n(4)] kind: Option<ForwarderKind>,
+    /// Address of the worker balancing messages across the upstreams of
+    /// the forwarder, if it has several.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[b(5)] balancer_address: Option<CowStr<'a>>,
 }
 
 impl<'a> ForwarderInfo<'a> {
+    pub fn with_kind(mut self, kind: ForwarderKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    pub fn with_balancer_address(mut self, address: impl Into<String>) -> Self {
+        self.balancer_address = Some(address.into().into());
+        self
+    }
+
+    pub fn to_owned<'r>(&self) -> ForwarderInfo<'r> {
+        ForwarderInfo {
+            #[cfg(feature = "tag")]
+            tag: self.tag.to_owned(),
+            forwarding_route: self.forwarding_route.to_owned(),
+            remote_address: self.remote_address.to_owned(),
+            worker_address: self.worker_address.to_owned(),
+            kind: self.kind,
+            balancer_address: self.balancer_address.as_ref().map(|a| a.to_owned()),
+        }
+    }
+
     pub fn forwarding_route(&'a self) -> &'a str {
         &self.forwarding_route
     }
 
+    /// The address of the forwarder at the node it was created at.
+    ///
+    /// For forwarders created with a valid alias (see
+    /// [`is_valid_remote_address`]), `/service/<remote_address>` parses
+    /// back as a [`MultiAddr`].
     pub fn remote_address(&'a self) -> &'a str {
         &self.remote_address
     }
+
+    /// The remote address as a `/service` multiaddr.
+    pub fn remote_address_multiaddr(&self) -> Option<MultiAddr> {
+        service_multiaddr(&self.remote_address)
+    }
+
+    pub fn worker_address(&'a self) -> &'a str {
+        &self.worker_address
+    }
+
+    pub fn kind(&self) -> Option<ForwarderKind> {
+        self.kind
+    }
+
+    pub fn balancer_address(&self) -> Option<&str> {
+        self.balancer_address.as_deref()
+    }
+
+    /// The forwarding route as a multiaddr, starting at the node which
+    /// created the forwarder and ending at its remote address.
+    pub fn forwarding_route_multiaddr(&self) -> Option<MultiAddr> {
+        Route::parse(self.forwarding_route.as_ref()).and_then(|r| crate::route_to_multiaddr(&r))
+    }
 }
 
 impl<'a> From<RemoteForwarderInfo> for ForwarderInfo<'a> {
@@ -103,6 +351,139 @@ impl<'a> From<RemoteForwarderInfo> for ForwarderInfo<'a> {
             forwarding_route: inner.forwarding_route().to_string().into(),
             remote_address: inner.remote_address().to_string().into(),
             worker_address: inner.worker_address().to_string().into(),
+            kind: None,
+            balancer_address: None,
+        }
+    }
+}
+
+/// Whether `addr` can be used as the remote address of a forwarder, that is
+/// if `/service/<addr>` parses as a [`MultiAddr`] holding `addr` unchanged.
+pub fn is_valid_remote_address(addr: &str) -> bool {
+    service_multiaddr(addr).is_some()
+}
+
+fn service_multiaddr(addr: &str) -> Option<MultiAddr> {
+    if addr.is_empty() {
+        return None;
+    }
+    let ma = MultiAddr::from_str(&format!("/service/{addr}")).ok()?;
+    let mut protos = ma.iter();
+    let value = protos.next()?;
+    let unchanged = protos.next().is_none() && value.cast::<Service>()?.deref() == addr;
+    unchanged.then_some(ma)
+}
+
+/// Response body when draining a forwarder, or checking how draining goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Decode, Encode, serde::Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct DrainStatus {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<5093806>,
+    #[n(1)] draining: bool,
+    /// Messages forwarded since the forwarder was created, by all of its
+    /// workers.
+    #[n(2)] forwarded: u64,
+}
+
+impl DrainStatus {
+    pub fn new(draining: bool, forwarded: u64) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            draining,
+            forwarded,
+        }
+    }
+
+    pub fn draining(&self) -> bool {
+        self.draining
+    }
+
+    pub fn forwarded(&self) -> u64 {
+        self.forwarded
+    }
+}
+
+/// Something that happened to a forwarder, e.g. its connection was lost
+#[derive(Debug, Clone, PartialEq, Eq, Decode, Encode, serde::Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ForwarderEvent<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<8141925>,
+    /// Increases by one with every event of the forwarder.
+    #[n(1)] seq: u64,
+    /// Seconds since the Unix epoch.
+    #[n(2)] time: u64,
+    #[b(3)] message: CowStr<'a>,
+}
+
+impl<'a> ForwarderEvent<'a> {
+    pub fn new(seq: u64, time: u64, message: impl Into<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            seq,
+            time,
+            message: message.into(),
+        }
+    }
+
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// Response body when returning the recent events of a forwarder, oldest first
+#[derive(Debug, Clone, Decode, Encode, serde::Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ForwarderEventList<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<3527094>,
+    #[b(1)] pub list: Vec<ForwarderEvent<'a>>
+}
+
+impl<'a> ForwarderEventList<'a> {
+    pub fn new(list: Vec<ForwarderEvent<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            list,
+        }
+    }
+}
+
+/// Response body when returning a list of forwarders
+#[derive(Debug, Clone, Decode, Encode, serde::Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ForwarderList<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<4952862>,
+    #[b(1)] pub list: Vec<ForwarderInfo<'a>>
+}
+
+impl<'a> ForwarderList<'a> {
+    pub fn new(list: Vec<ForwarderInfo<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            list,
         }
     }
 }
@@ -121,6 +502,143 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn remote_address_round_trip() {
+        for addr in ["forward_to_blue", "d8cb2a17", "forward_to_a.b-c_d", "0#x"] {
+            assert!(is_valid_remote_address(addr), "{addr}");
+            let info = ForwarderInfo {
+                #[cfg(feature = "tag")]
+                tag: Default::default(),
+                forwarding_route: "0#sc => 0#forward_to_blue".into(),
+                remote_address: addr.into(),
+                worker_address: "0#worker".into(),
+                kind: None,
+                balancer_address: None,
+            };
+            let printed = format!("/service/{}", info.remote_address());
+            let parsed = MultiAddr::from_str(&printed).unwrap();
+            assert_eq!(parsed.to_string(), printed);
+            assert_eq!(info.remote_address_multiaddr(), Some(parsed));
+        }
+        for addr in ["", "a/b", "forward_to_blue/", "/service/x"] {
+            assert!(!is_valid_remote_address(addr), "{addr}");
+        }
+    }
+
+    #[test]
+    fn owned_forwarder_info() {
+        use std::collections::HashSet;
+
+        let buf = {
+            let info = ForwarderInfo {
+                #[cfg(feature = "tag")]
+                tag: Default::default(),
+                forwarding_route: "0#sc => 0#forward_to_blue".into(),
+                remote_address: "forward_to_blue".into(),
+                worker_address: "0#worker".into(),
+                kind: None,
+                balancer_address: None,
+            };
+            minicbor::to_vec(info).unwrap()
+        };
+        let owned = {
+            let borrowed: ForwarderInfo = minicbor::decode(&buf).unwrap();
+            assert!(borrowed.remote_address.is_borrowed());
+            borrowed.to_owned()
+        };
+        drop(buf);
+        assert!(!owned.remote_address.is_borrowed());
+
+        let mut moved = owned.clone();
+        moved.forwarding_route = "0#other => 0#forward_to_blue".into();
+        assert_ne!(owned, moved);
+
+        let set: HashSet<_> = [owned.clone(), owned.clone(), moved].into_iter().collect();
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&owned));
+    }
+
+    #[test]
+    fn forwarder_kind() {
+        assert_eq!(ForwarderKind::of_alias(Some("blue")), ForwarderKind::Static);
+        assert_eq!(ForwarderKind::of_alias(None), ForwarderKind::Ephemeral);
+
+        let info = ForwarderInfo {
+            #[cfg(feature = "tag")]
+            tag: Default::default(),
+            forwarding_route: "0#sc => 0#forward_to_blue".into(),
+            remote_address: "forward_to_blue".into(),
+            worker_address: "0#worker".into(),
+            kind: None,
+            balancer_address: None,
+        };
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["kind"], serde_json::Value::Null);
+
+        let json = serde_json::to_value(info.clone().with_kind(ForwarderKind::Wildcard)).unwrap();
+        assert_eq!(json["kind"], "wildcard");
+
+        let info = info.with_kind(ForwarderKind::Static);
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["kind"], "static");
+
+        let buf = minicbor::to_vec(&info).unwrap();
+        let decoded: ForwarderInfo = minicbor::decode(&buf).unwrap();
+        assert_eq!(decoded.kind(), Some(ForwarderKind::Static));
+    }
+
+    /// The request of a client predating most fields of CreateForwarder.
+    #[derive(Debug, Decode, Encode)]
+    #[rustfmt::skip]
+    #[cbor(map)]
+    struct OldCreateForwarder {
+        #[cfg(feature = "tag")]
+        #[n(0)] tag: TypeTag<3386455>,
+        #[n(1)] address: MultiAddr,
+        #[n(3)] at_rust_node: bool,
+    }
+
+    #[test]
+    fn create_forwarder_compatibility() {
+        let address = MultiAddr::from_str("/node/relay").unwrap();
+
+        // Older clients
+        let old = OldCreateForwarder {
+            #[cfg(feature = "tag")]
+            tag: Default::default(),
+            address: address.clone(),
+            at_rust_node: true,
+        };
+        let bytes = minicbor::to_vec(&old).unwrap();
+        let req: CreateForwarder = minicbor::decode(&bytes).unwrap();
+        assert_eq!(req.address(), &address);
+        assert!(req.at_rust_node());
+        assert_eq!(req.version(), 0);
+        assert_eq!(req.alias(), None);
+        assert!(req.upstreams().is_empty());
+
+        // Older nodes
+        let mut req = CreateForwarder::at_node(address.clone(), Some("blue".into()), true, None);
+        req.set_expires_in(Some(Duration::from_secs(60)));
+        assert_eq!(req.version(), API_VERSION);
+        let old: OldCreateForwarder = minicbor::decode(&minicbor::to_vec(&req).unwrap()).unwrap();
+        assert_eq!(old.address, address);
+        assert!(old.at_rust_node);
+
+        // Newer clients
+        let mut buf = Vec::new();
+        let mut e = minicbor::Encoder::new(&mut buf);
+        e.map(if cfg!(feature = "tag") { 4 } else { 3 }).unwrap();
+        #[cfg(feature = "tag")]
+        e.u8(0).unwrap().encode(TypeTag::<3386455>).unwrap();
+        e.u8(1).unwrap().encode(&address).unwrap();
+        e.u8(3).unwrap().bool(false).unwrap();
+        e.u8(100).unwrap().str("a field of the future").unwrap();
+        let req: CreateForwarder = minicbor::decode(&buf).unwrap();
+        assert_eq!(req.address(), &address);
+        assert!(!req.at_rust_node());
+    }
+
     #[ockam_macros::test]
     async fn create_forwarder(ctx: &mut Context) -> Result<()> {
         let cloud_address = match std::env::var("CLOUD_ADDRESS") {
@@ -1,7 +1,13 @@
+use crate::nodes::models::forwarder::{ForwarderEvent, ForwarderInfo, ForwarderKind};
 use crate::nodes::service::Alias;
-use ockam_core::compat::collections::BTreeMap;
-use ockam_core::{Address, Route};
+use crate::session::Key;
+use ockam::remote::RemoteForwarderInfo;
+use ockam_core::compat::collections::{BTreeMap, VecDeque};
+use ockam_core::compat::sync::Arc;
+use ockam_core::{AccessControl, Address, Route};
 use ockam_identity::IdentityIdentifier;
+use ockam_node::tokio::time::Instant;
+use std::time::Duration;
 
 #[derive(Default)]
 pub(crate) struct SecureChannelRegistry {
@@ -143,6 +149,147 @@ impl OutletInfo {
     }
 }
 
+pub(crate) struct ForwarderRegistryInfo {
+    pub(crate) info: RemoteForwarderInfo,
+    pub(crate) kind: ForwarderKind,
+    /// Session keeping the forwarder's secure channel alive, if any.
+    pub(crate) session: Option<Key>,
+    /// Registrations at the upstreams after the first one, together with
+    /// the worker balancing messages across all of them.
+    pub(crate) balanced: Option<BalancedUpstreams>,
+    /// Route the forwarder was registered through, to register it again
+    /// under another name.
+    pub(crate) route: Route,
+    /// Whether the forwarder keeps registering itself.
+    pub(crate) heartbeats: bool,
+    /// Access control of the forwarder's worker, if it has one.
+    pub(crate) access_control: Option<Arc<dyn AccessControl>>,
+    /// When the node deletes the forwarder, if it expires.
+    pub(crate) expires_at: Option<Instant>,
+    pub(crate) events: ForwarderEvents,
+}
+
+/// The last events of a forwarder, for `forwarder logs`.
+#[derive(Default)]
+pub(crate) struct ForwarderEvents {
+    next_seq: u64,
+    events: VecDeque<ForwarderEvent<'static>>,
+    /// Messages forwarded by the workers of the forwarder when they were
+    /// last asked.
+    forwarded: u64,
+}
+
+impl ForwarderEvents {
+    /// Older events are dropped.
+    const CAPACITY: usize = 100;
+
+    pub(crate) fn push(&mut self, message: impl Into<String>) {
+        let time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        if self.events.len() == Self::CAPACITY {
+            self.events.pop_front();
+        }
+        self.events
+            .push_back(ForwarderEvent::new(self.next_seq, time, message.into()));
+        self.next_seq += 1;
+    }
+
+    /// Record the messages relayed since the last call, given how many the
+    /// workers of the forwarder have forwarded in total.
+    ///
+    /// A lower total means that the workers were replaced since, e.g. by a
+    /// rename, and counted again from zero.
+    pub(crate) fn relayed(&mut self, forwarded: u64) {
+        let n = forwarded.checked_sub(self.forwarded).unwrap_or(forwarded);
+        if n > 0 {
+            self.push(format!(
+                "relayed {n} message{}",
+                if n == 1 { "" } else { "s" }
+            ));
+        }
+        self.forwarded = forwarded;
+    }
+
+    pub(crate) fn list(&self) -> Vec<ForwarderEvent<'static>> {
+        self.events.iter().cloned().collect()
+    }
+}
+
+pub(crate) struct BalancedUpstreams {
+    pub(crate) balancer: Address,
+    pub(crate) others: Vec<RemoteForwarderInfo>,
+}
+
+impl ForwarderRegistryInfo {
+    pub(crate) fn forwarder_info<'a>(&self) -> ForwarderInfo<'a> {
+        let info = ForwarderInfo::from(self.info.clone()).with_kind(self.kind);
+        match &self.balanced {
+            Some(b) => info.with_balancer_address(b.balancer.address()),
+            None => info,
+        }
+    }
+}
+
+/// The responses to the requests made with an idempotency key, e.g. the
+/// forwarders created by `forwarder create --idempotency-key`.
+pub(crate) struct IdempotencyKeys<T> {
+    creations: BTreeMap<String, IdempotentCreation<T>>,
+}
+
+impl<T> Default for IdempotencyKeys<T> {
+    fn default() -> Self {
+        Self {
+            creations: BTreeMap::new(),
+        }
+    }
+}
+
+struct IdempotentCreation<T> {
+    /// The request, without its key.
+    request: Vec<u8>,
+    response: T,
+    at: Instant,
+}
+
+/// What to answer to a request with an idempotency key.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Replay<T> {
+    /// The key is new, or was last used too long ago: handle the request.
+    New,
+    /// The same request was handled with this key: answer as back then.
+    Original(T),
+    /// Another request was handled with this key.
+    Mismatch,
+}
+
+impl<T: Clone> IdempotencyKeys<T> {
+    /// How long a key is remembered after the creation it was used for.
+    pub(crate) const WINDOW: Duration = Duration::from_secs(10 * 60);
+
+    pub(crate) fn replay(&mut self, key: &str, request: &[u8], now: Instant) -> Replay<T> {
+        self.creations
+            .retain(|_, c| now.saturating_duration_since(c.at) < Self::WINDOW);
+        match self.creations.get(key) {
+            None => Replay::New,
+            Some(c) if c.request == request => Replay::Original(c.response.clone()),
+            Some(_) => Replay::Mismatch,
+        }
+    }
+
+    /// Remember the response to `request`, under `key`.
+    pub(crate) fn record(&mut self, key: String, request: Vec<u8>, response: T, now: Instant) {
+        self.creations.insert(
+            key,
+            IdempotentCreation {
+                request,
+                response,
+                at: now,
+            },
+        );
+    }
+}
+
 #[derive(Default)]
 pub(crate) struct Registry {
     pub(crate) secure_channels: SecureChannelRegistry,
@@ -161,4 +308,64 @@ pub(crate) struct Registry {
     // FIXME: wow this is a terrible way to store data
     pub(crate) inlets: BTreeMap<Alias, InletInfo>,
     pub(crate) outlets: BTreeMap<Alias, OutletInfo>,
+    /// Forwarders created by this node, keyed by their remote address.
+    pub(crate) forwarders: BTreeMap<String, ForwarderRegistryInfo>,
+    /// Forwarders created with an idempotency key, by key.
+    pub(crate) idempotency_keys: IdempotencyKeys<ForwarderInfo<'static>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forwarder_events() {
+        let mut events = ForwarderEvents::default();
+        events.push("created");
+        events.relayed(0);
+        events.relayed(1);
+        events.relayed(4);
+        events.relayed(4);
+        events.relayed(2);
+        let messages: Vec<_> = events
+            .list()
+            .iter()
+            .map(|e| e.message().to_string())
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "created",
+                "relayed 1 message",
+                "relayed 3 messages",
+                "relayed 2 messages"
+            ]
+        );
+
+        for i in 0..ForwarderEvents::CAPACITY {
+            events.push(format!("event {i}"));
+        }
+        let list = events.list();
+        assert_eq!(list.len(), ForwarderEvents::CAPACITY);
+        assert_eq!(list[0].seq(), 4);
+        assert_eq!(list[0].message(), "event 0");
+    }
+
+    #[test]
+    fn idempotency_keys() {
+        let mut keys = IdempotencyKeys::default();
+        let info = "forward_to_blue";
+        let now = Instant::now();
+        assert_eq!(keys.replay("k1", b"request", now), Replay::New);
+        keys.record("k1".into(), b"request".to_vec(), info, now);
+
+        let later = now + Duration::from_secs(60);
+        assert_eq!(keys.replay("k1", b"request", later), Replay::Original(info));
+        assert_eq!(keys.replay("k1", b"other", later), Replay::Mismatch);
+        assert_eq!(keys.replay("k2", b"other", later), Replay::New);
+
+        // Forgotten once the window is over
+        let after = now + IdempotencyKeys::<&str>::WINDOW;
+        assert_eq!(keys.replay("k1", b"other", after), Replay::New);
+    }
 }
@@ -3,7 +3,9 @@
 use minicbor::Decoder;
 
 use ockam::compat::asynchronous::RwLock;
+use ockam::remote::RemoteForwarderRequest;
 use ockam::{Address, Context, ForwardingService, Result, Routed, TcpTransport, Worker};
+use ockam_abac::Expr;
 use ockam_core::api::{Error, Method, Request, Response, ResponseBuilder, Status};
 use ockam_core::compat::{
     boxed::Box,
@@ -23,6 +25,7 @@ use std::collections::BTreeMap;
 use std::error::Error as _;
 use std::path::PathBuf;
 use std::time::Duration;
+use tracing::Instrument;
 
 use super::models::secure_channel::CredentialExchangeMode;
 use super::registry::Registry;
@@ -31,7 +34,10 @@ use crate::config::lookup::ProjectLookup;
 use crate::error::ApiError;
 use crate::lmdb::LmdbStorage;
 use crate::nodes::config::NodeConfig;
-use crate::nodes::models::base::NodeStatus;
+use crate::nodes::models::base::{
+    AccessControlDescription, AccessControlTrace, AuthorizationStatsList, NodeStatus, NodeVersion,
+    TraceAccessControl, WorkerAuthorizationStats, API_VERSION,
+};
 use crate::nodes::models::transport::{TransportMode, TransportType};
 use crate::session::util::starts_with_host_tcp_secure;
 use crate::session::{Medic, Sessions};
@@ -117,6 +123,8 @@ pub struct NodeManager {
     sessions: Arc<Mutex<Sessions>>,
     medic: JoinHandle<Result<(), ockam_core::Error>>,
     policies: LmdbStorage,
+    /// Policy of the default access control of the node, if any.
+    default_policy: Option<Expr>,
 }
 
 pub struct NodeManagerWorker {
@@ -354,6 +362,7 @@ impl NodeManager {
             },
             sessions,
             policies: policies_storage,
+            default_policy: None,
         };
 
         if !general_options.skip_defaults {
@@ -533,6 +542,67 @@ impl NodeManagerWorker {
                     ))
                     .to_vec()?
             }
+            (Get, ["node", "version"]) => Response::ok(req.id())
+                .body(NodeVersion::new(env!("CARGO_PKG_VERSION"), API_VERSION))
+                .to_vec()?,
+            (Get, ["node", "authorization_stats"]) => {
+                let list = ctx
+                    .authorization_stats()
+                    .into_iter()
+                    .map(|(address, count)| {
+                        WorkerAuthorizationStats::new(
+                            address.to_string(),
+                            count.allowed,
+                            count.denied,
+                        )
+                    })
+                    .collect();
+                Response::ok(req.id())
+                    .body(AuthorizationStatsList::new(list))
+                    .to_vec()?
+            }
+            (Get, ["node", "access_control", address]) => {
+                match ctx.describe_access_control(&Address::from_string(*address)) {
+                    Some(description) => Response::ok(req.id())
+                        .body(AccessControlDescription::new(*address, description))
+                        .to_vec()?,
+                    None => {
+                        let mut err = Error::new(req.path())
+                            .with_message(format!("no worker has the address {address}"));
+                        if let Some(m) = req.method() {
+                            err.set_method(m)
+                        }
+                        Response::not_found(req.id()).body(err).to_vec()?
+                    }
+                }
+            }
+            (Post, ["node", "access_control", address, "trace"]) => {
+                let body: TraceAccessControl = dec.decode()?;
+                let local_msg = match body.local_message() {
+                    Ok(local_msg) => local_msg,
+                    Err(e) => {
+                        return Ok(Response::bad_request(req.id())
+                            .body(format!("invalid message: {e}"))
+                            .to_vec()?)
+                    }
+                };
+                match ctx
+                    .trace_access_control(&Address::from_string(*address), &local_msg)
+                    .await
+                {
+                    Some(trace) => Response::ok(req.id())
+                        .body(AccessControlTrace::from(&trace))
+                        .to_vec()?,
+                    None => {
+                        let mut err = Error::new(req.path())
+                            .with_message(format!("no worker has the address {address}"));
+                        if let Some(m) = req.method() {
+                            err.set_method(m)
+                        }
+                        Response::not_found(req.id()).body(err).to_vec()?
+                    }
+                }
+            }
 
             // ==*== Tcp Connection ==*==
             // TODO: Get all tcp connections
@@ -576,6 +646,7 @@ impl NodeManagerWorker {
             }
 
             // ==*== Credentials ==*==
+            (Get, ["node", "credentials"]) => self.credential_status(req).await?.to_vec()?,
             (Post, ["node", "credentials", "actions", "get"]) => {
                 self.get_credential(req, dec).await?.to_vec()?
             }
@@ -649,6 +720,31 @@ impl NodeManagerWorker {
 
             // ==*== Forwarder commands ==*==
             (Post, ["node", "forwarder"]) => self.create_forwarder(ctx, req.id(), dec).await?,
+            (Get, ["node", "forwarder"]) => {
+                let node_manager = self.node_manager.read().await;
+                self.get_forwarders(req, &node_manager.registry).to_vec()?
+            }
+            (Get, ["node", "forwarder", remote_address]) => {
+                let node_manager = self.node_manager.read().await;
+                self.show_forwarder(req, &node_manager.registry, remote_address)?
+            }
+            (Put, ["node", "forwarder", remote_address]) => {
+                self.rename_forwarder(ctx, req, dec, remote_address).await?
+            }
+            (Delete, ["node", "forwarder", remote_address]) => {
+                self.delete_forwarder(ctx, req, remote_address).await?
+            }
+            (Post, ["node", "forwarder", remote_address, "drain"]) => {
+                self.drain_forwarder(ctx, req, remote_address, RemoteForwarderRequest::Drain)
+                    .await?
+            }
+            (Get, ["node", "forwarder", remote_address, "events"]) => {
+                self.get_forwarder_events(ctx, req, remote_address).await?
+            }
+            (Get, ["node", "forwarder", remote_address, "drain"]) => {
+                self.drain_forwarder(ctx, req, remote_address, RemoteForwarderRequest::Status)
+                    .await?
+            }
 
             // ==*== Inlets & Outlets ==*==
             (Get, ["node", "inlet"]) => {
@@ -663,6 +759,24 @@ impl NodeManagerWorker {
             (Post, ["node", "outlet"]) => self.create_outlet(req, dec).await?.to_vec()?,
             (Delete, ["node", "portal"]) => todo!(),
 
+            (Post, ["node", "policy"]) => self
+                .node_manager
+                .write()
+                .await
+                .set_default_policy(ctx, req, dec)?
+                .to_vec()?,
+            (Get, ["node", "policy"]) => self
+                .node_manager
+                .read()
+                .await
+                .get_default_policy(req)
+                .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?,
+            (Delete, ["node", "policy"]) => self
+                .node_manager
+                .write()
+                .await
+                .del_default_policy(ctx, req)
+                .to_vec()?,
             (Post, ["policy", resource, action]) => self
                 .node_manager
                 .read()
@@ -791,7 +905,12 @@ impl Worker for NodeManagerWorker {
             }
         };
 
-        let r = match self.handle_request(ctx, &req, &mut dec).await {
+        let span = request_span(&req);
+        let r = match self
+            .handle_request(ctx, &req, &mut dec)
+            .instrument(span)
+            .await
+        {
             Ok(r) => r,
             Err(err) => {
                 error! {
@@ -821,6 +940,30 @@ impl Worker for NodeManagerWorker {
     }
 }
 
+/// The span of handling `req`, which continues the trace of its sender when
+/// it sent its trace context.
+fn request_span(req: &Request) -> tracing::Span {
+    let method = req.method().map(|m| m.to_string()).unwrap_or_default();
+    let span = debug_span!(
+        target: TARGET,
+        "node.request",
+        rpc.method = %format!("{method} {}", req.path()),
+        rpc.id = %req.id(),
+    );
+    #[cfg(feature = "otel")]
+    if let Some(traceparent) = req.trace_context() {
+        use opentelemetry::propagation::TextMapPropagator;
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+        let carrier =
+            std::collections::HashMap::from([("traceparent".to_string(), traceparent.to_string())]);
+        let parent =
+            opentelemetry::sdk::propagation::TraceContextPropagator::new().extract(&carrier);
+        span.set_parent(parent);
+    }
+    span
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use crate::nodes::NodeManager;
@@ -1,7 +1,9 @@
 use crate::authenticator::direct::Client;
 use crate::error::ApiError;
 use crate::multiaddr_to_route;
-use crate::nodes::models::credentials::{GetCredentialRequest, PresentCredentialRequest};
+use crate::nodes::models::credentials::{
+    CredentialStatus, GetCredentialRequest, PresentCredentialRequest,
+};
 use crate::nodes::service::map_multiaddr_err;
 use crate::nodes::NodeManager;
 use crate::DefaultAddress;
@@ -84,6 +86,24 @@ impl NodeManagerWorker {
         Ok(response)
     }
 
+    pub(super) async fn credential_status(
+        &self,
+        req: &Request<'_>,
+    ) -> Result<ResponseBuilder<CredentialStatus>> {
+        let node_manager = self.node_manager.read().await;
+        let credential = node_manager.identity()?.credential().await.is_some();
+        let authorities = node_manager
+            .authorities
+            .as_ref()
+            .map_or(0, |a| a.0.len() as u32);
+        let status = CredentialStatus::new(
+            node_manager.enable_credential_checks,
+            credential,
+            authorities,
+        );
+        Ok(Response::ok(req.id()).body(status))
+    }
+
     pub(super) async fn present_credential(
         &self,
         req: &Request<'_>,
@@ -1,25 +1,42 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use minicbor::Decoder;
 
 use ockam::compat::asynchronous::RwLock;
-use ockam::remote::RemoteForwarder;
-use ockam::Result;
-use ockam_core::api::{Id, Response, Status};
-use ockam_core::AsyncTryClone;
+use ockam::remote::{RemoteForwarder, RemoteForwarderInfo, RemoteForwarderRequest};
+use ockam::{Balancer, ForwardingService, Result, TCP};
+use ockam_abac::expr::str;
+use ockam_abac::{Env, Expr, PolicyAccessControl};
+use ockam_core::api::{Id, Request, Response, ResponseBuilder, Status};
+use ockam_core::{AccessControl, AsyncTryClone, Route};
 use ockam_identity::IdentityIdentifier;
-use ockam_multiaddr::MultiAddr;
-use ockam_node::tokio::time::timeout;
+use ockam_multiaddr::proto::Project;
+use ockam_multiaddr::{MultiAddr, Protocol};
+use ockam_node::tokio;
+use ockam_node::tokio::time::{sleep_until, timeout, Instant};
 use ockam_node::Context;
 
 use crate::error::ApiError;
-use crate::nodes::models::forwarder::{CreateForwarder, ForwarderInfo};
+use crate::nodes::models::base::API_VERSION;
+use crate::nodes::models::forwarder::{
+    is_valid_remote_address, CreateForwarder, DrainStatus, ForwarderEvent, ForwarderEventList,
+    ForwarderInfo, ForwarderKind, ForwarderList, RenameForwarder,
+};
+use crate::nodes::registry::{
+    BalancedUpstreams, ForwarderEvents, ForwarderRegistryInfo, Registry, Replay,
+};
 use crate::session::util;
-use crate::session::{Replacer, Session};
+use crate::session::{Data, Replacer, Session};
+use crate::{actions, resources};
 use crate::{multiaddr_to_route, try_multiaddr_to_addr};
 
 use super::{NodeManager, NodeManagerWorker};
 
+/// Key of the remote address the forwarder of a session is registered
+/// under in the session data, which changes when it has no alias.
+const REMOTE_ADDRESS: &str = "remote-address";
+
 impl NodeManagerWorker {
     pub(super) async fn create_forwarder(
         &mut self,
@@ -31,7 +48,116 @@ impl NodeManagerWorker {
         let mut node_manager = self.node_manager.write().await;
         let req: CreateForwarder = dec.decode()?;
 
-        debug!(addr = %req.address(), alias = ?req.alias(), "Handling CreateForwarder request");
+        debug!(id = %rid, addr = %req.address(), alias = ?req.alias(), version = req.version(), "Handling CreateForwarder request");
+
+        if req.version() > API_VERSION {
+            warn!(id = %rid, version = req.version(), "CreateForwarder request of a newer client, ignoring the options this node doesn't know of");
+        }
+
+        if let Some(alias) = req.alias() {
+            if !is_valid_remote_address(alias) {
+                return Ok(Response::bad_request(rid)
+                    .body(format!("invalid forwarder alias: {alias}"))
+                    .to_vec()?);
+            }
+        }
+
+        if req.wildcard() && !req.at_rust_node() {
+            return Ok(Response::bad_request(rid)
+                .body("wildcard forwarders can only be created at rust nodes")
+                .to_vec()?);
+        }
+
+        // A request made again with the same key gets the forwarder created
+        // the first time, as long as the rest of the request is the same
+        let idempotency = match req.idempotency_key() {
+            Some(key) => {
+                let mut unkeyed = req.clone();
+                unkeyed.set_idempotency_key(None);
+                let request = minicbor::to_vec(&unkeyed)?;
+                let keys = &mut node_manager.registry.idempotency_keys;
+                match keys.replay(key, &request, Instant::now()) {
+                    Replay::Original(b) => {
+                        debug!(re = %rid, remote_address = %b.remote_address(), "CreateForwarder request already processed with this idempotency key");
+                        return Ok(Response::ok(rid).body(b).to_vec()?);
+                    }
+                    Replay::Mismatch => {
+                        return Ok(Response::builder(rid, Status::Conflict)
+                            .body(format!(
+                                "idempotency key {key} was already used to create another forwarder"
+                            ))
+                            .to_vec()?);
+                    }
+                    // Registered under its alias, which has to be free
+                    Replay::New
+                        if req.alias().map_or(false, |alias| {
+                            node_manager.registry.forwarders.contains_key(alias)
+                        }) =>
+                    {
+                        return Ok(Response::builder(rid, Status::Conflict)
+                            .body(format!(
+                                "forwarder {} already exists, and was not created with idempotency key {key}",
+                                req.alias().unwrap_or_default()
+                            ))
+                            .to_vec()?);
+                    }
+                    Replay::New => Some((key.to_string(), request)),
+                }
+            }
+            None => None,
+        };
+
+        if !req.upstreams().is_empty() && (req.wildcard() || !req.at_rust_node()) {
+            return Ok(Response::bad_request(rid)
+                .body("balanced forwarders can only be created at rust nodes, without wildcard")
+                .to_vec()?);
+        }
+
+        // Only once the request is known to be valid, as this connects
+        if let Some(keepalive) = req.keepalive() {
+            if req.upstreams().is_empty() {
+                node_manager
+                    .enable_keepalive(req.address(), keepalive)
+                    .await?;
+            }
+            for upstream in req.upstreams() {
+                node_manager
+                    .enable_keepalive(upstream.address(), keepalive)
+                    .await?;
+            }
+        }
+
+        if !req.upstreams().is_empty() {
+            return match node_manager.create_balanced_forwarder(ctx, &req).await {
+                Ok(f) => {
+                    let b = f.forwarder_info().to_owned();
+                    if let Some(at) = f.expires_at {
+                        let ctx = ctx.async_try_clone().await?;
+                        expire_forwarder(manager, ctx, f.info.remote_address().to_string(), at);
+                    }
+                    node_manager
+                        .registry
+                        .forwarders
+                        .insert(f.info.remote_address().to_string(), f);
+                    if let Some((key, request)) = idempotency {
+                        node_manager.registry.idempotency_keys.record(
+                            key,
+                            request,
+                            b.to_owned(),
+                            Instant::now(),
+                        );
+                    }
+                    debug!(re = %rid, remote_address = %b.remote_address(), "CreateForwarder request processed, sending back response");
+                    Ok(Response::ok(rid).body(b).to_vec()?)
+                }
+                Err(err) => {
+                    error!(re = %rid, ?err, "Failed to create forwarder");
+                    Ok(Response::builder(rid, Status::InternalServerError)
+                        .body(err.to_string())
+                        .to_vec()?)
+                }
+            };
+        }
 
         let (sec_chan, suffix) = node_manager
             .connect(req.address(), req.authorized(), None)
@@ -41,38 +167,84 @@ impl NodeManagerWorker {
         let route = multiaddr_to_route(&full)
             .ok_or_else(|| ApiError::message("invalid address: {addr}"))?;
 
+        let access_control = node_manager.forwarder_access_control(req.access_policy());
+        let mut session = None;
         let forwarder = if req.at_rust_node() {
-            if let Some(alias) = req.alias() {
-                RemoteForwarder::create_static_without_heartbeats(ctx, route, alias).await
+            let alias = if req.wildcard() {
+                Some(ForwardingService::WILDCARD_ALIAS)
             } else {
-                RemoteForwarder::create(ctx, route).await
-            }
-        } else {
-            let f = if let Some(alias) = req.alias() {
-                RemoteForwarder::create_static(ctx, route, alias).await
-            } else {
-                RemoteForwarder::create(ctx, route).await
+                req.alias()
             };
-            if f.is_ok() && !sec_chan.is_empty() {
+            create_remote_forwarder(ctx, route.clone(), alias, false, access_control.clone()).await
+        } else {
+            let f = create_remote_forwarder(
+                ctx,
+                route.clone(),
+                req.alias(),
+                true,
+                access_control.clone(),
+            )
+            .await;
+            if let (Ok(info), false) = (&f, sec_chan.is_empty()) {
                 let ctx = Arc::new(ctx.async_try_clone().await?);
+                let mut s = Session::new(sec_chan);
+                s.data()
+                    .put(REMOTE_ADDRESS, info.remote_address().to_string());
                 let repl = replacer(
-                    manager,
+                    manager.clone(),
                     ctx,
+                    s.data(),
                     req.address().clone(),
                     req.alias().map(|a| a.to_string()),
                     req.authorized(),
+                    access_control.clone(),
+                    req.keepalive(),
                 );
-                let mut s = Session::new(sec_chan);
                 s.set_replacer(repl);
-                node_manager.sessions.lock().unwrap().add(s);
+                session = Some(node_manager.sessions.lock().unwrap().add(s));
             }
             f
         };
 
         match forwarder {
             Ok(info) => {
-                let b = ForwarderInfo::from(info);
+                let kind = if req.wildcard() {
+                    ForwarderKind::Wildcard
+                } else {
+                    ForwarderKind::of_alias(req.alias())
+                };
+                let expires_at = req.expires_in().map(|ttl| Instant::now() + ttl);
+                let mut events = ForwarderEvents::default();
+                events.push(format!("created at {}", info.forwarding_route()));
+                node_manager.registry.forwarders.insert(
+                    info.remote_address().to_string(),
+                    ForwarderRegistryInfo {
+                        info: info.clone(),
+                        kind,
+                        session,
+                        balanced: None,
+                        route,
+                        heartbeats: !req.at_rust_node(),
+                        access_control,
+                        expires_at,
+                        events,
+                    },
+                );
+                if let Some(at) = expires_at {
+                    let ctx = ctx.async_try_clone().await?;
+                    expire_forwarder(manager, ctx, info.remote_address().to_string(), at);
+                }
+                let b = ForwarderInfo::from(info).with_kind(kind);
+                if let Some((key, request)) = idempotency {
+                    node_manager.registry.idempotency_keys.record(
+                        key,
+                        request,
+                        b.to_owned(),
+                        Instant::now(),
+                    );
+                }
                 debug!(
+                    re = %rid,
                     forwarding_route = %b.forwarding_route(),
                     remote_address = %b.remote_address(),
                     "CreateForwarder request processed, sending back response"
@@ -80,62 +252,540 @@ impl NodeManagerWorker {
                 Ok(Response::ok(rid).body(b).to_vec()?)
             }
             Err(err) => {
-                error!(?err, "Failed to create forwarder");
+                error!(re = %rid, ?err, "Failed to create forwarder");
                 Ok(Response::builder(rid, Status::InternalServerError)
                     .body(err.to_string())
                     .to_vec()?)
             }
         }
     }
+
+    pub(super) async fn rename_forwarder(
+        &mut self,
+        ctx: &mut Context,
+        req: &Request<'_>,
+        dec: &mut Decoder<'_>,
+        remote_address: &str,
+    ) -> Result<Vec<u8>> {
+        let manager = self.node_manager.clone();
+        let mut node_manager = self.node_manager.write().await;
+        let body: RenameForwarder = dec.decode()?;
+        let alias = body.alias();
+
+        debug!(%remote_address, %alias, "Handling RenameForwarder request");
+
+        if !is_valid_remote_address(alias) {
+            return Ok(Response::bad_request(req.id())
+                .body(format!("invalid forwarder alias: {alias}"))
+                .to_vec()?);
+        }
+        let f = match node_manager.registry.forwarders.get(remote_address) {
+            Some(f) => f,
+            None => return Ok(Response::not_found(req.id()).to_vec()?),
+        };
+        // The others are either registered under a random address, or
+        // recreated under their name by their session or balancer.
+        if f.kind != ForwarderKind::Static || f.session.is_some() || f.balanced.is_some() {
+            return Ok(Response::bad_request(req.id())
+                .body("only static forwarders created at a single rust node can be renamed")
+                .to_vec()?);
+        }
+        if node_manager.registry.forwarders.contains_key(alias) {
+            return Ok(Response::builder(req.id(), Status::Conflict)
+                .body(format!("forwarder {alias} already exists"))
+                .to_vec()?);
+        }
+
+        // Register the new name through the same route before stopping the
+        // worker of the old one, so that the forwarder is never missing.
+        let info = match create_remote_forwarder(
+            ctx,
+            f.route.clone(),
+            Some(alias),
+            f.heartbeats,
+            f.access_control.clone(),
+        )
+        .await
+        {
+            Ok(info) => info,
+            Err(err) => {
+                error!(%remote_address, ?err, "Failed to rename forwarder");
+                return Ok(Response::builder(req.id(), Status::InternalServerError)
+                    .body(err.to_string())
+                    .to_vec()?);
+            }
+        };
+        let old = node_manager
+            .registry
+            .forwarders
+            .remove(remote_address)
+            .ok_or_else(|| ApiError::generic("forwarder vanished while renaming it"))?;
+        ctx.stop_worker(old.info.worker_address().clone()).await?;
+        let mut f = ForwarderRegistryInfo {
+            info: info.clone(),
+            ..old
+        };
+        f.events.push(format!(
+            "renamed from {remote_address}, created at {}",
+            info.forwarding_route()
+        ));
+        let b = f.forwarder_info();
+        if let Some(at) = f.expires_at {
+            let ctx = ctx.async_try_clone().await?;
+            expire_forwarder(manager, ctx, info.remote_address().to_string(), at);
+        }
+        node_manager
+            .registry
+            .forwarders
+            .insert(info.remote_address().to_string(), f);
+        debug!(%remote_address, new_remote_address = %b.remote_address(), "RenameForwarder request processed, sending back response");
+        Ok(Response::ok(req.id()).body(b).to_vec()?)
+    }
+
+    pub(super) async fn delete_forwarder(
+        &mut self,
+        ctx: &mut Context,
+        req: &Request<'_>,
+        remote_address: &str,
+    ) -> Result<Vec<u8>> {
+        let mut node_manager = self.node_manager.write().await;
+
+        debug!(%remote_address, "Handling DeleteForwarder request");
+
+        match node_manager.delete_forwarder(ctx, remote_address).await? {
+            Some(_) => Ok(Response::ok(req.id()).to_vec()?),
+            None => Ok(Response::not_found(req.id()).to_vec()?),
+        }
+    }
+
+    pub(super) async fn drain_forwarder(
+        &mut self,
+        ctx: &mut Context,
+        req: &Request<'_>,
+        remote_address: &str,
+        drain: RemoteForwarderRequest,
+    ) -> Result<Vec<u8>> {
+        let mut node_manager = self.node_manager.write().await;
+
+        debug!(%remote_address, ?drain, "Handling DrainForwarder request");
+
+        match node_manager
+            .drain_forwarder(ctx, remote_address, drain)
+            .await?
+        {
+            Some(status) => Ok(Response::ok(req.id()).body(status).to_vec()?),
+            None => Ok(Response::not_found(req.id()).to_vec()?),
+        }
+    }
+
+    pub(super) async fn get_forwarder_events(
+        &mut self,
+        ctx: &mut Context,
+        req: &Request<'_>,
+        remote_address: &str,
+    ) -> Result<Vec<u8>> {
+        let mut node_manager = self.node_manager.write().await;
+        match node_manager.forwarder_events(ctx, remote_address).await? {
+            Some(list) => Ok(Response::ok(req.id())
+                .body(ForwarderEventList::new(list))
+                .to_vec()?),
+            None => Ok(Response::not_found(req.id()).to_vec()?),
+        }
+    }
+
+    pub(super) fn get_forwarders<'a>(
+        &self,
+        req: &Request<'a>,
+        registry: &'a Registry,
+    ) -> ResponseBuilder<ForwarderList<'a>> {
+        Response::ok(req.id()).body(ForwarderList::new(
+            registry
+                .forwarders
+                .values()
+                .map(|f| f.forwarder_info())
+                .collect(),
+        ))
+    }
+
+    pub(super) fn show_forwarder<'a>(
+        &self,
+        req: &Request<'a>,
+        registry: &'a Registry,
+        remote_address: &str,
+    ) -> Result<Vec<u8>> {
+        debug!(%remote_address, "Handling ShowForwarder request");
+        match registry.forwarders.get(remote_address) {
+            Some(f) => Ok(Response::ok(req.id()).body(f.forwarder_info()).to_vec()?),
+            None => Ok(Response::not_found(req.id()).to_vec()?),
+        }
+    }
+}
+
+impl NodeManager {
+    /// Enable TCP keepalive on the connection to the first hop of `addr`,
+    /// connecting to it first if needed. Addresses which don't start with
+    /// a TCP hop, or a project reached over TCP, are left alone.
+    async fn enable_keepalive(&self, addr: &MultiAddr, keepalive: Duration) -> Result<()> {
+        let addr = match addr.first() {
+            Some(p) if p.code() == Project::CODE => {
+                let p = p
+                    .cast::<Project>()
+                    .ok_or_else(|| ApiError::message("invalid project protocol in multiaddr"))?;
+                self.resolve_project(&p)?.0
+            }
+            _ => addr.clone(),
+        };
+        let route = match multiaddr_to_route(&addr) {
+            Some(route) => route,
+            None => return Ok(()),
+        };
+        match route.next() {
+            Ok(hop) if hop.transport_type() == TCP => {
+                debug!(%addr, ?keepalive, "enabling keepalive");
+                self.tcp_transport
+                    .connect_with_keepalive(hop.address(), keepalive)
+                    .await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// The access control of a forwarder created with the given policy.
+    fn forwarder_access_control(&self, policy: Option<&Expr>) -> Option<Arc<dyn AccessControl>> {
+        let policy = policy?.clone();
+        let mut env = Env::new();
+        env.put("resource.id", str(resources::FORWARDER.as_str()));
+        env.put("action.id", str(actions::HANDLE_MESSAGE.as_str()));
+        let store = self.authenticated_storage.clone();
+        Some(Arc::new(PolicyAccessControl::new(policy, store, env)))
+    }
+
+    /// Stop a forwarder and forget about it, together with the session
+    /// that would otherwise recreate it.
+    pub(super) async fn delete_forwarder(
+        &mut self,
+        ctx: &Context,
+        remote_address: &str,
+    ) -> Result<Option<RemoteForwarderInfo>> {
+        let f = match self.registry.forwarders.remove(remote_address) {
+            Some(f) => f,
+            None => return Ok(None),
+        };
+        debug!(%remote_address, "deleting forwarder");
+        if let Some(key) = &f.session {
+            self.sessions.lock().unwrap().remove(key);
+        }
+        if let Some(b) = &f.balanced {
+            ctx.stop_worker(b.balancer.clone()).await?;
+            for info in &b.others {
+                ctx.stop_worker(info.worker_address().clone()).await?;
+            }
+        }
+        ctx.stop_worker(f.info.worker_address().clone()).await?;
+        Ok(Some(f.info))
+    }
+
+    /// Send `req` to the workers of a forwarder, adding up their status.
+    ///
+    /// A forwarder which starts draining is also forgotten by its session
+    /// so that it isn't recreated.
+    pub(super) async fn drain_forwarder(
+        &mut self,
+        ctx: &Context,
+        remote_address: &str,
+        req: RemoteForwarderRequest,
+    ) -> Result<Option<DrainStatus>> {
+        let f = match self.registry.forwarders.get_mut(remote_address) {
+            Some(f) => f,
+            None => return Ok(None),
+        };
+        if req == RemoteForwarderRequest::Drain {
+            if let Some(key) = f.session.take() {
+                self.sessions.lock().unwrap().remove(&key);
+            }
+            f.events.push("draining");
+        }
+        let others = f.balanced.iter().flat_map(|b| b.others.iter());
+        let mut draining = true;
+        let mut forwarded = 0;
+        for info in std::iter::once(&f.info).chain(others) {
+            let status = RemoteForwarder::request(ctx, info, req).await?;
+            draining &= status.draining();
+            forwarded += status.forwarded();
+        }
+        Ok(Some(DrainStatus::new(draining, forwarded)))
+    }
+
+    /// The recent events of a forwarder, after recording the messages its
+    /// workers relayed since the last time.
+    pub(super) async fn forwarder_events(
+        &mut self,
+        ctx: &Context,
+        remote_address: &str,
+    ) -> Result<Option<Vec<ForwarderEvent<'static>>>> {
+        let f = match self.registry.forwarders.get_mut(remote_address) {
+            Some(f) => f,
+            None => return Ok(None),
+        };
+        let others = f.balanced.iter().flat_map(|b| b.others.iter());
+        let mut forwarded = 0;
+        for info in std::iter::once(&f.info).chain(others) {
+            match RemoteForwarder::request(ctx, info, RemoteForwarderRequest::Status).await {
+                Ok(status) => forwarded += status.forwarded(),
+                Err(err) => {
+                    f.events.push(format!(
+                        "worker {} does not answer: {err}",
+                        info.worker_address()
+                    ));
+                    return Ok(Some(f.events.list()));
+                }
+            }
+        }
+        f.events.relayed(forwarded);
+        Ok(Some(f.events.list()))
+    }
+
+    /// Register a forwarder at each of the upstreams of `req`, and start the
+    /// worker balancing messages across them.
+    async fn create_balanced_forwarder(
+        &mut self,
+        ctx: &Context,
+        req: &CreateForwarder<'_>,
+    ) -> Result<ForwarderRegistryInfo> {
+        let access_control = self.forwarder_access_control(req.access_policy());
+        let mut created: Vec<RemoteForwarderInfo> = Vec::new();
+        let mut routes = Vec::new();
+        let registered = async {
+            for upstream in req.upstreams() {
+                let (sec_chan, suffix) = self
+                    .connect(upstream.address(), req.authorized(), None)
+                    .await?;
+                let full = sec_chan.try_with(&suffix)?;
+                let route = multiaddr_to_route(&full)
+                    .ok_or_else(|| ApiError::message(format!("invalid address: {full}")))?;
+                let info = create_remote_forwarder(
+                    ctx,
+                    route.clone(),
+                    req.alias(),
+                    false,
+                    access_control.clone(),
+                )
+                .await?;
+                created.push(info);
+                routes.push((route, upstream.weight()));
+            }
+            Balancer::create(ctx, routes.clone()).await
+        }
+        .await;
+        let first_route = routes
+            .first()
+            .map(|(r, _)| r.clone())
+            .unwrap_or_else(|| Route::new().into());
+
+        match registered {
+            Ok(balancer) => {
+                let mut events = ForwarderEvents::default();
+                for (route, weight) in &routes {
+                    events.push(format!("created at {route} with weight {weight}"));
+                }
+                let mut others = created.into_iter();
+                let info = others
+                    .next()
+                    .ok_or_else(|| ApiError::generic("no upstream"))?;
+                Ok(ForwarderRegistryInfo {
+                    info,
+                    kind: ForwarderKind::of_alias(req.alias()),
+                    session: None,
+                    balanced: Some(BalancedUpstreams {
+                        balancer,
+                        others: others.collect(),
+                    }),
+                    route: first_route,
+                    heartbeats: false,
+                    access_control,
+                    expires_at: req.expires_in().map(|ttl| Instant::now() + ttl),
+                    events,
+                })
+            }
+            Err(err) => {
+                for info in created {
+                    let _ = ctx.stop_worker(info.worker_address().clone()).await;
+                }
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Delete the forwarder registered under `remote_address` at `at`, unless
+/// it was deleted or renamed in the meantime.
+fn expire_forwarder(
+    manager: Arc<RwLock<NodeManager>>,
+    ctx: Context,
+    remote_address: String,
+    at: Instant,
+) {
+    tokio::spawn(async move {
+        sleep_until(at).await;
+        let mut this = manager.write().await;
+        let expires_at = this
+            .registry
+            .forwarders
+            .get(&remote_address)
+            .and_then(|f| f.expires_at);
+        if expires_at != Some(at) {
+            return;
+        }
+        if let Err(err) = this.delete_forwarder(&ctx, &remote_address).await {
+            warn!(%remote_address, %err, "failed to delete expired forwarder");
+        }
+    });
+}
+
+/// Start the worker of a forwarder registered at `route`, only forwarding
+/// the messages allowed by `access_control` when given.
+///
+/// Forwarders with an alias are static, and `heartbeats` tells whether
+/// they keep registering themselves, which only projects support.
+async fn create_remote_forwarder(
+    ctx: &Context,
+    route: Route,
+    alias: Option<&str>,
+    heartbeats: bool,
+    access_control: Option<Arc<dyn AccessControl>>,
+) -> Result<RemoteForwarderInfo> {
+    match (alias, access_control) {
+        (Some(alias), None) if heartbeats => {
+            RemoteForwarder::create_static(ctx, route, alias).await
+        }
+        (Some(alias), Some(ac)) if heartbeats => {
+            RemoteForwarder::create_static_with_access_control(ctx, route, alias, ac).await
+        }
+        (Some(alias), None) => {
+            RemoteForwarder::create_static_without_heartbeats(ctx, route, alias).await
+        }
+        (Some(alias), Some(ac)) => {
+            RemoteForwarder::create_static_without_heartbeats_with_access_control(
+                ctx, route, alias, ac,
+            )
+            .await
+        }
+        (None, None) => RemoteForwarder::create(ctx, route).await,
+        (None, Some(ac)) => RemoteForwarder::create_with_access_control(ctx, route, ac).await,
+    }
 }
 
 /// Create a session replacer.
 ///
 /// This returns a function that accepts the previous ping address (e.g.
 /// the secure channel worker address) and constructs the whole route
-/// again.
+/// again. A forwarder without an alias is registered under a new remote
+/// address, to which its registry entry is moved.
+#[allow(clippy::too_many_arguments)]
 fn replacer(
     manager: Arc<RwLock<NodeManager>>,
     ctx: Arc<Context>,
+    data: Data,
     addr: MultiAddr,
     alias: Option<String>,
     auth: Option<IdentityIdentifier>,
+    access_control: Option<Arc<dyn AccessControl>>,
+    keepalive: Option<Duration>,
 ) -> Replacer {
     Box::new(move |prev| {
         let ctx = ctx.clone();
+        let data = data.clone();
         let addr = addr.clone();
         let alias = alias.clone();
         let auth = auth.clone();
+        let access_control = access_control.clone();
         let manager = manager.clone();
         Box::pin(async move {
             debug!(%prev, %addr, "creating new remote forwarder");
+            let remote_address = data.get::<String>(REMOTE_ADDRESS).unwrap_or_default();
             let f = async {
                 let prev = try_multiaddr_to_addr(&prev)?;
                 let mut this = manager.write().await;
+                if let Some(f) = this.registry.forwarders.get_mut(&remote_address) {
+                    f.events.push("connection lost, reconnecting");
+                }
                 let _ = this.delete_secure_channel(&prev).await;
+                if let Some(keepalive) = keepalive {
+                    this.enable_keepalive(&addr, keepalive).await?;
+                }
                 let timeout = Some(util::MAX_CONNECT_TIME);
                 let (sec, rest) = this.connect(&addr, auth, timeout).await?;
                 let a = sec.clone().try_with(&rest)?;
                 let r = multiaddr_to_route(&a)
                     .ok_or_else(|| ApiError::message(format!("invalid multiaddr: {a}")))?;
-                if let Some(alias) = &alias {
-                    RemoteForwarder::create_static(&ctx, r, alias).await?;
-                } else {
-                    RemoteForwarder::create(&ctx, r).await?;
+                let info = create_remote_forwarder(
+                    &ctx,
+                    r.clone(),
+                    alias.as_deref(),
+                    true,
+                    access_control.clone(),
+                )
+                .await?;
+                let created = format!("created at {}", info.forwarding_route());
+                match this.registry.forwarders.get_mut(info.remote_address()) {
+                    Some(f) => {
+                        f.events.push(format!("reconnected, {created}"));
+                        f.info = info;
+                        f.route = r;
+                    }
+                    None => {
+                        let old = match this.registry.forwarders.remove(&remote_address) {
+                            Some(old) => old,
+                            None => {
+                                ctx.stop_worker(info.worker_address().clone()).await?;
+                                return Err(ApiError::generic(
+                                    "forwarder deleted while reconnecting",
+                                ));
+                            }
+                        };
+                        let new_address = info.remote_address().to_string();
+                        let mut events = old.events;
+                        events.push(format!(
+                            "replacing forwarder {remote_address} after its connection was lost, {created}"
+                        ));
+                        if let Some(at) = old.expires_at {
+                            let ctx = ctx.as_ref().async_try_clone().await?;
+                            expire_forwarder(manager.clone(), ctx, new_address.clone(), at);
+                        }
+                        let f = ForwarderRegistryInfo {
+                            info,
+                            kind: ForwarderKind::of_alias(alias.as_deref()),
+                            session: old.session,
+                            balanced: None,
+                            route: r,
+                            heartbeats: true,
+                            access_control,
+                            expires_at: old.expires_at,
+                            events,
+                        };
+                        this.registry.forwarders.insert(new_address.clone(), f);
+                        data.put(REMOTE_ADDRESS, new_address);
+                    }
                 }
                 Ok(sec)
             };
-            match timeout(util::MAX_RECOVERY_TIME, f).await {
+            let err = match timeout(util::MAX_RECOVERY_TIME, f).await {
                 Err(_) => {
                     warn!(%addr, "timeout creating new remote forwarder");
-                    Err(ApiError::generic("timeout"))
+                    ApiError::generic("timeout")
                 }
                 Ok(Err(e)) => {
                     warn!(%addr, err = %e, "error creating new remote forwarder");
-                    Err(e)
+                    e
                 }
-                Ok(Ok(a)) => Ok(a),
+                Ok(Ok(a)) => return Ok(a),
+            };
+            let mut this = manager.write().await;
+            if let Some(f) = this.registry.forwarders.get_mut(&remote_address) {
+                f.events.push(format!("reconnecting failed: {err}"));
             }
+            Err(err)
         })
     })
 }
@@ -1,9 +1,12 @@
 use crate::nodes::models::policy::{Policy, PolicyList};
+use crate::{actions, resources};
 use either::Either;
 use minicbor::Decoder;
-use ockam_abac::{Action, PolicyStorage, Resource};
+use ockam_abac::expr::str;
+use ockam_abac::{Action, Env, PolicyAccessControl, PolicyStorage, Resource};
 use ockam_core::api::{Error, Request, Response, ResponseBuilder};
 use ockam_core::Result;
+use ockam_node::Context;
 
 use super::NodeManager;
 
@@ -62,4 +65,54 @@ impl NodeManager {
         self.policies.del_policy(&r, &a).await?;
         Ok(Response::ok(req.id()))
     }
+
+    /// Make the workers of the node only accept the messages satisfying the
+    /// policy, before their own access control.
+    ///
+    /// The policy is evaluated like those of the resources of the node, with
+    /// `resource.id` being `node`. It is forgotten when the node stops.
+    pub(super) fn set_default_policy(
+        &mut self,
+        ctx: &Context,
+        req: &Request<'_>,
+        dec: &mut Decoder<'_>,
+    ) -> Result<ResponseBuilder<()>> {
+        let p: Policy = dec.decode()?;
+        let mut env = Env::new();
+        env.put("resource.id", str(resources::NODE.as_str()));
+        env.put("action.id", str(actions::HANDLE_MESSAGE.as_str()));
+        let store = self.authenticated_storage.clone();
+        ctx.set_default_access_control(PolicyAccessControl::new(
+            p.expression().clone(),
+            store,
+            env,
+        ));
+        self.default_policy = Some(p.expression().clone());
+        Ok(Response::ok(req.id()))
+    }
+
+    pub(super) fn get_default_policy<'a>(
+        &self,
+        req: &'a Request<'_>,
+    ) -> Either<ResponseBuilder<Error<'a>>, ResponseBuilder<Policy>> {
+        if let Some(e) = &self.default_policy {
+            Either::Right(Response::ok(req.id()).body(Policy::new(e.clone())))
+        } else {
+            let mut err = Error::new(req.path()).with_message("no default policy");
+            if let Some(m) = req.method() {
+                err.set_method(m)
+            }
+            Either::Left(Response::not_found(req.id()).body(err))
+        }
+    }
+
+    pub(super) fn del_default_policy(
+        &mut self,
+        ctx: &Context,
+        req: &Request<'_>,
+    ) -> ResponseBuilder<()> {
+        ctx.clear_default_access_control();
+        self.default_policy = None;
+        Response::ok(req.id())
+    }
 }
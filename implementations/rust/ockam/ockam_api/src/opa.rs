@@ -0,0 +1,372 @@
+//! Authorization of messages by an [Open Policy Agent][opa] (OPA) server.
+//!
+//! [`OpaAccessControl`] asks OPA's data API whether a message is allowed,
+//! posting an input document describing the message to
+//! `<server>/v1/data/<query>` and allowing the message only when the result
+//! is `true`. Any other result, including an undefined one, an error or no
+//! answer within the timeout, denies the message.
+//!
+//! # Input document
+//!
+//! The input document is versioned and only changes in backwards compatible
+//! ways, i.e. by adding fields, as long as its `version` stays the same:
+//!
+//! ```json
+//! {
+//!   "input": {
+//!     "version": 1,
+//!     "onward_route": ["0#echo"],
+//!     "return_route": ["1#127.0.0.1:4000", "0#3c4d1e7a0c6d4e53"],
+//!     "subject": {
+//!       "identifier": "P6474cfdbf547240b6d716bff89c976810859bc3f47be8ea4acbb56e2d0f15a07",
+//!       "attributes": { "role": "member" }
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! - `onward_route` and `return_route` are the routes of the message, each
+//!   address written as `<transport type>#<address>`.
+//! - `subject` is `null` for messages which didn't arrive over a secure
+//!   channel. Otherwise it holds the identifier of the identity at the other
+//!   end of the channel and the attributes of the credential it presented.
+//!   `attributes` is empty when it presented none, and attributes whose
+//!   value isn't valid UTF-8 are left out.
+//!
+//! A policy allowing the members to reach the `echo` service could read:
+//!
+//! ```rego
+//! package ockam
+//!
+//! default allow := false
+//!
+//! allow {
+//!     input.subject.attributes.role == "member"
+//!     input.onward_route[0] == "0#echo"
+//! }
+//! ```
+//!
+//! [opa]: https://www.openpolicyagent.org
+
+use core::{fmt, str};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use ockam_core::compat::boxed::Box;
+use ockam_core::{async_trait, AccessControl, LocalMessage, Result, Route};
+use ockam_identity::authenticated_storage::AuthenticatedStorage;
+use ockam_identity::credential::AttributesStorageUtils;
+use ockam_identity::IdentitySecureChannelLocalInfo;
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+
+use crate::error::ApiError;
+
+/// The version of the input document sent to OPA.
+pub const INPUT_VERSION: u32 = 1;
+
+/// The timeout of an [`OpaAccessControl`] unless given another one.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Authorizes messages by querying an OPA server, see the [module
+/// documentation](self) for the document OPA gets.
+pub struct OpaAccessControl<S> {
+    endpoint: Url,
+    client: Client,
+    timeout: Duration,
+    attributes: S,
+}
+
+impl<S> OpaAccessControl<S> {
+    /// Create a new `OpaAccessControl` asking the OPA server at `server`,
+    /// e.g. `http://127.0.0.1:8181`, for the value of the document at
+    /// `query`, e.g. `ockam/allow`.
+    ///
+    /// Subject attributes are read from the given authenticated storage.
+    pub fn new(server: &str, query: &str, store: S) -> Result<Self> {
+        let query = query.trim_matches('/');
+        if query.is_empty() {
+            return Err(ApiError::generic("the OPA query must not be empty"));
+        }
+        let endpoint = format!("{}/v1/data/{query}", server.trim_end_matches('/'));
+        let endpoint = Url::parse(&endpoint)
+            .map_err(|e| ApiError::message(format!("invalid OPA server {server}: {e}")))?;
+        if !matches!(endpoint.scheme(), "http" | "https") {
+            return Err(ApiError::message(format!(
+                "invalid OPA server {server}: expected an http or https URL"
+            )));
+        }
+        Ok(OpaAccessControl {
+            endpoint,
+            client: Client::new(),
+            timeout: DEFAULT_TIMEOUT,
+            attributes: store,
+        })
+    }
+
+    /// Deny the messages OPA hasn't answered for within `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The URL the queries are posted to.
+    pub fn endpoint(&self) -> &Url {
+        &self.endpoint
+    }
+}
+
+impl<S> fmt::Debug for OpaAccessControl<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OpaAccessControl")
+            .field("endpoint", &self.endpoint.as_str())
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Query<'a> {
+    input: &'a Input<'a>,
+}
+
+#[derive(Debug, Serialize)]
+struct Input<'a> {
+    version: u32,
+    onward_route: Vec<String>,
+    return_route: Vec<String>,
+    subject: Option<Subject<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct Subject<'a> {
+    identifier: String,
+    attributes: BTreeMap<String, &'a str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Decision {
+    result: Option<serde_json::Value>,
+}
+
+fn route(r: &Route) -> Vec<String> {
+    r.iter().map(|a| a.to_string()).collect()
+}
+
+impl<S> OpaAccessControl<S> {
+    async fn query(&self, input: &Input<'_>) -> Result<bool> {
+        let res = self
+            .client
+            .post(self.endpoint.clone())
+            .timeout(self.timeout)
+            .json(&Query { input })
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(ApiError::wrap)?;
+        let decision: Decision = res.json().await.map_err(ApiError::wrap)?;
+        match decision.result {
+            Some(serde_json::Value::Bool(b)) => Ok(b),
+            Some(x) => {
+                warn!(endpoint = %self.endpoint, result = %x, "OPA result is not a boolean");
+                Ok(false)
+            }
+            None => {
+                debug!(endpoint = %self.endpoint, "OPA result is undefined");
+                Ok(false)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<S> AccessControl for OpaAccessControl<S>
+where
+    S: AuthenticatedStorage,
+{
+    async fn is_authorized(&self, msg: &LocalMessage) -> Result<bool> {
+        let attrs;
+        let subject = match IdentitySecureChannelLocalInfo::find_info(msg) {
+            Ok(info) => {
+                let id = info.their_identity_id();
+                attrs = AttributesStorageUtils::get_attributes(id, &self.attributes)
+                    .await?
+                    .unwrap_or_default();
+                let mut attributes = BTreeMap::new();
+                for (k, v) in &attrs {
+                    match str::from_utf8(v) {
+                        Ok(s) => {
+                            attributes.insert(k.clone(), s);
+                        }
+                        Err(e) => {
+                            warn!(%id, err = %e, key = %k, "failed to interpret attribute as string")
+                        }
+                    }
+                }
+                Some(Subject {
+                    identifier: id.to_string(),
+                    attributes,
+                })
+            }
+            Err(_) => None,
+        };
+        let transport = msg.transport();
+        let input = Input {
+            version: INPUT_VERSION,
+            onward_route: route(&transport.onward_route),
+            return_route: route(&transport.return_route),
+            subject,
+        };
+        match self.query(&input).await {
+            Ok(b) => {
+                debug!(endpoint = %self.endpoint, is_authorized = %b, "OPA queried");
+                Ok(b)
+            }
+            Err(e) => {
+                warn!(endpoint = %self.endpoint, err = %e, "OPA query failed, denying");
+                Ok(false)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::thread;
+
+    use ockam_core::{route, LocalMessage, TransportMessage};
+    use ockam_identity::authenticated_storage::mem::InMemoryStorage;
+    use ockam_identity::credential::{Attributes, AttributesEntry, Timestamp};
+    use ockam_identity::{IdentityIdentifier, IdentitySecureChannelLocalInfo, IdentityStateConst};
+    use ockam_node::Context;
+
+    use super::*;
+
+    /// Start an OPA stand-in answering one query with `body` after
+    /// `delay`, and return its address and the query it received.
+    fn opa(body: &'static str, delay: Duration) -> (String, mpsc::Receiver<serde_json::Value>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut len = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(v) = line.to_lowercase().strip_prefix("content-length:") {
+                    len = v.trim().parse().unwrap();
+                }
+            }
+            let mut query = vec![0; len];
+            reader.read_exact(&mut query).unwrap();
+            tx.send(serde_json::from_slice(&query).unwrap()).unwrap();
+            thread::sleep(delay);
+            let res = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            let _ = reader.get_mut().write_all(res.as_bytes());
+        });
+        (addr, rx)
+    }
+
+    fn message(subject: Option<&str>) -> LocalMessage {
+        let transport = TransportMessage::v1(route!["echo"], route!["app"], Vec::new());
+        let local_info = match subject {
+            Some(id) => IdentitySecureChannelLocalInfo::mark(
+                Vec::new(),
+                IdentityIdentifier::from_key_id(id),
+            )
+            .unwrap(),
+            None => Vec::new(),
+        };
+        LocalMessage::new(transport, local_info)
+    }
+
+    #[test]
+    fn endpoint() {
+        let ac = OpaAccessControl::new("http://127.0.0.1:8181", "/ockam/allow", ()).unwrap();
+        assert_eq!(
+            ac.endpoint().as_str(),
+            "http://127.0.0.1:8181/v1/data/ockam/allow"
+        );
+        let ac = OpaAccessControl::new("https://opa.example.com/api/", "ockam/allow", ()).unwrap();
+        assert_eq!(
+            ac.endpoint().as_str(),
+            "https://opa.example.com/api/v1/data/ockam/allow"
+        );
+        assert!(OpaAccessControl::new("127.0.0.1:8181", "ockam/allow", ()).is_err());
+        assert!(OpaAccessControl::new("localhost:8181", "ockam/allow", ()).is_err());
+        assert!(OpaAccessControl::new("http://127.0.0.1:8181", "/", ()).is_err());
+    }
+
+    #[ockam_macros::test]
+    async fn allow_and_input_document(ctx: &mut Context) -> Result<()> {
+        let store = InMemoryStorage::new();
+        let mut attrs = Attributes::new();
+        attrs.put("role", b"member");
+        let expires = Timestamp::from(u64::from(Timestamp::now().unwrap()) + 60);
+        let entry = minicbor::to_vec(AttributesEntry::new(attrs, expires))?;
+        store
+            .set(
+                "Pabcd",
+                IdentityStateConst::ATTRIBUTES_KEY.to_string(),
+                entry,
+            )
+            .await?;
+        let (addr, query) = opa(r#"{"result": true}"#, Duration::ZERO);
+        let ac = OpaAccessControl::new(&addr, "ockam/allow", store)?;
+        assert!(ac.is_authorized(&message(Some("abcd"))).await?);
+        assert_eq!(
+            query.recv().unwrap(),
+            serde_json::json!({
+                "input": {
+                    "version": 1,
+                    "onward_route": ["0#echo"],
+                    "return_route": ["0#app"],
+                    "subject": {
+                        "identifier": "Pabcd",
+                        "attributes": { "role": "member" }
+                    }
+                }
+            })
+        );
+        ctx.stop().await
+    }
+
+    #[ockam_macros::test]
+    async fn deny_unless_true(ctx: &mut Context) -> Result<()> {
+        for body in [
+            r#"{"result": false}"#,
+            r#"{"result": "yes"}"#,
+            r#"{}"#,
+            r#"not json"#,
+        ] {
+            let (addr, query) = opa(body, Duration::ZERO);
+            let ac = OpaAccessControl::new(&addr, "ockam/allow", InMemoryStorage::new())?;
+            assert!(!ac.is_authorized(&message(None)).await?, "{body}");
+            assert_eq!(
+                query.recv().unwrap()["input"]["subject"],
+                serde_json::Value::Null
+            );
+        }
+        ctx.stop().await
+    }
+
+    #[ockam_macros::test]
+    async fn deny_on_timeout(ctx: &mut Context) -> Result<()> {
+        let (addr, _query) = opa(r#"{"result": true}"#, Duration::from_secs(2));
+        let ac = OpaAccessControl::new(&addr, "ockam/allow", InMemoryStorage::new())?
+            .with_timeout(Duration::from_millis(100));
+        assert!(!ac.is_authorized(&message(None)).await?);
+        ctx.stop().await
+    }
+}
@@ -12,10 +12,10 @@ use ockam_node::tokio::sync::mpsc;
 use ockam_node::tokio::task::JoinSet;
 use ockam_node::tokio::time::{timeout, Duration};
 use ockam_node::Context;
-use sessions::{Key, Ping, Status};
+use sessions::{Ping, Status};
 use tracing as log;
 
-pub use sessions::{Data, Replacer, Session, Sessions};
+pub use sessions::{Data, Key, Replacer, Session, Sessions};
 
 const MAX_FAILURES: usize = 3;
 const DELAY: Duration = Duration::from_secs(3);
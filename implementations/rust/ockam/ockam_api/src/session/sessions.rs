@@ -67,6 +67,15 @@ impl Sessions {
         k
     }
 
+    pub fn remove(&mut self, k: &Key) -> Option<Session> {
+        log::debug! {
+            target: "ockam_api::session",
+            key = %k,
+            "session removed"
+        }
+        self.map.remove(k)
+    }
+
     #[allow(unused)]
     pub fn session(&self, k: &Key) -> Option<&Session> {
         self.map.get(k)
@@ -6,7 +6,7 @@ use ockam::{Address, Error, TCP};
 use ockam_core::{Route, LOCAL};
 use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp};
 use ockam_multiaddr::{MultiAddr, Protocol};
-use std::net::{SocketAddrV4, SocketAddrV6};
+use std::net::{IpAddr, SocketAddrV4, SocketAddrV6};
 
 /// Go through a multiaddr and remove all instances of
 /// `/node/<whatever>` out of it and replaces it with a fully
@@ -185,6 +185,19 @@ pub fn addr_to_multiaddr<T: Into<Address>>(a: T) -> Option<MultiAddr> {
 
 /// Tells whether the input MultiAddr references a local node or a remote node.
 ///
+/// Only the first protocol of the address is looked at. The address is local
+/// if it starts with:
+///
+/// - `/node`,
+/// - `/dnsaddr/localhost`, in any case, or a `/dnsaddr` holding a loopback IP,
+/// - `/ip4` with a loopback address, i.e. in `127.0.0.0/8`,
+/// - `/ip6` with a loopback address, i.e. `::1` or the IPv4-mapped
+///   `::ffff:127.0.0.0/104`.
+///
+/// It is remote if it starts with `/project`, or with any other host.
+/// Whatever follows the host, e.g. its `/tcp` port, does not matter.
+/// Addresses starting with another protocol are an error.
+///
 /// This should be called before cleaning the MultiAddr.
 pub fn is_local_node(ma: &MultiAddr) -> anyhow::Result<bool> {
     let at_rust_node;
@@ -198,25 +211,28 @@ pub fn is_local_node(ma: &MultiAddr) -> anyhow::Result<bool> {
             Node::CODE => {
                 at_rust_node = true;
             }
-            // A "/dnsaddr" will be local if it is "localhost"
+            // A "/dnsaddr" will be local if it is "localhost" or a loopback IP
             DnsAddr::CODE => {
                 at_rust_node = p
                     .cast::<DnsAddr>()
-                    .map(|dnsaddr| (*dnsaddr).eq("localhost"))
+                    .map(|dnsaddr| {
+                        dnsaddr.eq_ignore_ascii_case("localhost")
+                            || IpAddr::from_str(&dnsaddr).map_or(false, is_loopback)
+                    })
                     .ok_or_else(|| anyhow!("Invalid \"dnsaddr\" value"))?;
             }
             // A "/ip4" will be local if it matches the loopback address
             Ip4::CODE => {
                 at_rust_node = p
                     .cast::<Ip4>()
-                    .map(|ip4| ip4.is_loopback())
+                    .map(|ip4| is_loopback(IpAddr::V4(*ip4)))
                     .ok_or_else(|| anyhow!("Invalid \"ip4\" value"))?;
             }
             // A "/ip6" will be local if it matches the loopback address
             Ip6::CODE => {
                 at_rust_node = p
                     .cast::<Ip6>()
-                    .map(|ip6| ip6.is_loopback())
+                    .map(|ip6| is_loopback(IpAddr::V6(*ip6)))
                     .ok_or_else(|| anyhow!("Invalid \"ip6\" value"))?;
             }
             // A MultiAddr starting with "/service" could reference both local and remote nodes.
@@ -230,6 +246,40 @@ pub fn is_local_node(ma: &MultiAddr) -> anyhow::Result<bool> {
     }
 }
 
+/// Whether `ip` is a loopback address, including IPv4 loopback addresses
+/// mapped to IPv6.
+fn is_loopback(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip4) => ip4.is_loopback(),
+        IpAddr::V6(ip6) => {
+            ip6.is_loopback() || ip6.to_ipv4_mapped().map_or(false, |ip4| ip4.is_loopback())
+        }
+    }
+}
+
+#[test]
+fn local_node_addresses() {
+    let local = |s: &str| is_local_node(&s.parse().unwrap()).unwrap();
+
+    assert!(local("/ip4/127.0.0.1/tcp/4000"));
+    assert!(local("/ip4/127.1.2.3/tcp/4000"));
+    assert!(local("/ip6/::1/tcp/4000"));
+    assert!(local("/ip6/::ffff:127.0.0.1/tcp/4000"));
+    assert!(local("/dnsaddr/localhost/tcp/4000"));
+    assert!(local("/dnsaddr/LocalHost/tcp/4000"));
+    assert!(local("/dnsaddr/127.0.0.1/tcp/4000"));
+    assert!(local("/node/n1"));
+
+    assert!(!local("/ip4/10.0.0.1/tcp/4000"));
+    assert!(!local("/ip4/192.168.1.1/tcp/4000"));
+    assert!(!local("/ip6/2001:db8::1/tcp/4000"));
+    assert!(!local("/ip6/::ffff:10.0.0.1/tcp/4000"));
+    assert!(!local("/dnsaddr/relay.example.com/tcp/4000"));
+    assert!(!local("/project/p1"));
+
+    assert!(is_local_node(&"/service/api".parse().unwrap()).is_err());
+}
+
 #[test]
 fn clean_multiaddr_simple() {
     use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
@@ -18,6 +18,7 @@ const STATUS: &[Status] = &[
     Status::MethodNotAllowed,
     Status::InternalServerError,
     Status::NotImplemented,
+    Status::ServiceUnavailable,
 ];
 
 #[derive(Debug, Clone)]
@@ -4,7 +4,7 @@ use crate::{
 };
 use ockam_core::compat::{rand::random, vec::Vec};
 use ockam_core::{Address, Result, Route};
-use ockam_node::Context;
+use ockam_node::{Context, WorkerBuilder};
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info};
 
@@ -64,7 +64,9 @@ impl SecureChannel {
         let address = address.into();
         let channel_listener = SecureChannelListener::new(new_key_exchanger, vault);
         info!("Starting SecureChannel listener at {}", &address);
-        ctx.start_worker(address, channel_listener).await?;
+        WorkerBuilder::without_access_control(address, channel_listener)
+            .start(ctx)
+            .await?;
 
         Ok(())
     }
@@ -117,7 +119,9 @@ impl SecureChannel {
         .await?;
 
         let mut child_ctx = ctx.new_detached(callback_address).await?;
-        ctx.start_worker(address_remote.clone(), decryptor).await?;
+        WorkerBuilder::without_access_control(address_remote.clone(), decryptor)
+            .start(ctx)
+            .await?;
 
         let resp = child_ctx
             .receive_timeout::<KeyExchangeCompleted>(120)
@@ -7,7 +7,7 @@ use ockam_core::{async_trait, route};
 use ockam_core::{
     Address, Any, Decodable, LocalMessage, Result, Route, Routed, TransportMessage, Worker,
 };
-use ockam_node::Context;
+use ockam_node::{Context, WorkerBuilder};
 use tracing::{debug, info};
 
 struct DecryptorReadyState {
@@ -189,7 +189,9 @@ impl<V: SecureChannelVault, K: SecureChannelKeyExchanger> SecureChannelDecryptor
             self.remote_route.clone(),
             self.vault.async_try_clone().await?,
         );
-        ctx.start_worker(address_local.clone(), encryptor).await?;
+        WorkerBuilder::without_access_control(address_local.clone(), encryptor)
+            .start(ctx)
+            .await?;
 
         info!(
             "Started SecureChannel {} at local: {}, remote: {}",
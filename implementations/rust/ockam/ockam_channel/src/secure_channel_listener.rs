@@ -5,7 +5,7 @@ use ockam_core::compat::{boxed::Box, vec::Vec};
 use ockam_core::{
     Address, Encodable, LocalMessage, Message, Result, Routed, TransportMessage, Worker,
 };
-use ockam_node::Context;
+use ockam_node::{Context, WorkerBuilder};
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
@@ -80,7 +80,8 @@ impl<V: SecureChannelVault, N: SecureChannelNewKeyExchanger> Worker
         let vault = self.vault.async_try_clone().await?;
         let decryptor = SecureChannelDecryptor::new_responder(key_exchanger, None, vault).await?;
 
-        ctx.start_worker(vec![address_remote.clone()], decryptor)
+        WorkerBuilder::without_access_control(vec![address_remote.clone()], decryptor)
+            .start(ctx)
             .await?;
 
         // We want this message's return route lead to the remote channel worker, not listener
@@ -1,5 +1,6 @@
 use std::fmt::{Debug, Display, Formatter};
 
+use crate::forwarder::ForwarderError;
 use crate::util::ConfigError;
 use crate::{exitcode, ExitCode};
 
@@ -45,6 +46,19 @@ impl From<ConfigError> for Error {
     }
 }
 
+impl From<ForwarderError> for Error {
+    fn from(e: ForwarderError) -> Self {
+        let code = match e {
+            ForwarderError::UnknownNode(_) | ForwarderError::InvalidArgument(_) => exitcode::USAGE,
+            ForwarderError::NotFound { .. } => exitcode::NOUSER,
+            ForwarderError::Rpc(_) => exitcode::UNAVAILABLE,
+            ForwarderError::Timeout(_) | ForwarderError::Unavailable(_) => exitcode::TEMPFAIL,
+            ForwarderError::Unsupported(_) => exitcode::PROTOCOL,
+        };
+        Error::new(code, e.into())
+    }
+}
+
 impl From<ockam::Error> for Error {
     fn from(e: ockam::Error) -> Self {
         Error::new(exitcode::SOFTWARE, e.into())
@@ -1,101 +1,1303 @@
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
 use anyhow::{anyhow, Context as _};
 use clap::Args;
 use ockam::identity::IdentityIdentifier;
-use ockam_multiaddr::proto::Project;
+use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Tcp};
 use rand::prelude::random;
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tracing::{debug, debug_span, field, Instrument, Span};
 
 use ockam::{Context, TcpTransport};
-use ockam_api::is_local_node;
-use ockam_api::nodes::models::forwarder::{CreateForwarder, ForwarderInfo};
-use ockam_core::api::Request;
-use ockam_multiaddr::{proto::Node, MultiAddr, Protocol};
+use ockam_abac::Expr;
+use ockam_api::nodes::models::forwarder::{is_valid_remote_address, ForwarderInfo, ForwarderKind};
+use ockam_api::{is_local_node, DefaultAddress};
+use ockam_core::api::{Id, Request};
+use ockam_multiaddr::{Match, MultiAddr, Protocol};
 
-use crate::forwarder::HELP_DETAIL;
+use crate::forwarder::jsonl::{ForwarderSpec, SpecResult};
+use crate::forwarder::metrics::Metrics;
+use crate::forwarder::plan::{ForwarderPlan, PlannedUpstream};
+use crate::forwarder::selector::LabelSelector;
+use crate::forwarder::template::{AliasTemplate, FormatTemplate, Tag};
+use crate::forwarder::util::{
+    check_available, check_conflict, delete_forwarder, find_forwarder, first_reachable,
+    forwarder_alias, forwarder_name, forwarder_rpc, list_forwarders_if_supported, node_version,
+    resolve_dns, resolve_nodes, tcp_transport, with_retries, ApiNode, IpFamily, FORWARD_TO_PREFIX,
+    RESERVED_NAMES,
+};
+use crate::forwarder::{help_detail, ApiOpts, ForwarderError};
+use crate::tcp::inlet::InletConfig;
 use crate::util::output::Output;
-use crate::util::{extract_address_value, node_rpc, RpcBuilder};
+use crate::util::{comma_separated, node_rpc, node_rpc_with_context, parse_duration};
 use crate::Result;
-use crate::{help, CommandGlobalOpts};
+use crate::{CommandGlobalOpts, OutputFormat};
+
+const HELP_DETAIL: &str = "\
+Examples:
+
+```sh
+    # Create a forwarder with a random name and keep the name for later commands
+    $ NAME=$(ockam forwarder create --at /node/green --to /node/blue --print-name)
+    $ ockam forwarder ping $NAME --to /node/blue
+
+    # Create a forwarder which is deleted after 30 minutes
+    $ ockam forwarder create tmp --at /node/green --to /node/blue --expires-in 30m
+    /service/forward_to_tmp
+
+    # Move the forwarder called blue to another relay
+    $ ockam forwarder create blue --at /node/yellow --to /node/blue --on-conflict replace
+    /service/forward_to_blue
+
+    # Create the forwarder only if blue doesn't have one called blue yet
+    $ ockam forwarder create blue --at /node/green --to /node/blue --on-conflict skip
+
+    # Balance messages across two relays, sending twice as many to the first
+    $ ockam forwarder create blue --at /node/relay1 --weight 2 --at /node/relay2 --weight 1 --to /node/blue
+
+    # Only forward the messages of identities with the web component attribute
+    $ ockam forwarder create blue --at /node/green --to /node/blue --access-policy '(= subject.component \"web\")'
+
+    # Create the forwarder with node blue, or with node blue2 if blue is down
+    $ ockam forwarder create blue --at /node/green --to blue,blue2
+
+    # Create the forwarder with every node tagged env=prod and region=us
+    $ ockam node create blue --tag env=prod --tag region=us
+    $ ockam forwarder create edge --at /node/green --label-selector env=prod,region=us
+    blue: /service/forward_to_edge
+
+    # Create a forwarder registered under an alias of your own
+    $ ockam forwarder create blue --at /node/green --to /node/blue --alias-template '{env}-{name}' --tag env=prod
+    /service/prod-blue
+
+    # Relay the messages for every service green doesn't know to blue
+    $ ockam forwarder create --at /node/green --to /node/blue --wildcard
+    $ ockam message send hello --to /node/green/service/uppercase
+```
+";
 
 /// Create Forwarders
 #[derive(Clone, Debug, Args)]
 #[command(
     arg_required_else_help = true,
-    after_long_help = help::template(HELP_DETAIL)
+    after_long_help = help_detail(HELP_DETAIL)
 )]
 pub struct CreateCommand {
-    /// Name of the forwarder (optional)
-    #[arg(hide_default_value = true, default_value_t = hex::encode(&random::<[u8;4]>()))]
-    forwarder_name: String,
+    /// Name of the forwarder (optional, random by default, or derived from
+    /// the --idempotency-key)
+    #[arg(value_parser = service_name)]
+    forwarder_name: Option<String>,
+
+    /// Node for which to create the forwarder, or a route to it from a node,
+    /// e.g. /node/hub/service/forward_to_edge. Several, separated by commas,
+    /// are tried in turn until one responds
+    #[arg(
+        long,
+        id = "NODE",
+        display_order = 900,
+        env = "OCKAM_FORWARDER_TO",
+        required_unless_present = "label_selector"
+    )]
+    to: Option<String>,
+
+    #[command(flatten)]
+    api: ApiOpts,
 
-    /// Node for which to create the forwarder
-    #[arg(long, id = "NODE", display_order = 900)]
-    to: String,
+    /// Route to the node at which to create the forwarder (optional),
+    /// `@<ALIAS>` for a route saved with --save-as, or `forwarder:<NAME>` to
+    /// create it behind an existing forwarder. `<IP>:<PORT>` and
+    /// `[<IPv6>]:<PORT>` stand for a /tcp route to that address. A leading
+    /// `/secure/<IDENTITY>` hop reaches the node over a secure channel to
+    /// that identity. Repeat it to balance messages across several rust nodes
+    #[arg(
+        long,
+        id = "ROUTE",
+        display_order = 900,
+        required_unless_present = "from_stdin_jsonl",
+        env = "OCKAM_FORWARDER_AT"
+    )]
+    at: Vec<At>,
 
-    /// Route to the node at which to create the forwarder (optional)
-    #[arg(long, id = "ROUTE", display_order = 900)]
-    at: MultiAddr,
+    /// Share of the messages going to each --at node, given once per --at
+    /// in the same order (optional, 1 by default)
+    #[arg(long, value_name = "WEIGHT", display_order = 900, value_parser = clap::value_parser!(u32).range(1..))]
+    weight: Vec<u32>,
 
     /// Authorized identity for secure channel connection (optional)
     #[arg(long, id = "AUTHORIZED", display_order = 900)]
     authorized: Option<IdentityIdentifier>,
+
+    /// Id of the request sent to the node, logged by both the command and
+    /// the node to correlate them (optional, random by default)
+    #[arg(long, id = "REQUEST_ID", display_order = 900, hide_default_value = true, default_value_t = Id::fresh())]
+    request_id: Id,
+
+    /// Key under which the --to node remembers the creation for 10 minutes,
+    /// so that running the command again with it returns the same forwarder
+    /// instead of creating another one. Reusing it with other options fails
+    /// (optional)
+    #[arg(long, value_name = "KEY", display_order = 900)]
+    idempotency_key: Option<String>,
+
+    /// Wait up to this many seconds for the node to accept connections,
+    /// e.g. right after starting it (optional, no wait by default)
+    #[arg(long, value_name = "SECONDS", display_order = 900, default_value_t = 0)]
+    node_startup_wait: u64,
+
+    /// How many times to send the request again when the node is temporarily
+    /// unavailable (optional)
+    #[arg(long, value_name = "COUNT", display_order = 900, default_value_t = 3)]
+    retries: usize,
+
+    /// Print only the name of the forwarder, e.g. to capture a random
+    /// name in a script (optional)
+    #[arg(long, display_order = 900, conflicts_with = "quiet")]
+    print_name: bool,
+
+    /// Print this line instead of the address of the forwarder, e.g.
+    /// '{name} -> {remote_address}'. The placeholders are {name},
+    /// {remote_address}, {address}, {worker_address}, {forwarding_route},
+    /// {kind}, {balancer_address} and {node} (optional)
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        display_order = 900,
+        conflicts_with = "print_name"
+    )]
+    format_template: Option<FormatTemplate>,
+
+    /// Register the forwarder under the alias made from this template, e.g.
+    /// '{env}-forward-{name}', instead of forward_to_<NAME> at nodes and
+    /// <NAME> at projects. {name} is the forwarder name, the other
+    /// placeholders are the keys of --tag (optional)
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        display_order = 900,
+        conflicts_with = "wildcard"
+    )]
+    alias_template: Option<AliasTemplate>,
+
+    /// Value of a placeholder of --alias-template, e.g. env=prod (optional)
+    #[arg(
+        long,
+        value_name = "KEY=VALUE",
+        display_order = 900,
+        requires = "alias_template"
+    )]
+    tag: Vec<Tag>,
+
+    /// Create the wildcard forwarder of the --at node, relaying the messages
+    /// for all its unknown services. A node has at most one, and projects
+    /// none (optional)
+    #[arg(long, display_order = 900, conflicts_with = "forwarder_name")]
+    wildcard: bool,
+
+    /// Delete the forwarder after this long, e.g. 30m or 2h. The expiration
+    /// is lost when the --to node restarts (optional)
+    #[arg(long, value_name = "DURATION", display_order = 900, value_parser = expires_in)]
+    expires_in: Option<Duration>,
+
+    /// Only forward the messages of the senders satisfying this policy
+    /// expression, e.g. (= subject.role "member"), checked against the
+    /// credential they presented to the --to node (optional)
+    #[arg(long, value_name = "EXPR", display_order = 900)]
+    access_policy: Option<Expr>,
+
+    /// What to do when the --to node already has a forwarder with this
+    /// name (optional)
+    #[arg(long, value_enum, value_name = "ACTION", display_order = 900, default_value_t = OnConflict::Error)]
+    on_conflict: OnConflict,
+
+    /// Succeed without printing anything when the --to node already has a
+    /// forwarder with this name, like --on-conflict skip (optional)
+    #[arg(long, display_order = 900, conflicts_with = "on_conflict")]
+    quiet_on_exists: bool,
+
+    /// Resolve the DNS names of --at to IPv4 addresses when they have some,
+    /// instead of letting the --to node resolve them (optional)
+    #[arg(long, display_order = 900, conflicts_with = "prefer_ipv6")]
+    prefer_ipv4: bool,
+
+    /// Resolve the DNS names of --at to IPv6 addresses when they have some,
+    /// instead of letting the --to node resolve them (optional)
+    #[arg(long, display_order = 900)]
+    prefer_ipv6: bool,
+
+    /// Enable TCP keepalive on the connection to the --at node, probing it
+    /// after this many idle seconds, also once the forwarder reconnects
+    /// (optional)
+    #[arg(long, value_name = "SECONDS", display_order = 900, value_parser = clap::value_parser!(u64).range(1..))]
+    keepalive: Option<u64>,
+
+    /// Fail when a project of --at is missing from the local cache instead
+    /// of sending the request, which never fetches projects from the
+    /// Orchestrator (optional)
+    #[arg(long, display_order = 900)]
+    no_refresh: bool,
+
+    /// Send the request without first checking that the --to node supports
+    /// all the options given (optional)
+    #[arg(long, display_order = 900)]
+    skip_version_check: bool,
+
+    /// Save the resolved --at route as @ALIAS once the forwarder is
+    /// created, for later --at @ALIAS. Only one --at can be saved (optional)
+    #[arg(long, value_name = "ALIAS", display_order = 900, value_parser = route_alias)]
+    save_as: Option<String>,
+
+    /// Overwrite the route already saved as the --save-as alias (optional)
+    #[arg(long, display_order = 900, requires = "save_as")]
+    force: bool,
+
+    /// Write the inlet reaching --outlet-address through the forwarder to
+    /// this file once it is created, for `tcp-inlet create --from-file`
+    /// (optional)
+    #[arg(
+        long,
+        value_name = "FILE",
+        display_order = 900,
+        conflicts_with = "wildcard"
+    )]
+    emit_inlet_config: Option<PathBuf>,
+
+    /// Address of the outlet of the --to node, for --emit-inlet-config
+    /// (optional)
+    #[arg(
+        long,
+        value_name = "ADDRESS",
+        display_order = 900,
+        default_value = "outlet",
+        requires = "emit_inlet_config"
+    )]
+    outlet_address: String,
+
+    /// Update the forwarder metrics of this Prometheus textfile once the
+    /// forwarder is created, keeping its other metrics. Failing to update it
+    /// is only a warning (optional)
+    #[arg(long, value_name = "PATH", display_order = 900)]
+    metrics_file: Option<PathBuf>,
+
+    /// Create a forwarder for each line of JSON read from standard input as
+    /// it arrives, e.g. {"name": "blue", "at": "/node/green"}, printing a
+    /// line of JSON with the result of each. The name and at of a line
+    /// replace the forwarder name and --at of the command (optional)
+    #[arg(
+        long,
+        display_order = 900,
+        conflicts_with_all = [
+            "forwarder_name",
+            "idempotency_key",
+            "save_as",
+            "emit_inlet_config",
+            "print_name",
+            "format_template"
+        ]
+    )]
+    from_stdin_jsonl: bool,
+
+    /// Stop at the first malformed line of --from-stdin-jsonl instead of
+    /// reporting and skipping it (optional)
+    #[arg(long, display_order = 900, requires = "from_stdin_jsonl")]
+    strict: bool,
+
+    /// Write what would be created, with --at resolved, to this file instead
+    /// of creating the forwarder, for `forwarder apply --plan` (optional)
+    #[arg(
+        long,
+        value_name = "FILE",
+        display_order = 900,
+        conflicts_with_all = [
+            "from_stdin_jsonl",
+            "save_as",
+            "emit_inlet_config",
+            "metrics_file"
+        ]
+    )]
+    plan_out: Option<PathBuf>,
+
+    /// Create the forwarder for every node created with `node create --tag`
+    /// whose tags match, e.g. env=prod,region=us, instead of for --to. Each
+    /// requirement is KEY=VALUE, KEY!=VALUE, KEY or !KEY. OCKAM_FORWARDER_TO
+    /// counts as --to, so it must be unset (optional)
+    #[arg(
+        long,
+        value_name = "SELECTOR",
+        display_order = 900,
+        conflicts_with_all = [
+            "NODE",
+            "from_stdin_jsonl",
+            "save_as",
+            "emit_inlet_config",
+            "plan_out",
+            "print_name",
+            "format_template"
+        ]
+    )]
+    label_selector: Option<LabelSelector>,
+}
+
+impl CreateCommand {
+    fn ip_family(&self) -> Option<IpFamily> {
+        match (self.prefer_ipv4, self.prefer_ipv6) {
+            (true, _) => Some(IpFamily::V4),
+            (_, true) => Some(IpFamily::V6),
+            _ => None,
+        }
+    }
+
+    /// The options given which older nodes would ignore, with the API
+    /// version of the node which introduced them.
+    fn api_requirements(&self) -> Vec<(&'static str, u32)> {
+        [
+            ("--expires-in", 1, self.expires_in.is_some()),
+            ("--wildcard", 1, self.wildcard),
+            ("several --at", 1, self.at.len() > 1),
+            ("--access-policy", 1, self.access_policy.is_some()),
+            ("--keepalive", 1, self.keepalive.is_some()),
+            ("--idempotency-key", 2, self.idempotency_key.is_some()),
+        ]
+        .into_iter()
+        .filter(|(_, _, given)| *given)
+        .map(|(option, api, _)| (option, api))
+        .collect()
+    }
+}
+
+/// Why `node`, of software and API `version` if known, does not support all
+/// the `requirements` of [`CreateCommand::api_requirements`], if it doesn't.
+fn unsupported(
+    node: &str,
+    version: Option<(&str, u32)>,
+    requirements: &[(&'static str, u32)],
+) -> Option<ForwarderError> {
+    let api = version.map_or(0, |(_, api)| api);
+    let (options, needed): (Vec<_>, Vec<_>) = requirements
+        .iter()
+        .filter(|(_, needed)| *needed > api)
+        .copied()
+        .unzip();
+    let needed = needed.into_iter().max()?;
+    let node = match version {
+        Some((version, api)) => format!("node {node} (version {version}, API version {api})"),
+        None => format!("node {node} (too old to report its version)"),
+    };
+    Some(ForwarderError::Unsupported(anyhow!(
+        "{node} doesn't support {}, which needs API version {needed}; upgrade the node, or use --skip-version-check to send the request anyway",
+        comma_separated(&options)
+    )))
+}
+
+/// The name of a forwarder created without one: random, unless an
+/// idempotency key is given, so that the requests made with that key are
+/// the same.
+fn default_name(idempotency_key: Option<&str>) -> String {
+    match idempotency_key {
+        Some(key) => hex::encode(&Sha256::digest(key.as_bytes())[..4]),
+        None => hex::encode(random::<[u8; 4]>()),
+    }
+}
+
+/// Forwarder names end up in `/service/<name>` addresses, so they must not
+/// contain anything that would break parsing those back.
+fn service_name(s: &str) -> anyhow::Result<String> {
+    if is_valid_remote_address(s) {
+        Ok(s.to_string())
+    } else {
+        Err(anyhow!("'{s}' can not be used in a /service address"))
+    }
+}
+
+/// Route aliases are referenced as `--at @<ALIAS>`.
+fn route_alias(s: &str) -> anyhow::Result<String> {
+    if !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        Ok(s.to_string())
+    } else {
+        Err(anyhow!(
+            "'{s}' is not a valid alias, use letters, digits, '_' and '-'"
+        ))
+    }
+}
+
+fn expires_in(s: &str) -> anyhow::Result<Duration> {
+    match parse_duration(s)? {
+        d if d.is_zero() => Err(anyhow!("the duration must be positive")),
+        d => Ok(d),
+    }
+}
+
+/// The alias made from `template` for the forwarder called `name`, which
+/// must be usable in a `/service` address and not clash with a node service.
+fn templated_alias(
+    template: &AliasTemplate,
+    name: &str,
+    tags: &[Tag],
+) -> std::result::Result<String, ForwarderError> {
+    let alias = template
+        .render(name, tags)
+        .map_err(ForwarderError::InvalidArgument)?;
+    if !is_valid_remote_address(&alias) {
+        return Err(ForwarderError::InvalidArgument(anyhow!(
+            "the alias '{alias}' made from --alias-template can not be used in a /service address"
+        )));
+    }
+    if RESERVED_NAMES.contains(&alias.as_str()) {
+        return Err(ForwarderError::InvalidArgument(anyhow!(
+            "the alias '{alias}' made from --alias-template is reserved for a node service"
+        )));
+    }
+    Ok(alias)
+}
+
+/// What `forwarder create` does about an existing forwarder with the name
+/// of the new one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum OnConflict {
+    /// Fail, leaving the existing forwarder
+    Error,
+    /// Delete the existing forwarder, then create the new one
+    Replace,
+    /// Leave the existing forwarder and succeed without creating anything
+    Skip,
+}
+
+/// Where to create a forwarder.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum At {
+    Route(MultiAddr),
+    /// An existing forwarder of the `--to` node, referenced by name.
+    Forwarder(String),
+    /// A route saved with `--save-as`, referenced by alias.
+    Alias(String),
+}
+
+impl FromStr for At {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if let Some(alias) = s.strip_prefix('@') {
+            return Ok(At::Alias(route_alias(alias)?));
+        }
+        match s.strip_prefix("forwarder:") {
+            Some("") => Err(anyhow!("missing forwarder name")),
+            Some(name) => Ok(At::Forwarder(name.to_string())),
+            None => match socket_shorthand(s) {
+                Some(ma) => Ok(At::Route(ma)),
+                None => Ok(At::Route(s.parse()?)),
+            },
+        }
+    }
+}
+
+/// The multiaddr of an `<IPv4>:<PORT>` or `[<IPv6>]:<PORT>` shorthand, e.g.
+/// `/ip6/::1/tcp/4000` for `[::1]:4000`.
+fn socket_shorthand(s: &str) -> Option<MultiAddr> {
+    let ma = match SocketAddr::from_str(s).ok()? {
+        SocketAddr::V4(addr) => format!("/ip4/{}/tcp/{}", addr.ip(), addr.port()),
+        SocketAddr::V6(addr) => format!("/ip6/{}/tcp/{}", addr.ip(), addr.port()),
+    };
+    ma.parse().ok()
 }
 
 impl CreateCommand {
     pub fn run(self, options: CommandGlobalOpts) {
-        node_rpc(rpc, (options, self));
+        node_rpc(rpc, (options, self, None));
+    }
+
+    pub(crate) async fn run_with_context(
+        self,
+        ctx: &Context,
+        options: CommandGlobalOpts,
+        tcp: Option<TcpTransport>,
+    ) -> Result<()> {
+        node_rpc_with_context(ctx, rpc, (options, self, tcp)).await
     }
 }
 
-async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, CreateCommand)) -> Result<()> {
-    let tcp = TcpTransport::create(&ctx).await?;
-    let api_node = extract_address_value(&cmd.to)?;
-    let at_rust_node = is_local_node(&cmd.at).context("Argument --at is not valid")?;
+async fn rpc(
+    ctx: Context,
+    (opts, cmd, tcp): (CommandGlobalOpts, CreateCommand, Option<TcpTransport>),
+) -> Result<()> {
+    let tcp = tcp_transport(&ctx, tcp).await?;
+    if cmd.from_stdin_jsonl {
+        create_from_stdin(&ctx, &opts, &tcp, &cmd).await
+    } else if let Some(selector) = &cmd.label_selector {
+        create_for_selected(&ctx, &opts, &tcp, &cmd, selector).await
+    } else {
+        create(&ctx, &opts, &tcp, &cmd).await.map(|_| ())
+    }
+}
 
-    let lookup = opts.config.lookup();
+/// Create a forwarder for each line of standard input, reading the next
+/// line only once the forwarder of the previous one is created, so that
+/// any number of them can be piped in.
+async fn create_from_stdin(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    cmd: &CreateCommand,
+) -> Result<()> {
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let (mut number, mut failed) = (0, 0);
+    loop {
+        let line = lines
+            .next_line()
+            .await
+            .context("failed to read standard input")
+            .map_err(ForwarderError::InvalidArgument)?;
+        let line = match line {
+            Some(line) => line,
+            None => break,
+        };
+        number += 1;
+        let spec = match ForwarderSpec::parse(number, &line) {
+            Ok(Some(spec)) => spec,
+            Ok(None) => continue,
+            Err(e) if cmd.strict => return Err(ForwarderError::InvalidArgument(e).into()),
+            Err(e) => {
+                SpecResult {
+                    line: number,
+                    name: None,
+                    remote_address: None,
+                    error: Some(format!("{e:#}")),
+                }
+                .print();
+                failed += 1;
+                continue;
+            }
+        };
+
+        let mut entry = cmd.clone();
+        entry.request_id = Id::fresh();
+        entry.forwarder_name = spec.name.clone();
+        if let Some(at) = &spec.at {
+            // Checked when parsing the spec
+            entry.at = vec![At::from_str(at).map_err(ForwarderError::InvalidArgument)?];
+        }
+        let result = if entry.at.is_empty() {
+            Err(ForwarderError::InvalidArgument(anyhow!(
+                "line {number} has no at, and --at is not given"
+            ))
+            .into())
+        } else {
+            create(ctx, opts, tcp, &entry).await
+        };
+        let result = match result {
+            Ok(remote_address) => SpecResult {
+                line: number,
+                name: spec.name,
+                remote_address,
+                error: None,
+            },
+            Err(e) => {
+                failed += 1;
+                SpecResult {
+                    line: number,
+                    name: spec.name,
+                    remote_address: None,
+                    error: Some(e.to_string()),
+                }
+            }
+        };
+        result.print();
+    }
+
+    if failed > 0 {
+        return Err(ForwarderError::Rpc(anyhow!(
+            "the forwarders of {failed} of the {number} lines of standard input could not be created"
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// Create the forwarder for each node matching `selector`, one after the
+/// other, printing a line with the result of each.
+async fn create_for_selected(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    cmd: &CreateCommand,
+    selector: &LabelSelector,
+) -> Result<()> {
+    let nodes = selector.select(opts);
+    if nodes.is_empty() {
+        eprintln!(
+            "Warning: no node matches the label selector {selector}, no forwarder was created"
+        );
+        return Ok(());
+    }
+    let mut failed = 0;
+    for node in &nodes {
+        let mut entry = cmd.clone();
+        entry.request_id = Id::fresh();
+        entry.to = Some(node.clone());
+        match create(ctx, opts, tcp, &entry).await {
+            Ok(Some(remote_address)) => println!("{node}: /service/{remote_address}"),
+            Ok(None) => println!("{node}: skipped, the forwarder exists"),
+            Err(e) => {
+                failed += 1;
+                eprintln!("{node}: {e}");
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(ForwarderError::Rpc(anyhow!(
+            "the forwarder could not be created for {failed} of the {} nodes matching {selector}",
+            nodes.len()
+        ))
+        .into());
+    }
+    Ok(())
+}
 
-    let mut ma = MultiAddr::default();
-
-    for proto in cmd.at.iter() {
-        match proto.code() {
-            Node::CODE => {
-                let alias = proto
-                    .cast::<Node>()
-                    .ok_or_else(|| anyhow!("invalid node address protocol"))?;
-                let addr = lookup
-                    .node_address(&alias)
-                    .ok_or_else(|| anyhow!("no address for node {}", &*alias))?;
-                ma.try_extend(&addr)?
+/// Create the forwarder described by `cmd`, returning its remote address,
+/// or nothing when it already exists and is left as it is.
+#[tracing::instrument(
+    name = "forwarder.create",
+    skip_all,
+    fields(forwarder = field::Empty, node = cmd.to.as_deref().unwrap_or_default(), id = %cmd.request_id)
+)]
+async fn create(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    cmd: &CreateCommand,
+) -> Result<Option<String>> {
+    let requested_name = match &cmd.forwarder_name {
+        Some(name) => name.clone(),
+        None => default_name(cmd.idempotency_key.as_deref()),
+    };
+    Span::current().record("forwarder", field::display(&requested_name));
+    let name = forwarder_name(&requested_name)?;
+    let templated_alias = match &cmd.alias_template {
+        Some(template) => Some(templated_alias(template, name, &cmd.tag)?),
+        None => None,
+    };
+    if let Some(alias) = &cmd.save_as {
+        if cmd.at.len() > 1 {
+            return Err(ForwarderError::InvalidArgument(anyhow!(
+                "--save-as can only be used with a single --at"
+            ))
+            .into());
+        }
+        if !cmd.force && opts.config.lookup().get_route(alias).is_some() {
+            return Err(ForwarderError::InvalidArgument(anyhow!(
+                "a route is already saved as @{alias}, use --force to overwrite it"
+            ))
+            .into());
+        }
+    }
+    if cmd.emit_inlet_config.is_some() && cmd.at.len() > 1 {
+        return Err(ForwarderError::InvalidArgument(anyhow!(
+            "--emit-inlet-config can only be used with a single --at"
+        ))
+        .into());
+    }
+
+    let wait = Duration::from_secs(cmd.node_startup_wait);
+    // Required unless --label-selector, which sets it for each matching node
+    let nodes = cmd.to.as_deref().unwrap_or_default();
+    let (api_node, to) = first_reachable(ctx, opts, tcp, nodes, wait, &cmd.api).await?;
+    if to != nodes {
+        eprintln!("Using node {to}, the first of {nodes} to respond");
+    }
+
+    let requirements = cmd.api_requirements();
+    if !cmd.skip_version_check && !requirements.is_empty() {
+        let version = node_version(ctx, opts, tcp, &api_node, &cmd.api).await?;
+        let version = version.as_ref().map(|(v, api)| (v.as_str(), *api));
+        if let Some(err) = unsupported(&api_node.name, version, &requirements) {
+            return Err(err.into());
+        }
+    }
+
+    if !cmd.weight.is_empty() && cmd.weight.len() != cmd.at.len() {
+        return Err(ForwarderError::InvalidArgument(anyhow!(
+            "--weight must be given once for each --at"
+        ))
+        .into());
+    }
+
+    let mut resolved = Vec::with_capacity(cmd.at.len());
+    let mut authorized = cmd.authorized.clone();
+    for at in &cmd.at {
+        let span = debug_span!(
+            "forwarder.resolve_at",
+            ?at,
+            peer.address = %api_node,
+            route = field::Empty
+        );
+        let (at, ma, at_rust_node, identity) = resolve_at(ctx, opts, tcp, &api_node, cmd, at)
+            .instrument(span.clone())
+            .await?;
+        if let Some(identity) = identity {
+            if authorized.as_ref().map_or(false, |a| a != &identity) {
+                return Err(ForwarderError::InvalidArgument(anyhow!(
+                    "the /secure identity {identity} of --at differs from the other authorized identity"
+                ))
+                .into());
             }
-            _ => ma.push_back_value(&proto)?,
+            authorized = Some(identity);
         }
+        // Equivalent routes make for the same forwarder
+        let ma = ma.canonicalize();
+        span.record("route", field::display(&ma));
+        resolved.push((at, ma, at_rust_node));
+    }
+    let balanced = resolved.len() > 1;
+    if balanced && (cmd.wildcard || resolved.iter().any(|(_, _, at_rust_node)| !at_rust_node)) {
+        return Err(ForwarderError::InvalidArgument(anyhow!(
+            "--at can only be repeated with node addresses, without --wildcard"
+        ))
+        .into());
+    }
+    let upstreams = if balanced {
+        let weights = cmd.weight.iter().copied().chain(std::iter::repeat(1));
+        resolved
+            .iter()
+            .zip(weights)
+            .map(|((_, ma, _), weight)| PlannedUpstream {
+                route: ma.clone(),
+                weight,
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let (at, ma, at_rust_node) = resolved.swap_remove(0);
+
+    if cmd.wildcard && !at_rust_node {
+        return Err(ForwarderError::InvalidArgument(anyhow!(
+            "--wildcard can only be used with node addresses"
+        ))
+        .into());
+    }
+
+    if name != requested_name && !cmd.wildcard {
+        eprintln!(
+            "Warning: the '{FORWARD_TO_PREFIX}' prefix is reserved and was removed from the forwarder name, using '{name}'"
+        );
     }
 
-    let req = {
-        let alias = if at_rust_node {
-            format!("forward_to_{}", cmd.forwarder_name)
+    let alias = match templated_alias {
+        Some(alias) => alias,
+        None => forwarder_alias(name, at_rust_node)?,
+    };
+    if RESERVED_NAMES.contains(&name) && !cmd.wildcard {
+        eprintln!(
+            "Warning: '{name}' is the address of a node service, the forwarder is registered as {alias}"
+        );
+    }
+    let on_conflict = if cmd.quiet_on_exists {
+        OnConflict::Skip
+    } else {
+        cmd.on_conflict
+    };
+    // Nodes predating the listing get the request without a check, as before
+    let existing = match list_forwarders_if_supported(ctx, opts, tcp, &api_node, &cmd.api).await? {
+        Some(existing) => existing,
+        None => {
+            debug!(node = %api_node, "node predates listing forwarders, not checking for conflicts");
+            Vec::new()
+        }
+    };
+    let existing_addresses = existing.iter().map(|f| f.remote_address.clone()).collect();
+    let conflict = existing.into_iter().find(|f| {
+        if cmd.wildcard {
+            f.kind == Some(ForwarderKind::Wildcard)
         } else {
-            cmd.forwarder_name.clone()
-        };
-        let body = if cmd.at.matches(0, &[Project::CODE.into()]) {
-            if cmd.authorized.is_some() {
-                return Err(anyhow!("--authorized can not be used with project addresses").into());
+            f.is_named(&alias)
+        }
+    });
+    let mut replaced = None;
+    if let Some(f) = conflict {
+        match on_conflict {
+            // The node tells whether the forwarder is the one it created
+            // with this key, or another one
+            OnConflict::Error if cmd.idempotency_key.is_some() => {
+                debug!(remote_address = %f.remote_address, node = %api_node, "forwarder already exists, sending the idempotency key");
+            }
+            OnConflict::Error => {
+                return Err(ForwarderError::InvalidArgument(anyhow!(
+                    "forwarder {} already exists, use --on-conflict replace or skip",
+                    f.remote_address
+                ))
+                .into())
+            }
+            OnConflict::Skip => {
+                debug!(%alias, node = %api_node, "forwarder already exists, not creating it");
+                return Ok(None);
+            }
+            // The plan deletes it when applied
+            OnConflict::Replace if cmd.plan_out.is_some() => replaced = Some(f.remote_address),
+            OnConflict::Replace => {
+                debug!(remote_address = %f.remote_address, node = %api_node, "deleting the forwarder to replace");
+                delete_forwarder(ctx, opts, tcp, &api_node, &cmd.api, &f.remote_address).await?;
+                replaced = Some(f.remote_address);
+            }
+        }
+    }
+
+    let saved_route = cmd.save_as.as_ref().map(|_| ma.clone());
+    let inlet_route = cmd.emit_inlet_config.as_ref().map(|_| ma.clone());
+    let inlet_authorized = authorized.clone();
+    let span = debug_span!(
+        "forwarder.rpc_request",
+        forwarder.name = %alias,
+        peer.address = %api_node,
+        rpc.method = "POST /node/forwarder",
+        route = %ma
+    );
+    let plan = ForwarderPlan {
+        to: to.to_string(),
+        alias,
+        at: ma,
+        at_project: at.matches(0, &[Project::CODE.into()]),
+        at_rust_node,
+        upstreams,
+        authorized,
+        wildcard: cmd.wildcard,
+        expires_in: cmd.expires_in.map(|d| d.as_secs()),
+        access_policy: cmd.access_policy.as_ref().map(|e| e.to_string()),
+        keepalive: cmd.keepalive,
+        idempotency_key: cmd.idempotency_key.clone(),
+        replaces: replaced.clone(),
+        existing: existing_addresses,
+    };
+    if let Some(path) = &cmd.plan_out {
+        // Fail now rather than when applying it
+        plan.body()?;
+        plan.write(path)?;
+        eprintln!(
+            "Planned forwarder {} at {} for node {to}, run `ockam forwarder apply --plan {}` to create it",
+            plan.alias,
+            plan.at,
+            path.display()
+        );
+        return Ok(None);
+    }
+    let result: Result<String> = async {
+        let body = plan.body()?;
+        debug!(id = %cmd.request_id, node = %api_node, addr = %body.address(), "sending CreateForwarder request");
+        let body = &body;
+
+        let remote_address = with_retries(cmd.retries, || async {
+            let req = Request::post("/node/forwarder")
+                .id(cmd.request_id)
+                .body(body.clone());
+            let mut rpc = forwarder_rpc(ctx, opts, tcp, &api_node, &cmd.api)?;
+            rpc.request(req).await.map_err(|e| {
+                debug!(re = %cmd.request_id, err = %e, "CreateForwarder request failed");
+                ForwarderError::from_rpc(e)
+            })?;
+            if let Ok((hdr, _)) = rpc.check_response() {
+                debug!(re = %hdr.re(), status = ?hdr.status(), "received CreateForwarder response");
             }
-            CreateForwarder::at_project(ma, Some(alias))
+            check_available(&rpc)?;
+            check_conflict(&rpc)?;
+            let info = rpc
+                .parse_response::<ForwarderInfo>()
+                .map_err(ForwarderError::Rpc)?;
+            let remote_address = info.remote_address().to_string();
+            if cmd.from_stdin_jsonl || cmd.label_selector.is_some() {
+                // Printed with the line of input, or the node
+            } else if cmd.print_name && cmd.wildcard {
+                // The wildcard forwarder has no name of its own
+                println!("{}", info.remote_address());
+            } else if cmd.print_name {
+                println!("{name}");
+            } else if let (Some(template), OutputFormat::Plain) =
+                (&cmd.format_template, &opts.global_args.output_format)
+            {
+                println!("{}", template.render(&info, to));
+            } else {
+                rpc.print_response(info).map_err(ForwarderError::Rpc)?;
+            }
+            Ok(remote_address)
+        })
+        .await?;
+        Ok(remote_address)
+    }
+    .instrument(span)
+    .await;
+    if let (Err(_), Some(replaced)) = (&result, replaced) {
+        eprintln!(
+            "The existing forwarder {replaced} was deleted, but the new one could not be created"
+        );
+    }
+    let remote_address = result?;
+    if let (Some(alias), Some(route)) = (&cmd.save_as, saved_route) {
+        opts.config.set_route_alias(alias, route);
+        if let Err(e) = opts.config.persist_config_updates() {
+            eprintln!("Warning: the forwarder was created, but its route could not be saved as @{alias}: {e:#}");
+        }
+    }
+    if let Some(path) = &cmd.metrics_file {
+        if let Err(e) = Metrics::record_create(path, &api_node.to_string()) {
+            eprintln!(
+                "Warning: the forwarder was created, but its metrics could not be updated: {e:#}"
+            );
+        }
+    }
+    if let (Some(path), Some(route)) = (&cmd.emit_inlet_config, inlet_route) {
+        emit_inlet_config(
+            path,
+            route,
+            &remote_address,
+            &cmd.outlet_address,
+            inlet_authorized,
+        )?;
+    }
+    Ok(Some(remote_address))
+}
+
+/// Write the inlet reaching the outlet at `outlet_address` of the `--to`
+/// node, through the forwarder at `remote_address` of `route`.
+fn emit_inlet_config(
+    path: &Path,
+    mut route: MultiAddr,
+    remote_address: &str,
+    outlet_address: &str,
+    authorized: Option<IdentityIdentifier>,
+) -> Result<()> {
+    route.push_back(Service::new(remote_address))?;
+    route.push_back(Service::new(outlet_address))?;
+    let config = InletConfig {
+        to: route,
+        from: None,
+        authorized,
+        check_credential: false,
+    };
+    let written = config.write(path);
+    if written.is_err() {
+        eprintln!(
+            "The forwarder {remote_address} was created, but its inlet config could not be written"
+        );
+    }
+    written
+}
+
+/// Resolve an `--at` into the route it designates, the same route with the
+/// node names replaced by their addresses, whether it leads to a rust node,
+/// and the identity of its inline secure channel, if any.
+async fn resolve_at(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    api_node: &ApiNode,
+    cmd: &CreateCommand,
+    at: &At,
+) -> Result<(MultiAddr, MultiAddr, bool, Option<IdentityIdentifier>)> {
+    let (at, at_rust_node, identity) = match at {
+        At::Route(at) => {
+            let (at, identity) = inline_secure_channel(at)?;
+            let at_rust_node = is_local_node(&at)
+                .context("Argument --at is not valid")
+                .map_err(ForwarderError::InvalidArgument)?;
+            (at, at_rust_node, identity)
+        }
+        // Chained forwarders are registered with the forwarding service of
+        // the `--to` node, reached through the relay of the existing one.
+        At::Alias(alias) => {
+            let route = opts
+                .config
+                .lookup()
+                .get_route(alias)
+                .cloned()
+                .ok_or_else(|| {
+                    ForwarderError::InvalidArgument(anyhow!(
+                        "no route is saved as @{alias}, see --save-as"
+                    ))
+                })?;
+            let at_rust_node = is_local_node(&route)
+                .with_context(|| format!("The route saved as @{alias} is not valid"))
+                .map_err(ForwarderError::InvalidArgument)?;
+            (route, at_rust_node, None)
+        }
+        At::Forwarder(name) => {
+            let forwarder = find_forwarder(ctx, opts, tcp, api_node, &cmd.api, name).await?;
+            let route = forwarder.route.ok_or_else(|| {
+                ForwarderError::InvalidArgument(anyhow!(
+                    "the route to forwarder {name} can not be expressed as a multiaddr"
+                ))
+            })?;
+            (route, true, None)
+        }
+    };
+
+    let mut ma = resolve_nodes(opts, &at)?;
+    check_secure_hops(&ma)?;
+    if cmd.no_refresh {
+        check_cached_projects(opts, &ma)?;
+    }
+    if let Some(family) = cmd.ip_family() {
+        ma = resolve_dns(&ma, family).await?;
+    }
+    Ok((at, ma, at_rust_node, identity))
+}
+
+/// The identity of a `/secure/<IDENTITY>` hop, as opposed to the
+/// `/secure/<LISTENER>` hops naming the secure channel listener of a node.
+fn secure_identity(value: &str) -> Option<IdentityIdentifier> {
+    let hex = value.strip_prefix('P')?;
+    if hex.len() == 64 && hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        IdentityIdentifier::from_str(value).ok()
+    } else {
+        None
+    }
+}
+
+/// Turn a route starting with `/secure/<IDENTITY>`, followed by a node or a
+/// host and a tcp port, into the route of a secure channel to the default
+/// listener of that node, returning the identity it must authenticate as.
+fn inline_secure_channel(
+    at: &MultiAddr,
+) -> std::result::Result<(MultiAddr, Option<IdentityIdentifier>), ForwarderError> {
+    let invalid = |e: ockam_multiaddr::Error| ForwarderError::InvalidArgument(e.into());
+    for (i, p) in at.iter().enumerate() {
+        let identity = match p.cast::<Secure>().and_then(|v| secure_identity(&v)) {
+            Some(identity) => identity,
+            None => continue,
+        };
+        let (_, rest) = at.split(1);
+        let host = Match::any([DnsAddr::CODE, Ip4::CODE, Ip6::CODE]);
+        let node = if rest.matches(0, &[Node::CODE.into()]) {
+            1
+        } else if rest.matches(0, &[host, Tcp::CODE.into()]) {
+            2
         } else {
-            CreateForwarder::at_node(ma, Some(alias), at_rust_node, cmd.authorized)
+            0
         };
-        Request::post("/node/forwarder").body(body)
-    };
+        if i != 0 || node == 0 {
+            return Err(ForwarderError::InvalidArgument(anyhow!(
+                "/secure/{identity} must start --at, followed by the node to connect to"
+            )));
+        }
+        let (node, rest) = rest.split(node);
+        let mut ma = node;
+        ma.push_back(Secure::new(DefaultAddress::SECURE_CHANNEL_LISTENER))
+            .map_err(invalid)?;
+        let ma = ma.try_with(rest.iter()).map_err(invalid)?;
+        return Ok((ma, Some(identity)));
+    }
+    Ok((at.clone(), None))
+}
 
-    let mut rpc = RpcBuilder::new(&ctx, &opts, &api_node).tcp(&tcp)?.build();
-    rpc.request(req).await?;
-    rpc.parse_and_print_response::<ForwarderInfo>()?;
+/// Check that the projects of a resolved route are in the local cache of
+/// projects, filled by `ockam project list`.
+fn check_cached_projects(
+    opts: &CommandGlobalOpts,
+    ma: &MultiAddr,
+) -> std::result::Result<(), ForwarderError> {
+    let lookup = opts.config.lookup();
+    for p in ma.iter().filter(|p| p.code() == Project::CODE) {
+        let name = p
+            .cast::<Project>()
+            .ok_or_else(|| ForwarderError::InvalidArgument(anyhow!("invalid project in --at")))?;
+        if lookup.get_project(&name).is_none() {
+            return Err(ForwarderError::InvalidArgument(anyhow!(
+                "project {} not in local cache; run `ockam project list` first",
+                &*name
+            )));
+        }
+    }
+    Ok(())
+}
 
+/// Check that the `/secure/<LISTENER>` hops of a resolved route are where
+/// the node creates secure channels: right after the host and tcp port the
+/// route starts with, or at the end of the route.
+fn check_secure_hops(ma: &MultiAddr) -> std::result::Result<(), ForwarderError> {
+    if ma.matches(0, &[Project::CODE.into()]) {
+        return Ok(());
+    }
+    let last = ma.iter().count().saturating_sub(1);
+    let host = Match::any([DnsAddr::CODE, Ip4::CODE, Ip6::CODE]);
+    let after_host = ma.matches(0, &[host, Tcp::CODE.into(), Secure::CODE.into()]);
+    for (i, p) in ma.iter().enumerate() {
+        if p.code() == Secure::CODE && i != last && !(i == 2 && after_host) {
+            return Err(ForwarderError::InvalidArgument(anyhow!(
+                "a /secure hop of --at must follow the host and /tcp port the route starts with, or end it"
+            )));
+        }
+    }
     Ok(())
 }
 
 impl Output for ForwarderInfo<'_> {
     fn output(&self) -> anyhow::Result<String> {
-        Ok(format!("/service/{}", self.remote_address()))
+        let mut output = format!("/service/{}", self.remote_address());
+        if let Some(balancer) = self.balancer_address() {
+            output.push_str(&format!(", balanced by /service/{balancer}"));
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exitcode;
+    use ockam_core::errcode::{Kind, Origin};
+
+    #[test]
+    fn parse_at() {
+        assert_eq!(
+            At::from_str("forwarder:relay").unwrap(),
+            At::Forwarder("relay".into())
+        );
+        assert_eq!(
+            At::from_str("/node/n1").unwrap(),
+            At::Route("/node/n1".parse().unwrap())
+        );
+        assert_eq!(At::from_str("@hub").unwrap(), At::Alias("hub".into()));
+        assert!(At::from_str("forwarder:").is_err());
+        assert!(At::from_str("@").is_err());
+        assert!(At::from_str("@hub/1").is_err());
+        assert!(At::from_str("relay").is_err());
+    }
+
+    #[test]
+    fn parse_at_socket_shorthand() {
+        let route = |s: &str| At::Route(s.parse().unwrap());
+        assert_eq!(
+            At::from_str("[::1]:4000").unwrap(),
+            route("/ip6/::1/tcp/4000")
+        );
+        assert_eq!(
+            At::from_str("[2001:db8::7]:443").unwrap(),
+            route("/ip6/2001:db8::7/tcp/443")
+        );
+        assert_eq!(
+            At::from_str("127.0.0.1:4000").unwrap(),
+            route("/ip4/127.0.0.1/tcp/4000")
+        );
+        // Multiaddrs are left as they are
+        for ma in ["/ip6/::1/tcp/4000", "/ip4/127.0.0.1/tcp/4000/service/api"] {
+            assert_eq!(At::from_str(ma).unwrap(), route(ma));
+        }
+        for s in ["[::1]", "::1:4000", "[::1]:port", "127.0.0.1:99999"] {
+            assert!(At::from_str(s).is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn inline_secure_channels() {
+        let id = format!("P{}", "ab".repeat(32));
+        let parse = |s: &str| MultiAddr::from_str(s).unwrap();
+
+        let (ma, identity) =
+            inline_secure_channel(&parse(&format!("/secure/{id}/ip4/10.0.0.1/tcp/4000"))).unwrap();
+        assert_eq!(ma, parse("/ip4/10.0.0.1/tcp/4000/secure/api"));
+        assert_eq!(identity, Some(IdentityIdentifier::from_str(&id).unwrap()));
+
+        let (ma, _) = inline_secure_channel(&parse(&format!(
+            "/secure/{id}/node/green/service/forward_to_x"
+        )))
+        .unwrap();
+        assert_eq!(ma, parse("/node/green/secure/api/service/forward_to_x"));
+
+        // Listener names are left alone
+        let at = parse("/ip4/10.0.0.1/tcp/4000/secure/api");
+        assert_eq!(inline_secure_channel(&at).unwrap(), (at, None));
+
+        for at in [
+            format!("/secure/{id}/service/x"),
+            format!("/secure/{id}"),
+            format!("/node/green/secure/{id}"),
+        ] {
+            assert!(inline_secure_channel(&parse(&at)).is_err(), "{at}");
+        }
+    }
+
+    #[test]
+    fn secure_hops() {
+        for (at, valid) in [
+            ("/ip4/10.0.0.1/tcp/4000/secure/api/service/x", true),
+            (
+                "/ip4/10.0.0.1/tcp/4000/service/forward_to_x/secure/api",
+                true,
+            ),
+            ("/ip4/10.0.0.1/tcp/4000", true),
+            (
+                "/ip4/10.0.0.1/tcp/4000/service/x/secure/api/service/y",
+                false,
+            ),
+            ("/secure/api/ip4/10.0.0.1/tcp/4000", false),
+        ] {
+            let ma = MultiAddr::from_str(at).unwrap();
+            assert_eq!(check_secure_hops(&ma).is_ok(), valid, "{at}");
+        }
+    }
+
+    #[test]
+    fn exit_codes_per_failure_class() {
+        let code = |e: ForwarderError| crate::Error::from(e).code();
+
+        assert_eq!(
+            code(ForwarderError::UnknownNode("n1".into())),
+            exitcode::USAGE
+        );
+        assert_eq!(
+            code(ForwarderError::InvalidArgument(anyhow!("bad"))),
+            exitcode::USAGE
+        );
+
+        let timeout = ockam_core::Error::new_without_cause(Origin::Node, Kind::Timeout);
+        let err = anyhow::Error::from(timeout).context("Failed to receive response from node");
+        assert_eq!(code(ForwarderError::from_rpc(err)), exitcode::TEMPFAIL);
+
+        let other = ockam_core::Error::new_without_cause(Origin::Node, Kind::Io);
+        assert_eq!(
+            code(ForwarderError::from_rpc(other.into())),
+            exitcode::UNAVAILABLE
+        );
+
+        assert_eq!(
+            code(ForwarderError::Unsupported(anyhow!("old"))),
+            exitcode::PROTOCOL
+        );
+    }
+
+    #[test]
+    fn default_names() {
+        assert_eq!(
+            default_name(Some("deploy-42")),
+            default_name(Some("deploy-42"))
+        );
+        assert_ne!(
+            default_name(Some("deploy-42")),
+            default_name(Some("deploy-43"))
+        );
+        assert_eq!(default_name(None).len(), 8);
+        assert!(is_valid_remote_address(&default_name(Some("deploy-42"))));
+    }
+
+    #[test]
+    fn api_versions() {
+        let requirements = [("--expires-in", 1), ("--keepalive", 2)];
+        let message = |version| unsupported("blue", version, &requirements).map(|e| e.to_string());
+
+        assert_eq!(message(Some(("0.20.0", 2))), None);
+        assert_eq!(
+            message(Some(("0.19.0", 1))).unwrap(),
+            "node blue (version 0.19.0, API version 1) doesn't support --keepalive, which needs API version 2; \
+             upgrade the node, or use --skip-version-check to send the request anyway"
+        );
+        assert_eq!(
+            message(None).unwrap(),
+            "node blue (too old to report its version) doesn't support --expires-in, --keepalive, which needs API version 2; \
+             upgrade the node, or use --skip-version-check to send the request anyway"
+        );
+        assert_eq!(unsupported("blue", None, &[]).map(|e| e.to_string()), None);
     }
 }
@@ -0,0 +1,145 @@
+use std::time::Duration;
+
+use anyhow::anyhow;
+use clap::Args;
+
+use ockam::{Context, TcpTransport};
+
+use crate::forwarder::util::{
+    delete_forwarder, drain_forwarder, find_forwarder, tcp_transport, ApiNode,
+};
+use crate::forwarder::{help_detail, ApiOpts, ForwarderError};
+use crate::util::{node_rpc, node_rpc_with_context};
+use crate::CommandGlobalOpts;
+use crate::Result;
+
+const HELP_DETAIL: &str = "\
+Examples:
+
+```sh
+    # Delete a forwarder, letting the messages in flight through it arrive first
+    $ ockam forwarder delete blue --to /node/blue --drain --timeout 60
+```
+";
+
+/// Delete a forwarder
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    after_long_help = help_detail(HELP_DETAIL)
+)]
+pub struct DeleteCommand {
+    /// Name or remote address of the forwarder
+    forwarder_name: String,
+
+    /// Node on which the forwarder was created
+    #[arg(long, id = "NODE", display_order = 900)]
+    to: String,
+
+    /// Let the messages in flight through the forwarder arrive before
+    /// deleting it, once a second went by without any (optional)
+    #[arg(long, display_order = 900)]
+    drain: bool,
+
+    /// How many seconds to wait for the forwarder to drain
+    #[arg(long, value_name = "SECONDS", display_order = 900, requires = "drain", default_value_t = 30, value_parser = clap::value_parser!(u64).range(1..))]
+    timeout: u64,
+
+    /// What to do when the forwarder is still forwarding messages after
+    /// --timeout seconds
+    #[arg(long, value_enum, value_name = "ACTION", display_order = 900, requires = "drain", default_value_t = OnTimeout::Force)]
+    on_timeout: OnTimeout,
+
+    #[command(flatten)]
+    api: ApiOpts,
+}
+
+/// What `forwarder delete --drain` does about a forwarder which doesn't
+/// drain in time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum OnTimeout {
+    /// Delete the forwarder anyway, with a warning
+    Force,
+    /// Keep the forwarder, draining, and fail
+    Abort,
+}
+
+impl DeleteCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self, None));
+    }
+
+    pub(crate) async fn run_with_context(
+        self,
+        ctx: &Context,
+        options: CommandGlobalOpts,
+        tcp: Option<TcpTransport>,
+    ) -> Result<()> {
+        node_rpc_with_context(ctx, rpc, (options, self, tcp)).await
+    }
+}
+
+/// Interval between two checks of a draining forwarder. The forwarder is
+/// drained once it forwarded nothing during a whole interval.
+const DRAIN_POLL_MILLIS: u64 = 1000;
+
+async fn rpc(
+    ctx: Context,
+    (opts, cmd, tcp): (CommandGlobalOpts, DeleteCommand, Option<TcpTransport>),
+) -> Result<()> {
+    let tcp = tcp_transport(&ctx, tcp).await?;
+    let api_node = ApiNode::parse(&opts, &cmd.to)?;
+
+    let forwarder =
+        find_forwarder(&ctx, &opts, &tcp, &api_node, &cmd.api, &cmd.forwarder_name).await?;
+    let remote_address = forwarder.remote_address;
+
+    if cmd.drain {
+        let timeout = Duration::from_secs(cmd.timeout);
+        let deadline = tokio::time::Instant::now() + timeout;
+        let drain = |start| {
+            drain_forwarder(
+                &ctx,
+                &opts,
+                &tcp,
+                &api_node,
+                &cmd.api,
+                &remote_address,
+                start,
+            )
+        };
+        let mut forwarded = drain(true).await?.forwarded();
+        let drained = loop {
+            if tokio::time::Instant::now() >= deadline {
+                break false;
+            }
+            tokio::time::sleep(Duration::from_millis(DRAIN_POLL_MILLIS)).await;
+            let status = drain(false).await?;
+            if status.forwarded() == forwarded {
+                break true;
+            }
+            forwarded = status.forwarded();
+        };
+        if !drained {
+            match cmd.on_timeout {
+                OnTimeout::Force => eprintln!(
+                    "Warning: forwarder {remote_address} was still forwarding messages after {}s, deleting it anyway",
+                    cmd.timeout
+                ),
+                OnTimeout::Abort => {
+                    return Err(ForwarderError::Timeout(anyhow!(
+                        "forwarder {remote_address} was still forwarding messages after {}s, it was not deleted",
+                        cmd.timeout
+                    ))
+                    .into())
+                }
+            }
+        }
+    }
+
+    delete_forwarder(&ctx, &opts, &tcp, &api_node, &cmd.api, &remote_address).await?;
+    if !opts.global_args.quiet {
+        println!("Forwarder {remote_address} deleted");
+    }
+    Ok(())
+}
@@ -0,0 +1,324 @@
+use anyhow::anyhow;
+use clap::Args;
+use serde::Serialize;
+
+use ockam::{Context, TcpTransport};
+
+use crate::forwarder::util::{
+    credential_status, find_forwarder, node_version, ping_forwarder, resolve_dns, resolve_nodes,
+    tcp_transport, ApiNode, ForwarderEntry, IpFamily,
+};
+use crate::forwarder::{help_detail, ApiOpts, ForwarderError};
+use crate::util::{node_rpc, node_rpc_with_context};
+use crate::{CommandGlobalOpts, OutputFormat};
+use crate::{Error, Result};
+
+const HELP_DETAIL: &str = "\
+Examples:
+
+```sh
+    # Find out why messages don't flow through the forwarder
+    $ ockam forwarder doctor blue --to /node/blue
+```
+";
+
+/// Check why a forwarder isn't working: whether its node answers, has it, can
+/// still resolve its route, gets an echo back through it and holds a credential
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    after_long_help = help_detail(HELP_DETAIL)
+)]
+pub struct DoctorCommand {
+    /// Name or remote address of the forwarder
+    forwarder_name: String,
+
+    /// Node on which the forwarder was created
+    #[arg(long, id = "NODE", display_order = 900)]
+    to: String,
+
+    #[command(flatten)]
+    api: ApiOpts,
+}
+
+impl DoctorCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self, None));
+    }
+
+    pub(crate) async fn run_with_context(
+        self,
+        ctx: &Context,
+        options: CommandGlobalOpts,
+        tcp: Option<TcpTransport>,
+    ) -> Result<()> {
+        node_rpc_with_context(ctx, rpc, (options, self, tcp)).await
+    }
+}
+
+/// Outcome of one check of a [`Report`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Pass,
+    Fail,
+    /// Not run, as a check it depends on failed.
+    Skip,
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    forwarder: String,
+    node: String,
+    checks: Vec<Check>,
+    /// Error of the first failed check, which gives the exit status.
+    #[serde(skip)]
+    first_error: Option<ForwarderError>,
+}
+
+impl Report {
+    fn pass(&mut self, name: &'static str, detail: impl Into<String>) {
+        self.checks.push(Check {
+            name,
+            status: Status::Pass,
+            detail: detail.into(),
+            hint: None,
+        })
+    }
+
+    fn fail(&mut self, name: &'static str, err: ForwarderError, hint: impl Into<String>) {
+        self.checks.push(Check {
+            name,
+            status: Status::Fail,
+            detail: err.to_string(),
+            hint: Some(hint.into()),
+        });
+        self.first_error.get_or_insert(err);
+    }
+
+    fn skip(&mut self, name: &'static str, detail: impl Into<String>) {
+        self.checks.push(Check {
+            name,
+            status: Status::Skip,
+            detail: detail.into(),
+            hint: None,
+        })
+    }
+
+    fn failed(&self) -> usize {
+        self.checks
+            .iter()
+            .filter(|c| c.status == Status::Fail)
+            .count()
+    }
+}
+
+async fn rpc(
+    ctx: Context,
+    (opts, cmd, tcp): (CommandGlobalOpts, DoctorCommand, Option<TcpTransport>),
+) -> Result<()> {
+    let tcp = tcp_transport(&ctx, tcp).await?;
+    let api_node = ApiNode::parse(&opts, &cmd.to)?;
+    let report = diagnose(&ctx, &opts, &tcp, &api_node, &cmd).await;
+
+    match opts.global_args.output_format {
+        OutputFormat::Plain => print_report(&report),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&report).map_err(|e| ForwarderError::Rpc(e.into()))?
+        ),
+    }
+
+    let failed = report.failed();
+    match report.first_error {
+        Some(err) => Err(Error::new(
+            Error::from(err).code(),
+            anyhow!("{failed} of {} checks failed", report.checks.len()),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Run the checks in order, skipping those which need a check that failed.
+async fn diagnose(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    api_node: &ApiNode,
+    cmd: &DoctorCommand,
+) -> Report {
+    let mut report = Report {
+        forwarder: cmd.forwarder_name.clone(),
+        node: api_node.to_string(),
+        checks: Vec::new(),
+        first_error: None,
+    };
+    let node = &api_node.name;
+
+    match node_version(ctx, opts, tcp, api_node, &cmd.api).await {
+        Ok(Some((version, api))) => report.pass(
+            "node",
+            format!("node {api_node} runs version {version}, API version {api}"),
+        ),
+        Ok(None) => report.pass(
+            "node",
+            format!("node {api_node} answers, it predates API versions"),
+        ),
+        Err(err) => {
+            let hint = match err {
+                ForwarderError::UnknownNode(_) => format!(
+                    "create the node with `ockam node create {node}`, or see the existing ones with `ockam node list`"
+                ),
+                _ => format!(
+                    "check that the node is running with `ockam node show {node}`, and start it with `ockam node start {node}`"
+                ),
+            };
+            report.fail("node", err, hint);
+            for name in ["forwarder", "route", "upstream", "credential"] {
+                report.skip(name, "needs the node to be reachable");
+            }
+            return report;
+        }
+    }
+
+    let forwarder =
+        match find_forwarder(ctx, opts, tcp, api_node, &cmd.api, &cmd.forwarder_name).await {
+            Ok(forwarder) => {
+                report.pass(
+                    "forwarder",
+                    format!(
+                        "node {api_node} has forwarder /service/{}",
+                        forwarder.remote_address
+                    ),
+                );
+                Some(forwarder)
+            }
+            Err(err) => {
+                report.fail(
+                    "forwarder",
+                    err,
+                    format!(
+                        "create it with `ockam forwarder create {} --at <RELAY> --to {}`",
+                        cmd.forwarder_name, cmd.to
+                    ),
+                );
+                None
+            }
+        };
+
+    match forwarder {
+        Some(forwarder) => {
+            check_forwarder(ctx, opts, tcp, api_node, cmd, &mut report, &forwarder).await
+        }
+        None => {
+            report.skip("route", "needs the forwarder");
+            report.skip("upstream", "needs the forwarder");
+        }
+    }
+
+    match credential_status(ctx, opts, tcp, api_node, &cmd.api).await {
+        Ok(None) => report.skip(
+            "credential",
+            format!("node {api_node} predates /node/credentials"),
+        ),
+        Ok(Some(status)) if !status.checks => report.pass(
+            "credential",
+            "credential checks are disabled, no credential is needed",
+        ),
+        Ok(Some(status)) if status.credential => {
+            report.pass("credential", format!("node {api_node} holds a credential"))
+        }
+        Ok(Some(status)) if status.authorities > 0 => report.pass(
+            "credential",
+            format!(
+                "node {api_node} will get a credential from one of its {} authorities",
+                status.authorities
+            ),
+        ),
+        Ok(Some(_)) => report.fail(
+            "credential",
+            ForwarderError::Rpc(anyhow!(
+                "node {api_node} checks credentials, but has none and no authority to get one from"
+            )),
+            "enroll the node with a project, or create it again without --enable-credential-checks",
+        ),
+        Err(err) => report.fail("credential", err, format!("check the logs of node {node}")),
+    }
+
+    report
+}
+
+/// Check that the route of `forwarder` still resolves and that its upstream
+/// answers.
+async fn check_forwarder(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    api_node: &ApiNode,
+    cmd: &DoctorCommand,
+    report: &mut Report,
+    forwarder: &ForwarderEntry,
+) {
+    let recreate = format!(
+        "create it again with `ockam forwarder create {} --at <RELAY> --to {} --on-conflict replace`",
+        cmd.forwarder_name, cmd.to
+    );
+
+    let resolved = match &forwarder.route {
+        Some(route) => match resolve_nodes(opts, route) {
+            Ok(ma) => resolve_dns(&ma, IpFamily::V4).await,
+            Err(err) => Err(err),
+        },
+        None => Err(ForwarderError::Rpc(anyhow!(
+            "the route to forwarder /service/{} can not be expressed as a multiaddr",
+            forwarder.remote_address
+        ))),
+    };
+    match resolved {
+        Ok(route) => report.pass("route", format!("the route resolves to {route}")),
+        Err(err) => {
+            report.fail("route", err, recreate);
+            report.skip("upstream", "needs the route to resolve");
+            return;
+        }
+    }
+
+    match ping_forwarder(ctx, opts, tcp, api_node, &cmd.api, forwarder).await {
+        Ok(elapsed) => report.pass(
+            "upstream",
+            format!(
+                "an echo message came back in {:.3}ms",
+                elapsed.as_secs_f64() * 1000.0
+            ),
+        ),
+        Err(err) => report.fail(
+            "upstream",
+            err,
+            format!("check that the relay is running, then {recreate}"),
+        ),
+    }
+}
+
+fn print_report(report: &Report) {
+    println!("Forwarder {} at node {}:", report.forwarder, report.node);
+    for check in &report.checks {
+        let status = match check.status {
+            Status::Pass => "PASS",
+            Status::Fail => "FAIL",
+            Status::Skip => "SKIP",
+        };
+        println!("  {status}  {:<10}  {}", check.name, check.detail);
+        if let Some(hint) = &check.hint {
+            println!("        {:<10}  hint: {hint}", "");
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context as _};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+use ockam::{Context, TcpTransport};
+use ockam_api::is_local_node;
+use ockam_api::nodes::models::forwarder::ForwarderKind;
+use ockam_multiaddr::proto::Service;
+use ockam_multiaddr::MultiAddr;
+
+use crate::forwarder::util::{
+    list_forwarders, tcp_transport, ApiNode, ForwarderEntry, FORWARD_TO_PREFIX,
+};
+use crate::forwarder::{help_detail, ApiOpts, ForwarderError};
+use crate::util::{node_rpc, node_rpc_with_context};
+use crate::CommandGlobalOpts;
+use crate::Result;
+
+const HELP_DETAIL: &str = "\
+Examples:
+
+```sh
+    # Save the forwarders of blue, and create them again for another node
+    $ ockam forwarder export --to /node/blue --to-file forwarders.json
+    $ ockam forwarder import --to /node/purple --from-file forwarders.json
+```
+";
+
+/// Save the forwarders of a node to a JSON file
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    after_long_help = help_detail(HELP_DETAIL)
+)]
+pub struct ExportCommand {
+    /// Node whose forwarders to export
+    #[arg(long, id = "NODE", display_order = 900)]
+    to: String,
+
+    #[command(flatten)]
+    api: ApiOpts,
+
+    /// File to write the forwarders to (optional, standard output by default)
+    #[arg(long, value_name = "FILE", display_order = 900)]
+    to_file: Option<PathBuf>,
+}
+
+/// The content of the files written by `forwarder export`.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ForwarderExport {
+    pub(crate) forwarders: Vec<ExportedForwarder>,
+}
+
+/// A forwarder, with what is needed to create it again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ExportedForwarder {
+    /// Name the forwarder was created with, missing for the forwarders
+    /// registered under a random address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) name: Option<String>,
+    /// Route to the node at which the forwarder was created.
+    pub(crate) at: MultiAddr,
+    /// Whether `at` is a rust node rather than a project.
+    pub(crate) at_node: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) kind: Option<ForwarderKind>,
+}
+
+impl ExportedForwarder {
+    /// Describe `entry`, failing for forwarders whose route to the node they
+    /// were created at would not be valid from another node.
+    pub(crate) fn of_entry(entry: &ForwarderEntry) -> std::result::Result<Self, ForwarderError> {
+        let remote_address = &entry.remote_address;
+        let mut at = entry.route.clone().ok_or_else(|| {
+            ForwarderError::Rpc(anyhow!(
+                "the route to forwarder {remote_address} can not be expressed as a multiaddr"
+            ))
+        })?;
+        match at.pop_back() {
+            Some(p) if p.cast::<Service>().map_or(false, |s| &*s == remote_address) => {}
+            _ => {
+                return Err(ForwarderError::Rpc(anyhow!(
+                    "the route to forwarder {remote_address} does not end with its address"
+                )))
+            }
+        }
+        // Routes through a secure channel start with the local address of
+        // the channel, meaningless on another node
+        is_local_node(&at)
+            .with_context(|| {
+                format!("the route to forwarder {remote_address} is local to the node")
+            })
+            .map_err(ForwarderError::Rpc)?;
+
+        let named = remote_address.strip_prefix(FORWARD_TO_PREFIX);
+        let (name, at_node) = match entry.kind {
+            Some(ForwarderKind::Wildcard) => (None, true),
+            Some(ForwarderKind::Ephemeral) => (None, is_local_node(&at).unwrap_or(false)),
+            _ => match named {
+                Some(name) => (Some(name.to_string()), true),
+                None => (Some(remote_address.clone()), false),
+            },
+        };
+        Ok(ExportedForwarder {
+            name,
+            at,
+            at_node,
+            kind: entry.kind,
+        })
+    }
+}
+
+impl ExportCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self, None));
+    }
+
+    pub(crate) async fn run_with_context(
+        self,
+        ctx: &Context,
+        options: CommandGlobalOpts,
+        tcp: Option<TcpTransport>,
+    ) -> Result<()> {
+        node_rpc_with_context(ctx, rpc, (options, self, tcp)).await
+    }
+}
+
+async fn rpc(
+    ctx: Context,
+    (opts, cmd, tcp): (CommandGlobalOpts, ExportCommand, Option<TcpTransport>),
+) -> Result<()> {
+    let tcp = tcp_transport(&ctx, tcp).await?;
+    let api_node = ApiNode::parse(&opts, &cmd.to)?;
+
+    let mut export = ForwarderExport::default();
+    for entry in list_forwarders(&ctx, &opts, &tcp, &api_node, &cmd.api).await? {
+        match ExportedForwarder::of_entry(&entry) {
+            Ok(f) => export.forwarders.push(f),
+            Err(e) => eprintln!("Skipping forwarder {}: {e}", entry.remote_address),
+        }
+    }
+
+    let json = serde_json::to_string_pretty(&export)?;
+    match &cmd.to_file {
+        Some(path) => std::fs::write(path, json + "\n")
+            .with_context(|| format!("failed to write {}", path.display()))?,
+        None => println!("{json}"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(remote_address: &str, route: &str, kind: Option<ForwarderKind>) -> ForwarderEntry {
+        ForwarderEntry {
+            remote_address: remote_address.to_string(),
+            route: Some(route.parse().unwrap()),
+            kind,
+        }
+    }
+
+    #[test]
+    fn exported_forwarders() {
+        let f = ExportedForwarder::of_entry(&entry(
+            "forward_to_blue",
+            "/ip4/127.0.0.1/tcp/4000/service/forward_to_blue",
+            Some(ForwarderKind::Static),
+        ))
+        .unwrap();
+        assert_eq!(f.name.as_deref(), Some("blue"));
+        assert_eq!(f.at.to_string(), "/ip4/127.0.0.1/tcp/4000");
+        assert!(f.at_node);
+
+        let f = ExportedForwarder::of_entry(&entry(
+            "0a1b2c3d",
+            "/dnsaddr/relay.example.com/tcp/4000/service/0a1b2c3d",
+            Some(ForwarderKind::Ephemeral),
+        ))
+        .unwrap();
+        assert_eq!(f.name, None);
+        assert!(!f.at_node);
+
+        // Through a secure channel, or not ending with the forwarder
+        for (address, route) in [
+            ("blue", "/service/6a3f/service/blue"),
+            ("blue", "/ip4/127.0.0.1/tcp/4000/service/red"),
+        ] {
+            assert!(ExportedForwarder::of_entry(&entry(address, route, None)).is_err());
+        }
+
+        let export = ForwarderExport {
+            forwarders: vec![f],
+        };
+        let json = serde_json::to_string(&export).unwrap();
+        assert_eq!(
+            json,
+            r#"{"forwarders":[{"at":"/dnsaddr/relay.example.com/tcp/4000","at_node":false,"kind":"ephemeral"}]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<ForwarderExport>(&json).unwrap(),
+            export
+        );
+    }
+}
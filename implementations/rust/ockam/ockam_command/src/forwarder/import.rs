@@ -0,0 +1,348 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context as _};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+use ockam::{Context, TcpTransport};
+use ockam_api::nodes::models::forwarder::{CreateForwarder, ForwarderInfo, ForwarderKind};
+use ockam_core::api::Request;
+
+use crate::forwarder::export::{ExportedForwarder, ForwarderExport};
+use crate::forwarder::util::{
+    check_available, forwarder_alias, forwarder_name, forwarder_rpc, list_forwarders,
+    resolve_nodes, tcp_transport, ApiNode, ForwarderEntry, Progress,
+};
+use crate::forwarder::{help_detail, ApiOpts, ForwarderError};
+use crate::util::{node_rpc, node_rpc_with_context};
+use crate::Result;
+use crate::{exitcode, CommandGlobalOpts};
+
+const HELP_DETAIL: &str = "\
+Examples:
+
+```sh
+    # Save the forwarders of blue, and create them again for another node
+    $ ockam forwarder export --to /node/blue --to-file forwarders.json
+    $ ockam forwarder import --to /node/purple --from-file forwarders.json
+
+    # Finish an interrupted import, skipping the forwarders it already created
+    $ ockam forwarder import --to /node/purple --from-file forwarders.json --resume
+```
+";
+
+/// Create the forwarders saved by `forwarder export`
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    after_long_help = help_detail(HELP_DETAIL)
+)]
+pub struct ImportCommand {
+    /// Node for which to create the forwarders
+    #[arg(long, id = "NODE", display_order = 900)]
+    to: String,
+
+    #[command(flatten)]
+    api: ApiOpts,
+
+    /// File written by `forwarder export`
+    #[arg(long, value_name = "FILE", display_order = 900)]
+    from_file: PathBuf,
+
+    /// Skip the forwarders imported by a previous, interrupted run, as
+    /// recorded in the state file (optional)
+    #[arg(long, display_order = 900)]
+    resume: bool,
+
+    /// File recording the progress of the import (optional, the --from-file
+    /// path with a .state suffix by default)
+    #[arg(long, value_name = "FILE", display_order = 900)]
+    state_file: Option<PathBuf>,
+}
+
+impl ImportCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self, None));
+    }
+
+    pub(crate) async fn run_with_context(
+        self,
+        ctx: &Context,
+        options: CommandGlobalOpts,
+        tcp: Option<TcpTransport>,
+    ) -> Result<()> {
+        node_rpc_with_context(ctx, rpc, (options, self, tcp)).await
+    }
+}
+
+async fn rpc(
+    ctx: Context,
+    (opts, cmd, tcp): (CommandGlobalOpts, ImportCommand, Option<TcpTransport>),
+) -> Result<()> {
+    let json = std::fs::read_to_string(&cmd.from_file)
+        .with_context(|| format!("failed to read {}", cmd.from_file.display()))
+        .map_err(ForwarderError::InvalidArgument)?;
+    let export: ForwarderExport = serde_json::from_str(&json)
+        .with_context(|| format!("invalid forwarder export {}", cmd.from_file.display()))
+        .map_err(ForwarderError::InvalidArgument)?;
+
+    let state_file = cmd
+        .state_file
+        .clone()
+        .unwrap_or_else(|| ImportState::default_path(&cmd.from_file));
+    let mut state = if cmd.resume {
+        ImportState::load(&state_file)?
+    } else {
+        ImportState::default()
+    };
+
+    let tcp = tcp_transport(&ctx, tcp).await?;
+    let api_node = ApiNode::parse(&opts, &cmd.to)?;
+    let existing = list_forwarders(&ctx, &opts, &tcp, &api_node, &cmd.api).await?;
+
+    let mut failed = 0;
+    let mut progress = Progress::new(&opts, "Imported", export.forwarders.len());
+    for (index, f) in export.forwarders.iter().enumerate() {
+        let label = f.name.as_deref().unwrap_or("without a name");
+        if state.is_imported(index, f) {
+            progress.clear();
+            eprintln!("Skipping forwarder {label}: imported by a previous run");
+            progress.step();
+            continue;
+        }
+        if exists(&existing, f) {
+            progress.clear();
+            eprintln!("Skipping forwarder {label}: it already exists");
+            progress.step();
+            continue;
+        }
+        let result = import(&ctx, &opts, &tcp, &api_node, &cmd.api, f).await;
+        progress.clear();
+        match result {
+            Ok(remote_address) => {
+                println!("/service/{remote_address}");
+                state.imported(index, f);
+                state.save(&state_file)?;
+            }
+            Err(e) => {
+                eprintln!("Failed to import forwarder {label} at {}: {e}", f.at);
+                failed += 1;
+            }
+        }
+        progress.step();
+    }
+
+    if failed > 0 {
+        return Err(ForwarderError::Rpc(anyhow!(
+            "{failed} of {} forwarders could not be imported, run the command again with --resume to retry them",
+            export.forwarders.len()
+        ))
+        .into());
+    }
+    ImportState::remove(&state_file)
+}
+
+/// The forwarders imported so far, saved after every one of them so that
+/// an interrupted import can be resumed.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct ImportState {
+    imported: Vec<ImportedForwarder>,
+}
+
+/// An entry of the import file, identified by its position in the file and
+/// its content, so that entries which moved or changed are imported again.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct ImportedForwarder {
+    index: usize,
+    forwarder: ExportedForwarder,
+}
+
+impl ImportState {
+    fn default_path(from_file: &Path) -> PathBuf {
+        let mut path = from_file.as_os_str().to_owned();
+        path.push(".state");
+        PathBuf::from(path)
+    }
+
+    /// Read the state saved at `path`, nothing having been imported when
+    /// there is no such file.
+    fn load(path: &Path) -> Result<Self> {
+        let json = match std::fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => {
+                let e = anyhow::Error::new(e)
+                    .context(format!("failed to read the state file {}", path.display()));
+                return Err(crate::Error::new(exitcode::IOERR, e));
+            }
+        };
+        let state = serde_json::from_str(&json)
+            .with_context(|| format!("invalid state file {}", path.display()))
+            .map_err(ForwarderError::InvalidArgument)?;
+        Ok(state)
+    }
+
+    fn is_imported(&self, index: usize, f: &ExportedForwarder) -> bool {
+        self.imported
+            .iter()
+            .any(|i| i.index == index && &i.forwarder == f)
+    }
+
+    fn imported(&mut self, index: usize, f: &ExportedForwarder) {
+        self.imported.push(ImportedForwarder {
+            index,
+            forwarder: f.clone(),
+        })
+    }
+
+    /// Replace the state file at `path` atomically, writing a temporary
+    /// file next to it and renaming it.
+    fn save(&self, path: &Path) -> Result<()> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+        let write = || -> std::io::Result<()> {
+            let mut file = std::fs::File::create(&tmp)?;
+            file.write_all(&serde_json::to_vec(self)?)?;
+            file.sync_all()?;
+            std::fs::rename(&tmp, path)
+        };
+        write().map_err(|e| {
+            let e = anyhow::Error::new(e)
+                .context(format!("failed to write the state file {}", path.display()));
+            crate::Error::new(exitcode::IOERR, e)
+        })
+    }
+
+    fn remove(path: &Path) -> Result<()> {
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                let e = anyhow::Error::new(e).context(format!(
+                    "failed to remove the state file {}",
+                    path.display()
+                ));
+                Err(crate::Error::new(exitcode::IOERR, e))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Whether `f` is already among the `existing` forwarders of the node.
+///
+/// Forwarders without a name are registered under a new random address
+/// every time and never exist already, except for the node's only
+/// wildcard forwarder.
+pub(crate) fn exists(existing: &[ForwarderEntry], f: &ExportedForwarder) -> bool {
+    match (&f.name, f.kind) {
+        (_, Some(ForwarderKind::Wildcard)) => existing
+            .iter()
+            .any(|e| e.kind == Some(ForwarderKind::Wildcard)),
+        (Some(name), _) => existing.iter().any(|e| e.is_named(name)),
+        (None, _) => false,
+    }
+}
+
+/// Create `f` again, returning its remote address.
+pub(crate) async fn import(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    api_node: &ApiNode,
+    api: &ApiOpts,
+    f: &ExportedForwarder,
+) -> std::result::Result<String, ForwarderError> {
+    let ma = resolve_nodes(opts, &f.at)?;
+    let wildcard = f.kind == Some(ForwarderKind::Wildcard);
+    let alias = match (&f.name, wildcard) {
+        (Some(name), false) => {
+            let name = forwarder_name(name)?;
+            Some(forwarder_alias(name, f.at_node)?)
+        }
+        _ => None,
+    };
+    let mut body = CreateForwarder::at_node(ma, alias, f.at_node, None);
+    body.set_wildcard(wildcard);
+
+    let mut rpc = forwarder_rpc(ctx, opts, tcp, api_node, api)?;
+    rpc.request(Request::post("/node/forwarder").body(body))
+        .await
+        .map_err(ForwarderError::from_rpc)?;
+    check_available(&rpc)?;
+    let info = rpc
+        .parse_response::<ForwarderInfo>()
+        .map_err(ForwarderError::Rpc)?;
+    Ok(info.remote_address().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exported(name: Option<&str>, kind: ForwarderKind) -> ExportedForwarder {
+        ExportedForwarder {
+            name: name.map(String::from),
+            at: "/node/green".parse().unwrap(),
+            at_node: true,
+            kind: Some(kind),
+        }
+    }
+
+    #[test]
+    fn existing_forwarders() {
+        let existing = [ForwarderEntry {
+            remote_address: "forward_to_blue".into(),
+            route: None,
+            kind: Some(ForwarderKind::Static),
+        }];
+        assert!(exists(
+            &existing,
+            &exported(Some("blue"), ForwarderKind::Static)
+        ));
+        assert!(!exists(
+            &existing,
+            &exported(Some("red"), ForwarderKind::Static)
+        ));
+        assert!(!exists(
+            &existing,
+            &exported(None, ForwarderKind::Ephemeral)
+        ));
+        assert!(!exists(&existing, &exported(None, ForwarderKind::Wildcard)));
+
+        let existing = [ForwarderEntry {
+            remote_address: "0a1b2c3d".into(),
+            route: None,
+            kind: Some(ForwarderKind::Wildcard),
+        }];
+        assert!(exists(&existing, &exported(None, ForwarderKind::Wildcard)));
+    }
+
+    #[test]
+    fn import_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = ImportState::default_path(&dir.path().join("f.json"));
+        assert_eq!(path, dir.path().join("f.json.state"));
+        assert_eq!(ImportState::load(&path).unwrap(), ImportState::default());
+
+        let blue = exported(Some("blue"), ForwarderKind::Static);
+        let random = exported(None, ForwarderKind::Ephemeral);
+        let mut state = ImportState::default();
+        state.imported(0, &blue);
+        state.imported(2, &random);
+        state.save(&path).unwrap();
+
+        let state = ImportState::load(&path).unwrap();
+        assert!(state.is_imported(0, &blue));
+        assert!(state.is_imported(2, &random));
+        assert!(!state.is_imported(1, &random));
+        assert!(!state.is_imported(0, &exported(Some("red"), ForwarderKind::Static)));
+
+        ImportState::remove(&path).unwrap();
+        assert!(!path.exists());
+        ImportState::remove(&path).unwrap();
+
+        std::fs::write(&path, "{").unwrap();
+        assert!(ImportState::load(&path).is_err());
+    }
+}
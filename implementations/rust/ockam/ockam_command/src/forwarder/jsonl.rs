@@ -0,0 +1,123 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context as _};
+use serde::{Deserialize, Serialize};
+
+use ockam_api::nodes::models::forwarder::is_valid_remote_address;
+
+use crate::forwarder::create::At;
+
+/// A line of `forwarder create --from-stdin-jsonl`, e.g.
+/// `{"name": "blue", "at": "/node/green"}`.
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ForwarderSpec {
+    /// Name of the forwarder, random when missing.
+    #[serde(default)]
+    pub(crate) name: Option<String>,
+    /// Where to create the forwarder, like `--at`, which is used when
+    /// missing.
+    #[serde(default)]
+    pub(crate) at: Option<String>,
+}
+
+impl ForwarderSpec {
+    /// Parse the line `number` of the input, which may be blank.
+    pub(crate) fn parse(number: usize, line: &str) -> anyhow::Result<Option<Self>> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+        let spec: Self = serde_json::from_str(line)
+            .with_context(|| format!("line {number} is not a valid forwarder spec"))?;
+        if let Some(at) = &spec.at {
+            At::from_str(at).with_context(|| format!("line {number} has an invalid at"))?;
+        }
+        if let Some(name) = &spec.name {
+            if !is_valid_remote_address(name) {
+                return Err(anyhow!(
+                    "line {number} has a name which can not be used in a /service address"
+                ));
+            }
+        }
+        Ok(Some(spec))
+    }
+}
+
+/// The line printed for each line of the input.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct SpecResult {
+    pub(crate) line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) name: Option<String>,
+    /// Address of the forwarder created, missing when it already existed
+    /// and --on-conflict skip left it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) remote_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) error: Option<String>,
+}
+
+impl SpecResult {
+    pub(crate) fn print(&self) {
+        // Serializing strings and numbers can't fail
+        if let Ok(json) = serde_json::to_string(self) {
+            println!("{json}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_specs() {
+        assert_eq!(ForwarderSpec::parse(1, "  ").unwrap(), None);
+        assert_eq!(
+            ForwarderSpec::parse(1, r#"{"name": "blue", "at": "/node/green"}"#).unwrap(),
+            Some(ForwarderSpec {
+                name: Some("blue".into()),
+                at: Some("/node/green".into())
+            })
+        );
+        assert_eq!(
+            ForwarderSpec::parse(1, "{}").unwrap(),
+            Some(ForwarderSpec::default())
+        );
+        for line in [
+            "{",
+            r#"{"name": 1}"#,
+            r#"{"name": ""}"#,
+            r#"{"name": "blue/green"}"#,
+            r#"{"at": "relay"}"#,
+            r#"{"name": "blue", "color": "blue"}"#,
+        ] {
+            let err = ForwarderSpec::parse(7, line).unwrap_err();
+            assert!(err.to_string().starts_with("line 7 "), "{line}");
+        }
+    }
+
+    #[test]
+    fn result_lines() {
+        let created = SpecResult {
+            line: 1,
+            name: Some("blue".into()),
+            remote_address: Some("forward_to_blue".into()),
+            error: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&created).unwrap(),
+            r#"{"line":1,"name":"blue","remote_address":"forward_to_blue"}"#
+        );
+        let failed = SpecResult {
+            line: 2,
+            name: None,
+            remote_address: None,
+            error: Some("line 2 is not a valid forwarder spec".into()),
+        };
+        assert_eq!(
+            serde_json::to_string(&failed).unwrap(),
+            r#"{"line":2,"error":"line 2 is not a valid forwarder spec"}"#
+        );
+    }
+}
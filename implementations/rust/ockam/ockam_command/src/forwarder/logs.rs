@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use clap::Args;
+
+use ockam::{Context, TcpTransport};
+use ockam_api::nodes::models::forwarder::{ForwarderEvent, ForwarderEventList};
+use ockam_core::api::{Request, Status};
+
+use crate::forwarder::util::{
+    check_available, find_forwarder, forwarder_rpc, tcp_transport, ApiNode,
+};
+use crate::forwarder::{help_detail, ApiOpts, ForwarderError};
+use crate::util::{node_rpc, node_rpc_with_context};
+use crate::Result;
+use crate::{CommandGlobalOpts, OutputFormat};
+
+const HELP_DETAIL: &str = "\
+Examples:
+
+```sh
+    # See what happened to the forwarder lately, and keep watching it
+    $ ockam forwarder logs blue --to /node/blue --follow
+```
+";
+
+/// Print the recent events of a forwarder
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    after_long_help = help_detail(HELP_DETAIL)
+)]
+pub struct LogsCommand {
+    /// Name or remote address of the forwarder
+    forwarder_name: String,
+
+    /// Node on which the forwarder was created
+    #[arg(long, id = "NODE", display_order = 900)]
+    to: String,
+
+    /// Keep printing new events, asking the node for them every second,
+    /// until interrupted (optional)
+    #[arg(long, short, display_order = 900)]
+    follow: bool,
+
+    #[command(flatten)]
+    api: ApiOpts,
+}
+
+impl LogsCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self, None));
+    }
+
+    pub(crate) async fn run_with_context(
+        self,
+        ctx: &Context,
+        options: CommandGlobalOpts,
+        tcp: Option<TcpTransport>,
+    ) -> Result<()> {
+        node_rpc_with_context(ctx, rpc, (options, self, tcp)).await
+    }
+}
+
+/// Interval between two requests for the events of a followed forwarder.
+const FOLLOW_POLL_MILLIS: u64 = 1000;
+
+async fn rpc(
+    ctx: Context,
+    (opts, cmd, tcp): (CommandGlobalOpts, LogsCommand, Option<TcpTransport>),
+) -> Result<()> {
+    let tcp = tcp_transport(&ctx, tcp).await?;
+    let api_node = ApiNode::parse(&opts, &cmd.to)?;
+
+    let forwarder =
+        find_forwarder(&ctx, &opts, &tcp, &api_node, &cmd.api, &cmd.forwarder_name).await?;
+    let remote_address = forwarder.remote_address;
+
+    let mut next_seq = 0;
+    loop {
+        let mut rpc = forwarder_rpc(&ctx, &opts, &tcp, &api_node, &cmd.api)?;
+        rpc.request(Request::get(format!(
+            "/node/forwarder/{remote_address}/events"
+        )))
+        .await
+        .map_err(ForwarderError::from_rpc)?;
+        check_available(&rpc)?;
+        if let Ok((hdr, _)) = rpc.check_response() {
+            if hdr.status() == Some(Status::NotFound) && next_seq > 0 {
+                eprintln!("Forwarder {remote_address} was deleted");
+                return Ok(());
+            }
+        }
+        let events = rpc
+            .parse_response::<ForwarderEventList>()
+            .map_err(ForwarderError::Rpc)?;
+        for event in &events.list {
+            if event.seq() >= next_seq {
+                print_event(&opts, event)?;
+                next_seq = event.seq() + 1;
+            }
+        }
+        if !cmd.follow {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(FOLLOW_POLL_MILLIS)).await;
+    }
+}
+
+fn print_event(opts: &CommandGlobalOpts, event: &ForwarderEvent) -> Result<()> {
+    match opts.global_args.output_format {
+        OutputFormat::Plain => println!("{} {}", utc_time(event.time()), event.message()),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string(event).map_err(|e| ForwarderError::Rpc(e.into()))?
+        ),
+    }
+    Ok(())
+}
+
+/// Format seconds since the Unix epoch as an RFC 3339 UTC time, e.g.
+/// `2022-11-02T09:05:00Z`.
+fn utc_time(secs: u64) -> String {
+    let (days, secs) = (secs / 86400, secs % 86400);
+    // Civil date of a day count, from http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z % 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utc_times() {
+        assert_eq!(utc_time(0), "1970-01-01T00:00:00Z");
+        assert_eq!(utc_time(951782400), "2000-02-29T00:00:00Z");
+        assert_eq!(utc_time(1667379900), "2022-11-02T09:05:00Z");
+        assert_eq!(utc_time(1704067199), "2023-12-31T23:59:59Z");
+    }
+}
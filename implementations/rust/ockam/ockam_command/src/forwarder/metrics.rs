@@ -0,0 +1,237 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Context as _};
+use nix::fcntl::{flock, FlockArg};
+
+const CREATED_TOTAL: &str = "ockam_forwarder_created_total";
+const LAST_CREATE_TIMESTAMP: &str = "ockam_forwarder_last_create_timestamp";
+
+/// The metrics of forwarder create, as written in a Prometheus textfile,
+/// e.g. for the textfile collector of node_exporter.
+///
+/// The samples of other metrics found in the file are kept as they are.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Metrics {
+    /// Forwarders created, by node.
+    created_total: BTreeMap<String, u64>,
+    /// Unix time of the last forwarder created, by node.
+    last_create_timestamp: BTreeMap<String, u64>,
+    /// Lines of the other metrics.
+    others: Vec<String>,
+}
+
+impl Metrics {
+    /// Record that a forwarder was just created with `node`, in the textfile
+    /// at `path`.
+    ///
+    /// The file is locked while it is read and replaced, through `<PATH>.lock`,
+    /// so that concurrent commands don't lose each other's updates, and it is
+    /// replaced atomically, so that collectors never read half of it.
+    pub(crate) fn record_create(path: &Path, node: &str) -> anyhow::Result<()> {
+        let _lock = lock(path)?;
+        let mut metrics = match fs::read_to_string(path) {
+            Ok(text) => Metrics::parse(&text)
+                .with_context(|| format!("{} is not a Prometheus textfile", path.display()))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Metrics::default(),
+            Err(e) => {
+                return Err(
+                    anyhow::Error::new(e).context(format!("failed to read {}", path.display()))
+                )
+            }
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        metrics.created(node, now);
+        write(path, &metrics.render())
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    fn created(&mut self, node: &str, now: u64) {
+        let labels = labels(node);
+        *self.created_total.entry(labels.clone()).or_default() += 1;
+        self.last_create_timestamp.insert(labels, now);
+    }
+
+    fn parse(text: &str) -> anyhow::Result<Self> {
+        let mut metrics = Metrics::default();
+        for line in text.lines() {
+            let ours = match line.split_whitespace().collect::<Vec<_>>()[..] {
+                ["#", "HELP" | "TYPE", name, ..] => {
+                    if name == CREATED_TOTAL || name == LAST_CREATE_TIMESTAMP {
+                        continue;
+                    }
+                    None
+                }
+                _ => [
+                    (CREATED_TOTAL, &mut metrics.created_total),
+                    (LAST_CREATE_TIMESTAMP, &mut metrics.last_create_timestamp),
+                ]
+                .into_iter()
+                .find_map(|(name, samples)| Some((line.strip_prefix(name)?, samples)))
+                .filter(|(rest, _)| rest.starts_with(['{', ' '])),
+            };
+            match ours {
+                Some((rest, samples)) => {
+                    let (labels, value) = rest
+                        .rsplit_once(' ')
+                        .ok_or_else(|| anyhow!("missing value in '{line}'"))?;
+                    let value = value
+                        .parse()
+                        .map_err(|_| anyhow!("invalid value in '{line}'"))?;
+                    samples.insert(labels.trim().to_string(), value);
+                }
+                None if line.trim().is_empty() => {}
+                None => metrics.others.push(line.to_string()),
+            }
+        }
+        Ok(metrics)
+    }
+
+    fn render(&self) -> String {
+        let mut text = String::new();
+        for line in &self.others {
+            text.push_str(line);
+            text.push('\n');
+        }
+        for (name, kind, help, samples) in [
+            (
+                CREATED_TOTAL,
+                "counter",
+                "Forwarders created by ockam forwarder create.",
+                &self.created_total,
+            ),
+            (
+                LAST_CREATE_TIMESTAMP,
+                "gauge",
+                "Unix time of the last forwarder created by ockam forwarder create.",
+                &self.last_create_timestamp,
+            ),
+        ] {
+            if samples.is_empty() {
+                continue;
+            }
+            text.push_str(&format!("# HELP {name} {help}\n# TYPE {name} {kind}\n"));
+            for (labels, value) in samples {
+                text.push_str(&format!("{name}{labels} {value}\n"));
+            }
+        }
+        text
+    }
+}
+
+/// The labels of the samples of `node`.
+fn labels(node: &str) -> String {
+    let mut value = String::with_capacity(node.len());
+    for c in node.chars() {
+        match c {
+            '\\' => value.push_str("\\\\"),
+            '"' => value.push_str("\\\""),
+            '\n' => value.push_str("\\n"),
+            c => value.push(c),
+        }
+    }
+    format!("{{node=\"{value}\"}}")
+}
+
+/// `path` with `suffix` appended, e.g. `metrics.prom.lock`.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut sibling = path.as_os_str().to_owned();
+    sibling.push(suffix);
+    PathBuf::from(sibling)
+}
+
+/// Hold an exclusive lock on the textfile at `path` until the returned file
+/// is dropped.
+fn lock(path: &Path) -> anyhow::Result<File> {
+    let lock_path = sibling(path, ".lock");
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("failed to open {}", lock_path.display()))?;
+    flock(file.as_raw_fd(), FlockArg::LockExclusive)
+        .with_context(|| format!("failed to lock {}", lock_path.display()))?;
+    Ok(file)
+}
+
+/// Replace the file at `path` with `text`, atomically.
+fn write(path: &Path, text: &str) -> io::Result<()> {
+    let tmp = sibling(path, ".tmp");
+    let mut file = File::create(&tmp)?;
+    file.write_all(text.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_textfile() {
+        let text = "\
+# HELP other_metric Something else.
+# TYPE other_metric gauge
+other_metric 7
+# HELP ockam_forwarder_created_total Forwarders created by ockam forwarder create.
+# TYPE ockam_forwarder_created_total counter
+ockam_forwarder_created_total{node=\"blue\"} 2
+# HELP ockam_forwarder_last_create_timestamp Unix time of the last forwarder created by ockam forwarder create.
+# TYPE ockam_forwarder_last_create_timestamp gauge
+ockam_forwarder_last_create_timestamp{node=\"blue\"} 1000
+";
+        let mut metrics = Metrics::parse(text).unwrap();
+        assert_eq!(metrics.render(), text);
+
+        metrics.created("blue", 2000);
+        metrics.created("gr\"een", 3000);
+        assert_eq!(
+            metrics.render(),
+            "\
+# HELP other_metric Something else.
+# TYPE other_metric gauge
+other_metric 7
+# HELP ockam_forwarder_created_total Forwarders created by ockam forwarder create.
+# TYPE ockam_forwarder_created_total counter
+ockam_forwarder_created_total{node=\"blue\"} 3
+ockam_forwarder_created_total{node=\"gr\\\"een\"} 1
+# HELP ockam_forwarder_last_create_timestamp Unix time of the last forwarder created by ockam forwarder create.
+# TYPE ockam_forwarder_last_create_timestamp gauge
+ockam_forwarder_last_create_timestamp{node=\"blue\"} 2000
+ockam_forwarder_last_create_timestamp{node=\"gr\\\"een\"} 3000
+"
+        );
+    }
+
+    #[test]
+    fn invalid_textfile() {
+        assert!(Metrics::parse("ockam_forwarder_created_total{node=\"blue\"}").is_err());
+        assert!(Metrics::parse("ockam_forwarder_created_total{node=\"blue\"} x").is_err());
+        // Other metrics are left alone, whatever they look like
+        assert!(Metrics::parse("ockam_forwarder_created_totals x").is_ok());
+    }
+
+    #[test]
+    fn concurrent_updates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("forwarders.prom");
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let path = path.clone();
+                std::thread::spawn(move || Metrics::record_create(&path, "blue").unwrap())
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        let metrics = Metrics::parse(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(metrics.created_total.get("{node=\"blue\"}"), Some(&8));
+    }
+}
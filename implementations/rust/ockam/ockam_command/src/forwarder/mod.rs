@@ -1,10 +1,38 @@
 use clap::{Args, Subcommand};
 
 pub(crate) use create::CreateCommand;
+pub(crate) use delete::DeleteCommand;
+pub(crate) use doctor::DoctorCommand;
+pub(crate) use export::ExportCommand;
+pub(crate) use import::ImportCommand;
+pub(crate) use logs::LogsCommand;
+use ockam::{Context, TcpTransport};
+use ockam_core::errcode::Kind;
+use ockam_core::AsyncTryClone;
+pub(crate) use ping::PingCommand;
+pub(crate) use plan::ApplyCommand;
+pub(crate) use relocate::MoveCommand;
+pub(crate) use rename::RenameCommand;
+pub(crate) use selector::node_tag;
 
+use crate::util::comma_separated;
 use crate::{help, CommandGlobalOpts};
 
 mod create;
+mod delete;
+mod doctor;
+mod export;
+mod import;
+mod jsonl;
+mod logs;
+mod metrics;
+mod ping;
+mod plan;
+mod relocate;
+mod rename;
+mod selector;
+mod template;
+mod util;
 
 const HELP_DETAIL: &str = "\
 About:
@@ -47,27 +75,162 @@ About:
     from both yellow and blue and only sees encrypted traffic.
 ";
 
+/// Exit statuses of the forwarder subcommands, shown in the help of each
+const EXIT_STATUS: &str = "\
+Exit Status:
+    0   The command succeeded.
+    64  Usage error: an unknown node in --to or --at, or an invalid --at route.
+    67  The forwarder does not exist on the node, e.g. in --at forwarder:<NAME>.
+    69  The node could not be reached or it failed to process the request.
+    75  The node did not answer in time or was temporarily unavailable, retrying
+        may succeed, or a forwarder did not drain in time with --on-timeout abort.
+    76  The --to node is too old for some of the options of forwarder create.
+";
+
+/// The help shown after `help_detail`, e.g. the examples of a subcommand,
+/// followed by the exit statuses
+pub(crate) fn help_detail(help_detail: &str) -> &'static str {
+    help::template(&format!("{help_detail}\n{EXIT_STATUS}"))
+}
+
 /// Manage Forwarders
 #[derive(Clone, Debug, Args)]
 #[command(
     arg_required_else_help = true,
     subcommand_required = true,
-    after_long_help = help::template(HELP_DETAIL)
+    after_long_help = help_detail(HELP_DETAIL)
 )]
 pub struct ForwarderCommand {
     #[command(subcommand)]
     subcommand: ForwarderSubCommand,
 }
 
+/// Options shared by the forwarder subcommands for talking to the `--to` node.
+#[derive(Clone, Debug, Args)]
+pub struct ApiOpts {
+    /// Path prepended to the requests sent to the node, for nodes behind a
+    /// proxy serving their API under a path, e.g. /api/v1 (optional)
+    #[arg(
+        long,
+        value_name = "PREFIX",
+        default_value = "",
+        hide_default_value = true,
+        display_order = 901
+    )]
+    pub api_prefix: String,
+}
+
 #[derive(Clone, Debug, Subcommand)]
 pub enum ForwarderSubCommand {
     Create(CreateCommand),
+    Apply(ApplyCommand),
+    Ping(PingCommand),
+    Export(ExportCommand),
+    Import(ImportCommand),
+    Rename(RenameCommand),
+    Move(MoveCommand),
+    Delete(DeleteCommand),
+    Logs(LogsCommand),
+    Doctor(DoctorCommand),
+}
+
+/// Failure classes of the forwarder commands.
+///
+/// Every variant maps to a distinct exit code (see `From<ForwarderError>`
+/// for [`crate::Error`]) so that scripts can tell them apart.
+#[derive(thiserror::Error, Debug)]
+pub enum ForwarderError {
+    #[error("no address for node {0}")]
+    UnknownNode(String),
+    #[error("forwarder {name} not found{}", existing_forwarders(.existing))]
+    NotFound { name: String, existing: Vec<String> },
+    #[error("{0:#}")]
+    InvalidArgument(anyhow::Error),
+    #[error("{0:#}")]
+    Rpc(anyhow::Error),
+    #[error("{0:#}")]
+    Timeout(anyhow::Error),
+    /// The node answered 503 Service Unavailable.
+    #[error("{0:#}")]
+    Unavailable(anyhow::Error),
+    /// The node is too old for some of the options given.
+    #[error("{0:#}")]
+    Unsupported(anyhow::Error),
+}
+
+impl ForwarderError {
+    /// Classify an error returned while talking to a node.
+    pub fn from_rpc(err: anyhow::Error) -> Self {
+        let timed_out = err.chain().any(|e| {
+            matches!(
+                e.downcast_ref::<ockam_core::Error>(),
+                Some(e) if e.code().kind == Kind::Timeout
+            )
+        });
+        if timed_out {
+            ForwarderError::Timeout(err)
+        } else {
+            ForwarderError::Rpc(err)
+        }
+    }
+
+    /// Whether sending the same request again may succeed.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ForwarderError::Unavailable(_))
+    }
+}
+
+fn existing_forwarders(existing: &[String]) -> String {
+    if existing.is_empty() {
+        String::new()
+    } else {
+        format!(", existing forwarders: {}", comma_separated(existing))
+    }
 }
 
 impl ForwarderCommand {
     pub fn run(self, opts: CommandGlobalOpts) {
         match self.subcommand {
             ForwarderSubCommand::Create(c) => c.run(opts),
+            ForwarderSubCommand::Apply(c) => c.run(opts),
+            ForwarderSubCommand::Ping(c) => c.run(opts),
+            ForwarderSubCommand::Export(c) => c.run(opts),
+            ForwarderSubCommand::Import(c) => c.run(opts),
+            ForwarderSubCommand::Rename(c) => c.run(opts),
+            ForwarderSubCommand::Move(c) => c.run(opts),
+            ForwarderSubCommand::Delete(c) => c.run(opts),
+            ForwarderSubCommand::Logs(c) => c.run(opts),
+            ForwarderSubCommand::Doctor(c) => c.run(opts),
+        }
+    }
+
+    /// Run the command within a node which is already running, for
+    /// applications embedding the forwarder commands, instead of
+    /// bootstrapping a node of its own like [`ForwarderCommand::run`].
+    ///
+    /// The nodes given with `--to` are reached through `tcp` when it is
+    /// given, otherwise a new transport is created on `ctx`.
+    pub async fn run_with_context(
+        self,
+        ctx: &Context,
+        opts: CommandGlobalOpts,
+        tcp: Option<&TcpTransport>,
+    ) -> crate::Result<()> {
+        let tcp = match tcp {
+            Some(tcp) => Some(tcp.async_try_clone().await?),
+            None => None,
+        };
+        match self.subcommand {
+            ForwarderSubCommand::Create(c) => c.run_with_context(ctx, opts, tcp).await,
+            ForwarderSubCommand::Apply(c) => c.run_with_context(ctx, opts, tcp).await,
+            ForwarderSubCommand::Ping(c) => c.run_with_context(ctx, opts, tcp).await,
+            ForwarderSubCommand::Export(c) => c.run_with_context(ctx, opts, tcp).await,
+            ForwarderSubCommand::Import(c) => c.run_with_context(ctx, opts, tcp).await,
+            ForwarderSubCommand::Rename(c) => c.run_with_context(ctx, opts, tcp).await,
+            ForwarderSubCommand::Move(c) => c.run_with_context(ctx, opts, tcp).await,
+            ForwarderSubCommand::Delete(c) => c.run_with_context(ctx, opts, tcp).await,
+            ForwarderSubCommand::Logs(c) => c.run_with_context(ctx, opts, tcp).await,
+            ForwarderSubCommand::Doctor(c) => c.run_with_context(ctx, opts, tcp).await,
         }
     }
 }
@@ -0,0 +1,218 @@
+use clap::Args;
+
+use ockam::{Context, TcpTransport};
+
+use crate::forwarder::util::{
+    find_forwarder, ping_forwarder, ping_forwarder_with_size, tcp_transport, ApiNode,
+    ForwarderEntry, PING_PAYLOAD_SIZE,
+};
+use crate::forwarder::{help_detail, ApiOpts};
+use crate::util::{node_rpc, node_rpc_with_context};
+use crate::CommandGlobalOpts;
+use crate::Result;
+
+const HELP_DETAIL: &str = "\
+Examples:
+
+```sh
+    # Check that messages flow through the forwarder
+    $ ockam forwarder ping blue --to /node/blue
+
+    # Find the largest message the route through the forwarder carries
+    $ ockam forwarder ping blue --to /node/blue --probe-size
+```
+";
+
+/// Send an echo message through a forwarder
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    after_long_help = help_detail(HELP_DETAIL)
+)]
+pub struct PingCommand {
+    /// Name or remote address of the forwarder
+    forwarder_name: String,
+
+    /// Node on which the forwarder was created
+    #[arg(long, id = "NODE", display_order = 900)]
+    to: String,
+
+    /// Find the largest payload an echo message through the forwarder can
+    /// carry, halving the range of sizes with each message
+    #[arg(long, display_order = 900)]
+    probe_size: bool,
+
+    /// Largest payload in bytes to try with --probe-size
+    #[arg(long, value_name = "BYTES", display_order = 900, requires = "probe_size", default_value_t = 1024 * 1024, value_parser = clap::value_parser!(u64).range(PING_PAYLOAD_SIZE as u64..))]
+    max_size: u64,
+
+    #[command(flatten)]
+    api: ApiOpts,
+}
+
+impl PingCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self, None));
+    }
+
+    pub(crate) async fn run_with_context(
+        self,
+        ctx: &Context,
+        options: CommandGlobalOpts,
+        tcp: Option<TcpTransport>,
+    ) -> Result<()> {
+        node_rpc_with_context(ctx, rpc, (options, self, tcp)).await
+    }
+}
+
+async fn rpc(
+    ctx: Context,
+    (opts, cmd, tcp): (CommandGlobalOpts, PingCommand, Option<TcpTransport>),
+) -> Result<()> {
+    let tcp = tcp_transport(&ctx, tcp).await?;
+    let api_node = ApiNode::parse(&opts, &cmd.to)?;
+
+    let forwarder =
+        find_forwarder(&ctx, &opts, &tcp, &api_node, &cmd.api, &cmd.forwarder_name).await?;
+    if cmd.probe_size {
+        return probe_size(&ctx, &opts, &tcp, &api_node, &cmd, &forwarder).await;
+    }
+    let elapsed = ping_forwarder(&ctx, &opts, &tcp, &api_node, &cmd.api, &forwarder).await?;
+
+    println!(
+        "Reply from /service/{}: time={:.3}ms",
+        forwarder.remote_address,
+        elapsed.as_secs_f64() * 1000.0
+    );
+    Ok(())
+}
+
+/// Send echo messages with payloads of the sizes [`SizeSearch`] picks, and
+/// print the largest which came back.
+async fn probe_size(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    api_node: &ApiNode,
+    cmd: &PingCommand,
+    forwarder: &ForwarderEntry,
+) -> Result<()> {
+    // Fails like forwarder ping when nothing comes back at all
+    ping_forwarder(ctx, opts, tcp, api_node, &cmd.api, forwarder).await?;
+
+    let mut search = SizeSearch::new(PING_PAYLOAD_SIZE, cmd.max_size as usize);
+    let mut failure = None;
+    while let Some(size) = search.next() {
+        let result =
+            ping_forwarder_with_size(ctx, opts, tcp, api_node, &cmd.api, forwarder, size).await;
+        if !opts.global_args.quiet {
+            match &result {
+                Ok(elapsed) => {
+                    eprintln!("{size} bytes: time={:.3}ms", elapsed.as_secs_f64() * 1000.0)
+                }
+                Err(e) => eprintln!("{size} bytes: {e}"),
+            }
+        }
+        search.record(size, result.is_ok());
+        if let Err(e) = result {
+            failure = Some(e);
+        }
+    }
+
+    let remote_address = &forwarder.remote_address;
+    match search.failed() {
+        None => println!(
+            "Echo messages through /service/{remote_address} carry at least {} bytes",
+            search.largest()
+        ),
+        Some(failed) => {
+            println!(
+                "Echo messages through /service/{remote_address} carry up to {} bytes",
+                search.largest()
+            );
+            if let Some(e) = failure {
+                eprintln!("{failed} bytes failed: {e}");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Binary search of the largest payload size with which an echo message comes
+/// back, between a size known to work and a maximum to try.
+///
+/// The maximum is tried first, so that a route without a limit below it takes
+/// a single message.
+#[derive(Debug)]
+struct SizeSearch {
+    /// Largest size which worked.
+    largest: usize,
+    /// Smallest size which failed, if any.
+    failed: Option<usize>,
+    max: usize,
+}
+
+impl SizeSearch {
+    fn new(works: usize, max: usize) -> Self {
+        Self {
+            largest: works,
+            failed: None,
+            max: max.max(works),
+        }
+    }
+
+    /// The size to try next, if the largest size working isn't known yet.
+    fn next(&self) -> Option<usize> {
+        match self.failed {
+            None if self.largest < self.max => Some(self.max),
+            None => None,
+            Some(failed) if failed - self.largest > 1 => {
+                Some(self.largest + (failed - self.largest) / 2)
+            }
+            Some(_) => None,
+        }
+    }
+
+    fn record(&mut self, size: usize, worked: bool) {
+        if worked {
+            self.largest = self.largest.max(size);
+        } else {
+            self.failed = Some(self.failed.map_or(size, |failed| failed.min(size)));
+        }
+    }
+
+    fn largest(&self) -> usize {
+        self.largest
+    }
+
+    fn failed(&self) -> Option<usize> {
+        self.failed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(works: usize, max: usize, limit: usize) -> (usize, Option<usize>, usize) {
+        let mut search = SizeSearch::new(works, max);
+        let mut tries = 0;
+        while let Some(size) = search.next() {
+            search.record(size, size <= limit);
+            tries += 1;
+        }
+        (search.largest(), search.failed(), tries)
+    }
+
+    #[test]
+    fn size_search() {
+        // No limit below the maximum
+        assert_eq!(search(16, 1024, 4096), (1024, None, 1));
+        assert_eq!(search(16, 16, 4096), (16, None, 0));
+
+        assert_eq!(search(16, 1024, 1000), (1000, Some(1001), 11));
+        assert_eq!(search(16, 1024, 1023), (1023, Some(1024), 11));
+        assert_eq!(search(16, 1024, 16), (16, Some(17), 10));
+        assert_eq!(search(16, 1 << 20, 65535).0, 65535);
+    }
+}
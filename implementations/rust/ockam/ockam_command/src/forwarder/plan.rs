@@ -0,0 +1,315 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context as _};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+use ockam::identity::IdentityIdentifier;
+use ockam::{Context, TcpTransport};
+use ockam_abac::Expr;
+use ockam_api::nodes::models::forwarder::{CreateForwarder, ForwarderInfo, Upstream};
+use ockam_core::api::Request;
+use ockam_multiaddr::MultiAddr;
+
+use crate::forwarder::util::{
+    check_available, check_conflict, delete_forwarder, forwarder_rpc, list_forwarders_if_supported,
+    tcp_transport, ApiNode,
+};
+use crate::forwarder::{help_detail, ApiOpts, ForwarderError};
+use crate::util::{comma_separated, node_rpc, node_rpc_with_context};
+use crate::Result;
+use crate::{exitcode, CommandGlobalOpts};
+
+/// What `forwarder create --plan-out` would create, with its `--at` resolved,
+/// for `forwarder apply` to create exactly that later.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ForwarderPlan {
+    /// Node for which to create the forwarder, the one of --to which
+    /// responded.
+    pub(crate) to: String,
+    /// Address under which the forwarder is registered.
+    pub(crate) alias: String,
+    /// Resolved route to the node at which to create the forwarder.
+    pub(crate) at: MultiAddr,
+    /// Whether `at` is a project, rather than a node.
+    #[serde(default)]
+    pub(crate) at_project: bool,
+    /// Whether `at` is a rust node.
+    #[serde(default)]
+    pub(crate) at_rust_node: bool,
+    /// Routes the forwarder balances messages across, with several --at.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) upstreams: Vec<PlannedUpstream>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) authorized: Option<IdentityIdentifier>,
+    #[serde(default)]
+    pub(crate) wildcard: bool,
+    /// Seconds after which the node deletes the forwarder.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) expires_in: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) access_policy: Option<String>,
+    /// Idle seconds after which the connection to `at` is probed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) keepalive: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) idempotency_key: Option<String>,
+    /// Existing forwarder to delete first, with --on-conflict replace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) replaces: Option<String>,
+    /// Remote addresses of the forwarders the node had when planning.
+    #[serde(default)]
+    pub(crate) existing: Vec<String>,
+}
+
+/// A route of [`ForwarderPlan::upstreams`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct PlannedUpstream {
+    pub(crate) route: MultiAddr,
+    pub(crate) weight: u32,
+}
+
+impl ForwarderPlan {
+    /// The request creating the planned forwarder.
+    pub(crate) fn body(&self) -> std::result::Result<CreateForwarder<'static>, ForwarderError> {
+        let mut body = if self.at_project {
+            if self.authorized.is_some() {
+                return Err(ForwarderError::InvalidArgument(anyhow!(
+                    "--authorized can not be used with project addresses"
+                )));
+            }
+            CreateForwarder::at_project(self.at.clone(), Some(self.alias.clone()))
+        } else {
+            CreateForwarder::at_node(
+                self.at.clone(),
+                Some(self.alias.clone()),
+                self.at_rust_node,
+                self.authorized.clone(),
+            )
+        };
+        let access_policy = match &self.access_policy {
+            Some(expr) => Some(
+                Expr::from_str(expr)
+                    .with_context(|| format!("invalid access policy {expr}"))
+                    .map_err(ForwarderError::InvalidArgument)?,
+            ),
+            None => None,
+        };
+        body.set_expires_in(self.expires_in.map(Duration::from_secs));
+        body.set_wildcard(self.wildcard);
+        body.set_upstreams(
+            self.upstreams
+                .iter()
+                .map(|u| Upstream::new(u.route.clone(), u.weight))
+                .collect(),
+        );
+        body.set_access_policy(access_policy);
+        body.set_keepalive(self.keepalive.map(Duration::from_secs));
+        body.set_idempotency_key(self.idempotency_key.clone());
+        Ok(body)
+    }
+
+    /// How the `existing` forwarders of the node differ from those it had
+    /// when planning, if they do.
+    fn changes(&self, existing: &[String]) -> Option<String> {
+        let added: Vec<_> = existing
+            .iter()
+            .filter(|f| !self.existing.contains(f))
+            .collect();
+        let removed: Vec<_> = self
+            .existing
+            .iter()
+            .filter(|f| !existing.contains(f))
+            .collect();
+        let mut changes = Vec::new();
+        if !added.is_empty() {
+            changes.push(format!("created {}", comma_separated(&added)));
+        }
+        if !removed.is_empty() {
+            changes.push(format!("deleted {}", comma_separated(&removed)));
+        }
+        if changes.is_empty() {
+            None
+        } else {
+            Some(changes.join("; "))
+        }
+    }
+
+    pub(crate) fn read(path: &Path) -> Result<Self> {
+        let s = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read the plan {}", path.display()))
+            .map_err(|e| crate::Error::new(exitcode::IOERR, e))?;
+        let plan = serde_json::from_str(&s)
+            .with_context(|| format!("invalid plan {}", path.display()))
+            .map_err(|e| crate::Error::new(exitcode::DATAERR, e))?;
+        Ok(plan)
+    }
+
+    /// Replace the file at `path` atomically, writing a temporary file next
+    /// to it and renaming it.
+    pub(crate) fn write(&self, path: &Path) -> Result<()> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+        let write = || -> std::io::Result<()> {
+            let mut file = std::fs::File::create(&tmp)?;
+            file.write_all(&serde_json::to_vec_pretty(self)?)?;
+            file.write_all(b"\n")?;
+            file.sync_all()?;
+            std::fs::rename(&tmp, path)
+        };
+        write().map_err(|e| {
+            let e = anyhow::Error::new(e)
+                .context(format!("failed to write the plan {}", path.display()));
+            crate::Error::new(exitcode::IOERR, e)
+        })
+    }
+}
+
+const HELP_DETAIL: &str = "\
+Examples:
+
+```sh
+    # Review what a forwarder create would do, then do exactly that
+    $ ockam forwarder create blue --at /node/green --to /node/blue --plan-out plan.json
+    $ ockam forwarder apply --plan plan.json
+    /service/forward_to_blue
+```
+";
+
+/// Create the forwarder planned by `forwarder create --plan-out`
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    after_long_help = help_detail(HELP_DETAIL)
+)]
+pub struct ApplyCommand {
+    /// File written by `forwarder create --plan-out`
+    #[arg(long, value_name = "FILE", display_order = 900)]
+    plan: PathBuf,
+
+    #[command(flatten)]
+    api: ApiOpts,
+}
+
+impl ApplyCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self, None));
+    }
+
+    pub(crate) async fn run_with_context(
+        self,
+        ctx: &Context,
+        options: CommandGlobalOpts,
+        tcp: Option<TcpTransport>,
+    ) -> Result<()> {
+        node_rpc_with_context(ctx, rpc, (options, self, tcp)).await
+    }
+}
+
+async fn rpc(
+    ctx: Context,
+    (opts, cmd, tcp): (CommandGlobalOpts, ApplyCommand, Option<TcpTransport>),
+) -> Result<()> {
+    let plan = ForwarderPlan::read(&cmd.plan)?;
+    let body = plan.body()?;
+
+    let tcp = tcp_transport(&ctx, tcp).await?;
+    let api_node = ApiNode::parse(&opts, &plan.to)?;
+    let existing: Vec<String> =
+        list_forwarders_if_supported(&ctx, &opts, &tcp, &api_node, &cmd.api)
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|f| f.remote_address)
+            .collect();
+    if let Some(changes) = plan.changes(&existing) {
+        eprintln!(
+            "Warning: the forwarders of node {} changed since the plan was made: {changes}",
+            plan.to
+        );
+    }
+
+    if let Some(replaced) = &plan.replaces {
+        if existing.contains(replaced) {
+            delete_forwarder(&ctx, &opts, &tcp, &api_node, &cmd.api, replaced).await?;
+        }
+    }
+    let mut rpc = forwarder_rpc(&ctx, &opts, &tcp, &api_node, &cmd.api)?;
+    rpc.request(Request::post("/node/forwarder").body(body))
+        .await
+        .map_err(ForwarderError::from_rpc)?;
+    check_available(&rpc)?;
+    check_conflict(&rpc)?;
+    let info = rpc
+        .parse_response::<ForwarderInfo>()
+        .map_err(ForwarderError::Rpc)?;
+    rpc.print_response(info).map_err(ForwarderError::Rpc)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan() -> ForwarderPlan {
+        ForwarderPlan {
+            to: "blue".into(),
+            alias: "forward_to_blue".into(),
+            at: "/ip4/127.0.0.1/tcp/4000".parse().unwrap(),
+            at_project: false,
+            at_rust_node: true,
+            upstreams: vec![],
+            authorized: None,
+            wildcard: false,
+            expires_in: Some(60),
+            access_policy: Some(r#"(= subject.component "web")"#.into()),
+            keepalive: None,
+            idempotency_key: None,
+            replaces: None,
+            existing: vec!["forward_to_red".into()],
+        }
+    }
+
+    #[test]
+    fn plan_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plan.json");
+        plan().write(&path).unwrap();
+        assert_eq!(ForwarderPlan::read(&path).unwrap(), plan());
+
+        std::fs::write(&path, "{").unwrap();
+        assert!(ForwarderPlan::read(&path).is_err());
+    }
+
+    #[test]
+    fn plan_body() {
+        let body = plan().body().unwrap();
+        assert_eq!(body.alias(), Some("forward_to_blue"));
+        assert!(body.at_rust_node());
+        assert_eq!(body.expires_in(), Some(Duration::from_secs(60)));
+        assert!(body.access_policy().is_some());
+
+        let mut plan = plan();
+        plan.access_policy = Some("(= subject.component".into());
+        assert!(plan.body().is_err());
+    }
+
+    #[test]
+    fn node_changes() {
+        let plan = plan();
+        assert_eq!(plan.changes(&["forward_to_red".into()]), None);
+        assert_eq!(
+            plan.changes(&["forward_to_red".into(), "forward_to_blue".into()])
+                .unwrap(),
+            "created forward_to_blue"
+        );
+        assert_eq!(
+            plan.changes(&["forward_to_green".into()]).unwrap(),
+            "created forward_to_green; deleted forward_to_red"
+        );
+    }
+}
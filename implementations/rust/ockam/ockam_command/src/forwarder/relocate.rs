@@ -0,0 +1,107 @@
+use anyhow::anyhow;
+use clap::Args;
+
+use ockam::{Context, TcpTransport};
+
+use crate::forwarder::export::ExportedForwarder;
+use crate::forwarder::import::{exists, import};
+use crate::forwarder::util::{
+    delete_forwarder, find_forwarder, list_forwarders, tcp_transport, ApiNode,
+};
+use crate::forwarder::{help_detail, ApiOpts, ForwarderError};
+use crate::util::{node_rpc, node_rpc_with_context};
+use crate::CommandGlobalOpts;
+use crate::Result;
+
+const HELP_DETAIL: &str = "\
+Examples:
+
+```sh
+    # Move the forwarder called blue from node blue to node purple, through the same relay
+    $ ockam forwarder move blue --from /node/blue --to /node/purple
+    /service/forward_to_blue
+```
+";
+
+/// Move a forwarder to another node
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    after_long_help = help_detail(HELP_DETAIL)
+)]
+pub struct MoveCommand {
+    /// Name or remote address of the forwarder
+    name: String,
+
+    /// Node on which the forwarder was created
+    #[arg(long, value_name = "NODE", display_order = 900)]
+    from: String,
+
+    /// Node for which to create the forwarder instead
+    #[arg(long, id = "NODE", display_order = 900)]
+    to: String,
+
+    #[command(flatten)]
+    api: ApiOpts,
+}
+
+impl MoveCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self, None));
+    }
+
+    pub(crate) async fn run_with_context(
+        self,
+        ctx: &Context,
+        options: CommandGlobalOpts,
+        tcp: Option<TcpTransport>,
+    ) -> Result<()> {
+        node_rpc_with_context(ctx, rpc, (options, self, tcp)).await
+    }
+}
+
+async fn rpc(
+    ctx: Context,
+    (opts, cmd, tcp): (CommandGlobalOpts, MoveCommand, Option<TcpTransport>),
+) -> Result<()> {
+    let tcp = tcp_transport(&ctx, tcp).await?;
+    let from = ApiNode::parse(&opts, &cmd.from)?;
+    let to = ApiNode::parse(&opts, &cmd.to)?;
+
+    let entry = find_forwarder(&ctx, &opts, &tcp, &from, &cmd.api, &cmd.name).await?;
+    let f = ExportedForwarder::of_entry(&entry)?;
+    let existing = list_forwarders(&ctx, &opts, &tcp, &to, &cmd.api).await?;
+    if exists(&existing, &f) {
+        return Err(ForwarderError::InvalidArgument(anyhow!(
+            "node {} already has forwarder {}",
+            cmd.to,
+            cmd.name
+        ))
+        .into());
+    }
+
+    let remote_address = import(&ctx, &opts, &tcp, &to, &cmd.api, &f).await?;
+    if let Err(e) =
+        delete_forwarder(&ctx, &opts, &tcp, &from, &cmd.api, &entry.remote_address).await
+    {
+        let rollback = delete_forwarder(&ctx, &opts, &tcp, &to, &cmd.api, &remote_address).await;
+        let outcome = match rollback {
+            Ok(()) => format!(
+                "forwarder {remote_address} created for node {} was deleted again",
+                cmd.to
+            ),
+            Err(rollback) => format!(
+                "deleting forwarder {remote_address} created for node {} failed too: {rollback}",
+                cmd.to
+            ),
+        };
+        return Err(ForwarderError::Rpc(anyhow!(
+            "failed to delete forwarder {} of node {}: {e}; {outcome}",
+            entry.remote_address,
+            cmd.from
+        ))
+        .into());
+    }
+    println!("/service/{remote_address}");
+    Ok(())
+}
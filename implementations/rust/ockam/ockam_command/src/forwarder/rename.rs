@@ -0,0 +1,125 @@
+use anyhow::anyhow;
+use clap::Args;
+
+use ockam::{Context, TcpTransport};
+use ockam_api::nodes::models::forwarder::{
+    is_valid_remote_address, ForwarderInfo, ForwarderKind, RenameForwarder,
+};
+use ockam_core::api::{Request, Status};
+
+use crate::forwarder::util::{
+    check_available, forwarder_alias, forwarder_name, forwarder_rpc, list_forwarders,
+    tcp_transport, ApiNode, FORWARD_TO_PREFIX,
+};
+use crate::forwarder::{help_detail, ApiOpts, ForwarderError};
+use crate::util::{node_rpc, node_rpc_with_context};
+use crate::CommandGlobalOpts;
+use crate::Result;
+
+const HELP_DETAIL: &str = "\
+Examples:
+
+```sh
+    # Give the forwarder called blue another name
+    $ ockam forwarder rename blue web --to /node/blue
+    /service/forward_to_web
+```
+";
+
+/// Give a forwarder another name
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    after_long_help = help_detail(HELP_DETAIL)
+)]
+pub struct RenameCommand {
+    /// Name or remote address of the forwarder
+    old_name: String,
+
+    /// New name of the forwarder
+    new_name: String,
+
+    /// Node on which the forwarder was created
+    #[arg(long, id = "NODE", display_order = 900)]
+    to: String,
+
+    #[command(flatten)]
+    api: ApiOpts,
+}
+
+impl RenameCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self, None));
+    }
+
+    pub(crate) async fn run_with_context(
+        self,
+        ctx: &Context,
+        options: CommandGlobalOpts,
+        tcp: Option<TcpTransport>,
+    ) -> Result<()> {
+        node_rpc_with_context(ctx, rpc, (options, self, tcp)).await
+    }
+}
+
+async fn rpc(
+    ctx: Context,
+    (opts, cmd, tcp): (CommandGlobalOpts, RenameCommand, Option<TcpTransport>),
+) -> Result<()> {
+    let name = forwarder_name(&cmd.new_name)?;
+    if !is_valid_remote_address(name) {
+        return Err(ForwarderError::InvalidArgument(anyhow!(
+            "'{name}' can not be used in a /service address"
+        ))
+        .into());
+    }
+
+    let tcp = tcp_transport(&ctx, tcp).await?;
+    let api_node = ApiNode::parse(&opts, &cmd.to)?;
+    let existing = list_forwarders(&ctx, &opts, &tcp, &api_node, &cmd.api).await?;
+    let forwarder = match existing.iter().find(|f| f.is_named(&cmd.old_name)) {
+        Some(f) => f,
+        None => {
+            return Err(ForwarderError::NotFound {
+                name: cmd.old_name,
+                existing: existing.into_iter().map(|f| f.remote_address).collect(),
+            }
+            .into())
+        }
+    };
+    if forwarder.kind.map_or(false, |k| k != ForwarderKind::Static) {
+        return Err(ForwarderError::InvalidArgument(anyhow!(
+            "forwarder {} has no name to change",
+            cmd.old_name
+        ))
+        .into());
+    }
+    if existing.iter().any(|f| f.is_named(name)) {
+        return Err(
+            ForwarderError::InvalidArgument(anyhow!("forwarder {name} already exists")).into(),
+        );
+    }
+
+    // Keep the prefix of the forwarders created at nodes
+    let alias = forwarder_alias(
+        name,
+        forwarder.remote_address.starts_with(FORWARD_TO_PREFIX),
+    )?;
+    let req = Request::put(format!("/node/forwarder/{}", forwarder.remote_address))
+        .body(RenameForwarder::new(alias));
+    let mut rpc = forwarder_rpc(&ctx, &opts, &tcp, &api_node, &cmd.api)?;
+    rpc.request(req).await.map_err(ForwarderError::from_rpc)?;
+    check_available(&rpc)?;
+    if let Ok((hdr, dec)) = rpc.check_response() {
+        if hdr.status() == Some(Status::Conflict) {
+            return Err(
+                ForwarderError::InvalidArgument(anyhow!(rpc.parse_err_msg(hdr, dec))).into(),
+            );
+        }
+    }
+    let info = rpc
+        .parse_response::<ForwarderInfo>()
+        .map_err(ForwarderError::Rpc)?;
+    rpc.print_response(info).map_err(ForwarderError::Rpc)?;
+    Ok(())
+}
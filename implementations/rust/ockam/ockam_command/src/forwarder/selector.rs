@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use crate::forwarder::template::Tag;
+use crate::CommandGlobalOpts;
+
+/// A `KEY=VALUE` given with `node create --tag`, e.g. `env=prod`.
+pub(crate) fn node_tag(s: &str) -> anyhow::Result<(String, String)> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| anyhow!("expected KEY=VALUE, got '{s}'"))?;
+    Tag::check_key(key)?;
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// A requirement of a [`LabelSelector`] on the tags of a node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Requirement {
+    /// `KEY=VALUE`
+    Equals(String, String),
+    /// `KEY!=VALUE`, also met by nodes without the tag
+    NotEquals(String, String),
+    /// `KEY`
+    Exists(String),
+    /// `!KEY`
+    NotExists(String),
+}
+
+impl Requirement {
+    fn is_met(&self, tags: &BTreeMap<String, String>) -> bool {
+        match self {
+            Requirement::Equals(key, value) => tags.get(key) == Some(value),
+            Requirement::NotEquals(key, value) => tags.get(key) != Some(value),
+            Requirement::Exists(key) => tags.contains_key(key),
+            Requirement::NotExists(key) => !tags.contains_key(key),
+        }
+    }
+}
+
+/// `--label-selector`, e.g. `env=prod,region=us`, selecting the nodes
+/// created with `node create --tag` whose tags meet every requirement.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct LabelSelector {
+    selector: String,
+    requirements: Vec<Requirement>,
+}
+
+impl LabelSelector {
+    pub(crate) fn matches(&self, tags: &BTreeMap<String, String>) -> bool {
+        self.requirements.iter().all(|r| r.is_met(tags))
+    }
+
+    /// Names of the nodes of the configuration which match, in order.
+    pub(crate) fn select(&self, opts: &CommandGlobalOpts) -> Vec<String> {
+        opts.config
+            .inner()
+            .nodes
+            .iter()
+            .filter(|(_, node)| self.matches(node.tags()))
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+}
+
+impl FromStr for LabelSelector {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut requirements = Vec::new();
+        for part in s.split(',').map(str::trim) {
+            let requirement = if let Some((key, value)) = part.split_once("!=") {
+                Tag::check_key(key)?;
+                Requirement::NotEquals(key.to_string(), value.to_string())
+            } else if let Some((key, value)) = part.split_once('=') {
+                Tag::check_key(key)?;
+                Requirement::Equals(key.to_string(), value.to_string())
+            } else if let Some(key) = part.strip_prefix('!') {
+                Tag::check_key(key)?;
+                Requirement::NotExists(key.to_string())
+            } else {
+                Tag::check_key(part)?;
+                Requirement::Exists(part.to_string())
+            };
+            requirements.push(requirement);
+        }
+        Ok(LabelSelector {
+            selector: s.to_string(),
+            requirements,
+        })
+    }
+}
+
+impl fmt::Display for LabelSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.selector)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(tags: &[(&str, &str)]) -> BTreeMap<String, String> {
+        tags.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_selectors() {
+        let selector: LabelSelector = "env=prod, region!=eu,gpu,!canary".parse().unwrap();
+        assert_eq!(
+            selector.requirements,
+            vec![
+                Requirement::Equals("env".into(), "prod".into()),
+                Requirement::NotEquals("region".into(), "eu".into()),
+                Requirement::Exists("gpu".into()),
+                Requirement::NotExists("canary".into()),
+            ]
+        );
+        for s in ["", "env=prod,", "=prod", "e nv=prod", "!"] {
+            assert!(s.parse::<LabelSelector>().is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn match_tags() {
+        let selector: LabelSelector = "env=prod,region=us".parse().unwrap();
+        assert!(selector.matches(&tags(&[("env", "prod"), ("region", "us")])));
+        assert!(selector.matches(&tags(&[("env", "prod"), ("region", "us"), ("gpu", "")])));
+        assert!(!selector.matches(&tags(&[("env", "prod")])));
+        assert!(!selector.matches(&tags(&[("env", "dev"), ("region", "us")])));
+
+        let selector: LabelSelector = "region!=eu,!canary".parse().unwrap();
+        assert!(selector.matches(&tags(&[])));
+        assert!(!selector.matches(&tags(&[("region", "eu")])));
+        assert!(!selector.matches(&tags(&[("canary", "true")])));
+    }
+
+    #[test]
+    fn node_tags() {
+        assert_eq!(
+            node_tag("env=prod").unwrap(),
+            ("env".to_string(), "prod".to_string())
+        );
+        assert_eq!(
+            node_tag("name=blue").unwrap(),
+            ("name".to_string(), "blue".to_string())
+        );
+        assert!(node_tag("env").is_err());
+        assert!(node_tag("=prod").is_err());
+    }
+}
@@ -0,0 +1,317 @@
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use ockam_api::nodes::models::forwarder::{ForwarderInfo, ForwarderKind};
+
+use crate::forwarder::util::FORWARD_TO_PREFIX;
+
+/// The line printed after creating a forwarder, given with
+/// `--format-template`, e.g. `{name} -> {remote_address}`.
+///
+/// `{{` and `}}` stand for literal braces.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormatTemplate {
+    parts: Vec<Part<Placeholder>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Part<P> {
+    Literal(String),
+    Placeholder(P),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Placeholder {
+    Name,
+    RemoteAddress,
+    Address,
+    WorkerAddress,
+    ForwardingRoute,
+    Kind,
+    BalancerAddress,
+    Node,
+}
+
+impl Placeholder {
+    const ALL: [(&'static str, Placeholder); 8] = [
+        ("name", Placeholder::Name),
+        ("remote_address", Placeholder::RemoteAddress),
+        ("address", Placeholder::Address),
+        ("worker_address", Placeholder::WorkerAddress),
+        ("forwarding_route", Placeholder::ForwardingRoute),
+        ("kind", Placeholder::Kind),
+        ("balancer_address", Placeholder::BalancerAddress),
+        ("node", Placeholder::Node),
+    ];
+
+    fn parse(name: &str) -> anyhow::Result<Self> {
+        match Self::ALL.iter().find(|(n, _)| *n == name) {
+            Some((_, p)) => Ok(*p),
+            None => {
+                let known: Vec<_> = Self::ALL.iter().map(|(n, _)| format!("{{{n}}}")).collect();
+                Err(anyhow!(
+                    "unknown placeholder {{{name}}}, expected one of {}",
+                    known.join(", ")
+                ))
+            }
+        }
+    }
+}
+
+impl FromStr for FormatTemplate {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(FormatTemplate {
+            parts: parse(s, Placeholder::parse)?,
+        })
+    }
+}
+
+/// Split a template into its literals and its placeholders, which are
+/// checked by `placeholder`.
+fn parse<P>(
+    s: &str,
+    placeholder: impl Fn(&str) -> anyhow::Result<P>,
+) -> anyhow::Result<Vec<Part<P>>> {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some('{') | None => {
+                            return Err(anyhow!(
+                                "unclosed placeholder {{{name}, use {{{{ for a literal {{"
+                            ))
+                        }
+                        Some(c) => name.push(c),
+                    }
+                }
+                if !literal.is_empty() {
+                    parts.push(Part::Literal(std::mem::take(&mut literal)));
+                }
+                parts.push(Part::Placeholder(placeholder(&name)?));
+            }
+            '}' => return Err(anyhow!("unmatched }}, use }}}} for a literal }}")),
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        parts.push(Part::Literal(literal));
+    }
+    Ok(parts)
+}
+
+impl FormatTemplate {
+    /// Render the template for the forwarder `info` created for `node`.
+    ///
+    /// Placeholders without a value, e.g. `{balancer_address}` for a
+    /// forwarder which isn't balanced, render as nothing.
+    pub fn render(&self, info: &ForwarderInfo, node: &str) -> String {
+        self.render_with(|p| {
+            let remote_address = info.remote_address();
+            match p {
+                Placeholder::Name => remote_address
+                    .strip_prefix(FORWARD_TO_PREFIX)
+                    .unwrap_or(remote_address)
+                    .to_string(),
+                Placeholder::RemoteAddress => remote_address.to_string(),
+                Placeholder::Address => format!("/service/{remote_address}"),
+                Placeholder::WorkerAddress => info.worker_address().to_string(),
+                Placeholder::ForwardingRoute => info.forwarding_route().to_string(),
+                Placeholder::Kind => kind(info.kind()).to_string(),
+                Placeholder::BalancerAddress => {
+                    info.balancer_address().unwrap_or_default().to_string()
+                }
+                Placeholder::Node => node.to_string(),
+            }
+        })
+    }
+
+    fn render_with(&self, value: impl Fn(Placeholder) -> String) -> String {
+        let mut line = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(s) => line.push_str(s),
+                Part::Placeholder(p) => line.push_str(&value(*p)),
+            }
+        }
+        line
+    }
+}
+
+/// The alias a forwarder is registered under, given with `--alias-template`,
+/// e.g. `{env}-forward-{name}`.
+///
+/// `{name}` is the name of the forwarder, and the other placeholders are
+/// filled with the values of the `--tag`s having their name as key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AliasTemplate {
+    parts: Vec<Part<String>>,
+}
+
+impl FromStr for AliasTemplate {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = parse(s, |key| {
+            Tag::check_key(key)?;
+            Ok(key.to_string())
+        })?;
+        Ok(AliasTemplate { parts })
+    }
+}
+
+impl AliasTemplate {
+    /// Render the alias of the forwarder called `name`, failing when a
+    /// placeholder has no tag.
+    pub fn render(&self, name: &str, tags: &[Tag]) -> anyhow::Result<String> {
+        let mut alias = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(s) => alias.push_str(s),
+                Part::Placeholder(key) if key == "name" => alias.push_str(name),
+                Part::Placeholder(key) => match tags.iter().rev().find(|t| &t.key == key) {
+                    Some(tag) => alias.push_str(&tag.value),
+                    None => return Err(anyhow!("no --tag {key}=<VALUE> for {{{key}}}")),
+                },
+            }
+        }
+        Ok(alias)
+    }
+}
+
+/// A `KEY=VALUE` given with `--tag`, e.g. `env=prod`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tag {
+    key: String,
+    value: String,
+}
+
+impl Tag {
+    /// Keys are made of ASCII letters, digits, `_` and `-`, and `name` is
+    /// taken by the name of the forwarder.
+    pub(crate) fn check_key(key: &str) -> anyhow::Result<()> {
+        let valid = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+        if key.is_empty() || !key.chars().all(valid) {
+            Err(anyhow!(
+                "invalid tag key '{key}', use ASCII letters, digits, _ and -"
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl FromStr for Tag {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = s
+            .split_once('=')
+            .ok_or_else(|| anyhow!("expected KEY=VALUE, got '{s}'"))?;
+        Tag::check_key(key)?;
+        if key == "name" {
+            return Err(anyhow!(
+                "{{name}} is the name of the forwarder, it can't be a tag"
+            ));
+        }
+        Ok(Tag {
+            key: key.to_string(),
+            value: value.to_string(),
+        })
+    }
+}
+
+fn kind(kind: Option<ForwarderKind>) -> &'static str {
+    match kind {
+        Some(ForwarderKind::Static) => "static",
+        Some(ForwarderKind::Ephemeral) => "ephemeral",
+        Some(ForwarderKind::Wildcard) => "wildcard",
+        None => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(template: &str) -> anyhow::Result<String> {
+        let template = FormatTemplate::from_str(template)?;
+        Ok(template.render_with(|p| match p {
+            Placeholder::Name => "blue".to_string(),
+            Placeholder::RemoteAddress => "forward_to_blue".to_string(),
+            Placeholder::Kind => kind(Some(ForwarderKind::Static)).to_string(),
+            Placeholder::BalancerAddress => String::new(),
+            p => format!("{p:?}"),
+        }))
+    }
+
+    #[test]
+    fn render_placeholders() {
+        assert_eq!(
+            render("{name} -> {remote_address} (kind={kind})").unwrap(),
+            "blue -> forward_to_blue (kind=static)"
+        );
+        assert_eq!(render("{node}:{balancer_address}.").unwrap(), "Node:.");
+        assert_eq!(render("").unwrap(), "");
+    }
+
+    #[test]
+    fn escaped_braces() {
+        assert_eq!(render("{{{name}}}").unwrap(), "{blue}");
+        assert_eq!(render("{{name}}").unwrap(), "{name}");
+    }
+
+    fn tags(tags: &[&str]) -> Vec<Tag> {
+        tags.iter().map(|t| t.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn alias_templates() {
+        let template = AliasTemplate::from_str("{env}-forward-{name}").unwrap();
+        let alias = template.render("web", &tags(&["env=prod"])).unwrap();
+        assert_eq!(alias, "prod-forward-web");
+        // The last tag with a key wins
+        let alias = template
+            .render("web", &tags(&["env=dev", "env=prod"]))
+            .unwrap();
+        assert_eq!(alias, "prod-forward-web");
+        assert!(template.render("web", &[]).is_err());
+
+        let template = AliasTemplate::from_str("{{{name}}}").unwrap();
+        assert_eq!(template.render("web", &[]).unwrap(), "{web}");
+        for template in ["{}", "{a b}", "{name", "name}"] {
+            assert!(AliasTemplate::from_str(template).is_err(), "{template}");
+        }
+    }
+
+    #[test]
+    fn invalid_tags() {
+        for tag in ["env", "=prod", "name=web", "e.v=prod"] {
+            assert!(Tag::from_str(tag).is_err(), "{tag}");
+        }
+        assert_eq!(tags(&["env="])[0].value, "");
+        assert_eq!(tags(&["url=a=b"])[0].value, "a=b");
+    }
+
+    #[test]
+    fn invalid_templates() {
+        for template in ["{mode}", "{name", "{na{me}", "name}", "{}"] {
+            assert!(FormatTemplate::from_str(template).is_err(), "{template}");
+        }
+    }
+}
@@ -0,0 +1,888 @@
+use std::fmt;
+use std::future::Future;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context as _};
+use atty::Stream;
+use rand::prelude::random;
+
+use ockam::{Context, TcpTransport};
+use ockam_api::nodes::models::base::NodeVersion;
+use ockam_api::nodes::models::credentials::CredentialStatus;
+use ockam_api::nodes::models::forwarder::{DrainStatus, ForwarderKind, ForwarderList};
+use ockam_api::nodes::service::message::SendMessage;
+use ockam_api::nodes::NODEMANAGER_ADDR;
+use ockam_api::DefaultAddress;
+use ockam_core::api::{Request, Response, Status};
+use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Node, Service, Sni, Tcp, Tls};
+use ockam_multiaddr::{MultiAddr, MultiAddrBuilder, Protocol};
+use tokio_retry::strategy::ExponentialBackoff;
+use tokio_retry::RetryIf;
+use tracing::debug;
+
+use crate::forwarder::{ApiOpts, ForwarderError};
+use crate::util::{extract_address_value, Rpc, RpcBuilder};
+use crate::CommandGlobalOpts;
+
+/// Prefix of the remote address of forwarders created at rust nodes.
+pub(crate) const FORWARD_TO_PREFIX: &str = "forward_to_";
+
+/// Addresses of the services started by nodes and relays. Forwarders
+/// registered without the [`FORWARD_TO_PREFIX`], as happens at projects,
+/// must not use them as names as they would clash with these services.
+pub(crate) const RESERVED_NAMES: &[&str] = &[
+    DefaultAddress::VAULT_SERVICE,
+    DefaultAddress::IDENTITY_SERVICE,
+    DefaultAddress::AUTHENTICATED_SERVICE,
+    DefaultAddress::UPPERCASE_SERVICE,
+    DefaultAddress::ECHO_SERVICE,
+    DefaultAddress::CREDENTIAL_SERVICE,
+    DefaultAddress::SECURE_CHANNEL_LISTENER,
+    DefaultAddress::AUTHENTICATOR,
+    DefaultAddress::VERIFIER,
+    DefaultAddress::OKTA_IDENTITY_PROVIDER,
+    NODEMANAGER_ADDR,
+    "forwarding_service",
+    "static_forwarding_service",
+];
+
+/// Check the name of a forwarder to create.
+///
+/// The [`FORWARD_TO_PREFIX`] is stripped as it is added again when needed.
+pub(crate) fn forwarder_name(name: &str) -> Result<&str, ForwarderError> {
+    let stripped = name.strip_prefix(FORWARD_TO_PREFIX).unwrap_or(name);
+    if stripped.is_empty() {
+        return Err(ForwarderError::InvalidArgument(anyhow!(
+            "the forwarder name can not be just '{FORWARD_TO_PREFIX}'"
+        )));
+    }
+    Ok(stripped)
+}
+
+/// The alias under which the forwarder called `name` is registered, with
+/// the [`FORWARD_TO_PREFIX`] at nodes and without it at projects.
+///
+/// Only aliases without the prefix can clash with a node service, so a
+/// reserved name is rejected for those alone.
+pub(crate) fn forwarder_alias(name: &str, prefixed: bool) -> Result<String, ForwarderError> {
+    if prefixed {
+        return Ok(format!("{FORWARD_TO_PREFIX}{name}"));
+    }
+    if RESERVED_NAMES.contains(&name) {
+        return Err(ForwarderError::InvalidArgument(anyhow!(
+            "'{name}' is reserved for a node service and can not be used as a forwarder name"
+        )));
+    }
+    Ok(name.to_string())
+}
+
+/// A forwarder known to a node.
+pub(crate) struct ForwarderEntry {
+    pub(crate) remote_address: String,
+    /// Route from the node to the forwarder, if it can be expressed as a multiaddr.
+    pub(crate) route: Option<MultiAddr>,
+    /// Missing for forwarders listed by nodes predating it.
+    pub(crate) kind: Option<ForwarderKind>,
+}
+
+impl ForwarderEntry {
+    /// Whether this forwarder is the one called `name`, either by its
+    /// remote address or by the name it was created with.
+    pub(crate) fn is_named(&self, name: &str) -> bool {
+        self.remote_address == name
+            || self.remote_address.strip_prefix(FORWARD_TO_PREFIX) == Some(name)
+    }
+}
+
+/// Replace the `/node/<NAME>` hops of `route` with the addresses of these
+/// nodes, checking that the result is a valid route.
+///
+/// With `--verbose`, an error resolving a hop tells which one failed and the
+/// part of the route resolved before it.
+pub(crate) fn resolve_nodes(
+    opts: &CommandGlobalOpts,
+    route: &MultiAddr,
+) -> Result<MultiAddr, ForwarderError> {
+    let lookup = opts.config.lookup();
+
+    let mut ma = MultiAddr::default();
+
+    for (index, proto) in route.iter().enumerate() {
+        let resolved = match proto.code() {
+            Node::CODE => proto
+                .cast::<Node>()
+                .ok_or_else(|| {
+                    ForwarderError::InvalidArgument(anyhow!("invalid node address protocol"))
+                })
+                .and_then(|alias| {
+                    lookup
+                        .node_address(&alias)
+                        .ok_or_else(|| ForwarderError::UnknownNode(alias.to_string()))
+                })
+                .and_then(|addr| {
+                    ma.try_extend(addr.iter())
+                        .map_err(|e| ForwarderError::InvalidArgument(e.into()))
+                }),
+            _ => ma
+                .push_back_value(&proto)
+                .map_err(|e| ForwarderError::InvalidArgument(e.into())),
+        };
+        if let Err(e) = resolved {
+            if opts.global_args.verbose > 0 {
+                return Err(partially_resolved(e, route, index, &ma));
+            }
+            return Err(e);
+        }
+    }
+    MultiAddrBuilder::new()
+        .extend(&ma)
+        .build()
+        .map_err(|e| ForwarderError::InvalidArgument(e.into()))
+}
+
+/// Add to `err`, raised resolving the hop at `index` of `route`, the part of
+/// the route resolved so far. The exit code stays the same.
+fn partially_resolved(
+    err: ForwarderError,
+    route: &MultiAddr,
+    index: usize,
+    resolved: &MultiAddr,
+) -> ForwarderError {
+    let hops = route.iter().count();
+    let hop = MultiAddr::default()
+        .try_with(route.iter().nth(index))
+        .map(|hop| hop.to_string())
+        .unwrap_or_default();
+    let resolved = if resolved.is_empty() {
+        "nothing".to_string()
+    } else {
+        resolved.to_string()
+    };
+    ForwarderError::InvalidArgument(anyhow!(
+        "{err}\n  failed at hop {} of {hops} ({hop}) of {route}\n  resolved so far: {resolved}",
+        index + 1
+    ))
+}
+
+/// Address family preferred for the DNS names of a route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum IpFamily {
+    V4,
+    V6,
+}
+
+/// Pick the first address of `family` among `addrs`, or else the first one.
+fn pick_address(addrs: impl IntoIterator<Item = IpAddr>, family: IpFamily) -> Option<IpAddr> {
+    let addrs: Vec<IpAddr> = addrs.into_iter().collect();
+    let preferred = |ip: &&IpAddr| match family {
+        IpFamily::V4 => ip.is_ipv4(),
+        IpFamily::V6 => ip.is_ipv6(),
+    };
+    addrs
+        .iter()
+        .find(preferred)
+        .or_else(|| addrs.first())
+        .copied()
+}
+
+/// Replace the `/dnsaddr/<NAME>` hops of `route` with an address of `family`
+/// that the name resolves to, or an address of the other family if it has
+/// none.
+///
+/// The names of routes with a `/tls` but no `/sni` are kept, as the TLS
+/// session verifies them.
+pub(crate) async fn resolve_dns(
+    route: &MultiAddr,
+    family: IpFamily,
+) -> Result<MultiAddr, ForwarderError> {
+    let has = |c| route.iter().any(|p| p.code() == c);
+    if has(Tls::CODE) && !has(Sni::CODE) {
+        return Ok(route.clone());
+    }
+
+    let mut ma = MultiAddr::default();
+    let mut protos = route.iter().peekable();
+    while let Some(proto) = protos.next() {
+        let pushed = match proto.cast::<DnsAddr>() {
+            Some(name) => {
+                let port = protos
+                    .peek()
+                    .and_then(|p| p.cast::<Tcp>())
+                    .map_or(0, |tcp| tcp.0);
+                let addrs = tokio::net::lookup_host((&*name, port))
+                    .await
+                    .with_context(|| format!("failed to resolve {}", &*name))
+                    .map_err(ForwarderError::InvalidArgument)?;
+                match pick_address(addrs.map(|a| a.ip()), family) {
+                    Some(IpAddr::V4(ip)) => ma.push_back(Ip4::new(ip)),
+                    Some(IpAddr::V6(ip)) => ma.push_back(Ip6::new(ip)),
+                    None => {
+                        return Err(ForwarderError::InvalidArgument(anyhow!(
+                            "{} has no address",
+                            &*name
+                        )))
+                    }
+                }
+            }
+            None => ma.push_back_value(&proto),
+        };
+        pushed.map_err(|e| ForwarderError::InvalidArgument(e.into()))?;
+    }
+    Ok(ma)
+}
+
+/// The node whose API a forwarder subcommand talks to, given by `--to`.
+///
+/// Either a background node, e.g. `blue` or `/node/blue`, or a route from a
+/// background node to another node, e.g. `/node/hub/service/forward_to_edge`
+/// for an edge node only reached through its forwarder at the hub node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ApiNode {
+    /// Background node the requests are sent to.
+    pub(crate) name: String,
+    /// Route from `name` to the node handling the requests, with its
+    /// `/node/<NAME>` hops resolved.
+    via: Option<MultiAddr>,
+}
+
+impl ApiNode {
+    pub(crate) fn parse(opts: &CommandGlobalOpts, to: &str) -> Result<Self, ForwarderError> {
+        let (name, via) = split_api_node(to)?;
+        let via = match via {
+            Some(via) => Some(resolve_nodes(opts, &via)?),
+            None => None,
+        };
+        Ok(ApiNode { name, via })
+    }
+}
+
+/// Split a `--to` into the name of the background node and the route
+/// from it to the node handling the requests, if any.
+fn split_api_node(to: &str) -> Result<(String, Option<MultiAddr>), ForwarderError> {
+    match MultiAddr::from_str(to) {
+        Ok(ma) if ma.iter().nth(1).is_some() && ma.matches(0, &[Node::CODE.into()]) => {
+            let (first, rest) = ma.split(1);
+            let name = extract_address_value(&first.to_string())
+                .map_err(ForwarderError::InvalidArgument)?;
+            Ok((name, Some(rest)))
+        }
+        _ => {
+            let name = extract_address_value(to).map_err(ForwarderError::InvalidArgument)?;
+            Ok((name, None))
+        }
+    }
+}
+
+impl fmt::Display for ApiNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.via {
+            Some(via) => write!(f, "/node/{}{via}", self.name),
+            None => f.write_str(&self.name),
+        }
+    }
+}
+
+/// The transport of the embedding application when there is one, otherwise a
+/// new transport on `ctx`.
+pub(crate) async fn tcp_transport(
+    ctx: &Context,
+    tcp: Option<TcpTransport>,
+) -> ockam::Result<TcpTransport> {
+    match tcp {
+        Some(tcp) => Ok(tcp),
+        None => TcpTransport::create(ctx).await,
+    }
+}
+
+/// Build an RPC to `api_node`.
+pub(crate) fn forwarder_rpc<'a>(
+    ctx: &'a Context,
+    opts: &'a CommandGlobalOpts,
+    tcp: &'a TcpTransport,
+    api_node: &ApiNode,
+    api: &ApiOpts,
+) -> Result<Rpc<'a>, ForwarderError> {
+    let mut rpc = RpcBuilder::new(ctx, opts, &api_node.name)
+        .tcp(tcp)
+        .map_err(|_| ForwarderError::UnknownNode(api_node.name.clone()))?
+        .api_prefix(&api.api_prefix);
+    if let Some(via) = &api_node.via {
+        rpc = rpc.via(via).map_err(ForwarderError::InvalidArgument)?;
+    }
+    Ok(rpc.build())
+}
+
+/// Interval between two attempts of [`wait_for_node`].
+const NODE_STARTUP_RETRY_MILLIS: u64 = 250;
+
+/// Wait up to `wait` for the API of `api_node` to accept TCP connections.
+///
+/// A node that was just started may not be listening yet. With a zero
+/// `wait` nothing is attempted and the first request fails as usual.
+pub(crate) async fn wait_for_node(
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    api_node: &str,
+    wait: Duration,
+) -> Result<(), ForwarderError> {
+    if wait.is_zero() {
+        return Ok(());
+    }
+    let addr = node_addr(opts, api_node)?;
+    let deadline = tokio::time::Instant::now() + wait;
+    loop {
+        match tcp.connect(&addr).await {
+            Ok(_) => return Ok(()),
+            Err(e) if tokio::time::Instant::now() >= deadline => {
+                return Err(ForwarderError::Timeout(anyhow!(
+                    "node {api_node} did not accept connections within {}s: {e}",
+                    wait.as_secs()
+                )))
+            }
+            Err(e) => debug!(%addr, err = %e, "node not reachable yet, retrying"),
+        }
+        tokio::time::sleep(Duration::from_millis(NODE_STARTUP_RETRY_MILLIS)).await;
+    }
+}
+
+/// The address the API of the background node `api_node` listens at.
+fn node_addr(opts: &CommandGlobalOpts, api_node: &str) -> Result<String, ForwarderError> {
+    let port = opts
+        .config
+        .get_node_port(api_node)
+        .map_err(|_| ForwarderError::UnknownNode(api_node.to_string()))?;
+    Ok(format!("localhost:{port}"))
+}
+
+/// The first of the comma separated nodes of `to` whose API responds, with
+/// the part of `to` naming it.
+///
+/// A single node is returned once it accepts connections, without asking
+/// its API anything, as before failover was supported. With several, each one
+/// is waited for up to `wait`, connected to, and asked for its version in
+/// turn, so that a stopped node is skipped right away rather than once the
+/// request times out; when none of them responds, the error lists what went
+/// wrong with each.
+pub(crate) async fn first_reachable<'a>(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    to: &'a str,
+    wait: Duration,
+    api: &ApiOpts,
+) -> Result<(ApiNode, &'a str), ForwarderError> {
+    let mut candidates = to
+        .split(',')
+        .map(|to| ApiNode::parse(opts, to).map(|node| (node, to)))
+        .collect::<Result<Vec<_>, _>>()?;
+    if candidates.len() == 1 {
+        let (node, to) = candidates.swap_remove(0);
+        wait_for_node(opts, tcp, &node.name, wait).await?;
+        return Ok((node, to));
+    }
+    let mut errors = Vec::with_capacity(candidates.len());
+    for (node, to) in candidates {
+        let reachable = async {
+            wait_for_node(opts, tcp, &node.name, wait).await?;
+            let addr = node_addr(opts, &node.name)?;
+            tokio::net::TcpStream::connect(&addr).await.map_err(|e| {
+                ForwarderError::Rpc(anyhow!(
+                    "node {} did not accept connections: {e}",
+                    node.name
+                ))
+            })?;
+            node_version(ctx, opts, tcp, &node, api).await.map(|_| ())
+        };
+        match reachable.await {
+            Ok(()) => return Ok((node, to)),
+            Err(e) => {
+                debug!(%node, err = %e, "node not reachable, trying the next one");
+                errors.push(format!("{to}: {e}"));
+            }
+        }
+    }
+    Err(ForwarderError::Rpc(anyhow!(
+        "none of the --to nodes responded; {}",
+        errors.join("; ")
+    )))
+}
+
+/// Base delay, in milliseconds, of the exponential backoff of [`with_retries`].
+const RETRY_BACKOFF_MILLIS: u64 = 100;
+
+/// Run `attempt`, and again up to `retries` times while it fails with an
+/// error that may go away, see [`ForwarderError::is_retryable`]. Other
+/// errors are returned right away.
+pub(crate) async fn with_retries<T, F, Fut>(retries: usize, attempt: F) -> Result<T, ForwarderError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ForwarderError>>,
+{
+    let strategy = ExponentialBackoff::from_millis(RETRY_BACKOFF_MILLIS).take(retries);
+    RetryIf::spawn(strategy, attempt, |e: &ForwarderError| {
+        debug!(err = %e, retryable = e.is_retryable(), "request failed");
+        e.is_retryable()
+    })
+    .await
+}
+
+/// Turn a 503 Service Unavailable response into [`ForwarderError::Unavailable`].
+pub(crate) fn check_available(rpc: &Rpc) -> Result<(), ForwarderError> {
+    match rpc.check_response() {
+        Ok((hdr, dec)) if hdr.status() == Some(Status::ServiceUnavailable) => Err(
+            ForwarderError::Unavailable(anyhow!(rpc.parse_err_msg(hdr, dec))),
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Fail with the message of the node when it answered `409 Conflict`,
+/// which sending the request again won't change.
+pub(crate) fn check_conflict(rpc: &Rpc) -> Result<(), ForwarderError> {
+    match rpc.check_response() {
+        Ok((hdr, dec)) if hdr.status() == Some(Status::Conflict) => Err(
+            ForwarderError::InvalidArgument(anyhow!(rpc.parse_err_msg(hdr, dec))),
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Whether a node answered as nodes do to the endpoints they don't have,
+/// e.g. nodes predating the endpoint.
+fn predates_endpoint(hdr: &Response) -> bool {
+    hdr.status() == Some(Status::BadRequest)
+}
+
+/// List the forwarders created by `api_node`.
+pub(crate) async fn list_forwarders(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    api_node: &ApiNode,
+    api: &ApiOpts,
+) -> Result<Vec<ForwarderEntry>, ForwarderError> {
+    let mut rpc = forwarder_rpc(ctx, opts, tcp, api_node, api)?;
+    rpc.request(Request::get("/node/forwarder"))
+        .await
+        .map_err(ForwarderError::from_rpc)?;
+    forwarder_entries(&rpc)
+}
+
+/// List the forwarders created by `api_node`, or None when the node
+/// predates listing them and rejects it as an invalid endpoint.
+pub(crate) async fn list_forwarders_if_supported(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    api_node: &ApiNode,
+    api: &ApiOpts,
+) -> Result<Option<Vec<ForwarderEntry>>, ForwarderError> {
+    let mut rpc = forwarder_rpc(ctx, opts, tcp, api_node, api)?;
+    rpc.request(Request::get("/node/forwarder"))
+        .await
+        .map_err(ForwarderError::from_rpc)?;
+    check_available(&rpc)?;
+    if let Ok((hdr, _)) = rpc.check_response() {
+        if predates_endpoint(&hdr) {
+            return Ok(None);
+        }
+    }
+    forwarder_entries(&rpc).map(Some)
+}
+
+fn forwarder_entries(rpc: &Rpc) -> Result<Vec<ForwarderEntry>, ForwarderError> {
+    let list = rpc
+        .parse_response::<ForwarderList>()
+        .map_err(ForwarderError::Rpc)?;
+    Ok(list
+        .list
+        .iter()
+        .map(|f| ForwarderEntry {
+            remote_address: f.remote_address().to_string(),
+            route: f.forwarding_route_multiaddr(),
+            kind: f.kind(),
+        })
+        .collect())
+}
+
+/// The software and API versions of `api_node`, or None when the node
+/// predates `/node/version` and rejects it as an invalid endpoint.
+pub(crate) async fn node_version(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    api_node: &ApiNode,
+    api: &ApiOpts,
+) -> Result<Option<(String, u32)>, ForwarderError> {
+    let mut rpc = forwarder_rpc(ctx, opts, tcp, api_node, api)?;
+    rpc.request(Request::get("/node/version"))
+        .await
+        .map_err(ForwarderError::from_rpc)?;
+    check_available(&rpc)?;
+    if let Ok((hdr, _)) = rpc.check_response() {
+        if predates_endpoint(&hdr) {
+            return Ok(None);
+        }
+    }
+    let version = rpc
+        .parse_response::<NodeVersion>()
+        .map_err(ForwarderError::Rpc)?;
+    Ok(Some((version.version.to_string(), version.api)))
+}
+
+/// Whether `api_node` presents a credential over its secure channels and
+/// can, or None when the node predates `/node/credentials`.
+pub(crate) async fn credential_status(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    api_node: &ApiNode,
+    api: &ApiOpts,
+) -> Result<Option<CredentialStatus>, ForwarderError> {
+    let mut rpc = forwarder_rpc(ctx, opts, tcp, api_node, api)?;
+    rpc.request(Request::get("/node/credentials"))
+        .await
+        .map_err(ForwarderError::from_rpc)?;
+    check_available(&rpc)?;
+    if let Ok((hdr, _)) = rpc.check_response() {
+        if predates_endpoint(&hdr) {
+            return Ok(None);
+        }
+    }
+    rpc.parse_response::<CredentialStatus>()
+        .map(Some)
+        .map_err(ForwarderError::Rpc)
+}
+
+/// Delete the forwarder `remote_address` created by `api_node`.
+pub(crate) async fn delete_forwarder(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    api_node: &ApiNode,
+    api: &ApiOpts,
+    remote_address: &str,
+) -> Result<(), ForwarderError> {
+    let mut rpc = forwarder_rpc(ctx, opts, tcp, api_node, api)?;
+    rpc.request(Request::delete(format!("/node/forwarder/{remote_address}")))
+        .await
+        .map_err(ForwarderError::from_rpc)?;
+    check_available(&rpc)?;
+    rpc.is_ok().map_err(ForwarderError::Rpc)
+}
+
+/// Make the forwarder `remote_address` of `api_node` start draining, or
+/// only check how it goes when `start` is false.
+pub(crate) async fn drain_forwarder(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    api_node: &ApiNode,
+    api: &ApiOpts,
+    remote_address: &str,
+    start: bool,
+) -> Result<DrainStatus, ForwarderError> {
+    let path = format!("/node/forwarder/{remote_address}/drain");
+    let req = if start {
+        Request::post(path)
+    } else {
+        Request::get(path)
+    };
+    let mut rpc = forwarder_rpc(ctx, opts, tcp, api_node, api)?;
+    rpc.request(req).await.map_err(ForwarderError::from_rpc)?;
+    check_available(&rpc)?;
+    rpc.parse_response::<DrainStatus>()
+        .map_err(ForwarderError::Rpc)
+}
+
+/// Find the forwarder called `name` among the forwarders created by `api_node`.
+pub(crate) async fn find_forwarder(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    api_node: &ApiNode,
+    api: &ApiOpts,
+    name: &str,
+) -> Result<ForwarderEntry, ForwarderError> {
+    let mut entries = list_forwarders(ctx, opts, tcp, api_node, api).await?;
+    match entries.iter().position(|f| f.is_named(name)) {
+        Some(i) => Ok(entries.swap_remove(i)),
+        None => Err(ForwarderError::NotFound {
+            name: name.to_string(),
+            existing: entries.into_iter().map(|f| f.remote_address).collect(),
+        }),
+    }
+}
+
+/// Send an echo message through `forwarder`, returning how long the reply took.
+pub(crate) async fn ping_forwarder(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    api_node: &ApiNode,
+    api: &ApiOpts,
+    forwarder: &ForwarderEntry,
+) -> Result<Duration, ForwarderError> {
+    ping_forwarder_with_size(ctx, opts, tcp, api_node, api, forwarder, PING_PAYLOAD_SIZE).await
+}
+
+/// Size in bytes of the payload of the echo message sent by [`ping_forwarder`].
+pub(crate) const PING_PAYLOAD_SIZE: usize = 16;
+
+/// Like [`ping_forwarder`], with a payload of `size` bytes.
+pub(crate) async fn ping_forwarder_with_size(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    api_node: &ApiNode,
+    api: &ApiOpts,
+    forwarder: &ForwarderEntry,
+    size: usize,
+) -> Result<Duration, ForwarderError> {
+    let remote_address = &forwarder.remote_address;
+    let mut to = forwarder.route.clone().ok_or_else(|| {
+        ForwarderError::Rpc(anyhow!(
+            "the route to forwarder /service/{remote_address} can not be expressed as a multiaddr"
+        ))
+    })?;
+    to.push_back(Service::new(DefaultAddress::ECHO_SERVICE))
+        .map_err(|e| ForwarderError::Rpc(e.into()))?;
+
+    let bytes: Vec<u8> = (0..(size + 1) / 2).map(|_| random::<u8>()).collect();
+    let mut payload = hex::encode(bytes);
+    payload.truncate(size);
+    let mut rpc = forwarder_rpc(ctx, opts, tcp, api_node, api)?;
+    let start = Instant::now();
+    rpc.request(Request::post("v0/message").body(SendMessage::new(&to, payload.as_bytes())))
+        .await
+        .map_err(ForwarderError::from_rpc)?;
+    let reply = rpc
+        .parse_response::<Vec<u8>>()
+        .with_context(|| format!("upstream of forwarder /service/{remote_address} is unreachable"))
+        .map_err(ForwarderError::from_rpc)?;
+    let elapsed = start.elapsed();
+    if reply != payload.as_bytes() {
+        return Err(ForwarderError::Rpc(anyhow!(
+            "forwarder /service/{remote_address} returned an unexpected reply"
+        )));
+    }
+    Ok(elapsed)
+}
+
+/// Progress of an operation on many forwarders, reported on stderr.
+///
+/// Draws a bar when stderr is a terminal, otherwise prints a line about every
+/// tenth of the way. Nothing is reported for a single forwarder or with `--quiet`.
+pub(crate) struct Progress {
+    verb: &'static str,
+    done: usize,
+    total: usize,
+    enabled: bool,
+    tty: bool,
+}
+
+impl Progress {
+    const BAR_WIDTH: usize = 30;
+
+    pub(crate) fn new(opts: &CommandGlobalOpts, verb: &'static str, total: usize) -> Self {
+        Self {
+            verb,
+            done: 0,
+            total,
+            enabled: total > 1 && !opts.global_args.quiet,
+            tty: atty::is(Stream::Stderr),
+        }
+    }
+
+    /// Clear the bar, before anything else is printed.
+    pub(crate) fn clear(&self) {
+        if self.enabled && self.tty && self.done > 0 {
+            eprint!("\r\x1b[2K");
+        }
+    }
+
+    /// Report that one more forwarder has been processed.
+    pub(crate) fn step(&mut self) {
+        self.done += 1;
+        if !self.enabled {
+            return;
+        }
+        if self.tty {
+            eprint!("\r{}", bar(self.done, self.total, Self::BAR_WIDTH));
+            if self.done == self.total {
+                eprintln!();
+            }
+        } else if self.done == self.total || self.done % (self.total / 10).max(1) == 0 {
+            eprintln!("{} {} of {} forwarders", self.verb, self.done, self.total);
+        }
+    }
+}
+
+fn bar(done: usize, total: usize, width: usize) -> String {
+    let filled = done * width / total;
+    format!(
+        "[{}{}] {done}/{total}",
+        "#".repeat(filled),
+        " ".repeat(width - filled)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ockam_core::api::Id;
+    use std::cell::Cell;
+
+    /// Mock of the responses of a node, one status code per attempt.
+    fn respond(statuses: &[u16], attempts: &Cell<usize>) -> Result<(), ForwarderError> {
+        let status = statuses[attempts.get()];
+        attempts.set(attempts.get() + 1);
+        match status {
+            200 => Ok(()),
+            503 => Err(ForwarderError::Unavailable(anyhow!(
+                "503 ServiceUnavailable"
+            ))),
+            s => Err(ForwarderError::Rpc(anyhow!("{s}"))),
+        }
+    }
+
+    #[tokio::test]
+    async fn retries() {
+        let attempts = Cell::new(0);
+        let res = with_retries(3, || async { respond(&[503, 200], &attempts) }).await;
+        assert!(res.is_ok());
+        assert_eq!(attempts.get(), 2);
+
+        // Non-retryable errors fail fast
+        for status in [400, 404] {
+            let attempts = Cell::new(0);
+            let res = with_retries(3, || async { respond(&[status, 200], &attempts) }).await;
+            assert!(matches!(res, Err(ForwarderError::Rpc(_))));
+            assert_eq!(attempts.get(), 1);
+        }
+
+        // The number of retries is bounded
+        let attempts = Cell::new(0);
+        let res = with_retries(2, || async { respond(&[503, 503, 503, 200], &attempts) }).await;
+        assert!(matches!(res, Err(ForwarderError::Unavailable(_))));
+        assert_eq!(attempts.get(), 3);
+
+        // No retries
+        let attempts = Cell::new(0);
+        let res = with_retries(0, || async { respond(&[503, 200], &attempts) }).await;
+        assert!(res.is_err());
+        assert_eq!(attempts.get(), 1);
+    }
+
+    #[test]
+    fn preferred_address_family() {
+        let v4: IpAddr = "192.0.2.1".parse().unwrap();
+        let v6: IpAddr = "2001:db8::1".parse().unwrap();
+        assert_eq!(pick_address([v6, v4], IpFamily::V4), Some(v4));
+        assert_eq!(pick_address([v4, v6], IpFamily::V6), Some(v6));
+        assert_eq!(pick_address([v4], IpFamily::V6), Some(v4));
+        assert_eq!(pick_address([], IpFamily::V4), None);
+    }
+
+    #[tokio::test]
+    async fn resolve_dns_names() {
+        let route: MultiAddr = "/ip4/127.0.0.1/tcp/4000/service/api".parse().unwrap();
+        assert_eq!(resolve_dns(&route, IpFamily::V6).await.unwrap(), route);
+
+        // The TLS session verifies the name
+        let route: MultiAddr = "/dnsaddr/localhost/tcp/443/tls".parse().unwrap();
+        assert_eq!(resolve_dns(&route, IpFamily::V4).await.unwrap(), route);
+
+        let route: MultiAddr = "/dnsaddr/localhost/tcp/4000".parse().unwrap();
+        let resolved = resolve_dns(&route, IpFamily::V4).await.unwrap();
+        assert!(
+            resolved.matches(0, &[Ip4::CODE.into(), Tcp::CODE.into()])
+                || resolved.matches(0, &[Ip6::CODE.into(), Tcp::CODE.into()])
+        );
+    }
+
+    #[test]
+    fn progress_bar() {
+        assert_eq!(bar(0, 4, 8), "[        ] 0/4");
+        assert_eq!(bar(1, 4, 8), "[##      ] 1/4");
+        assert_eq!(bar(3, 7, 8), "[###     ] 3/7");
+        assert_eq!(bar(4, 4, 8), "[########] 4/4");
+    }
+
+    #[test]
+    fn api_nodes() {
+        assert_eq!(split_api_node("blue").unwrap(), ("blue".to_string(), None));
+        assert_eq!(
+            split_api_node("/node/blue").unwrap(),
+            ("blue".to_string(), None)
+        );
+        let (name, via) = split_api_node("/node/hub/service/forward_to_edge").unwrap();
+        assert_eq!(name, "hub");
+        assert_eq!(via.unwrap().to_string(), "/service/forward_to_edge");
+        assert!(split_api_node("/node").is_err());
+    }
+
+    #[test]
+    fn partial_resolution() {
+        let route: MultiAddr = "/ip4/127.0.0.1/tcp/4000/node/n1/service/api"
+            .parse()
+            .unwrap();
+        let resolved: MultiAddr = "/ip4/127.0.0.1/tcp/4000".parse().unwrap();
+        let err = ForwarderError::UnknownNode("n1".into());
+        let err = partially_resolved(err, &route, 2, &resolved);
+        assert!(matches!(err, ForwarderError::InvalidArgument(_)));
+        assert_eq!(
+            err.to_string(),
+            "no address for node n1\n  \
+             failed at hop 3 of 4 (/node/n1) of /ip4/127.0.0.1/tcp/4000/node/n1/service/api\n  \
+             resolved so far: /ip4/127.0.0.1/tcp/4000"
+        );
+
+        let err = ForwarderError::UnknownNode("n1".into());
+        let err = partially_resolved(err, &route, 0, &MultiAddr::default());
+        assert!(err.to_string().ends_with("resolved so far: nothing"));
+    }
+
+    #[test]
+    fn endpoints_of_older_nodes() {
+        // What nodes predating `GET /node/forwarder` answer to it
+        let old_node = Response::bad_request(Id::fresh())
+            .body("Invalid endpoint: /node/forwarder")
+            .to_vec()
+            .unwrap();
+        let hdr: Response = minicbor::decode(&old_node).unwrap();
+        assert!(predates_endpoint(&hdr));
+
+        let list = Response::ok(Id::fresh())
+            .body(ForwarderList::new(vec![]))
+            .to_vec()
+            .unwrap();
+        let hdr: Response = minicbor::decode(&list).unwrap();
+        assert!(!predates_endpoint(&hdr));
+    }
+
+    #[test]
+    fn forwarder_names() {
+        assert_eq!(forwarder_name("blue").unwrap(), "blue");
+        assert_eq!(forwarder_name("forward_to_blue").unwrap(), "blue");
+        assert_eq!(
+            forwarder_name("blue_forward_to_").unwrap(),
+            "blue_forward_to_"
+        );
+        assert!(forwarder_name("forward_to_").is_err());
+        assert_eq!(forwarder_name("forward_to_api").unwrap(), "api");
+    }
+
+    #[test]
+    fn forwarder_aliases() {
+        assert_eq!(forwarder_alias("blue", true).unwrap(), "forward_to_blue");
+        assert_eq!(forwarder_alias("blue", false).unwrap(), "blue");
+        // The prefix keeps reserved names from clashing with the services
+        assert_eq!(forwarder_alias("echo", true).unwrap(), "forward_to_echo");
+        assert!(forwarder_alias("echo", false).is_err());
+        assert!(forwarder_alias("static_forwarding_service", false).is_err());
+    }
+}
@@ -0,0 +1,64 @@
+use crate::util::output::Output;
+use crate::util::{extract_address_value, node_rpc, RpcBuilder};
+use crate::{help, node::HELP_DETAIL, CommandGlobalOpts};
+use clap::Args;
+use cli_table::{Cell, Style, Table};
+use ockam::{Context, TcpTransport};
+use ockam_api::nodes::models::base::{AuthorizationStatsList, WorkerAuthorizationStats};
+use ockam_core::api::Request;
+
+/// Show how many messages the access control of each worker allowed and denied
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, after_long_help = help::template(HELP_DETAIL))]
+pub struct AuthzStatsCommand {
+    /// Node whose workers to show
+    #[arg(long, id = "NODE", display_order = 900)]
+    to: String,
+}
+
+impl AuthzStatsCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, AuthzStatsCommand),
+) -> crate::Result<()> {
+    let node_name = extract_address_value(&cmd.to)?;
+    let tcp = TcpTransport::create(&ctx).await?;
+    let mut rpc = RpcBuilder::new(&ctx, &opts, &node_name).tcp(&tcp)?.build();
+    rpc.request(Request::get("/node/authorization_stats"))
+        .await?;
+    rpc.parse_and_print_response::<AuthorizationStatsList>()?;
+    Ok(())
+}
+
+impl Output for AuthorizationStatsList<'_> {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.list.is_empty() {
+            return Ok("No authorization decisions recorded".to_string());
+        }
+        let mut rows = vec![];
+        for WorkerAuthorizationStats {
+            address,
+            allowed,
+            denied,
+            ..
+        } in &self.list
+        {
+            rows.push([address.cell(), allowed.cell(), denied.cell()]);
+        }
+        let table = rows
+            .table()
+            .title([
+                "Worker".cell().bold(true),
+                "Allowed".cell().bold(true),
+                "Denied".cell().bold(true),
+            ])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
+}
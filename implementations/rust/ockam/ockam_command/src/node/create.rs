@@ -8,6 +8,7 @@ use std::{
     str::FromStr,
 };
 
+use crate::forwarder::node_tag;
 use crate::node::util::run::CommandsRunner;
 use crate::node::util::{
     add_project_authority, create_default_identity_if_needed, get_identity_override,
@@ -26,7 +27,7 @@ use crate::{
     CommandGlobalOpts,
 };
 use ockam::{Address, AsyncTryClone, TCP};
-use ockam::{Context, TcpTransport};
+use ockam::{Context, TcpTransport, WorkerBuilder};
 use ockam_api::{
     nodes::models::transport::{TransportMode, TransportType},
     nodes::{
@@ -92,6 +93,11 @@ pub struct CreateCommand {
 
     #[arg(long, hide = true)]
     pub config: Option<PathBuf>,
+
+    /// Tag the node, e.g. env=prod, for forwarder create --label-selector
+    /// to select it. Can be repeated (Optional).
+    #[arg(long = "tag", value_name = "KEY=VALUE", display_order = 900, value_parser = node_tag)]
+    pub tags: Vec<(String, String)>,
 }
 
 impl Default for CreateCommand {
@@ -108,6 +114,7 @@ impl Default for CreateCommand {
             no_watchdog: false,
             project: None,
             config: None,
+            tags: Vec::new(),
         }
     }
 }
@@ -150,6 +157,11 @@ fn run_impl(opts: CommandGlobalOpts, cmd: CreateCommand) -> crate::Result<()> {
             cfg.create_node(&cmd.node_name, addr, verbose)?;
             cfg.persist_config_updates()?;
         }
+        // A child process is not given the tags, they are set by its parent
+        if !cmd.tags.is_empty() {
+            cfg.set_node_tags(&cmd.node_name, cmd.tags.iter().cloned().collect())?;
+            cfg.persist_config_updates()?;
+        }
         embedded_node_that_is_not_stopped(run_foreground_node, (opts.clone(), cmd, addr))?;
     } else {
         if cmd.child_process {
@@ -235,7 +247,9 @@ async fn run_foreground_node(
     .await?;
     let node_manager_worker = NodeManagerWorker::new(node_man);
 
-    ctx.start_worker(NODEMANAGER_ADDR, node_manager_worker)
+    // The API of the node doesn't apply its default policy, which it sets
+    WorkerBuilder::without_access_control(NODEMANAGER_ADDR, node_manager_worker)
+        .start(&ctx)
         .await?;
 
     if let Some(path) = cmd.launch_config {
@@ -343,6 +357,7 @@ async fn spawn_background_node(
     // we can ask it for the correct log path, as well as
     // making sure the watchdog can do its job later on.
     cfg.create_node(&cmd.node_name, addr, verbose)?;
+    cfg.set_node_tags(&cmd.node_name, cmd.tags.iter().cloned().collect())?;
     cfg.persist_config_updates()?;
 
     create_default_identity_if_needed(&ctx, cfg).await?;
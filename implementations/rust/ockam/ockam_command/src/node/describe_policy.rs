@@ -0,0 +1,49 @@
+use crate::util::{extract_address_value, node_rpc, RpcBuilder};
+use crate::{help, node::HELP_DETAIL, CommandGlobalOpts};
+use anyhow::anyhow;
+use clap::Args;
+use ockam::{Context, TcpTransport};
+use ockam_api::nodes::models::base::AccessControlDescription;
+use ockam_core::api::{Request, Status};
+
+/// Show the access control a worker of a node applies to the messages it receives
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, after_long_help = help::template(HELP_DETAIL))]
+pub struct DescribePolicyCommand {
+    /// Node of the worker
+    #[arg(long, id = "NODE", display_order = 900)]
+    to: String,
+
+    /// Address of the worker, e.g. api
+    #[arg(long, id = "ADDRESS", display_order = 900)]
+    worker: String,
+}
+
+impl DescribePolicyCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, DescribePolicyCommand),
+) -> crate::Result<()> {
+    let node_name = extract_address_value(&cmd.to)?;
+    let worker = cmd.worker.trim_start_matches("/service/");
+    let tcp = TcpTransport::create(&ctx).await?;
+    let mut rpc = RpcBuilder::new(&ctx, &opts, &node_name).tcp(&tcp)?.build();
+    rpc.request(Request::get(format!("/node/access_control/{worker}")))
+        .await?;
+    if let Ok((hdr, _)) = rpc.check_response() {
+        if hdr.status() == Some(Status::NotFound) {
+            return Err(crate::Error::new(
+                crate::exitcode::NOUSER,
+                anyhow!("node {node_name} has no worker at {worker}"),
+            ));
+        }
+    }
+    let description: AccessControlDescription = rpc.parse_response()?;
+    println!("{}", description.description);
+    Ok(())
+}
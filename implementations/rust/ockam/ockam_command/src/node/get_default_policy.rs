@@ -0,0 +1,40 @@
+use crate::util::{extract_address_value, node_rpc, RpcBuilder};
+use crate::{help, node::HELP_DETAIL, CommandGlobalOpts};
+use clap::Args;
+use ockam::{Context, TcpTransport};
+use ockam_api::nodes::models::policy::Policy;
+use ockam_core::api::{Request, Status};
+
+/// Show the policy the workers of a node apply to the messages they receive
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, after_long_help = help::template(HELP_DETAIL))]
+pub struct GetDefaultPolicyCommand {
+    /// Node whose default policy to show
+    #[arg(long, id = "NODE", display_order = 900)]
+    to: String,
+}
+
+impl GetDefaultPolicyCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, GetDefaultPolicyCommand),
+) -> crate::Result<()> {
+    let node_name = extract_address_value(&cmd.to)?;
+    let tcp = TcpTransport::create(&ctx).await?;
+    let mut rpc = RpcBuilder::new(&ctx, &opts, &node_name).tcp(&tcp)?.build();
+    rpc.request(Request::get("/node/policy")).await?;
+    if let Ok((hdr, _)) = rpc.check_response() {
+        if hdr.status() == Some(Status::NotFound) {
+            println!("No default policy");
+            return Ok(());
+        }
+    }
+    let policy: Policy = rpc.parse_response()?;
+    println!("{}", policy.expression());
+    Ok(())
+}
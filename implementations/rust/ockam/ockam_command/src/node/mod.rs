@@ -1,22 +1,32 @@
+use authz_stats::AuthzStatsCommand;
 use clap::{Args, Subcommand};
 
 pub(crate) use create::CreateCommand;
 use delete::DeleteCommand;
+use describe_policy::DescribePolicyCommand;
+use get_default_policy::GetDefaultPolicyCommand;
 use list::ListCommand;
 use run::RunCommand;
+use set_default_policy::SetDefaultPolicyCommand;
 use show::ShowCommand;
 use start::StartCommand;
 use stop::StopCommand;
+use trace_authz::TraceAuthzCommand;
 
 use crate::{help, CommandGlobalOpts};
 
+mod authz_stats;
 mod create;
 mod delete;
+mod describe_policy;
+mod get_default_policy;
 mod list;
 mod run;
+mod set_default_policy;
 mod show;
 mod start;
 mod stop;
+mod trace_authz;
 pub mod util;
 
 const HELP_DETAIL: &str = "\
@@ -67,6 +77,16 @@ About:
         - A secure channel listener at /service/api
         - A tcp listener listening at some TCP port
 
+    Default Policy
+    ------
+
+    A node can have a default policy, which its workers apply to the messages they receive
+    before their own access control, e.g. to only accept the messages of members arriving
+    over secure channels. The subject attributes are those of the credential the sender
+    presented over its secure channel; messages without one are rejected. The API of the
+    node, its transports and its secure channels don't apply it, so that the messages
+    reach the workers they are for. The policy is forgotten when the node stops.
+
 Examples:
 ```sh
     # Create two nodes
@@ -99,12 +119,33 @@ Examples:
     # Create a node, and run it in the foreground with verbose traces
     $ ockam node create n1 --foreground -vvv
 
+    # Create a node with tags, for forwarder create --label-selector
+    $ ockam node create n1 --tag env=prod --tag region=us
+
     # Show information about a specific node
     $ ockam node show n1
 
     # List all created nodes
     $ ockam node list
 
+    # Show how many messages the access control of each worker of node n1
+    # allowed and denied
+    $ ockam node authz-stats --to n1
+
+    # Only let the workers of node n1 accept messages from members, over secure channels
+    $ ockam node set-default-policy '(= subject.role \"member\")' --to n1
+    $ ockam node get-default-policy --to n1
+    (= subject.role \"member\")
+
+    # Let the workers of node n1 accept all messages again
+    $ ockam node set-default-policy --clear --to n1
+
+    # Show the access control the uppercase service of node n1 applies
+    $ ockam node describe-policy --to n1 --worker uppercase
+
+    # Show why the uppercase service of node n1 would deny a message captured in m.json
+    $ ockam node trace-authz --to n1 --worker uppercase --message-file m.json
+
     # Delete the node
     $ ockam node delete n1
 
@@ -144,6 +185,16 @@ pub enum NodeSubcommand {
     Start(StartCommand),
     #[command(display_order = 800)]
     Stop(StopCommand),
+    #[command(display_order = 800)]
+    AuthzStats(AuthzStatsCommand),
+    #[command(display_order = 800)]
+    SetDefaultPolicy(SetDefaultPolicyCommand),
+    #[command(display_order = 800)]
+    GetDefaultPolicy(GetDefaultPolicyCommand),
+    #[command(display_order = 800)]
+    DescribePolicy(DescribePolicyCommand),
+    #[command(display_order = 800)]
+    TraceAuthz(TraceAuthzCommand),
 }
 
 impl NodeCommand {
@@ -156,6 +207,11 @@ impl NodeCommand {
             NodeSubcommand::Show(c) => c.run(options),
             NodeSubcommand::Start(c) => c.run(options),
             NodeSubcommand::Stop(c) => c.run(options),
+            NodeSubcommand::AuthzStats(c) => c.run(options),
+            NodeSubcommand::SetDefaultPolicy(c) => c.run(options),
+            NodeSubcommand::GetDefaultPolicy(c) => c.run(options),
+            NodeSubcommand::DescribePolicy(c) => c.run(options),
+            NodeSubcommand::TraceAuthz(c) => c.run(options),
         }
     }
 }
@@ -0,0 +1,48 @@
+use crate::util::{extract_address_value, node_rpc, RpcBuilder};
+use crate::{help, node::HELP_DETAIL, CommandGlobalOpts};
+use clap::Args;
+use ockam::{Context, TcpTransport};
+use ockam_abac::Expr;
+use ockam_api::nodes::models::policy::Policy;
+use ockam_core::api::Request;
+
+/// Make the workers of a node only accept the messages satisfying a policy
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, after_long_help = help::template(HELP_DETAIL))]
+pub struct SetDefaultPolicyCommand {
+    /// Policy the senders of the messages must satisfy, e.g. (= subject.role "member")
+    #[arg(required_unless_present = "clear")]
+    expression: Option<Expr>,
+
+    /// Node whose workers apply the policy
+    #[arg(long, id = "NODE", display_order = 900)]
+    to: String,
+
+    /// Remove the default policy of the node instead (optional)
+    #[arg(long, conflicts_with = "expression", display_order = 900)]
+    clear: bool,
+}
+
+impl SetDefaultPolicyCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, SetDefaultPolicyCommand),
+) -> crate::Result<()> {
+    let node_name = extract_address_value(&cmd.to)?;
+    let tcp = TcpTransport::create(&ctx).await?;
+    let mut rpc = RpcBuilder::new(&ctx, &opts, &node_name).tcp(&tcp)?.build();
+    match cmd.expression {
+        Some(expression) => {
+            rpc.request(Request::post("/node/policy").body(Policy::new(expression)))
+                .await?
+        }
+        None => rpc.request(Request::delete("/node/policy")).await?,
+    }
+    rpc.is_ok()?;
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use crate::util::output::Output;
+use crate::util::{extract_address_value, node_rpc, RpcBuilder};
+use crate::{exitcode, help, node::HELP_DETAIL, CommandGlobalOpts};
+use anyhow::{anyhow, Context as _};
+use clap::Args;
+use ockam::{Context, TcpTransport};
+use ockam_api::nodes::models::base::{AccessControlTrace, TraceAccessControl, TracedLocalInfo};
+use ockam_core::api::{Request, Status};
+use ockam_core::DecisionTrace;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Show how the access control of a worker of a node decides about a message
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, after_long_help = help::template(HELP_DETAIL))]
+pub struct TraceAuthzCommand {
+    /// Node of the worker
+    #[arg(long, id = "NODE", display_order = 900)]
+    to: String,
+
+    /// Address of the worker, e.g. api
+    #[arg(long, id = "ADDRESS", display_order = 900)]
+    worker: String,
+
+    /// JSON file describing the message, e.g.
+    /// {"return_route": ["1#127.0.0.1:52104", "app"], "payload": "68656c6c6f"}
+    #[arg(long, value_name = "FILE", display_order = 900)]
+    message_file: PathBuf,
+}
+
+impl TraceAuthzCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+/// A message as described in a `--message-file`
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MessageFile {
+    /// Addresses of the onward route, the worker's address when missing
+    #[serde(default)]
+    onward_route: Vec<String>,
+    #[serde(default)]
+    return_route: Vec<String>,
+    /// Hex-encoded payload
+    #[serde(default)]
+    payload: String,
+    #[serde(default)]
+    local_info: Vec<MessageFileLocalInfo>,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MessageFileLocalInfo {
+    type_identifier: String,
+    /// Hex-encoded data
+    data: String,
+}
+
+impl MessageFile {
+    fn read(path: &Path) -> crate::Result<Self> {
+        let s = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read the message {}", path.display()))
+            .map_err(|e| crate::Error::new(exitcode::IOERR, e))?;
+        serde_json::from_str(&s)
+            .with_context(|| format!("invalid message {}", path.display()))
+            .map_err(|e| crate::Error::new(exitcode::DATAERR, e))
+    }
+
+    fn body(&self, worker: &str) -> anyhow::Result<TraceAccessControl<'static>> {
+        let onward_route = if self.onward_route.is_empty() {
+            vec![worker.to_string().into()]
+        } else {
+            self.onward_route.iter().map(|a| a.clone().into()).collect()
+        };
+        let payload = hex::decode(&self.payload).context("the payload is not hex-encoded")?;
+        let local_info = self
+            .local_info
+            .iter()
+            .map(|i| {
+                let data = hex::decode(&i.data).with_context(|| {
+                    format!(
+                        "the data of local info {} is not hex-encoded",
+                        i.type_identifier
+                    )
+                })?;
+                Ok(TracedLocalInfo::new(i.type_identifier.clone(), data))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(TraceAccessControl::new(
+            onward_route,
+            self.return_route.iter().map(|a| a.clone().into()).collect(),
+            payload,
+            local_info,
+        ))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, TraceAuthzCommand),
+) -> crate::Result<()> {
+    let node_name = extract_address_value(&cmd.to)?;
+    let worker = cmd.worker.trim_start_matches("/service/");
+    let body = MessageFile::read(&cmd.message_file)?
+        .body(worker)
+        .map_err(|e| crate::Error::new(exitcode::DATAERR, e))?;
+    let tcp = TcpTransport::create(&ctx).await?;
+    let mut rpc = RpcBuilder::new(&ctx, &opts, &node_name).tcp(&tcp)?.build();
+    rpc.request(Request::post(format!("/node/access_control/{worker}/trace")).body(body))
+        .await?;
+    if let Ok((hdr, _)) = rpc.check_response() {
+        if hdr.status() == Some(Status::NotFound) {
+            return Err(crate::Error::new(
+                exitcode::NOUSER,
+                anyhow!("node {node_name} has no worker at {worker}"),
+            ));
+        }
+    }
+    rpc.parse_and_print_response::<AccessControlTrace>()?;
+    Ok(())
+}
+
+impl Output for AccessControlTrace {
+    fn output(&self) -> anyhow::Result<String> {
+        let trace = DecisionTrace::from(self).to_string();
+        Ok(trace.trim_end().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_file() {
+        let file: MessageFile = serde_json::from_str(
+            r#"{"return_route": ["1#127.0.0.1:4000", "app"], "payload": "6869",
+                "local_info": [{"type_identifier": "credential", "data": "0102"}]}"#,
+        )
+        .unwrap();
+        let body = file.body("uppercase").unwrap();
+        assert_eq!(body.onward_route, vec!["uppercase".to_string().into()]);
+        assert_eq!(body.return_route.len(), 2);
+        assert_eq!(&*body.payload, b"hi");
+        assert_eq!(&*body.local_info[0].data, &[1, 2]);
+
+        assert!(serde_json::from_str::<MessageFile>(r#"{"color": "blue"}"#).is_err());
+        let file = MessageFile {
+            payload: "hello".into(),
+            ..Default::default()
+        };
+        assert!(file.body("uppercase").is_err());
+    }
+}
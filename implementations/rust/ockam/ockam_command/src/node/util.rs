@@ -5,7 +5,7 @@ use sysinfo::{get_current_pid, ProcessExt, System, SystemExt};
 use tracing::trace;
 
 use ockam::identity::{Identity, PublicIdentity};
-use ockam::{Context, TcpTransport};
+use ockam::{Context, TcpTransport, WorkerBuilder};
 use ockam_api::config::cli;
 use ockam_api::config::cli::OckamConfig as OckamConfigApi;
 use ockam_api::nodes::models::transport::{TransportMode, TransportType};
@@ -76,7 +76,9 @@ pub async fn start_embedded_node(ctx: &Context, cfg: &OckamConfig) -> Result<Str
 
     let node_manager_worker = NodeManagerWorker::new(node_man);
 
-    ctx.start_worker(NODEMANAGER_ADDR, node_manager_worker)
+    // The API of the node doesn't apply its default policy, which it sets
+    WorkerBuilder::without_access_control(NODEMANAGER_ADDR, node_manager_worker)
+        .start(ctx)
         .await?;
 
     Ok(cmd.node_name.clone())
@@ -7,7 +7,7 @@ use tracing::debug;
 use ockam::identity::IdentityIdentifier;
 use ockam::TcpTransport;
 use ockam_api::cloud::project::Project;
-use ockam_api::config::lookup::{LookupMeta, ProjectAuthority, ProjectLookup};
+use ockam_api::config::lookup::{ConfigLookup, LookupMeta, ProjectAuthority, ProjectLookup};
 use ockam_api::multiaddr_to_addr;
 use ockam_api::nodes::models::secure_channel::*;
 use ockam_multiaddr::{MultiAddr, Protocol};
@@ -51,12 +51,13 @@ pub async fn get_projects_secure_channels_from_config_lookup(
     tcp: Option<&TcpTransport>,
     credential_exchange_mode: CredentialExchangeMode,
 ) -> Result<Vec<MultiAddr>> {
-    let cfg_lookup = opts.config.lookup();
+    let mut cfg_lookup = opts.config.lookup();
     let mut sc = Vec::with_capacity(meta.project.len());
 
-    // In case a project is missing from the config file, we fetch them all from the cloud.
+    // In case a project is missing from the config file, we fetch them all from the cloud,
+    // once, and look all the projects up in the refreshed lookup table.
     if cfg_lookup.has_unresolved_projects(meta) {
-        config::refresh_projects(ctx, opts, api_node, cloud_addr, tcp).await?;
+        cfg_lookup = config::refresh_projects(ctx, opts, api_node, cloud_addr, tcp).await?;
     }
 
     // Create a secure channel for each project.
@@ -336,17 +337,19 @@ pub mod config {
         inner.lookup.get_project(name).map(|s| s.id.clone())
     }
 
+    /// Fetch all the projects from the cloud and save them in the config,
+    /// returning the updated lookup table
     pub async fn refresh_projects(
         ctx: &Context,
         opts: &CommandGlobalOpts,
         api_node: &str,
         controller_route: &MultiAddr,
         tcp: Option<&TcpTransport>,
-    ) -> Result<()> {
+    ) -> Result<ConfigLookup> {
         let mut rpc = RpcBuilder::new(ctx, opts, api_node).tcp(tcp)?.build();
         rpc.request(api::project::list(controller_route)).await?;
         let projects = rpc.parse_response::<Vec<Project>>()?;
         set_projects(&opts.config, &projects).await?;
-        Ok(())
+        Ok(opts.config.lookup())
     }
 }
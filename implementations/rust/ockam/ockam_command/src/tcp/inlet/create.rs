@@ -1,7 +1,7 @@
 use crate::util::{bind_to_port_check, exitcode, extract_address_value, node_rpc, RpcBuilder};
 use crate::Result;
 use crate::{help, CommandGlobalOpts};
-use anyhow::anyhow;
+use anyhow::{anyhow, Context as _};
 use clap::Args;
 use ockam::identity::IdentityIdentifier;
 use ockam::{Context, TcpTransport};
@@ -10,7 +10,10 @@ use ockam_api::nodes::models::portal::InletStatus;
 use ockam_core::api::Request;
 use ockam_multiaddr::proto::{Node, Project};
 use ockam_multiaddr::{MultiAddr, Protocol as _};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
 use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 
 const HELP_DETAIL: &str = "\
 Examples:
@@ -31,7 +34,14 @@ Examples:
 
     # Access the service via the inlet/outlet pair
     $ curl 127.0.0.1:6000
+
+    # Create the inlet described by a file, e.g. written by forwarder create --emit-inlet-config
+    $ ockam tcp-inlet create --at /node/n2 --from 127.0.0.1:7000 --from-file inlet.json
 ```
+
+The file given to --from-file is a JSON object with the route to the outlet,
+\"to\", and optionally the other options of the inlet: \"from\", \"authorized\"
+and \"check_credential\". The flags given along with it take precedence.
 ";
 
 /// Create TCP Inlets
@@ -43,12 +53,26 @@ pub struct CreateCommand {
     at: String,
 
     /// Address on which to accept tcp connections.
-    #[arg(long, display_order = 900, id = "SOCKET_ADDRESS")]
-    from: SocketAddr,
+    #[arg(
+        long,
+        display_order = 900,
+        id = "SOCKET_ADDRESS",
+        required_unless_present = "from_file"
+    )]
+    from: Option<SocketAddr>,
 
     /// Route to a tcp outlet.
-    #[arg(long, display_order = 900, id = "ROUTE")]
-    to: MultiAddr,
+    #[arg(
+        long,
+        display_order = 900,
+        id = "ROUTE",
+        required_unless_present = "from_file"
+    )]
+    to: Option<MultiAddr>,
+
+    /// Read the inlet to create from a file, see below (optional)
+    #[arg(long, value_name = "FILE", display_order = 900)]
+    from_file: Option<PathBuf>,
 
     /// Authorized identity for secure channel connection (optional)
     #[arg(long, name = "AUTHORIZED", display_order = 900)]
@@ -65,11 +89,73 @@ impl CreateCommand {
     }
 }
 
+/// An inlet, as read by `tcp-inlet create --from-file`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct InletConfig {
+    /// Route to the outlet.
+    pub(crate) to: MultiAddr,
+    /// Address on which to accept tcp connections.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) from: Option<SocketAddr>,
+    /// Identity the secure channel to the outlet must authenticate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) authorized: Option<IdentityIdentifier>,
+    #[serde(default)]
+    pub(crate) check_credential: bool,
+}
+
+impl InletConfig {
+    pub(crate) fn read(path: &Path) -> Result<Self> {
+        let s = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read the inlet config {}", path.display()))
+            .map_err(|e| crate::Error::new(exitcode::IOERR, e))?;
+        let config = serde_json::from_str(&s)
+            .with_context(|| format!("invalid inlet config {}", path.display()))
+            .map_err(|e| crate::Error::new(exitcode::DATAERR, e))?;
+        Ok(config)
+    }
+
+    /// Replace the file at `path` atomically, writing a temporary file next
+    /// to it and renaming it.
+    pub(crate) fn write(&self, path: &Path) -> Result<()> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+        let write = || -> std::io::Result<()> {
+            let mut file = std::fs::File::create(&tmp)?;
+            file.write_all(&serde_json::to_vec_pretty(self)?)?;
+            file.write_all(b"\n")?;
+            file.sync_all()?;
+            std::fs::rename(&tmp, path)
+        };
+        write().map_err(|e| {
+            let e = anyhow::Error::new(e).context(format!(
+                "failed to write the inlet config {}",
+                path.display()
+            ));
+            crate::Error::new(exitcode::IOERR, e)
+        })
+    }
+}
+
 async fn rpc(ctx: Context, (opts, mut cmd): (CommandGlobalOpts, CreateCommand)) -> Result<()> {
+    if let Some(path) = &cmd.from_file {
+        let config = InletConfig::read(path)?;
+        cmd.to = cmd.to.or(Some(config.to));
+        cmd.from = cmd.from.or(config.from);
+        cmd.authorized = cmd.authorized.or(config.authorized);
+        cmd.check_credential |= config.check_credential;
+    }
+    let from = cmd.from.ok_or_else(|| {
+        crate::Error::new(
+            exitcode::USAGE,
+            anyhow!("--from is not given, and the inlet config has no \"from\""),
+        )
+    })?;
     let lookup = opts.config.lookup();
-    cmd.to = {
+    let to = {
         let mut to = MultiAddr::default();
-        for proto in cmd.to.iter() {
+        for proto in cmd.to.iter().flat_map(|to| to.iter()) {
             match proto.code() {
                 Node::CODE => {
                     let alias = proto
@@ -87,7 +173,7 @@ async fn rpc(ctx: Context, (opts, mut cmd): (CommandGlobalOpts, CreateCommand))
     };
 
     // Check if the port is used by some other services or process
-    if !bind_to_port_check(&cmd.from) {
+    if !bind_to_port_check(&from) {
         return Err(crate::error::Error::new(
             exitcode::IOERR,
             anyhow!("Another process is listening on the provided port!"),
@@ -98,13 +184,13 @@ async fn rpc(ctx: Context, (opts, mut cmd): (CommandGlobalOpts, CreateCommand))
     let node = extract_address_value(&cmd.at)?;
 
     let req = {
-        let payload = if cmd.to.matches(0, &[Project::CODE.into()]) {
+        let payload = if to.matches(0, &[Project::CODE.into()]) {
             if cmd.authorized.is_some() {
                 return Err(anyhow!("--authorized can not be used with project addresses").into());
             }
-            CreateInlet::via_project(cmd.from, cmd.to, cmd.check_credential)
+            CreateInlet::via_project(from, to, cmd.check_credential)
         } else {
-            CreateInlet::to_node(cmd.from, cmd.to, cmd.check_credential, cmd.authorized)
+            CreateInlet::to_node(from, to, cmd.check_credential, cmd.authorized)
         };
         Request::post("/node/inlet").body(payload)
     };
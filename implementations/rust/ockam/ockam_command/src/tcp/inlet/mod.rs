@@ -3,6 +3,7 @@ mod create;
 use crate::CommandGlobalOpts;
 use clap::{Args, Subcommand};
 use create::CreateCommand;
+pub(crate) use create::InletConfig;
 
 /// Manage TCP Inlets
 #[derive(Clone, Debug, Args)]
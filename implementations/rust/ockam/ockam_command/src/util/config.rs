@@ -1,6 +1,9 @@
 //! Handle local node configuration
 
-use std::{fs::create_dir_all, net::SocketAddr, ops::Deref, path::PathBuf, sync::RwLockReadGuard};
+use std::{
+    collections::BTreeMap, fs::create_dir_all, net::SocketAddr, ops::Deref, path::PathBuf,
+    sync::RwLockReadGuard,
+};
 
 use anyhow::{Context, Result};
 use slug::slugify;
@@ -11,6 +14,7 @@ use ockam_api::config::cli::NodeConfigOld;
 use ockam_api::config::lookup::ProjectLookup;
 use ockam_api::config::{cli, lookup::ConfigLookup, lookup::InternetAddress, Config};
 use ockam_api::nodes::config::NodeConfig;
+use ockam_multiaddr::MultiAddr;
 
 /// A simple wrapper around the main configuration structure to add
 /// local config utility/ query functions
@@ -278,6 +282,18 @@ impl OckamConfig {
         Ok(())
     }
 
+    /// Replace the tags of an existing node
+    pub fn set_node_tags(&self, name: &str, tags: BTreeMap<String, String>) -> Result<()> {
+        let mut inner = self.inner.write();
+
+        if !inner.nodes.contains_key(name) {
+            return Err(ConfigError::NotFound(name.to_string()).into());
+        }
+
+        inner.nodes.get_mut(name).unwrap().tags = tags;
+        Ok(())
+    }
+
     pub fn set_node_alias(&self, alias: String, addr: InternetAddress) {
         let mut inner = self.inner.write();
         inner.lookup.set_node(&alias, addr);
@@ -321,6 +337,12 @@ impl OckamConfig {
         inner.lookup.remove_projects();
     }
 
+    pub fn set_route_alias(&self, alias: &str, route: MultiAddr) {
+        let mut inner = self.inner.write();
+        trace!(%alias, %route, "Route stored in lookup table");
+        inner.lookup.set_route(alias, route);
+    }
+
     pub fn set_default_node(&self, name: &String) {
         let mut inner = self.inner.write();
         inner.default = Some(name.to_string());
@@ -1,6 +1,7 @@
 use core::time::Duration;
 use std::{
     env,
+    io::Write,
     net::{SocketAddr, TcpListener},
     path::Path,
     str::FromStr,
@@ -9,7 +10,7 @@ use std::{
 use anyhow::{anyhow, Context as _, Result};
 use crossbeam_channel::{bounded, Sender};
 use minicbor::{data::Type, Decode, Decoder, Encode};
-use tracing::{debug, error, trace};
+use tracing::{debug, debug_span, error, trace, Instrument};
 use tracing_subscriber::prelude::*;
 use tracing_subscriber::{filter::LevelFilter, fmt, EnvFilter};
 
@@ -31,6 +32,8 @@ pub mod startup;
 
 mod addon;
 mod config;
+#[cfg(feature = "otel")]
+pub(crate) mod otel;
 pub(crate) mod output;
 
 pub const DEFAULT_CONTROLLER_ADDRESS: &str = "/dnsaddr/orchestrator.ockam.io/tcp/6252/service/api";
@@ -49,6 +52,7 @@ pub struct RpcBuilder<'a> {
     node_name: String,
     to: Route,
     mode: RpcMode<'a>,
+    api_prefix: String,
 }
 
 impl<'a> RpcBuilder<'a> {
@@ -59,15 +63,33 @@ impl<'a> RpcBuilder<'a> {
             node_name: node_name.to_string(),
             to: NODEMANAGER_ADDR.into(),
             mode: RpcMode::Embedded,
+            api_prefix: String::new(),
         }
     }
 
+    /// Prepend `prefix` to the path of every request, for nodes behind a
+    /// proxy that serves their API under a path of its own.
+    pub fn api_prefix(mut self, prefix: &str) -> Self {
+        self.api_prefix = prefix.to_string();
+        self
+    }
+
     pub fn to(mut self, to: &MultiAddr) -> Result<Self> {
         self.to = ockam_api::multiaddr_to_route(to)
             .ok_or_else(|| anyhow!("failed to convert {to} to route"))?;
         Ok(self)
     }
 
+    /// Send the requests through `via`, a route from the node to another
+    /// node, e.g. the forwarder of a node that can not be reached directly.
+    /// The requests are then handled by that other node.
+    pub fn via(mut self, via: &MultiAddr) -> Result<Self> {
+        let via = ockam_api::multiaddr_to_route(via)
+            .ok_or_else(|| anyhow!("failed to convert {via} to route"))?;
+        self.to = self.to.modify().prepend_route(via).into();
+        Ok(self)
+    }
+
     /// When running multiple RPC's from a single command to a background node,
     /// a single TcpTransport must be shared amongst them, as we can only have one
     /// TcpTransport per Context.
@@ -90,6 +112,7 @@ impl<'a> RpcBuilder<'a> {
             node_name: self.node_name,
             to: self.to,
             mode: self.mode,
+            api_prefix: self.api_prefix,
         }
     }
 }
@@ -101,6 +124,7 @@ pub struct Rpc<'a> {
     node_name: String,
     to: Route,
     mode: RpcMode<'a>,
+    api_prefix: String,
 }
 
 impl<'a> Rpc<'a> {
@@ -114,6 +138,7 @@ impl<'a> Rpc<'a> {
             node_name,
             to: NODEMANAGER_ADDR.into(),
             mode: RpcMode::Embedded,
+            api_prefix: String::new(),
         })
     }
 
@@ -131,6 +156,7 @@ impl<'a> Rpc<'a> {
             node_name: node_name.to_string(),
             to: NODEMANAGER_ADDR.into(),
             mode: RpcMode::Background { cfg, tcp: None },
+            api_prefix: String::new(),
         })
     }
 
@@ -142,13 +168,20 @@ impl<'a> Rpc<'a> {
     where
         T: Encode<()>,
     {
-        let route = self.route_impl(self.ctx).await?;
-        self.buf = self
-            .ctx
-            .send_and_receive(route.clone(), req.to_vec()?)
-            .await
-            .context("Failed to receive response from node")?;
-        Ok(())
+        let req = self.with_api_prefix(req);
+        let span = self.span(&req);
+        async {
+            let req = Self::with_trace_context(req);
+            let route = self.route_impl(self.ctx).await?;
+            self.buf = self
+                .ctx
+                .send_and_receive(route.clone(), req.to_vec()?)
+                .await
+                .context("Failed to receive response from node")?;
+            Ok(())
+        }
+        .instrument(span)
+        .await
     }
 
     #[allow(unused)]
@@ -160,16 +193,58 @@ impl<'a> Rpc<'a> {
     where
         T: Encode<()>,
     {
-        let mut ctx = self.ctx.new_detached(Address::random_local()).await?;
-        let route = self.route_impl(&ctx).await?;
-        ctx.send(route.clone(), req.to_vec()?).await?;
-        self.buf = ctx
-            .receive_duration_timeout::<Vec<u8>>(timeout)
-            .await
-            .context("Failed to receive response from node")?
-            .take()
-            .body();
-        Ok(())
+        let req = self.with_api_prefix(req);
+        let span = self.span(&req);
+        async {
+            let req = Self::with_trace_context(req);
+            let mut ctx = self.ctx.new_detached(Address::random_local()).await?;
+            let route = self.route_impl(&ctx).await?;
+            ctx.send(route.clone(), req.to_vec()?).await?;
+            self.buf = ctx
+                .receive_duration_timeout::<Vec<u8>>(timeout)
+                .await
+                .context("Failed to receive response from node")?
+                .take()
+                .body();
+            Ok(())
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// The span of a request, with the attributes OpenTelemetry expects.
+    fn span<T>(&self, req: &RequestBuilder<'_, T>) -> tracing::Span {
+        let header = req.header();
+        let method = header.method().map(|m| m.to_string()).unwrap_or_default();
+        debug_span!(
+            "node_rpc",
+            peer.address = %self.node_name,
+            rpc.method = %format!("{method} {}", header.path()),
+            rpc.id = %header.id(),
+        )
+    }
+
+    /// Send the context of the current span along with `req`, for the node to
+    /// continue the trace.
+    #[cfg(feature = "otel")]
+    fn with_trace_context<T>(req: RequestBuilder<'_, T>) -> RequestBuilder<'_, T> {
+        match otel::trace_context() {
+            Some(context) => req.trace_context(context),
+            None => req,
+        }
+    }
+
+    #[cfg(not(feature = "otel"))]
+    fn with_trace_context<T>(req: RequestBuilder<'_, T>) -> RequestBuilder<'_, T> {
+        req
+    }
+
+    fn with_api_prefix<'r, T>(&self, req: RequestBuilder<'r, T>) -> RequestBuilder<'r, T> {
+        if self.api_prefix.is_empty() {
+            return req;
+        }
+        let path = join_api_path(&self.api_prefix, req.header().path());
+        req.path(path)
     }
 
     async fn route_impl(&mut self, ctx: &Context) -> Result<Route> {
@@ -284,12 +359,21 @@ impl<'a> Rpc<'a> {
         T: Output + serde::Serialize,
     {
         let o = match self.opts.global_args.output_format {
-            OutputFormat::Plain => b.output().context("Failed to serialize response body")?,
+            OutputFormat::Plain => b
+                .output_bytes()
+                .context("Failed to serialize response body")?,
             OutputFormat::Json => {
-                serde_json::to_string_pretty(&b).context("Failed to serialize response body")?
+                let mut json =
+                    serde_json::to_vec_pretty(&b).context("Failed to serialize response body")?;
+                json.push(b'\n');
+                json
             }
         };
-        println!("{}", o);
+        let mut stdout = std::io::stdout().lock();
+        stdout
+            .write_all(&o)
+            .and_then(|_| stdout.flush())
+            .context("Failed to write response body")?;
         Ok(b)
     }
 }
@@ -361,18 +445,36 @@ where
             if let Err(e) = res {
                 error!(%e);
                 eprintln!("{e:?}");
+                #[cfg(feature = "otel")]
+                otel::shutdown();
                 std::process::exit(e.code());
             }
             Ok(())
         },
         a,
     );
+    #[cfg(feature = "otel")]
+    otel::shutdown();
     if let Err(e) = res {
         eprintln!("Ockam node failed: {e}");
         std::process::exit(exitcode::SOFTWARE);
     }
 }
 
+/// Like [`node_rpc`], but runs `f` within a node which is already running,
+/// e.g. an application embedding a command, instead of bootstrapping one.
+///
+/// `f` gets a context detached from `ctx`. Errors are returned to the caller
+/// rather than ending the process, and the node is left running.
+pub async fn node_rpc_with_context<A, F, Fut>(ctx: &Context, f: F, a: A) -> crate::Result<()>
+where
+    F: FnOnce(Context, A) -> Fut,
+    Fut: core::future::Future<Output = crate::Result<()>>,
+{
+    let child_ctx = ctx.new_detached(Address::random_local()).await?;
+    f(child_ctx, a).await
+}
+
 pub fn embedded_node<A, F, Fut, T>(f: F, a: A) -> crate::Result<T>
 where
     A: Send + Sync + 'static,
@@ -451,24 +553,45 @@ pub fn setup_logging(verbose: u8, no_color: bool) {
     // Otherwise, use `verbose` to define the log level.
     let filter = match verbose {
         0 => match env::var("OCKAM_LOG") {
-            Ok(s) if !s.is_empty() => builder.with_env_var("OCKAM_LOG").from_env_lossy(),
-            _ => return,
+            Ok(s) if !s.is_empty() => Some(builder.with_env_var("OCKAM_LOG").from_env_lossy()),
+            _ => None,
         },
-        1 => builder
-            .with_default_directive(LevelFilter::INFO.into())
-            .parse_lossy(ockam_crates.map(|c| format!("{c}=info")).join(",")),
-        2 => builder
-            .with_default_directive(LevelFilter::DEBUG.into())
-            .parse_lossy(ockam_crates.map(|c| format!("{c}=debug")).join(",")),
-        _ => builder
-            .with_default_directive(LevelFilter::TRACE.into())
-            .parse_lossy(ockam_crates.map(|c| format!("{c}=trace")).join(",")),
+        1 => Some(
+            builder
+                .with_default_directive(LevelFilter::INFO.into())
+                .parse_lossy(ockam_crates.map(|c| format!("{c}=info")).join(",")),
+        ),
+        2 => Some(
+            builder
+                .with_default_directive(LevelFilter::DEBUG.into())
+                .parse_lossy(ockam_crates.map(|c| format!("{c}=debug")).join(",")),
+        ),
+        _ => Some(
+            builder
+                .with_default_directive(LevelFilter::TRACE.into())
+                .parse_lossy(ockam_crates.map(|c| format!("{c}=trace")).join(",")),
+        ),
     };
-    let fmt = fmt::Layer::default().with_ansi(!no_color);
+    let logs = filter.map(|filter| {
+        tracing_error::ErrorLayer::default()
+            .and_then(fmt::Layer::default().with_ansi(!no_color))
+            .with_filter(filter)
+    });
+    // Spans are exported whatever the log level, the filter of the logs
+    // only applies to them
+    #[cfg(feature = "otel")]
+    let traces = otel::layer().map(|layer| {
+        let ockam_spans = ockam_crates.map(|c| format!("{c}=debug")).join(",");
+        layer.with_filter(EnvFilter::builder().parse_lossy(ockam_spans))
+    });
+    #[cfg(not(feature = "otel"))]
+    let traces: Option<tracing_subscriber::layer::Identity> = None;
+    if logs.is_none() && traces.is_none() {
+        return;
+    }
     let result = tracing_subscriber::registry()
-        .with(filter)
-        .with(tracing_error::ErrorLayer::default())
-        .with(fmt)
+        .with(logs)
+        .with(traces)
         .try_init();
     if result.is_err() {
         eprintln!("Failed to initialise tracing logging.");
@@ -523,6 +646,52 @@ pub fn extract_address_value(input: &str) -> anyhow::Result<String> {
     Ok(addr)
 }
 
+/// Parse a human readable duration such as `90s`, `30m`, `2h`, `1d` or
+/// `1h30m`. A number without unit is a number of seconds.
+pub fn parse_duration(input: &str) -> Result<Duration> {
+    let invalid = || anyhow!("Invalid duration '{input}', expected e.g. 30s, 10m, 2h or 1d");
+    if input.is_empty() {
+        return Err(invalid());
+    }
+    if let Ok(secs) = input.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+    let mut total: u64 = 0;
+    let mut rest = input;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let n: u64 = rest[..digits].parse().map_err(|_| invalid())?;
+        let unit = match rest[digits..].chars().next() {
+            Some('s') => 1,
+            Some('m') => 60,
+            Some('h') => 60 * 60,
+            Some('d') => 24 * 60 * 60,
+            _ => return Err(invalid()),
+        };
+        total = n
+            .checked_mul(unit)
+            .and_then(|secs| total.checked_add(secs))
+            .ok_or_else(invalid)?;
+        rest = &rest[digits + 1..];
+    }
+    Ok(Duration::from_secs(total))
+}
+
+/// Join an API path prefix and a request path with exactly one `/` between them.
+///
+/// The result keeps the leading `/` of the prefix, if any, so that a prefix
+/// like `/api/v1` turns `/node/forwarder` into `/api/v1/node/forwarder`.
+pub fn join_api_path(prefix: &str, path: &str) -> String {
+    let prefix = prefix.trim_end_matches('/');
+    let path = path.trim_start_matches('/');
+    if path.is_empty() {
+        return prefix.to_string();
+    }
+    format!("{prefix}/{path}")
+}
+
 pub fn comma_separated<T: AsRef<str>>(data: &[T]) -> String {
     use itertools::Itertools;
 
@@ -590,6 +759,88 @@ pub async fn query_pid(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_join_api_path() {
+        assert_eq!(
+            join_api_path("/api/v1", "/node/forwarder"),
+            "/api/v1/node/forwarder"
+        );
+        assert_eq!(
+            join_api_path("/api/v1/", "/node/forwarder"),
+            "/api/v1/node/forwarder"
+        );
+        assert_eq!(join_api_path("/api/v1", "v0/message"), "/api/v1/v0/message");
+        assert_eq!(join_api_path("api", "node"), "api/node");
+        assert_eq!(join_api_path("/api", ""), "/api");
+    }
+
+    #[test]
+    fn test_output_bytes() {
+        struct Text;
+        impl Output for Text {
+            fn output(&self) -> anyhow::Result<String> {
+                Ok("text".to_string())
+            }
+        }
+
+        struct Binary;
+        impl Output for Binary {
+            fn output(&self) -> anyhow::Result<String> {
+                Ok("2 bytes".to_string())
+            }
+            fn output_bytes(&self) -> anyhow::Result<Vec<u8>> {
+                Ok(vec![0xff, 0x00])
+            }
+        }
+
+        assert_eq!(Text.output_bytes().unwrap(), b"text\n");
+        assert_eq!(Binary.output_bytes().unwrap(), [0xff, 0x00]);
+    }
+
+    #[test]
+    fn test_node_rpc_with_context() {
+        let res = embedded_node(
+            |ctx, _: ()| async move {
+                let ok = node_rpc_with_context(&ctx, |_, _: ()| async { Ok(()) }, ()).await;
+                let err = node_rpc_with_context(
+                    &ctx,
+                    |_, _: ()| async { Err(crate::Error::new(exitcode::USAGE, anyhow!("usage"))) },
+                    (),
+                )
+                .await;
+                Ok((ok.is_ok(), err.map_err(|e| e.code())))
+            },
+            (),
+        );
+        assert_eq!(res.unwrap(), (true, Err(exitcode::USAGE)));
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        let test_cases = vec![
+            ("0", Some(0)),
+            ("45", Some(45)),
+            ("30s", Some(30)),
+            ("30m", Some(30 * 60)),
+            ("2h", Some(2 * 3600)),
+            ("1d", Some(86400)),
+            ("1h30m", Some(5400)),
+            ("", None),
+            ("h", None),
+            ("10", Some(10)),
+            ("10x", None),
+            ("10m5", None),
+            ("-1s", None),
+        ];
+        for (input, expected) in test_cases {
+            assert_eq!(
+                parse_duration(input).ok().map(|d| d.as_secs()),
+                expected,
+                "{input}"
+            );
+        }
+    }
+
     #[test]
     fn test_extract_address_value() {
         let test_cases = vec![
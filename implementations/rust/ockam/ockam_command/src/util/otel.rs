@@ -0,0 +1,74 @@
+//! Export of the command spans to an OpenTelemetry collector.
+//!
+//! Built with the `otel` feature, and only enabled when
+//! `OTEL_EXPORTER_OTLP_ENDPOINT` is set, e.g. to `http://localhost:4318`, or
+//! `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT`, to the full URL of the traces.
+//! The spans are then sent, over OTLP/HTTP, to that collector, and the
+//! requests sent to nodes carry the context of the span they are sent from,
+//! so that the nodes continue the trace.
+
+use std::collections::HashMap;
+use std::env;
+
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry::sdk::propagation::TraceContextPropagator;
+use opentelemetry::sdk::{trace, Resource};
+use opentelemetry::{global, KeyValue};
+use opentelemetry_otlp::{
+    WithExportConfig, OTEL_EXPORTER_OTLP_ENDPOINT, OTEL_EXPORTER_OTLP_TRACES_ENDPOINT,
+};
+use tracing::{Span, Subscriber};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Name of the W3C trace context header, as carried by [`Request`](ockam_core::api::Request).
+const TRACEPARENT: &str = "traceparent";
+
+/// The layer exporting spans, if `OTEL_EXPORTER_OTLP_ENDPOINT` is set.
+pub(crate) fn layer<S>() -> Option<impl Layer<S>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let endpoint = match env::var(OTEL_EXPORTER_OTLP_TRACES_ENDPOINT) {
+        Ok(endpoint) => endpoint,
+        Err(_) => {
+            let base = env::var(OTEL_EXPORTER_OTLP_ENDPOINT).ok()?;
+            format!("{}/v1/traces", base.trim_end_matches('/'))
+        }
+    };
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    // Spans are exported from a thread of their own, as they may end before
+    // the runtime of the node starts, or after it stopped
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .http()
+                .with_env()
+                .with_endpoint(endpoint),
+        )
+        .with_trace_config(
+            trace::config().with_resource(Resource::new([KeyValue::new("service.name", "ockam")])),
+        )
+        .install_simple();
+    match tracer {
+        Ok(tracer) => Some(tracing_opentelemetry::layer().with_tracer(tracer)),
+        Err(e) => {
+            eprintln!("Failed to initialise OpenTelemetry export: {e}");
+            None
+        }
+    }
+}
+
+/// The `traceparent` of the current span, to send along with a request.
+pub(crate) fn trace_context() -> Option<String> {
+    let mut carrier = HashMap::new();
+    TraceContextPropagator::new().inject_context(&Span::current().context(), &mut carrier);
+    carrier.remove(TRACEPARENT)
+}
+
+/// Export the spans which haven't been yet, before the process exits.
+pub(crate) fn shutdown() {
+    global::shutdown_tracer_provider()
+}
@@ -38,14 +38,29 @@ use ockam_core::route;
 ///     }
 /// }
 /// ```
+///
+/// Text outputs only implement `output`. Outputs which are not text, e.g. an encoded
+/// binary format, override `output_bytes` instead, which is what gets written to stdout.
+/// They still implement `output`, e.g. to return a printable summary of the value.
 pub trait Output {
     fn output(&self) -> anyhow::Result<String>;
+
+    /// The bytes written to stdout, by default those of `output` followed by a newline.
+    fn output_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = self.output()?.into_bytes();
+        bytes.push(b'\n');
+        Ok(bytes)
+    }
 }
 
 impl<O: Output> Output for &O {
     fn output(&self) -> anyhow::Result<String> {
         (*self).output()
     }
+
+    fn output_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        (*self).output_bytes()
+    }
 }
 
 impl Output for Space<'_> {
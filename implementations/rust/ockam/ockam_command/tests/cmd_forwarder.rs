@@ -16,3 +16,1117 @@ fn valid_arguments() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn ping_valid_arguments() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("forwarder")
+        .arg("ping")
+        .arg("n1")
+        .arg("--to")
+        .arg("node_blue");
+    cmd.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn ping_probe_size() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("forwarder")
+        .arg("ping")
+        .arg("n1")
+        .arg("--to")
+        .arg("node_blue")
+        .arg("--probe-size")
+        .arg("--max-size")
+        .arg("65536");
+    cmd.assert().success();
+
+    // --max-size only applies to --probe-size
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("forwarder")
+        .arg("ping")
+        .arg("n1")
+        .arg("--to")
+        .arg("node_blue")
+        .arg("--max-size")
+        .arg("65536");
+    cmd.assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn request_id() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("forwarder")
+        .arg("create")
+        .arg("n1")
+        .arg("--at")
+        .arg("/ip4/127.0.0.1/tcp/8080")
+        .arg("--to")
+        .arg("node_blue")
+        .arg("--request-id")
+        .arg("0000abcd");
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("forwarder")
+        .arg("create")
+        .arg("n1")
+        .arg("--at")
+        .arg("/ip4/127.0.0.1/tcp/8080")
+        .arg("--to")
+        .arg("node_blue")
+        .arg("--request-id")
+        .arg("not-hex");
+    cmd.assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn invalid_forwarder_name() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("forwarder")
+        .arg("create")
+        .arg("n1/service/x")
+        .arg("--at")
+        .arg("/ip4/127.0.0.1/tcp/8080")
+        .arg("--to")
+        .arg("node_blue");
+    cmd.assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn expires_in() -> Result<(), Box<dyn std::error::Error>> {
+    for (duration, valid) in [
+        ("30m", true),
+        ("1h30m", true),
+        ("0s", false),
+        ("soon", false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .arg("n1")
+            .arg("--at")
+            .arg("/ip4/127.0.0.1/tcp/8080")
+            .arg("--to")
+            .arg("node_blue")
+            .arg("--expires-in")
+            .arg(duration);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn api_prefix() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("forwarder")
+        .arg("create")
+        .arg("n1")
+        .arg("--at")
+        .arg("/ip4/127.0.0.1/tcp/8080")
+        .arg("--to")
+        .arg("node_blue")
+        .arg("--api-prefix")
+        .arg("/api/v1");
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("forwarder")
+        .arg("ping")
+        .arg("n1")
+        .arg("--to")
+        .arg("node_blue")
+        .arg("--api-prefix")
+        .arg("/api/v1");
+    cmd.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn node_startup_wait() -> Result<(), Box<dyn std::error::Error>> {
+    for (seconds, valid) in [("10", true), ("0", true), ("-1", false), ("10s", false)] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .arg("n1")
+            .arg("--at")
+            .arg("/ip4/127.0.0.1/tcp/8080")
+            .arg("--to")
+            .arg("node_blue")
+            .arg("--node-startup-wait")
+            .arg(seconds);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn print_name() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (&["--print-name"][..], true),
+        (&["--print-name", "--quiet"][..], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .arg("--at")
+            .arg("/ip4/127.0.0.1/tcp/8080")
+            .arg("--to")
+            .arg("node_blue")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn wildcard() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (&["--wildcard"][..], true),
+        (&["--wildcard", "blue"][..], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .arg("--at")
+            .arg("/node/green")
+            .arg("--to")
+            .arg("node_blue")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn weighted_at() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (&["--at", "/node/green", "--at", "/node/red"][..], true),
+        (
+            &[
+                "--at",
+                "/node/green",
+                "--weight",
+                "3",
+                "--at",
+                "/node/red",
+                "--weight",
+                "1",
+            ][..],
+            true,
+        ),
+        (&["--at", "/node/green", "--weight", "0"][..], false),
+        (&["--at", "/node/green", "--weight", "heavy"][..], false),
+        (&["--weight", "1"][..], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .arg("--to")
+            .arg("node_blue")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn tls_at() -> Result<(), Box<dyn std::error::Error>> {
+    for (at, valid) in [
+        ("/dnsaddr/relay.example.com/tcp/443/tls", true),
+        ("/ip4/127.0.0.1/tcp/443/tls/sni/relay.example.com", true),
+        (
+            "/dnsaddr/relay.example.com/tcp/443/tls/relay.example.com",
+            false,
+        ),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .arg("--at")
+            .arg(at)
+            .arg("--to")
+            .arg("node_blue");
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn export_import() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (&["export", "--to", "node_blue"][..], true),
+        (
+            &["export", "--to", "node_blue", "--to-file", "f.json"][..],
+            true,
+        ),
+        (
+            &["import", "--to", "node_blue", "--from-file", "f.json"][..],
+            true,
+        ),
+        (
+            &[
+                "import",
+                "--to",
+                "node_blue",
+                "--from-file",
+                "f.json",
+                "--resume",
+                "--state-file",
+                "f.progress",
+            ][..],
+            true,
+        ),
+        (&["import", "--to", "node_blue"][..], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn env_defaults() -> Result<(), Box<dyn std::error::Error>> {
+    for (to, at, args, valid) in [
+        (Some("node_blue"), Some("/node/green"), &[][..], true),
+        (Some("node_blue"), None, &["--at", "/node/green"][..], true),
+        (Some("node_blue"), None, &["blue"][..], false),
+        // Flags override the environment
+        (
+            Some("node_blue"),
+            Some("not a route"),
+            &["--at", "/node/green"][..],
+            true,
+        ),
+        (None, None, &[][..], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.env_remove("OCKAM_FORWARDER_TO")
+            .env_remove("OCKAM_FORWARDER_AT")
+            .arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .args(args);
+        if let Some(to) = to {
+            cmd.env("OCKAM_FORWARDER_TO", to);
+        }
+        if let Some(at) = at {
+            cmd.env("OCKAM_FORWARDER_AT", at);
+        }
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn access_policy() -> Result<(), Box<dyn std::error::Error>> {
+    for (policy, valid) in [
+        (r#"(= subject.component "web")"#, true),
+        (
+            r#"(and (= subject.role "member") (= subject.project_id "p1"))"#,
+            true,
+        ),
+        (r#"(= subject.component "web""#, false),
+        ("", false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .arg("--at")
+            .arg("/node/green")
+            .arg("--to")
+            .arg("node_blue")
+            .arg("--access-policy")
+            .arg(policy);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn prefer_ip_family() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (&["--prefer-ipv4"][..], true),
+        (&["--prefer-ipv6"][..], true),
+        (&["--prefer-ipv4", "--prefer-ipv6"][..], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .arg("--at")
+            .arg("/dnsaddr/relay.example.com/tcp/4000")
+            .arg("--to")
+            .arg("node_blue")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn rename() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (
+            &["rename", "0a1b2c3d", "web", "--to", "node_blue"][..],
+            true,
+        ),
+        (&["rename", "0a1b2c3d", "web"][..], false),
+        (&["rename", "0a1b2c3d", "--to", "node_blue"][..], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn quiet_on_exists() -> Result<(), Box<dyn std::error::Error>> {
+    for args in [
+        &["--quiet-on-exists"][..],
+        &["--quiet-on-exists", "--wildcard"][..],
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .arg("--at")
+            .arg("/ip4/127.0.0.1/tcp/8080")
+            .arg("--to")
+            .arg("node_blue")
+            .args(args);
+        cmd.assert().success();
+    }
+
+    Ok(())
+}
+
+#[test]
+fn format_template() -> Result<(), Box<dyn std::error::Error>> {
+    for (template, valid) in [
+        ("{name} -> {remote_address} ({kind})", true),
+        ("{{{address}}}", true),
+        ("{mode}", false),
+        ("{name", false),
+        ("name}", false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .arg("--at")
+            .arg("/ip4/127.0.0.1/tcp/8080")
+            .arg("--to")
+            .arg("node_blue")
+            .arg("--format-template")
+            .arg(template);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn keepalive() -> Result<(), Box<dyn std::error::Error>> {
+    for (seconds, valid) in [("30", true), ("0", false), ("x", false)] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .arg("--at")
+            .arg("/ip4/127.0.0.1/tcp/8080")
+            .arg("--to")
+            .arg("node_blue")
+            .arg("--keepalive")
+            .arg(seconds);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn on_conflict() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (&["--on-conflict", "error"][..], true),
+        (&["--on-conflict", "replace"][..], true),
+        (&["--on-conflict", "skip", "--wildcard"][..], true),
+        (&["--on-conflict", "overwrite"][..], false),
+        (&["--on-conflict", "skip", "--quiet-on-exists"][..], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .arg("--at")
+            .arg("/ip4/127.0.0.1/tcp/8080")
+            .arg("--to")
+            .arg("node_blue")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn alias_template() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (
+            &["--alias-template", "{env}-{name}", "--tag", "env=prod"][..],
+            true,
+        ),
+        (&["--alias-template", "{name}"][..], true),
+        (
+            &[
+                "--alias-template",
+                "{env}-{name}",
+                "--tag",
+                "env=prod",
+                "--tag",
+                "env=test",
+            ][..],
+            true,
+        ),
+        (&["--tag", "env=prod"][..], false),
+        (&["--alias-template", "{e nv}"][..], false),
+        (&["--alias-template", "{name"][..], false),
+        (&["--alias-template", "{env}", "--tag", "env"][..], false),
+        (
+            &["--alias-template", "{env}", "--tag", "name=blue"][..],
+            false,
+        ),
+        (&["--alias-template", "{name}", "--wildcard"][..], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .arg("--at")
+            .arg("/ip4/127.0.0.1/tcp/8080")
+            .arg("--to")
+            .arg("node_blue")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn skip_version_check() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("forwarder")
+        .arg("create")
+        .arg("--at")
+        .arg("/ip4/127.0.0.1/tcp/8080")
+        .arg("--to")
+        .arg("node_blue")
+        .arg("--expires-in")
+        .arg("1h")
+        .arg("--skip-version-check");
+    cmd.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn save_as() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (&["--at", "/node/n1", "--save-as", "hub"][..], true),
+        (
+            &["--at", "/node/n1", "--save-as", "hub", "--force"][..],
+            true,
+        ),
+        (&["--at", "@hub"][..], true),
+        (&["--at", "/node/n1", "--force"][..], false),
+        (&["--at", "/node/n1", "--save-as", "h/b"][..], false),
+        (&["--at", "/node/n1", "--save-as", ""][..], false),
+        (&["--at", "@"][..], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .arg("--to")
+            .arg("node_blue")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn doctor() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (&["blue", "--to", "node_blue"][..], true),
+        (
+            &["blue", "--to", "/node/hub/service/forward_to_edge"][..],
+            true,
+        ),
+        (&["blue"][..], false),
+        (&["--to", "node_blue"][..], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("doctor")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn socket_shorthand_at() -> Result<(), Box<dyn std::error::Error>> {
+    for (at, valid) in [
+        ("[::1]:4000", true),
+        ("127.0.0.1:4000", true),
+        ("/ip6/::1/tcp/4000", true),
+        ("[::1]", false),
+        ("::1:4000", false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .arg("--at")
+            .arg(at)
+            .arg("--to")
+            .arg("node_blue");
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn emit_inlet_config() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (vec!["--emit-inlet-config", "inlet.json"], true),
+        (
+            vec![
+                "--emit-inlet-config",
+                "inlet.json",
+                "--outlet-address",
+                "web",
+            ],
+            true,
+        ),
+        (vec!["--outlet-address", "web"], false),
+        (
+            vec!["--emit-inlet-config", "inlet.json", "--wildcard"],
+            false,
+        ),
+        (vec!["--emit-inlet-config"], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .arg("--at")
+            .arg("/node/relay")
+            .arg("--to")
+            .arg("node_blue")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn idempotency_key() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (vec!["--idempotency-key", "deploy-42"], true),
+        (vec!["--idempotency-key"], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .arg("--at")
+            .arg("/node/relay")
+            .arg("--to")
+            .arg("node_blue")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn no_refresh() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (vec!["--no-refresh"], true),
+        (vec!["--no-refresh=yes"], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .arg("--at")
+            .arg("/project/default")
+            .arg("--to")
+            .arg("node_blue")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn failover() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (vec!["--to", "node_blue,node_green"], true),
+        (
+            vec!["--to", "/node/blue,/node/green/service/forward_to_blue"],
+            true,
+        ),
+        (vec!["--to"], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .arg("--at")
+            .arg("/node/relay")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn metrics_file() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (vec!["--metrics-file", "/tmp/ockam.prom"], true),
+        (vec!["--metrics-file"], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .arg("--at")
+            .arg("/node/relay")
+            .arg("--to")
+            .arg("node_blue")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn from_stdin_jsonl() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (&["--to", "node_blue", "--from-stdin-jsonl"][..], true),
+        (
+            &[
+                "--to",
+                "node_blue",
+                "--at",
+                "/node/relay",
+                "--from-stdin-jsonl",
+            ][..],
+            true,
+        ),
+        (
+            &["--to", "node_blue", "--from-stdin-jsonl", "--strict"][..],
+            true,
+        ),
+        (&["--to", "node_blue", "--strict"][..], false),
+        (
+            &["blue", "--to", "node_blue", "--from-stdin-jsonl"][..],
+            false,
+        ),
+        (
+            &["--to", "node_blue", "--from-stdin-jsonl", "--print-name"][..],
+            false,
+        ),
+        (&["--to", "node_blue"][..], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.env_remove("OCKAM_FORWARDER_AT")
+            .arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn move_forwarder() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (
+            &["blue", "--from", "node_blue", "--to", "node_purple"][..],
+            true,
+        ),
+        (&["--from", "node_blue", "--to", "node_purple"][..], false),
+        (&["blue", "--to", "node_purple"][..], false),
+        (&["blue", "--from", "node_blue"][..], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("move")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn plan_apply() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (
+            &[
+                "create",
+                "--at",
+                "/node/relay",
+                "--to",
+                "node_blue",
+                "--plan-out",
+                "plan.json",
+            ][..],
+            true,
+        ),
+        (
+            &[
+                "create",
+                "--at",
+                "/node/relay",
+                "--to",
+                "node_blue",
+                "--plan-out",
+                "plan.json",
+                "--save-as",
+                "hub",
+            ][..],
+            false,
+        ),
+        (&["apply", "--plan", "plan.json"][..], true),
+        (&["apply"][..], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn delete() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (&["blue", "--to", "node_blue"][..], true),
+        (&["blue", "--to", "node_blue", "--drain"][..], true),
+        (
+            &[
+                "blue",
+                "--to",
+                "node_blue",
+                "--drain",
+                "--timeout",
+                "5",
+                "--on-timeout",
+                "abort",
+            ][..],
+            true,
+        ),
+        (&["blue"][..], false),
+        (&["blue", "--to", "node_blue", "--timeout", "5"][..], false),
+        (
+            &["blue", "--to", "node_blue", "--drain", "--timeout", "0"][..],
+            false,
+        ),
+        (
+            &[
+                "blue",
+                "--to",
+                "node_blue",
+                "--drain",
+                "--on-timeout",
+                "wait",
+            ][..],
+            false,
+        ),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("delete")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn logs() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (&["blue", "--to", "node_blue"][..], true),
+        (&["blue", "--to", "node_blue", "--follow"][..], true),
+        (&["blue", "--to", "node_blue", "-f"][..], true),
+        (&["blue"][..], false),
+        (&["--to", "node_blue"][..], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("logs")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn label_selector() -> Result<(), Box<dyn std::error::Error>> {
+    for (args, valid) in [
+        (
+            &[
+                "blue",
+                "--at",
+                "/node/relay",
+                "--label-selector",
+                "env=prod",
+            ][..],
+            true,
+        ),
+        (
+            &[
+                "blue",
+                "--at",
+                "/node/relay",
+                "--label-selector",
+                "env=prod, region!=eu,gpu,!canary",
+            ][..],
+            true,
+        ),
+        (
+            &[
+                "blue",
+                "--at",
+                "/node/relay",
+                "--to",
+                "node_blue",
+                "--label-selector",
+                "env=prod",
+            ][..],
+            false,
+        ),
+        (
+            &[
+                "blue",
+                "--at",
+                "/node/relay",
+                "--label-selector",
+                "env=prod,",
+            ][..],
+            false,
+        ),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser")
+            .arg("forwarder")
+            .arg("create")
+            .env_remove("OCKAM_FORWARDER_TO")
+            .args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn label_selector_with_env_to() -> Result<(), Box<dyn std::error::Error>> {
+    // OCKAM_FORWARDER_TO counts as --to, which --label-selector conflicts with
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.env("OCKAM_FORWARDER_TO", "node_blue")
+        .arg("--test-argument-parser")
+        .arg("forwarder")
+        .arg("create")
+        .args([
+            "blue",
+            "--at",
+            "/node/relay",
+            "--label-selector",
+            "env=prod",
+        ]);
+    cmd.assert().failure();
+
+    Ok(())
+}
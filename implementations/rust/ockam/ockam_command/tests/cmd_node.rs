@@ -19,5 +19,72 @@ fn valid_arguments() -> Result<(), Box<dyn std::error::Error>> {
         .arg("node-name");
     cmd.assert().success();
 
+    // authorization stats of a node
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("node")
+        .arg("authz-stats")
+        .arg("--to")
+        .arg("/node/node-name");
+    cmd.assert().success();
+
+    // default policy of a node
+    for (args, valid) in [
+        (
+            &[
+                "set-default-policy",
+                "(= subject.role \"member\")",
+                "--to",
+                "n1",
+            ][..],
+            true,
+        ),
+        (&["set-default-policy", "--clear", "--to", "n1"][..], true),
+        (&["set-default-policy", "--to", "n1"][..], false),
+        (
+            &["set-default-policy", "(= subject.role", "--to", "n1"][..],
+            false,
+        ),
+        (
+            &["set-default-policy", "true", "--clear", "--to", "n1"][..],
+            false,
+        ),
+        (&["get-default-policy", "--to", "n1"][..], true),
+        (
+            &["describe-policy", "--to", "n1", "--worker", "uppercase"][..],
+            true,
+        ),
+        (&["describe-policy", "--to", "n1"][..], false),
+        (
+            &[
+                "trace-authz",
+                "--to",
+                "n1",
+                "--worker",
+                "uppercase",
+                "--message-file",
+                "m.json",
+            ][..],
+            true,
+        ),
+        (
+            &["trace-authz", "--to", "n1", "--worker", "uppercase"][..],
+            false,
+        ),
+        (
+            &["create", "n1", "--tag", "env=prod", "--tag", "region=us"][..],
+            true,
+        ),
+        (&["create", "n1", "--tag", "env"][..], false),
+    ] {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("--test-argument-parser").arg("node").args(args);
+        if valid {
+            cmd.assert().success();
+        } else {
+            cmd.assert().failure();
+        }
+    }
+
     Ok(())
 }
@@ -147,6 +147,56 @@ teardown() {
   assert_output --partial "/service/"
 }
 
+@test "set and clear the default policy of a node" {
+  $OCKAM node create n1
+
+  run --separate-stderr $OCKAM node get-default-policy --to n1
+  assert_output "No default policy"
+
+  $OCKAM node set-default-policy '(= subject.role "member")' --to n1
+  run --separate-stderr $OCKAM node get-default-policy --to n1
+  assert_success
+  assert_output '(= subject.role "member")'
+
+  $OCKAM node set-default-policy --clear --to n1
+  run --separate-stderr $OCKAM message send hello --to /node/n1/service/uppercase
+  assert_success
+  assert_output "HELLO"
+}
+
+@test "describe the access control of a worker" {
+  $OCKAM node create n1
+
+  run --separate-stderr $OCKAM node describe-policy --to n1 --worker uppercase
+  assert_success
+  assert_output "AllowAll"
+
+  $OCKAM node set-default-policy '(= subject.role "member")' --to n1
+  run --separate-stderr $OCKAM node describe-policy --to n1 --worker uppercase
+  assert_output 'All[Policy((= subject.role "member")), AllowAll]'
+
+  run $OCKAM node describe-policy --to n1 --worker unknown
+  assert_failure
+}
+
+@test "trace the access control of a worker" {
+  $OCKAM node create n1
+  message="$BATS_TMPDIR/message.json"
+  echo '{"return_route": ["app"], "payload": "68656c6c6f"}' > "$message"
+
+  run --separate-stderr $OCKAM node trace-authz --to n1 --worker uppercase --message-file "$message"
+  assert_success
+  assert_output "AllowAll: allowed"
+
+  $OCKAM node set-default-policy '(= subject.role "member")' --to n1
+  run --separate-stderr $OCKAM node trace-authz --to n1 --worker uppercase --message-file "$message"
+  assert_success
+  assert_line --index 0 "All: denied"
+
+  run $OCKAM node trace-authz --to n1 --worker unknown --message-file "$message"
+  assert_failure
+}
+
 @test "vault create" {
   run $OCKAM node create n1 --skip-defaults
   assert_success
@@ -245,6 +295,144 @@ teardown() {
   assert [ "$output" == "HELLO" ]
 }
 
+@test "print the name of a forwarder registered under an alias" {
+  $OCKAM node create n1
+  $OCKAM node create n2
+
+  run --separate-stderr $OCKAM forwarder create blue --at /node/n1 --to /node/n2 \
+    --alias-template '{env}-{name}' --tag env=prod --print-name
+  assert_success
+  assert_output "blue"
+  run --separate-stderr $OCKAM message send hello --to /node/n1/service/prod-blue/service/uppercase
+  assert_output "HELLO"
+}
+
+@test "create a forwarder named after a node service" {
+  $OCKAM node create n1
+  $OCKAM node create n2
+
+  run --separate-stderr $OCKAM forwarder create echo --at /node/n1 --to /node/n2
+  assert_success
+  assert_output "/service/forward_to_echo"
+  run --separate-stderr $OCKAM message send hello --to /node/n1/service/forward_to_echo/service/uppercase
+  assert_output "HELLO"
+}
+
+@test "create an existing forwarder with --quiet-on-exists" {
+  $OCKAM node create n1
+  $OCKAM node create n2
+
+  $OCKAM forwarder create n1 --at /node/n1 --to /node/n2
+  run --separate-stderr $OCKAM forwarder create n1 --at /node/n1 --to /node/n2 --quiet-on-exists
+
+  assert_success
+  assert_output ""
+}
+
+@test "create an existing forwarder with --on-conflict" {
+  $OCKAM node create n1
+  $OCKAM node create n2
+
+  $OCKAM forwarder create n1 --at /node/n1 --to /node/n2
+  run --separate-stderr $OCKAM forwarder create n1 --at /node/n1 --to /node/n2
+  assert_failure 64
+
+  run --separate-stderr $OCKAM forwarder create n1 --at /node/n1 --to /node/n2 --on-conflict replace
+  assert_success
+  assert_output "/service/forward_to_n1"
+
+  run --separate-stderr $OCKAM message send hello --to /node/n1/service/forward_to_n1/service/uppercase
+  assert_success
+  assert_output "HELLO"
+}
+
+@test "delete a forwarder balanced across several --at nodes once it expires" {
+  $OCKAM node create relay1
+  $OCKAM node create relay2
+  $OCKAM node create blue
+
+  run $OCKAM forwarder create blue --at /node/relay1 --at /node/relay2 --to /node/blue --expires-in 2s
+  assert_success
+  sleep 3
+
+  run $OCKAM forwarder ping blue --to /node/blue
+  assert_failure 67
+}
+
+@test "rename a forwarder and send message through it" {
+  $OCKAM node create n1
+  $OCKAM node create n2
+
+  $OCKAM forwarder create n1 --at /node/n1 --to /node/n2
+  $OCKAM forwarder rename n1 renamed --to /node/n2
+  run --separate-stderr $OCKAM message send hello --to /node/n1/service/forward_to_renamed/service/uppercase
+
+  assert_success
+  assert_output "HELLO"
+}
+
+@test "list the events of a forwarder" {
+  $OCKAM node create n1
+  $OCKAM node create n2
+
+  $OCKAM forwarder create n1 --at /node/n1 --to /node/n2
+  $OCKAM message send hello --to /node/n1/service/forward_to_n1/service/uppercase
+  run --separate-stderr $OCKAM forwarder logs n1 --to /node/n2
+  assert_success
+  assert_output --partial "created at"
+  assert_output --partial "relayed 1 message"
+}
+
+@test "create a forwarder with options checked against the node version" {
+  $OCKAM node create n1
+  $OCKAM node create n2
+
+  run --separate-stderr $OCKAM forwarder create n1 --at /node/n1 --to /node/n2 --keepalive 30
+  assert_success
+  assert_output "/service/forward_to_n1"
+}
+
+@test "save the route of a forwarder as an alias" {
+  $OCKAM node create n1
+  $OCKAM node create n2
+
+  $OCKAM forwarder create n1 --at /node/n1 --to /node/n2 --save-as hub
+  run --separate-stderr $OCKAM forwarder create n3 --at @hub --to /node/n2
+  assert_success
+  assert_output "/service/forward_to_n3"
+
+  run $OCKAM forwarder create n4 --at /node/n1 --to /node/n2 --save-as hub
+  assert_failure 64
+}
+
+@test "diagnose a forwarder" {
+  $OCKAM node create n1
+  $OCKAM node create n2
+
+  $OCKAM forwarder create n1 --at /node/n1 --to /node/n2
+  run --separate-stderr $OCKAM forwarder doctor n1 --to /node/n2
+  assert_success
+  assert_output --partial "PASS  upstream"
+
+  run --separate-stderr $OCKAM forwarder doctor n3 --to /node/n2
+  assert_failure 67
+  assert_output --partial "FAIL  forwarder"
+  assert_output --partial "SKIP  upstream"
+}
+
+@test "drain and delete a forwarder" {
+  $OCKAM node create n1
+  $OCKAM node create n2
+
+  $OCKAM forwarder create n1 --at /node/n1 --to /node/n2
+  run --separate-stderr $OCKAM forwarder delete n1 --to /node/n2 --drain --timeout 5
+  assert_success
+  assert_output "Forwarder forward_to_n1 deleted"
+
+  run $OCKAM forwarder delete n1 --to /node/n2
+  assert_failure 67
+}
+
 @test "create an inlet/outlet pair and move tcp traffic through it" {
   $OCKAM node create n1
   $OCKAM node create n2
@@ -271,6 +459,165 @@ teardown() {
   assert_success
 }
 
+@test "create an inlet from the config written by forwarder create and move tcp traffic through it" {
+  $OCKAM node create relay
+
+  $OCKAM node create blue
+  $OCKAM tcp-outlet create --at /node/blue --from /service/outlet --to 127.0.0.1:5000
+  $OCKAM forwarder create blue --at /node/relay --to /node/blue --emit-inlet-config "$BATS_TMPDIR/inlet.json"
+
+  $OCKAM node create green
+  $OCKAM tcp-inlet create --at /node/green --from 127.0.0.1:7000 --from-file "$BATS_TMPDIR/inlet.json"
+
+  run curl --fail --head 127.0.0.1:7000
+  assert_success
+}
+
+@test "create a forwarder once with an idempotency key" {
+  $OCKAM node create relay
+  $OCKAM node create blue
+
+  run --separate-stderr $OCKAM forwarder create --at /node/relay --to /node/blue --idempotency-key k1
+  assert_success
+  first="$output"
+
+  run --separate-stderr $OCKAM forwarder create --at /node/relay --to /node/blue --idempotency-key k1
+  assert_success
+  assert_output "$first"
+
+  run $OCKAM forwarder create --at /node/relay --to /node/blue --idempotency-key k1 --expires-in 1h
+  assert_failure 64
+}
+
+@test "create a forwarder with the first --to node which responds" {
+  $OCKAM node create relay
+  $OCKAM node create primary
+  $OCKAM node create secondary
+  $OCKAM node stop primary
+
+  run $OCKAM forwarder create blue --at /node/relay --to primary,secondary
+  assert_success
+  assert_output --partial "Using node secondary"
+  assert_output --partial "/service/forward_to_blue"
+
+  $OCKAM node stop secondary
+  run $OCKAM forwarder create blue2 --at /node/relay --to primary,secondary
+  assert_failure 69
+  assert_output --partial "primary: node primary did not accept connections"
+  assert_output --partial "secondary: node secondary did not accept connections"
+}
+
+@test "count the forwarders created in a Prometheus textfile" {
+  $OCKAM node create relay
+  $OCKAM node create blue
+  metrics="$BATS_TMPDIR/forwarders.prom"
+  rm -f "$metrics"
+
+  run $OCKAM forwarder create blue1 --at /node/relay --to /node/blue --metrics-file "$metrics"
+  assert_success
+  run $OCKAM forwarder create blue2 --at /node/relay --to blue --metrics-file "$metrics"
+  assert_success
+
+  run cat "$metrics"
+  assert_output --partial '# TYPE ockam_forwarder_created_total counter'
+  assert_output --partial 'ockam_forwarder_created_total{node="blue"} 2'
+  assert_output --partial 'ockam_forwarder_last_create_timestamp{node="blue"}'
+}
+
+@test "plan a forwarder, then apply the plan" {
+  $OCKAM node create relay
+  $OCKAM node create blue
+  plan="$BATS_TMPDIR/plan.json"
+  rm -f "$plan"
+
+  run $OCKAM forwarder create blue --at /node/relay --to /node/blue --plan-out "$plan"
+  assert_success
+  run $OCKAM forwarder ping blue --to /node/blue
+  assert_failure 67
+
+  run cat "$plan"
+  assert_output --partial '"alias": "forward_to_blue"'
+
+  $OCKAM forwarder create red --at /node/relay --to /node/blue
+  run $OCKAM forwarder apply --plan "$plan"
+  assert_success
+  assert_output --partial "/service/forward_to_blue"
+  assert_output --partial "changed since the plan was made: created forward_to_red"
+}
+
+@test "probe the largest message a forwarder carries" {
+  $OCKAM node create relay
+  $OCKAM node create blue
+  $OCKAM forwarder create blue --at /node/relay --to /node/blue
+
+  run $OCKAM forwarder ping blue --to /node/blue --probe-size --max-size 4096
+  assert_success
+  assert_output --partial "carry at least 4096 bytes"
+}
+
+@test "create a forwarder on the nodes matching a label selector" {
+  $OCKAM node create relay
+  $OCKAM node create blue --tag env=prod --tag region=us
+  $OCKAM node create green --tag env=dev --tag region=us
+
+  run --separate-stderr $OCKAM forwarder create edge --at /node/relay --label-selector env=prod,region=us
+  assert_success
+  assert_output "blue: /service/forward_to_edge"
+
+  run $OCKAM forwarder ping edge --to /node/blue
+  assert_success
+  run $OCKAM forwarder ping edge --to /node/green
+  assert_failure 67
+
+  run $OCKAM forwarder create edge --at /node/relay --label-selector env=staging
+  assert_success
+  assert_output --partial "Warning: no node matches the label selector env=staging"
+}
+
+@test "move a forwarder to another node" {
+  $OCKAM node create relay
+  $OCKAM node create blue
+  $OCKAM node create purple
+  $OCKAM forwarder create blue --at /node/relay --to /node/blue
+
+  run $OCKAM forwarder move blue --from /node/blue --to /node/purple
+  assert_success
+  assert_output "/service/forward_to_blue"
+
+  run $OCKAM forwarder ping blue --to /node/purple
+  assert_success
+  run $OCKAM forwarder ping blue --to /node/blue
+  assert_failure 67
+
+  # purple has it already
+  $OCKAM forwarder create blue --at /node/relay --to /node/blue
+  run $OCKAM forwarder move blue --from /node/blue --to /node/purple
+  assert_failure 64
+}
+
+@test "create forwarders from lines of JSON on stdin" {
+  $OCKAM node create relay
+  $OCKAM node create blue
+
+  run --separate-stderr bash -c "printf '%s\\n' '{\"name\": \"web\"}' 'not json' '{\"name\": \"db\", \"at\": \"/node/relay\"}' | $OCKAM forwarder create --at /node/relay --to /node/blue --from-stdin-jsonl"
+  assert_failure
+  assert_line --index 0 '{"line":1,"name":"web","remote_address":"forward_to_web"}'
+  assert_line --index 1 --partial '{"line":2,"error":"line 2 is not a valid forwarder spec'
+  assert_line --index 2 '{"line":3,"name":"db","remote_address":"forward_to_db"}'
+
+  run bash -c "echo 'not json' | $OCKAM forwarder create --at /node/relay --to /node/blue --from-stdin-jsonl --strict"
+  assert_failure 64
+  assert_output --partial "line 1 is not a valid forwarder spec"
+}
+
+@test "fail to create a forwarder at an uncached project with --no-refresh" {
+  $OCKAM node create blue
+
+  run $OCKAM forwarder create blue --at /project/uncached --to /node/blue --no-refresh
+  assert_failure 64
+  assert_output --partial "project uncached not in local cache"
+}
+
 @test "create a node and start services" {
   $OCKAM node create n1
 
@@ -1,9 +1,14 @@
 use crate::compat::boxed::Box;
+use crate::compat::string::{String, ToString};
 use crate::{LocalMessage, Result};
 use core::fmt::Debug;
 
 /// Defines the interface for message flow authorization.
 ///
+/// Implementations inspect the message through the accessors of
+/// [`LocalMessage`], e.g. [`source_addr`](LocalMessage::source_addr) or
+/// [`payload`](LocalMessage::payload).
+///
 /// # Examples
 ///
 /// ```
@@ -30,14 +35,200 @@ use core::fmt::Debug;
 pub trait AccessControl: Debug + Send + Sync + 'static {
     /// Return true if the message is allowed to pass, and false if not.
     async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool>;
+
+    /// Return true if the message is allowed to pass, and false if not,
+    /// giving up once `token` is cancelled.
+    ///
+    /// This is what the node calls, with the [`CancellationToken`] of the
+    /// worker receiving the message, so that controls doing long-running
+    /// asynchronous work can bail out when the worker or the node stops.
+    /// The default implementation ignores the token and delegates to
+    /// [`is_authorized`](Self::is_authorized).
+    async fn is_authorized_with_ctx(
+        &self,
+        local_msg: &LocalMessage,
+        _token: Option<&CancellationToken>,
+    ) -> Result<bool> {
+        self.is_authorized(local_msg).await
+    }
+
+    /// Return the message if it is allowed to pass, and None if not.
+    ///
+    /// Access controls may annotate the message they return, e.g. attach
+    /// the [`LocalInfo`](crate::LocalInfo) of the identity they matched, for
+    /// the worker receiving it. The default implementation returns the
+    /// message unchanged when [`is_authorized_with_ctx`](Self::is_authorized_with_ctx)
+    /// allows it.
+    async fn authorize(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<LocalMessage>> {
+        if self.is_authorized_with_ctx(&local_msg, token).await? {
+            Ok(Some(local_msg))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Return the message if it is allowed to pass, with the [`Completion`]
+    /// to drop once the worker handled it, and None if not.
+    ///
+    /// This is what the node calls. Access controls keeping track of the
+    /// messages in flight, like [`ConcurrencyLimitAccessControl`], register
+    /// in the `Completion` what to do once the message is handled, and access
+    /// controls made of others return the `Completion`s of the parts which
+    /// allowed the message. The default implementation returns the message of
+    /// [`authorize`](Self::authorize) with an empty `Completion`.
+    async fn authorize_tracked(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<(LocalMessage, Completion)>> {
+        Ok(self
+            .authorize(local_msg, token)
+            .await?
+            .map(|local_msg| (local_msg, Completion::new())))
+    }
+
+    /// Describe the policy this AccessControl enforces, for operators
+    ///
+    /// AccessControls made of others describe them in turn, e.g.
+    /// `All[AllowAll, IdentityIdAccessControl(3 identities)]`. The default
+    /// implementation returns the name of the type, without its module path
+    /// and generic parameters.
+    fn describe(&self) -> String {
+        short_type_name(core::any::type_name::<Self>()).to_string()
+    }
+
+    /// Decide about the message like [`is_authorized`](Self::is_authorized),
+    /// and explain the decision
+    ///
+    /// This asks the AccessControls as deciding does, but without changing
+    /// their state, e.g. caches, sequence numbers or audit records, so that
+    /// tracing a message doesn't change the decisions made for the next ones.
+    /// AccessControls made of others return the traces of those they asked,
+    /// so that the trace shows which one decided. The default implementation
+    /// traces the result of [`is_authorized`](Self::is_authorized) under the
+    /// [`describe`](Self::describe) of the AccessControl, so AccessControls
+    /// whose `is_authorized` changes their state override it.
+    async fn trace_decision(&self, local_msg: &LocalMessage) -> DecisionTrace {
+        let result = self.is_authorized(local_msg).await;
+        DecisionTrace::leaf(self.describe(), &result)
+    }
+
+    /// Authorize messages with this AccessControl, and with `fallback` when
+    /// this one fails, see [`FallbackAccessControl`]
+    fn or_on_error<F: AccessControl>(self, fallback: F) -> FallbackAccessControl<Self, F>
+    where
+        Self: Sized,
+    {
+        FallbackAccessControl::new(self, fallback)
+    }
+}
+
+/// `name` without its module path and generic parameters
+fn short_type_name(name: &str) -> &str {
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Defines the interface for authorizing the messages a worker sends.
+///
+/// This is the outgoing counterpart of [`AccessControl`]: it is asked
+/// whether a message may leave a worker rather than whether it may reach
+/// one.
+#[async_trait]
+#[allow(clippy::wrong_self_convention)]
+pub trait OutgoingAccessControl: Debug + Send + Sync + 'static {
+    /// Return true if the message is allowed to be sent, and false if not.
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool>;
 }
 
 mod all;
 mod allow_all;
 mod any;
+mod audit;
+mod bypass;
+mod caching;
+mod cancellation;
+mod completion;
+mod concurrency_limit;
+mod decisions;
 mod deny_all;
+mod directional;
+mod fallback;
+mod hops;
+mod negative_caching;
+mod parallel;
+mod reloadable;
+mod routing;
+mod sequence;
+mod service_name;
+mod trace;
+mod unanimous;
 
 pub use all::*;
 pub use allow_all::*;
 pub use any::*;
+pub use audit::*;
+pub use bypass::*;
+pub use caching::*;
+pub use cancellation::*;
+pub use completion::*;
+pub use concurrency_limit::*;
+pub use decisions::*;
 pub use deny_all::*;
+pub use directional::*;
+pub use fallback::*;
+pub use hops::*;
+pub use negative_caching::*;
+pub use parallel::*;
+pub use reloadable::*;
+pub use routing::*;
+pub use sequence::*;
+pub use service_name::*;
+pub use trace::*;
+pub use unanimous::*;
+
+#[cfg(all(feature = "alloc", any(test, feature = "test-utils")))]
+pub mod testing;
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        short_type_name, AccessControl, AllAccessControl, AllowAll, AnyAccessControl, DenyAll,
+        MaxHopsAccessControl, RoutingAccessControl,
+    };
+
+    #[test]
+    fn test_short_type_name() {
+        assert_eq!(
+            short_type_name("ockam_core::access_control::AllowAll"),
+            "AllowAll"
+        );
+        assert_eq!(
+            short_type_name("a::b::CachingAccessControl<a::AllowAll, a::c::{{closure}}>"),
+            "CachingAccessControl"
+        );
+        assert_eq!(short_type_name("AllowAll"), "AllowAll");
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(AllowAll.describe(), "AllowAll");
+        let access_control = AllAccessControl::new(
+            AllowAll,
+            AnyAccessControl::new(DenyAll, MaxHopsAccessControl::new(3)),
+        );
+        assert_eq!(
+            access_control.describe(),
+            "All[AllowAll, Any[DenyAll, MaxHops(3)]]"
+        );
+        let access_control = RoutingAccessControl::new(DenyAll).with_destination("api", AllowAll);
+        assert_eq!(
+            access_control.describe(),
+            "Routing[0#api: AllowAll, *: DenyAll]"
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use crate::access_control::AccessControl;
+use crate::access_control::{
+    AccessControl, CancellationToken, Completion, DecisionCounts, DecisionTrace,
+};
+use crate::compat::{format, string::String};
 use crate::{async_trait, compat::boxed::Box, LocalMessage, Result};
 
 /// Allows message that are allowed buy both AccessControls
@@ -7,21 +10,240 @@ pub struct AllAccessControl<F: AccessControl, S: AccessControl> {
     // TODO: Extend for more than 2 policies
     first: F,
     second: S,
+    counts: Option<DecisionCounts>,
 }
 
 impl<F: AccessControl, S: AccessControl> AllAccessControl<F, S> {
     /// Constructor
     pub fn new(first: F, second: S) -> Self {
-        AllAccessControl { first, second }
+        AllAccessControl {
+            first,
+            second,
+            counts: None,
+        }
+    }
+
+    /// Count how often each AccessControl makes the decision, see
+    /// [`decision_counts`](Self::decision_counts)
+    pub fn with_decision_counts(mut self) -> Self {
+        self.counts = Some(DecisionCounts::default());
+        self
+    }
+
+    /// How many decisions the first and the second AccessControl made,
+    /// if counted
+    pub fn decision_counts(&self) -> Option<[usize; 2]> {
+        self.counts.as_ref().map(DecisionCounts::snapshot)
+    }
+
+    fn decided(&self, index: usize) {
+        if let Some(counts) = &self.counts {
+            counts.record(index)
+        }
     }
 }
 
 #[async_trait]
 impl<F: AccessControl, S: AccessControl> AccessControl for AllAccessControl<F, S> {
     async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
-        Ok(self.first.is_authorized(local_msg).await?
-            && self.second.is_authorized(local_msg).await?)
+        self.is_authorized_with_ctx(local_msg, None).await
+    }
+
+    async fn is_authorized_with_ctx(
+        &self,
+        local_msg: &LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<bool> {
+        if !self.first.is_authorized_with_ctx(local_msg, token).await? {
+            self.decided(0);
+            return Ok(false);
+        }
+        let decision = self.second.is_authorized_with_ctx(local_msg, token).await?;
+        self.decided(1);
+        Ok(decision)
+    }
+
+    /// Return the message as annotated by both AccessControls in turn
+    async fn authorize(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<LocalMessage>> {
+        Ok(self
+            .authorize_tracked(local_msg, token)
+            .await?
+            .map(|(local_msg, _)| local_msg))
+    }
+
+    /// Return the message as annotated by both AccessControls in turn, with
+    /// the completions of both
+    async fn authorize_tracked(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<(LocalMessage, Completion)>> {
+        let (local_msg, mut completion) =
+            match self.first.authorize_tracked(local_msg, token).await? {
+                Some(first) => first,
+                None => {
+                    self.decided(0);
+                    return Ok(None);
+                }
+            };
+        let second = self.second.authorize_tracked(local_msg, token).await?;
+        self.decided(1);
+        Ok(second.map(|(local_msg, second)| {
+            completion.merge(second);
+            (local_msg, completion)
+        }))
+    }
+
+    /// Trace the first AccessControl, and the second one if the first
+    /// allowed the message
+    async fn trace_decision(&self, local_msg: &LocalMessage) -> DecisionTrace {
+        let first = self.first.trace_decision(local_msg).await;
+        if !first.outcome.is_allowed() {
+            return DecisionTrace::new("All", first.outcome.clone(), vec![first]);
+        }
+        let second = self.second.trace_decision(local_msg).await;
+        DecisionTrace::new("All", second.outcome.clone(), vec![first, second])
+    }
+
+    fn describe(&self) -> String {
+        format!("All[{}, {}]", self.first.describe(), self.second.describe())
     }
 }
 
-// TODO: Tests
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use crate::access_control::testing::{LocalMessageBuilder, MockAccessControl};
+    use crate::access_control::AnyAccessControl;
+    use crate::compat::boxed::Box;
+    use crate::compat::future::poll_once;
+    use crate::compat::string::String;
+    use crate::compat::vec::Vec;
+    use crate::{async_trait, LocalInfo, LocalMessage, Result};
+
+    use super::{AccessControl, AllAccessControl, CancellationToken};
+
+    /// Credential check attaching the identity it matched to the message
+    #[derive(Debug)]
+    struct Credential {
+        allow: bool,
+        identity: &'static str,
+    }
+
+    #[async_trait]
+    impl AccessControl for Credential {
+        async fn is_authorized(&self, _local_msg: &LocalMessage) -> Result<bool> {
+            Ok(self.allow)
+        }
+
+        async fn authorize(
+            &self,
+            mut local_msg: LocalMessage,
+            _token: Option<&CancellationToken>,
+        ) -> Result<Option<LocalMessage>> {
+            if !self.allow {
+                return Ok(None);
+            }
+            local_msg.append_local_info(LocalInfo::new(
+                String::from("identity"),
+                self.identity.as_bytes().to_vec(),
+            ));
+            Ok(Some(local_msg))
+        }
+    }
+
+    /// Identities attached by the combined access control, if it allows the message
+    fn authorize(access_control: impl AccessControl) -> Option<Vec<Vec<u8>>> {
+        let local_msg = poll_once(async {
+            access_control
+                .authorize(LocalMessageBuilder::new().build(), None)
+                .await
+        })
+        .unwrap()?;
+        Some(
+            local_msg
+                .local_info()
+                .iter()
+                .map(|i| i.data().to_vec())
+                .collect(),
+        )
+    }
+
+    fn credential(allow: bool, identity: &'static str) -> Credential {
+        Credential { allow, identity }
+    }
+
+    /// Decision of the combined access control, and how many times each
+    /// of its parts was asked
+    fn is_authorized(first: [bool; 1], second: [bool; 1]) -> (bool, usize, usize) {
+        let (first, second) = (
+            MockAccessControl::new(first),
+            MockAccessControl::new(second),
+        );
+        let access_control = AllAccessControl::new(first.clone(), second.clone());
+        let decision = poll_once(async {
+            access_control
+                .is_authorized(&LocalMessageBuilder::new().build())
+                .await
+        });
+        (decision.unwrap(), first.calls(), second.calls())
+    }
+
+    #[test]
+    fn test_all() {
+        assert_eq!(is_authorized([true], [true]), (true, 1, 1));
+        assert_eq!(is_authorized([true], [false]), (false, 1, 1));
+        assert_eq!(is_authorized([false], [true]), (false, 1, 0));
+    }
+
+    #[test]
+    fn test_all_keeps_annotations() {
+        assert_eq!(
+            authorize(AllAccessControl::new(
+                credential(true, "alice"),
+                credential(true, "bob")
+            )),
+            Some(vec![b"alice".to_vec(), b"bob".to_vec()])
+        );
+        assert_eq!(
+            authorize(AllAccessControl::new(
+                credential(true, "alice"),
+                credential(false, "bob")
+            )),
+            None
+        );
+        // The identity of the first Credential of the Any allowing it
+        assert_eq!(
+            authorize(AllAccessControl::new(
+                MockAccessControl::new([true]),
+                AnyAccessControl::new(credential(false, "alice"), credential(true, "bob"))
+            )),
+            Some(vec![b"bob".to_vec()])
+        );
+    }
+
+    #[test]
+    fn test_all_decision_counts() {
+        let access_control = AllAccessControl::new(
+            MockAccessControl::new([true, false, true, false]),
+            MockAccessControl::new([true, false]),
+        );
+        assert_eq!(access_control.decision_counts(), None);
+
+        let access_control = access_control.with_decision_counts();
+        for _ in 0..4 {
+            poll_once(async {
+                access_control
+                    .is_authorized(&LocalMessageBuilder::new().build())
+                    .await
+            })
+            .unwrap();
+        }
+        // The first denied twice, the second decided the other two
+        assert_eq!(access_control.decision_counts(), Some([2, 2]));
+    }
+}
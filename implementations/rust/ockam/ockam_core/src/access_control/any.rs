@@ -1,4 +1,7 @@
-use crate::access_control::AccessControl;
+use crate::access_control::{
+    AccessControl, CancellationToken, Completion, DecisionCounts, DecisionTrace, TraceOutcome,
+};
+use crate::compat::{format, string::String};
 use crate::{async_trait, compat::boxed::Box, LocalMessage, Result};
 
 /// Allows message that are allowed buy either AccessControls
@@ -7,21 +10,258 @@ pub struct AnyAccessControl<F: AccessControl, S: AccessControl> {
     // TODO: Extend for more than 2 policies
     first: F,
     second: S,
+    counts: Option<DecisionCounts>,
 }
 
 impl<F: AccessControl, S: AccessControl> AnyAccessControl<F, S> {
     /// Constructor
     pub fn new(first: F, second: S) -> Self {
-        AnyAccessControl { first, second }
+        AnyAccessControl {
+            first,
+            second,
+            counts: None,
+        }
+    }
+
+    /// Count how often each AccessControl makes the decision, see
+    /// [`decision_counts`](Self::decision_counts)
+    pub fn with_decision_counts(mut self) -> Self {
+        self.counts = Some(DecisionCounts::default());
+        self
+    }
+
+    /// How many decisions the first and the second AccessControl made,
+    /// if counted
+    pub fn decision_counts(&self) -> Option<[usize; 2]> {
+        self.counts.as_ref().map(DecisionCounts::snapshot)
+    }
+
+    fn decided(&self, index: usize) {
+        if let Some(counts) = &self.counts {
+            counts.record(index)
+        }
     }
 }
 
 #[async_trait]
 impl<F: AccessControl, S: AccessControl> AccessControl for AnyAccessControl<F, S> {
     async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
-        Ok(self.first.is_authorized(local_msg).await?
-            || self.second.is_authorized(local_msg).await?)
+        self.is_authorized_with_ctx(local_msg, None).await
+    }
+
+    async fn is_authorized_with_ctx(
+        &self,
+        local_msg: &LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<bool> {
+        if self.first.is_authorized_with_ctx(local_msg, token).await? {
+            self.decided(0);
+            return Ok(true);
+        }
+        let decision = self.second.is_authorized_with_ctx(local_msg, token).await?;
+        self.decided(1);
+        Ok(decision)
+    }
+
+    /// Return the message as annotated by the first AccessControl allowing it
+    async fn authorize(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<LocalMessage>> {
+        if let Some(local_msg) = self.first.authorize(local_msg.clone(), token).await? {
+            self.decided(0);
+            return Ok(Some(local_msg));
+        }
+        let local_msg = self.second.authorize(local_msg, token).await?;
+        self.decided(1);
+        Ok(local_msg)
+    }
+
+    /// Return the message and completion of the first AccessControl
+    /// allowing it
+    async fn authorize_tracked(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<(LocalMessage, Completion)>> {
+        if let Some(authorized) = self
+            .first
+            .authorize_tracked(local_msg.clone(), token)
+            .await?
+        {
+            self.decided(0);
+            return Ok(Some(authorized));
+        }
+        let authorized = self.second.authorize_tracked(local_msg, token).await?;
+        self.decided(1);
+        Ok(authorized)
+    }
+
+    /// Trace the first AccessControl, and the second one if the first
+    /// denied the message
+    async fn trace_decision(&self, local_msg: &LocalMessage) -> DecisionTrace {
+        let first = self.first.trace_decision(local_msg).await;
+        if first.outcome != TraceOutcome::Denied {
+            return DecisionTrace::new("Any", first.outcome.clone(), vec![first]);
+        }
+        let second = self.second.trace_decision(local_msg).await;
+        DecisionTrace::new("Any", second.outcome.clone(), vec![first, second])
+    }
+
+    fn describe(&self) -> String {
+        format!("Any[{}, {}]", self.first.describe(), self.second.describe())
     }
 }
 
-// TODO: Tests
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use crate::access_control::testing::{LocalMessageBuilder, MockAccessControl};
+    use crate::compat::boxed::Box;
+    use crate::compat::future::poll_once;
+    use crate::compat::string::String;
+    use crate::compat::vec::Vec;
+    use crate::{async_trait, LocalInfo, LocalMessage, Result};
+
+    use super::{AccessControl, AnyAccessControl, CancellationToken};
+
+    /// Credential check attaching the identity it matched to the message
+    #[derive(Debug)]
+    struct Credential {
+        allow: bool,
+        identity: &'static str,
+    }
+
+    #[async_trait]
+    impl AccessControl for Credential {
+        async fn is_authorized(&self, _local_msg: &LocalMessage) -> Result<bool> {
+            Ok(self.allow)
+        }
+
+        async fn authorize(
+            &self,
+            mut local_msg: LocalMessage,
+            _token: Option<&CancellationToken>,
+        ) -> Result<Option<LocalMessage>> {
+            if !self.allow {
+                return Ok(None);
+            }
+            local_msg.append_local_info(LocalInfo::new(
+                String::from("identity"),
+                self.identity.as_bytes().to_vec(),
+            ));
+            Ok(Some(local_msg))
+        }
+    }
+
+    /// Identities attached by the combined access control, if it allows the message
+    fn authorize(
+        first: (bool, &'static str),
+        second: (bool, &'static str),
+    ) -> Option<Vec<Vec<u8>>> {
+        let access_control = AnyAccessControl::new(
+            Credential {
+                allow: first.0,
+                identity: first.1,
+            },
+            Credential {
+                allow: second.0,
+                identity: second.1,
+            },
+        );
+        let local_msg = poll_once(async {
+            access_control
+                .authorize(LocalMessageBuilder::new().build(), None)
+                .await
+        })
+        .unwrap()?;
+        Some(
+            local_msg
+                .local_info()
+                .iter()
+                .map(|i| i.data().to_vec())
+                .collect(),
+        )
+    }
+
+    /// Decision of the combined access control, and how many times each
+    /// of its parts was asked
+    fn is_authorized(first: [bool; 1], second: [bool; 1]) -> (bool, usize, usize) {
+        let (first, second) = (
+            MockAccessControl::new(first),
+            MockAccessControl::new(second),
+        );
+        let access_control = AnyAccessControl::new(first.clone(), second.clone());
+        let decision = poll_once(async {
+            access_control
+                .is_authorized(&LocalMessageBuilder::new().build())
+                .await
+        });
+        (decision.unwrap(), first.calls(), second.calls())
+    }
+
+    #[test]
+    fn test_any() {
+        assert_eq!(is_authorized([false], [false]), (false, 1, 1));
+        assert_eq!(is_authorized([false], [true]), (true, 1, 1));
+        assert_eq!(is_authorized([true], [false]), (true, 1, 0));
+    }
+
+    #[test]
+    fn test_any_decision_counts() {
+        let access_control = AnyAccessControl::new(
+            MockAccessControl::new([true, true, false, true]),
+            MockAccessControl::new([false]),
+        )
+        .with_decision_counts();
+        for _ in 0..4 {
+            poll_once(async {
+                access_control
+                    .is_authorized(&LocalMessageBuilder::new().build())
+                    .await
+            })
+            .unwrap();
+        }
+        // The first allowed three times, the second decided once
+        assert_eq!(access_control.decision_counts(), Some([3, 1]));
+
+        // Messages authorized with annotations are counted too
+        poll_once(async {
+            access_control
+                .authorize(LocalMessageBuilder::new().build(), None)
+                .await
+        })
+        .unwrap();
+        assert_eq!(access_control.decision_counts(), Some([3, 2]));
+    }
+
+    #[test]
+    fn test_any_annotations() {
+        // The first allowing control's annotations win...
+        assert_eq!(
+            authorize((true, "alice"), (true, "bob")),
+            Some(vec![b"alice".to_vec()])
+        );
+        // ...and a denying control leaves none behind
+        assert_eq!(
+            authorize((false, "alice"), (true, "bob")),
+            Some(vec![b"bob".to_vec()])
+        );
+        assert_eq!(authorize((false, "alice"), (false, "bob")), None);
+    }
+
+    #[test]
+    fn test_default_authorize() {
+        let local_msg = LocalMessageBuilder::new().build();
+        let allowed = poll_once(async {
+            AnyAccessControl::new(
+                MockAccessControl::new([false]),
+                MockAccessControl::new([true]),
+            )
+            .authorize(local_msg.clone(), None)
+            .await
+        });
+        assert_eq!(allowed.ok(), Some(Some(local_msg)));
+    }
+}
@@ -0,0 +1,299 @@
+use crate::access_control::{AccessControl, CancellationToken, Completion, DecisionTrace};
+use crate::compat::boxed::Box;
+use crate::compat::sync::Arc;
+use crate::compat::{format, string::String};
+use crate::{async_trait, Address, LocalMessage, Result};
+use core::fmt::{self, Debug};
+
+/// What an [`AuditSink`] is told about the message a decision was made for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditMetadata {
+    /// The sender, the next address of the return route
+    pub source: Option<Address>,
+    /// The destination, the next address of the onward route
+    pub destination: Option<Address>,
+    /// When the decision was made, in seconds since the Unix epoch, if the
+    /// current time is known
+    pub timestamp: Option<u64>,
+}
+
+impl AuditMetadata {
+    /// Metadata of `local_msg`, timestamped now
+    pub fn new(local_msg: &LocalMessage) -> Self {
+        let transport = local_msg.transport();
+        AuditMetadata {
+            source: transport.return_route.next().ok().cloned(),
+            destination: transport.onward_route.next().ok().cloned(),
+            timestamp: now(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn now() -> Option<u64> {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs())
+}
+
+#[cfg(not(feature = "std"))]
+fn now() -> Option<u64> {
+    None
+}
+
+/// Records the decisions of an [`AuditedAccessControl`]
+#[async_trait]
+pub trait AuditSink: Debug + Send + Sync + 'static {
+    /// Record that the message described by `metadata` was allowed, or denied
+    async fn record(&self, allowed: bool, metadata: &AuditMetadata) -> Result<()>;
+}
+
+/// Records every decision of an AccessControl to an [`AuditSink`]
+///
+/// A decision is only returned once the sink recorded it: when the sink
+/// fails the message is denied with its error, so that no message passes
+/// without a record. Errors of the inner AccessControl are not recorded.
+pub struct AuditedAccessControl<A> {
+    inner: A,
+    sink: Arc<dyn AuditSink>,
+}
+
+impl<A: AccessControl> AuditedAccessControl<A> {
+    /// Constructor
+    pub fn new(inner: A, sink: Arc<dyn AuditSink>) -> Self {
+        AuditedAccessControl { inner, sink }
+    }
+}
+
+impl<A: Debug> Debug for AuditedAccessControl<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuditedAccessControl")
+            .field("inner", &self.inner)
+            .field("sink", &self.sink)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<A: AccessControl> AccessControl for AuditedAccessControl<A> {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        self.is_authorized_with_ctx(local_msg, None).await
+    }
+
+    async fn is_authorized_with_ctx(
+        &self,
+        local_msg: &LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<bool> {
+        let allowed = self.inner.is_authorized_with_ctx(local_msg, token).await?;
+        self.sink
+            .record(allowed, &AuditMetadata::new(local_msg))
+            .await?;
+        Ok(allowed)
+    }
+
+    /// Return the message as annotated by the inner AccessControl
+    async fn authorize(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<LocalMessage>> {
+        Ok(self
+            .authorize_tracked(local_msg, token)
+            .await?
+            .map(|(local_msg, _)| local_msg))
+    }
+
+    /// Return the message and completion of the inner AccessControl, once
+    /// the decision is recorded
+    async fn authorize_tracked(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<(LocalMessage, Completion)>> {
+        let metadata = AuditMetadata::new(&local_msg);
+        let authorized = self.inner.authorize_tracked(local_msg, token).await?;
+        self.sink.record(authorized.is_some(), &metadata).await?;
+        Ok(authorized)
+    }
+
+    /// Trace the inner AccessControl, without recording its decision, as
+    /// no message is let through
+    async fn trace_decision(&self, local_msg: &LocalMessage) -> DecisionTrace {
+        let inner = self.inner.trace_decision(local_msg).await;
+        DecisionTrace::new("Audited", inner.outcome.clone(), vec![inner])
+    }
+
+    fn describe(&self) -> String {
+        format!("Audited[{}]", self.inner.describe())
+    }
+}
+
+#[cfg(feature = "std")]
+pub use file::FileAuditSink;
+
+#[cfg(feature = "std")]
+mod file {
+    use super::{AuditMetadata, AuditSink};
+    use crate::compat::boxed::Box;
+    use crate::compat::sync::Mutex;
+    use crate::errcode::{Kind, Origin};
+    use crate::{async_trait, Address, Error, Result};
+    use std::fs::{File, OpenOptions};
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+
+    /// Appends each decision as a line to a file
+    ///
+    /// The line holds the timestamp, `allow` or `deny`, the source and the
+    /// destination, separated by tabs, and `-` for what is unknown. The file
+    /// is only ever appended to, and synced to disk after each line.
+    #[derive(Debug)]
+    pub struct FileAuditSink {
+        path: PathBuf,
+        file: Mutex<File>,
+    }
+
+    impl FileAuditSink {
+        /// Append to the file at `path`, creating it if needed
+        pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+            let path = path.as_ref().to_path_buf();
+            let file = OpenOptions::new()
+                .append(true)
+                .create(true)
+                .open(&path)
+                .map_err(|e| Error::new(Origin::Core, Kind::Io, e))?;
+            Ok(FileAuditSink {
+                path,
+                file: Mutex::new(file),
+            })
+        }
+
+        /// The file decisions are appended to
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+    }
+
+    fn field(address: &Option<Address>) -> String {
+        address
+            .as_ref()
+            .map(|a| a.to_string())
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    #[async_trait]
+    impl AuditSink for FileAuditSink {
+        async fn record(&self, allowed: bool, metadata: &AuditMetadata) -> Result<()> {
+            let line = format!(
+                "{}\t{}\t{}\t{}\n",
+                metadata
+                    .timestamp
+                    .map(|t| t.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                if allowed { "allow" } else { "deny" },
+                field(&metadata.source),
+                field(&metadata.destination),
+            );
+            let mut file = self
+                .file
+                .lock()
+                .map_err(|_| Error::new_without_cause(Origin::Core, Kind::Internal))?;
+            file.write_all(line.as_bytes())
+                .and_then(|_| file.sync_data())
+                .map_err(|e| Error::new(Origin::Core, Kind::Io, e))
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use crate::access_control::testing::{LocalMessageBuilder, MockAccessControl};
+    use crate::compat::boxed::Box;
+    use crate::compat::future::poll_once;
+    use crate::compat::sync::{Arc, Mutex};
+    use crate::compat::vec::Vec;
+    use crate::{async_trait, Address, Result};
+
+    use super::{AccessControl, AuditMetadata, AuditSink, AuditedAccessControl};
+
+    #[derive(Debug, Default)]
+    struct MemorySink {
+        records: Mutex<Vec<(bool, AuditMetadata)>>,
+    }
+
+    #[async_trait]
+    impl AuditSink for MemorySink {
+        async fn record(&self, allowed: bool, metadata: &AuditMetadata) -> Result<()> {
+            self.records
+                .lock()
+                .unwrap()
+                .push((allowed, metadata.clone()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_audited_records_decisions() -> Result<()> {
+        let sink = Arc::new(MemorySink::default());
+        let access_control =
+            AuditedAccessControl::new(MockAccessControl::new([true, false]), sink.clone());
+
+        for (source, allowed) in [("alice", true), ("bob", false)] {
+            let msg = LocalMessageBuilder::new()
+                .source(source)
+                .destination("a")
+                .build();
+            assert_eq!(
+                poll_once(async { access_control.is_authorized(&msg).await })?,
+                allowed
+            );
+        }
+
+        let records = sink.records.lock().unwrap();
+        assert_eq!(records.len(), 2);
+        for ((allowed, metadata), (source, expected)) in
+            records.iter().zip([("alice", true), ("bob", false)])
+        {
+            assert_eq!(*allowed, expected);
+            assert_eq!(metadata.source, Some(Address::from(source)));
+            assert_eq!(metadata.destination, Some(Address::from("a")));
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_file_audit_sink() -> Result<()> {
+        use super::FileAuditSink;
+
+        let path = std::env::temp_dir().join(format!("audit-{}.log", rand::random::<u64>()));
+        let sink = Arc::new(FileAuditSink::open(&path)?);
+        let access_control = AuditedAccessControl::new(MockAccessControl::new([false, true]), sink);
+        let msg = LocalMessageBuilder::new().source("alice").build();
+        poll_once(async { access_control.is_authorized(&msg).await })?;
+        poll_once(async { access_control.is_authorized(&msg).await })?;
+
+        // Opening the file again appends to it
+        let sink = FileAuditSink::open(&path)?;
+        poll_once(async { sink.record(true, &AuditMetadata::new(&msg)).await })?;
+
+        let log = std::fs::read_to_string(sink.path()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let decisions: Vec<_> = log
+            .lines()
+            .map(|l| l.split('\t').skip(1).collect::<Vec<_>>())
+            .collect();
+        assert_eq!(
+            decisions,
+            [
+                ["deny", "0#alice", "-"],
+                ["allow", "0#alice", "-"],
+                ["allow", "0#alice", "-"]
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,215 @@
+use crate::access_control::{
+    AccessControl, CancellationToken, Completion, DecisionTrace, TraceOutcome,
+};
+use crate::compat::boxed::Box;
+use crate::compat::sync::Arc;
+use crate::compat::{format, string::String};
+use crate::{async_trait, LocalMessage, Result};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// An AccessControl which operators can bypass, allowing all messages,
+/// e.g. while responding to an incident
+///
+/// While the bypass is off, the messages are authorized by the inner
+/// AccessControl. The bypass is turned on and off through the
+/// [`BypassHandle`]s returned by [`handle`](Self::handle), and is off when
+/// the AccessControl is created.
+#[derive(Debug)]
+pub struct BypassableAccessControl<A> {
+    inner: A,
+    bypassed: Arc<AtomicBool>,
+}
+
+impl<A: AccessControl> BypassableAccessControl<A> {
+    /// Constructor
+    pub fn new(inner: A) -> Self {
+        BypassableAccessControl {
+            inner,
+            bypassed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// A handle turning the bypass on and off
+    pub fn handle(&self) -> BypassHandle {
+        BypassHandle {
+            bypassed: self.bypassed.clone(),
+        }
+    }
+
+    fn is_bypassed(&self) -> bool {
+        self.bypassed.load(Ordering::Acquire)
+    }
+}
+
+/// Turns the bypass of a [`BypassableAccessControl`] on and off
+///
+/// Every change is logged as a warning, so that it shows in the logs of the
+/// node whoever makes it.
+#[derive(Debug, Clone)]
+pub struct BypassHandle {
+    bypassed: Arc<AtomicBool>,
+}
+
+impl BypassHandle {
+    /// Allow all messages when `bypassed`, or authorize them with the inner
+    /// AccessControl again
+    pub fn set_bypassed(&self, bypassed: bool) {
+        let was = self.bypassed.swap(bypassed, Ordering::AcqRel);
+        if bypassed {
+            tracing::warn!(
+                was_bypassed = was,
+                "access control bypass turned on, allowing all messages"
+            );
+        } else {
+            tracing::warn!(was_bypassed = was, "access control bypass turned off");
+        }
+    }
+
+    /// Whether all messages are allowed
+    pub fn is_bypassed(&self) -> bool {
+        self.bypassed.load(Ordering::Acquire)
+    }
+}
+
+#[async_trait]
+impl<A: AccessControl> AccessControl for BypassableAccessControl<A> {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        if self.is_bypassed() {
+            return Ok(true);
+        }
+        self.inner.is_authorized(local_msg).await
+    }
+
+    async fn is_authorized_with_ctx(
+        &self,
+        local_msg: &LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<bool> {
+        if self.is_bypassed() {
+            return Ok(true);
+        }
+        self.inner.is_authorized_with_ctx(local_msg, token).await
+    }
+
+    async fn authorize(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<LocalMessage>> {
+        if self.is_bypassed() {
+            return Ok(Some(local_msg));
+        }
+        self.inner.authorize(local_msg, token).await
+    }
+
+    async fn authorize_tracked(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<(LocalMessage, Completion)>> {
+        if self.is_bypassed() {
+            return Ok(Some((local_msg, Completion::new())));
+        }
+        self.inner.authorize_tracked(local_msg, token).await
+    }
+
+    async fn trace_decision(&self, local_msg: &LocalMessage) -> DecisionTrace {
+        if self.is_bypassed() {
+            return DecisionTrace::new("Bypassable(on)", TraceOutcome::Allowed, vec![]);
+        }
+        let inner = self.inner.trace_decision(local_msg).await;
+        DecisionTrace::new("Bypassable(off)", inner.outcome.clone(), vec![inner])
+    }
+
+    fn describe(&self) -> String {
+        let state = if self.is_bypassed() { "on" } else { "off" };
+        format!("Bypassable({state})[{}]", self.inner.describe())
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg(test)]
+mod tests {
+    use crate::access_control::testing::{LocalMessageBuilder, MockAccessControl};
+    use crate::compat::future::poll_once;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    use super::{AccessControl, BypassableAccessControl};
+
+    fn is_authorized(access_control: &impl AccessControl) -> bool {
+        poll_once(async {
+            access_control
+                .is_authorized(&LocalMessageBuilder::new().build())
+                .await
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_bypass() {
+        let inner = MockAccessControl::new([false, false]);
+        let access_control = BypassableAccessControl::new(inner.clone());
+        let handle = access_control.handle();
+        assert!(!handle.is_bypassed());
+        assert!(!is_authorized(&access_control));
+        assert_eq!(
+            access_control.describe(),
+            "Bypassable(off)[MockAccessControl]"
+        );
+
+        handle.set_bypassed(true);
+        assert!(is_authorized(&access_control));
+        assert!(is_authorized(&access_control));
+        assert_eq!(
+            access_control.describe(),
+            "Bypassable(on)[MockAccessControl]"
+        );
+        // The inner AccessControl isn't asked while bypassed
+        assert_eq!(inner.calls(), 1);
+
+        handle.set_bypassed(false);
+        assert!(!is_authorized(&access_control));
+        assert_eq!(inner.calls(), 2);
+    }
+
+    /// Collects what is logged
+    #[derive(Clone, Default)]
+    struct Logs(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Logs {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_bypass_toggles_are_logged() {
+        let logs = Logs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+
+        let access_control = BypassableAccessControl::new(MockAccessControl::new([]));
+        tracing::subscriber::with_default(subscriber, || {
+            let handle = access_control.handle();
+            handle.set_bypassed(true);
+            handle.set_bypassed(false);
+        });
+
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<_> = logs.lines().collect();
+        assert_eq!(lines.len(), 2, "{logs}");
+        assert!(lines[0].contains("WARN"), "{logs}");
+        assert!(lines[0].contains("bypass turned on"), "{logs}");
+        assert!(lines[1].contains("WARN"), "{logs}");
+        assert!(lines[1].contains("bypass turned off"), "{logs}");
+    }
+}
@@ -0,0 +1,260 @@
+use crate::access_control::{AccessControl, CancellationToken, Completion, DecisionTrace};
+use crate::compat::boxed::Box;
+use crate::compat::collections::BTreeMap;
+use crate::compat::sync::RwLock;
+use crate::compat::vec::Vec;
+use crate::compat::{format, string::String};
+use crate::errcode::{Kind, Origin};
+use crate::{async_trait, Address, Error, LocalMessage, Result};
+use core::fmt::{self, Debug};
+
+/// What a [`CachingAccessControl`] remembers a decision for
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CacheKey(Vec<Address>);
+
+impl CacheKey {
+    /// Constructor
+    pub fn new(addresses: Vec<Address>) -> Self {
+        CacheKey(addresses)
+    }
+
+    /// Key on the sender, the next address of the return route
+    pub fn source(local_msg: &LocalMessage) -> Option<CacheKey> {
+        let source = local_msg.source_addr()?;
+        Some(CacheKey(vec![source.clone()]))
+    }
+
+    /// Key on the destination, the next address of the onward route
+    pub fn destination(local_msg: &LocalMessage) -> Option<CacheKey> {
+        let destination = local_msg.destination_addr()?;
+        Some(CacheKey(vec![destination.clone()]))
+    }
+
+    /// Key on both the sender and the destination
+    pub fn source_and_destination(local_msg: &LocalMessage) -> Option<CacheKey> {
+        let source = local_msg.source_addr()?;
+        let destination = local_msg.destination_addr()?;
+        Some(CacheKey(vec![source.clone(), destination.clone()]))
+    }
+}
+
+/// Remembers the decisions of an AccessControl
+///
+/// The key function maps a message to the [`CacheKey`] its decision is
+/// stored under; messages it returns `None` for are always passed on to
+/// the inner AccessControl. Errors are not cached.
+///
+/// The key must capture everything the decision depends on. A key that is
+/// too coarse makes every message sharing it get the decision made for the
+/// first one, e.g. keying a [`RoutingAccessControl`](crate::RoutingAccessControl)
+/// on the source alone allows messages to every destination once one of
+/// them was allowed. Decisions are kept until [`clear`](Self::clear) is
+/// called. Messages answered from the cache come without the annotations and
+/// the [`Completion`] of the inner AccessControl, which is not asked, so an
+/// AccessControl deciding on more than the key, e.g. a
+/// [`ConcurrencyLimitAccessControl`](crate::ConcurrencyLimitAccessControl),
+/// belongs around the cache rather than in it.
+pub struct CachingAccessControl<A, F> {
+    inner: A,
+    key: F,
+    decisions: RwLock<BTreeMap<CacheKey, bool>>,
+}
+
+impl<A, F> CachingAccessControl<A, F>
+where
+    A: AccessControl,
+    F: Fn(&LocalMessage) -> Option<CacheKey> + Send + Sync + 'static,
+{
+    /// Constructor
+    pub fn new(inner: A, key: F) -> Self {
+        CachingAccessControl {
+            inner,
+            key,
+            decisions: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Forget all decisions
+    pub fn clear(&self) -> Result<()> {
+        self.decisions
+            .write()
+            .map_err(|_| Error::new_without_cause(Origin::Core, Kind::Internal))?
+            .clear();
+        Ok(())
+    }
+
+    fn cached(&self, key: &CacheKey) -> Result<Option<bool>> {
+        Ok(self
+            .decisions
+            .read()
+            .map_err(|_| Error::new_without_cause(Origin::Core, Kind::Internal))?
+            .get(key)
+            .copied())
+    }
+
+    fn remember(&self, key: CacheKey, decision: bool) -> Result<()> {
+        self.decisions
+            .write()
+            .map_err(|_| Error::new_without_cause(Origin::Core, Kind::Internal))?
+            .insert(key, decision);
+        Ok(())
+    }
+}
+
+impl<A: Debug, F> Debug for CachingAccessControl<A, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachingAccessControl")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<A, F> AccessControl for CachingAccessControl<A, F>
+where
+    A: AccessControl,
+    F: Fn(&LocalMessage) -> Option<CacheKey> + Send + Sync + 'static,
+{
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        self.is_authorized_with_ctx(local_msg, None).await
+    }
+
+    async fn is_authorized_with_ctx(
+        &self,
+        local_msg: &LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<bool> {
+        let key = match (self.key)(local_msg) {
+            Some(key) => key,
+            None => return self.inner.is_authorized_with_ctx(local_msg, token).await,
+        };
+        if let Some(decision) = self.cached(&key)? {
+            return Ok(decision);
+        }
+        let decision = self.inner.is_authorized_with_ctx(local_msg, token).await?;
+        self.remember(key, decision)?;
+        Ok(decision)
+    }
+
+    /// Return the message as annotated by the inner AccessControl, when it
+    /// is asked
+    async fn authorize(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<LocalMessage>> {
+        Ok(self
+            .authorize_tracked(local_msg, token)
+            .await?
+            .map(|(local_msg, _)| local_msg))
+    }
+
+    /// Return the message and completion of the inner AccessControl when it
+    /// is asked, and the message unchanged when the decision is cached
+    async fn authorize_tracked(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<(LocalMessage, Completion)>> {
+        let key = match (self.key)(&local_msg) {
+            Some(key) => key,
+            None => return self.inner.authorize_tracked(local_msg, token).await,
+        };
+        if let Some(decision) = self.cached(&key)? {
+            return Ok(decision.then(|| (local_msg, Completion::new())));
+        }
+        let authorized = self.inner.authorize_tracked(local_msg, token).await?;
+        self.remember(key, authorized.is_some())?;
+        Ok(authorized)
+    }
+
+    /// Trace the inner AccessControl, unless the decision is cached, without
+    /// caching it
+    async fn trace_decision(&self, local_msg: &LocalMessage) -> DecisionTrace {
+        if let Some(key) = (self.key)(local_msg) {
+            match self.cached(&key) {
+                Ok(Some(decision)) => {
+                    return DecisionTrace::leaf("Caching(cached)", &Ok(decision));
+                }
+                Ok(None) => {}
+                Err(e) => return DecisionTrace::leaf("Caching", &Err(e)),
+            }
+        }
+        let inner = self.inner.trace_decision(local_msg).await;
+        DecisionTrace::new("Caching", inner.outcome.clone(), vec![inner])
+    }
+
+    fn describe(&self) -> String {
+        format!("Caching[{}]", self.inner.describe())
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use crate::access_control::testing::{LocalMessageBuilder, MockAccessControl};
+    use crate::compat::future::poll_once;
+    use crate::{LocalMessage, Result};
+
+    use super::{AccessControl, CacheKey, CachingAccessControl};
+
+    fn msg(source: &str, destination: &str) -> LocalMessage {
+        LocalMessageBuilder::new()
+            .source(source)
+            .destination(destination)
+            .build()
+    }
+
+    fn is_authorized(
+        access_control: &impl AccessControl,
+        local_msg: &LocalMessage,
+    ) -> Result<bool> {
+        poll_once(async { access_control.is_authorized(local_msg).await })
+    }
+
+    #[test]
+    fn test_caching_source() -> Result<()> {
+        let mock = MockAccessControl::new([true, false]);
+        let access_control = CachingAccessControl::new(mock.clone(), CacheKey::source);
+
+        assert!(is_authorized(&access_control, &msg("alice", "a"))?);
+        // Same source, answered from the cache even with another destination
+        assert!(is_authorized(&access_control, &msg("alice", "b"))?);
+        assert_eq!(mock.calls(), 1);
+        assert!(!is_authorized(&access_control, &msg("bob", "a"))?);
+        assert_eq!(mock.calls(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_caching_source_and_destination() -> Result<()> {
+        let mock = MockAccessControl::new([true, false]);
+        let access_control =
+            CachingAccessControl::new(mock.clone(), CacheKey::source_and_destination);
+
+        assert!(is_authorized(&access_control, &msg("alice", "a"))?);
+        assert!(!is_authorized(&access_control, &msg("alice", "b"))?);
+        assert!(is_authorized(&access_control, &msg("alice", "a"))?);
+        assert!(!is_authorized(&access_control, &msg("alice", "b"))?);
+        assert_eq!(mock.calls(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_caching_clear() -> Result<()> {
+        let mock = MockAccessControl::new([true, false]);
+        let access_control = CachingAccessControl::new(mock.clone(), CacheKey::destination);
+
+        assert!(is_authorized(&access_control, &msg("alice", "a"))?);
+        access_control.clear()?;
+        assert!(!is_authorized(&access_control, &msg("alice", "a"))?);
+        assert_eq!(mock.calls(), 2);
+
+        // Messages without a key are not cached
+        let access_control = CachingAccessControl::new(mock.clone(), |_: &LocalMessage| None);
+        assert!(!is_authorized(&access_control, &msg("alice", "a"))?);
+        assert!(!is_authorized(&access_control, &msg("alice", "a"))?);
+        assert_eq!(mock.calls(), 4);
+        Ok(())
+    }
+}
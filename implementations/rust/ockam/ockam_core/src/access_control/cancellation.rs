@@ -0,0 +1,120 @@
+use crate::compat::sync::{Arc, Mutex};
+use crate::compat::task::{Context, Poll, Waker};
+use crate::compat::vec::Vec;
+use core::fmt::{self, Debug};
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Signals that the work of a worker should stop, e.g. because the node
+/// is shutting down
+///
+/// The node cancels the token of a worker once it stops handling messages,
+/// and passes it to [`AccessControl::is_authorized_with_ctx`](crate::AccessControl::is_authorized_with_ctx),
+/// so that access controls can abort the asynchronous work they started for
+/// a message. Clones share the same state.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl CancellationToken {
+    /// Constructor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel this token and wake up the tasks waiting for it
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::Release);
+        if let Ok(mut wakers) = self.inner.wakers.lock() {
+            for waker in wakers.drain(..) {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Whether this token was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Complete once this token is cancelled
+    pub fn cancelled(&self) -> Cancelled<'_> {
+        Cancelled { token: self }
+    }
+}
+
+impl Debug for CancellationToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// Future returned by [`CancellationToken::cancelled`]
+#[derive(Debug)]
+pub struct Cancelled<'a> {
+    token: &'a CancellationToken,
+}
+
+impl Future for Cancelled<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.token.is_cancelled() {
+            return Poll::Ready(());
+        }
+        if let Ok(mut wakers) = self.token.inner.wakers.lock() {
+            if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                wakers.push(cx.waker().clone());
+            }
+        }
+        // Cancelled while registering the waker
+        if self.token.is_cancelled() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use crate::compat::future::poll_once;
+    use futures_util::future::{select, Either};
+
+    use super::CancellationToken;
+
+    #[test]
+    fn test_cancellation() {
+        let token = CancellationToken::new();
+        let waiter = token.clone();
+        assert!(!waiter.is_cancelled());
+
+        // Not cancelled yet, the other future completes first
+        let first = poll_once(async {
+            match select(waiter.cancelled(), core::future::ready(())).await {
+                Either::Left(_) => Ok("cancelled"),
+                Either::Right(_) => Ok("ready"),
+            }
+        });
+        assert_eq!(first.ok(), Some("ready"));
+
+        token.cancel();
+        assert!(waiter.is_cancelled());
+        assert!(poll_once(async move {
+            waiter.cancelled().await;
+            crate::Result::Ok(())
+        })
+        .is_ok());
+    }
+}
@@ -0,0 +1,77 @@
+use crate::compat::boxed::Box;
+use crate::compat::vec::Vec;
+use core::fmt::{self, Debug};
+
+/// What to do once the worker receiving a message is done handling it
+///
+/// Returned with the message by [`AccessControl::authorize_tracked`](crate::AccessControl::authorize_tracked).
+/// The node drops it once the `handle_message` of the worker returned, which
+/// runs the hooks registered by the access controls which allowed the
+/// message, e.g. to release the slot a [`ConcurrencyLimitAccessControl`](crate::ConcurrencyLimitAccessControl)
+/// took for it.
+#[derive(Default)]
+pub struct Completion {
+    hooks: Vec<Box<dyn FnOnce() + Send>>,
+}
+
+impl Completion {
+    /// Constructor, for a message nothing waits for
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `hook` once the message is handled
+    pub fn on_complete(&mut self, hook: impl FnOnce() + Send + 'static) {
+        self.hooks.push(Box::new(hook))
+    }
+
+    /// Also run the hooks of `other` once the message is handled
+    pub fn merge(&mut self, mut other: Completion) {
+        self.hooks.append(&mut other.hooks)
+    }
+}
+
+impl Debug for Completion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Completion")
+            .field("hooks", &self.hooks.len())
+            .finish()
+    }
+}
+
+impl Drop for Completion {
+    fn drop(&mut self) {
+        for hook in self.hooks.drain(..) {
+            hook()
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use crate::compat::sync::{Arc, Mutex};
+    use crate::compat::vec::Vec;
+
+    use super::Completion;
+
+    #[test]
+    fn test_completion() {
+        let completed = Arc::new(Mutex::new(Vec::new()));
+        let hook = |n| {
+            let completed = completed.clone();
+            move || completed.lock().unwrap().push(n)
+        };
+
+        let mut completion = Completion::new();
+        completion.on_complete(hook(1));
+        let mut other = Completion::new();
+        other.on_complete(hook(2));
+        completion.merge(other);
+        // Merging moved the hooks, they are not run yet
+        assert!(completed.lock().unwrap().is_empty());
+
+        drop(completion);
+        assert_eq!(*completed.lock().unwrap(), vec![1, 2]);
+    }
+}
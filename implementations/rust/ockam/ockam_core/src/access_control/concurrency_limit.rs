@@ -0,0 +1,280 @@
+use crate::access_control::{AccessControl, CancellationToken, Completion};
+use crate::compat::boxed::Box;
+use crate::compat::collections::BTreeMap;
+use crate::compat::sync::{Arc, Mutex};
+use crate::compat::{format, string::String};
+use crate::errcode::{Kind, Origin};
+use crate::{async_trait, Address, Error, LocalMessage, Result};
+use core::fmt::{self, Debug};
+
+/// Messages in flight, by sender, `None` for messages without a return route
+type InFlight = BTreeMap<Option<Address>, usize>;
+
+/// Caps the number of messages of each sender in flight at once
+///
+/// A message is in flight from the moment it is allowed until the worker
+/// receiving it is done handling it, as signaled by the [`Completion`]
+/// returned by [`authorize_tracked`](AccessControl::authorize_tracked).
+/// Messages from a sender which already has `limit` messages in flight are
+/// denied. The sender is the next address of the return route.
+///
+/// Clones share their counts, so that a limit can cover a pool of workers.
+/// At most `capacity` senders are tracked at once, a sender being forgotten
+/// once it has no messages in flight; messages from new senders are denied
+/// while there are that many. Only `authorize_tracked` takes a slot, which
+/// the access controls made of others forward along with the `Completion`,
+/// [`is_authorized`](AccessControl::is_authorized) only checks that the
+/// sender is below its limit. A [`CachingAccessControl`](crate::CachingAccessControl)
+/// doesn't ask it for the messages it answers from its cache.
+#[derive(Clone)]
+pub struct ConcurrencyLimitAccessControl {
+    limit: usize,
+    capacity: usize,
+    in_flight: Arc<Mutex<InFlight>>,
+}
+
+impl ConcurrencyLimitAccessControl {
+    /// Constructor
+    pub fn new(limit: usize, capacity: usize) -> Self {
+        ConcurrencyLimitAccessControl {
+            limit,
+            capacity,
+            in_flight: Default::default(),
+        }
+    }
+
+    /// How many messages of `source` are in flight
+    pub fn in_flight(&self, source: &Address) -> Result<usize> {
+        let key = Some(source.clone());
+        self.with_in_flight(|in_flight| in_flight.get(&key).copied().unwrap_or_default())
+    }
+
+    fn with_in_flight<R>(&self, f: impl FnOnce(&mut InFlight) -> R) -> Result<R> {
+        let mut in_flight = self
+            .in_flight
+            .lock()
+            .map_err(|_| Error::new_without_cause(Origin::Core, Kind::Internal))?;
+        Ok(f(&mut in_flight))
+    }
+
+    fn source(local_msg: &LocalMessage) -> Option<Address> {
+        local_msg.source_addr().cloned()
+    }
+
+    /// Whether a message of `source` may be allowed, given `in_flight`
+    fn admits(&self, in_flight: &InFlight, source: &Option<Address>) -> bool {
+        match in_flight.get(source) {
+            Some(count) => *count < self.limit,
+            None => self.limit > 0 && in_flight.len() < self.capacity,
+        }
+    }
+}
+
+impl Debug for ConcurrencyLimitAccessControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConcurrencyLimitAccessControl")
+            .field("limit", &self.limit)
+            .field("capacity", &self.capacity)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl AccessControl for ConcurrencyLimitAccessControl {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        let source = Self::source(local_msg);
+        self.with_in_flight(|in_flight| self.admits(in_flight, &source))
+    }
+
+    async fn authorize_tracked(
+        &self,
+        local_msg: LocalMessage,
+        _token: Option<&CancellationToken>,
+    ) -> Result<Option<(LocalMessage, Completion)>> {
+        let source = Self::source(&local_msg);
+        let admitted = self.with_in_flight(|in_flight| {
+            let admitted = self.admits(in_flight, &source);
+            if admitted {
+                *in_flight.entry(source.clone()).or_default() += 1;
+            }
+            admitted
+        })?;
+        if !admitted {
+            return Ok(None);
+        }
+        let shared = self.in_flight.clone();
+        let mut completion = Completion::new();
+        completion.on_complete(move || {
+            if let Ok(mut in_flight) = shared.lock() {
+                if let Some(count) = in_flight.get_mut(&source) {
+                    *count -= 1;
+                    if *count == 0 {
+                        in_flight.remove(&source);
+                    }
+                }
+            }
+        });
+        Ok(Some((local_msg, completion)))
+    }
+
+    fn describe(&self) -> String {
+        format!("ConcurrencyLimit({} per sender)", self.limit)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use crate::access_control::testing::LocalMessageBuilder;
+    use crate::compat::boxed::Box;
+    use crate::compat::future::poll_once;
+    use crate::compat::sync::Arc;
+    use crate::{
+        async_trait, Address, AllAccessControl, AllParallelAccessControl, AllowAll,
+        AnyParallelAccessControl, AuditMetadata, AuditSink, AuditedAccessControl, Completion,
+        DenyAll, LocalMessage, NegativeCachingAccessControl, Result,
+    };
+    use core::time::Duration;
+
+    use super::{AccessControl, ConcurrencyLimitAccessControl};
+
+    fn msg(source: &str) -> LocalMessage {
+        LocalMessageBuilder::new().source(source).build()
+    }
+
+    /// The completion of the message, if allowed
+    fn authorize(
+        access_control: &ConcurrencyLimitAccessControl,
+        source: &str,
+    ) -> Result<Option<Completion>> {
+        let authorized =
+            poll_once(async { access_control.authorize_tracked(msg(source), None).await })?;
+        Ok(authorized.map(|(_, completion)| completion))
+    }
+
+    #[test]
+    fn test_concurrency_limit() -> Result<()> {
+        let access_control = ConcurrencyLimitAccessControl::new(2, 10);
+        let alice = Address::from_string("alice");
+
+        let first = authorize(&access_control, "alice")?.unwrap();
+        let second = authorize(&access_control, "alice")?.unwrap();
+        assert_eq!(access_control.in_flight(&alice)?, 2);
+        assert!(authorize(&access_control, "alice")?.is_none());
+        assert!(!poll_once(async {
+            access_control.is_authorized(&msg("alice")).await
+        })?);
+        // Other senders have limits of their own
+        assert!(authorize(&access_control, "bob")?.is_some());
+
+        // Completing a message frees its slot
+        drop(first);
+        assert_eq!(access_control.in_flight(&alice)?, 1);
+        let third = authorize(&access_control, "alice")?.unwrap();
+        drop((second, third));
+        assert_eq!(access_control.in_flight(&alice)?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_concurrency_limit_shared() -> Result<()> {
+        let access_control = ConcurrencyLimitAccessControl::new(1, 10);
+        // Clones share their counts, which clippy can't see
+        #[allow(clippy::redundant_clone)]
+        let pool = access_control.clone();
+
+        let completion = authorize(&access_control, "alice")?.unwrap();
+        assert!(authorize(&pool, "alice")?.is_none());
+        drop(completion);
+        assert!(authorize(&pool, "alice")?.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_concurrency_limit_capacity() -> Result<()> {
+        let access_control = ConcurrencyLimitAccessControl::new(1, 2);
+
+        let alice = authorize(&access_control, "alice")?.unwrap();
+        let _bob = authorize(&access_control, "bob")?.unwrap();
+        // Tracking two senders already
+        assert!(authorize(&access_control, "carol")?.is_none());
+
+        // Senders without messages in flight are forgotten
+        drop(alice);
+        assert!(authorize(&access_control, "carol")?.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_concurrency_limit_within_wrappers() -> Result<()> {
+        #[derive(Debug)]
+        struct NoSink;
+
+        #[async_trait]
+        impl AuditSink for NoSink {
+            async fn record(&self, _allowed: bool, _metadata: &AuditMetadata) -> Result<()> {
+                Ok(())
+            }
+        }
+
+        fn authorize(access_control: &impl AccessControl) -> Result<Option<Completion>> {
+            let authorized =
+                poll_once(async { access_control.authorize_tracked(msg("alice"), None).await })?;
+            Ok(authorized.map(|(_, completion)| completion))
+        }
+
+        fn check(limit: &ConcurrencyLimitAccessControl, access_control: impl AccessControl) {
+            let alice = Address::from_string("alice");
+            let completion = authorize(&access_control).unwrap().unwrap();
+            assert_eq!(limit.in_flight(&alice).unwrap(), 1);
+            assert!(authorize(&access_control).unwrap().is_none());
+            drop(completion);
+            assert_eq!(limit.in_flight(&alice).unwrap(), 0);
+        }
+
+        let limit = ConcurrencyLimitAccessControl::new(1, 10);
+        check(
+            &limit,
+            AuditedAccessControl::new(limit.clone(), Arc::new(NoSink)),
+        );
+        check(
+            &limit,
+            NegativeCachingAccessControl::with_clock(limit.clone(), Duration::ZERO, 10, || {
+                Duration::ZERO
+            }),
+        );
+        check(
+            &limit,
+            AllParallelAccessControl::new(limit.clone(), AllowAll),
+        );
+        check(
+            &limit,
+            AnyParallelAccessControl::new(DenyAll, limit.clone()),
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_concurrency_limit_within_all() -> Result<()> {
+        fn authorize(
+            access_control: &impl AccessControl,
+        ) -> Result<Option<(LocalMessage, Completion)>> {
+            poll_once(async { access_control.authorize_tracked(msg("alice"), None).await })
+        }
+        let limit = ConcurrencyLimitAccessControl::new(1, 10);
+        let alice = Address::from_string("alice");
+
+        // All keeps the slot taken by its first part until completion...
+        let allowed = AllAccessControl::new(limit.clone(), AllowAll);
+        let (_, completion) = authorize(&allowed)?.unwrap();
+        assert_eq!(limit.in_flight(&alice)?, 1);
+        drop(completion);
+        assert_eq!(limit.in_flight(&alice)?, 0);
+
+        // ...and frees it when its second part denies the message
+        let denied = AllAccessControl::new(limit.clone(), DenyAll);
+        assert!(authorize(&denied)?.is_none());
+        assert_eq!(limit.in_flight(&alice)?, 0);
+        Ok(())
+    }
+}
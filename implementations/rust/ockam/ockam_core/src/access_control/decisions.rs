@@ -0,0 +1,27 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counts how often each part of a combined AccessControl made the
+/// decision, e.g. denied a message for [`AllAccessControl`](crate::AllAccessControl)
+///
+/// The part asked first decides when it short-circuits the other one,
+/// otherwise the part asked second decides. Parts which are cheap to ask
+/// and often decide should come first.
+#[derive(Debug, Default)]
+pub struct DecisionCounts {
+    deciding: [AtomicUsize; 2],
+}
+
+impl DecisionCounts {
+    /// Record a decision made by the part at `index`
+    pub(crate) fn record(&self, index: usize) {
+        self.deciding[index].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// How many decisions the first and the second part made
+    pub fn snapshot(&self) -> [usize; 2] {
+        [
+            self.deciding[0].load(Ordering::Relaxed),
+            self.deciding[1].load(Ordering::Relaxed),
+        ]
+    }
+}
@@ -0,0 +1,139 @@
+use crate::access_control::{
+    AccessControl, CancellationToken, Completion, DecisionTrace, OutgoingAccessControl,
+};
+use crate::compat::boxed::Box;
+use crate::compat::{format, string::String};
+use crate::{async_trait, LocalMessage, Result};
+
+/// Combines an AccessControl for incoming messages with an
+/// OutgoingAccessControl for outgoing ones
+///
+/// As an [`AccessControl`] it delegates to `incoming`, as an
+/// [`OutgoingAccessControl`] to `outgoing`, so a single object can be
+/// installed for both directions.
+#[derive(Debug)]
+pub struct DirectionalAccessControl {
+    incoming: Box<dyn AccessControl>,
+    outgoing: Box<dyn OutgoingAccessControl>,
+}
+
+impl DirectionalAccessControl {
+    /// Constructor
+    pub fn new(incoming: impl AccessControl, outgoing: impl OutgoingAccessControl) -> Self {
+        DirectionalAccessControl {
+            incoming: Box::new(incoming),
+            outgoing: Box::new(outgoing),
+        }
+    }
+}
+
+#[async_trait]
+impl AccessControl for DirectionalAccessControl {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        self.incoming.is_authorized(local_msg).await
+    }
+
+    async fn is_authorized_with_ctx(
+        &self,
+        local_msg: &LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<bool> {
+        self.incoming.is_authorized_with_ctx(local_msg, token).await
+    }
+
+    async fn authorize(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<LocalMessage>> {
+        self.incoming.authorize(local_msg, token).await
+    }
+
+    async fn authorize_tracked(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<(LocalMessage, Completion)>> {
+        self.incoming.authorize_tracked(local_msg, token).await
+    }
+
+    /// Trace the AccessControl of the incoming messages
+    async fn trace_decision(&self, local_msg: &LocalMessage) -> DecisionTrace {
+        let incoming = self.incoming.trace_decision(local_msg).await;
+        DecisionTrace::new("Directional", incoming.outcome.clone(), vec![incoming])
+    }
+
+    /// Describe the AccessControl of the incoming messages
+    fn describe(&self) -> String {
+        format!("Directional[{}]", self.incoming.describe())
+    }
+}
+
+#[async_trait]
+impl OutgoingAccessControl for DirectionalAccessControl {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        self.outgoing.is_authorized(local_msg).await
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use crate::compat::boxed::Box;
+    use crate::compat::future::poll_once;
+    use crate::compat::sync::Arc;
+    use crate::{async_trait, route, LocalMessage, Result, TransportMessage};
+    use crate::{AllowAll, DenyAll};
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::{AccessControl, DirectionalAccessControl, OutgoingAccessControl};
+
+    /// Outgoing control with a fixed decision, counting its calls
+    #[derive(Debug)]
+    struct Outgoing {
+        allow: bool,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl OutgoingAccessControl for Outgoing {
+        async fn is_authorized(&self, _local_msg: &LocalMessage) -> Result<bool> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(self.allow)
+        }
+    }
+
+    fn check(access_control: &DirectionalAccessControl) -> (Result<bool>, Result<bool>) {
+        let local_message =
+            LocalMessage::new(TransportMessage::v1(route![], route![], vec![]), vec![]);
+        let incoming =
+            poll_once(async { AccessControl::is_authorized(access_control, &local_message).await });
+        let outgoing = poll_once(async {
+            OutgoingAccessControl::is_authorized(access_control, &local_message).await
+        });
+        (incoming, outgoing)
+    }
+
+    #[test]
+    fn test_directional() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let outgoing = Outgoing {
+            allow: false,
+            calls: calls.clone(),
+        };
+        let (incoming, outgoing) = check(&DirectionalAccessControl::new(AllowAll, outgoing));
+        assert_eq!(incoming.ok(), crate::allow().ok());
+        assert_eq!(outgoing.ok(), crate::deny().ok());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let outgoing = Outgoing {
+            allow: true,
+            calls: calls.clone(),
+        };
+        let (incoming, outgoing) = check(&DirectionalAccessControl::new(DenyAll, outgoing));
+        assert_eq!(incoming.ok(), crate::deny().ok());
+        assert_eq!(outgoing.ok(), crate::allow().ok());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
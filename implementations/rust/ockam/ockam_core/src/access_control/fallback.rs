@@ -0,0 +1,158 @@
+use crate::access_control::{AccessControl, CancellationToken, Completion, DecisionTrace};
+use crate::compat::boxed::Box;
+use crate::compat::{format, string::String};
+use crate::{async_trait, LocalMessage, Result};
+
+/// Authorizes messages with a primary AccessControl, and with a fallback
+/// AccessControl when the primary one fails
+///
+/// Only errors of the primary AccessControl make the fallback decide: when
+/// the primary AccessControl denies a message, the message is denied. This
+/// suits a primary AccessControl which may be unavailable, e.g. one asking a
+/// remote service, with a local allowlist as the fallback. Errors of the
+/// fallback AccessControl are returned.
+#[derive(Debug)]
+pub struct FallbackAccessControl<P: AccessControl, F: AccessControl> {
+    primary: P,
+    fallback: F,
+}
+
+impl<P: AccessControl, F: AccessControl> FallbackAccessControl<P, F> {
+    /// Constructor
+    pub fn new(primary: P, fallback: F) -> Self {
+        FallbackAccessControl { primary, fallback }
+    }
+}
+
+#[async_trait]
+impl<P: AccessControl, F: AccessControl> AccessControl for FallbackAccessControl<P, F> {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        self.is_authorized_with_ctx(local_msg, None).await
+    }
+
+    async fn is_authorized_with_ctx(
+        &self,
+        local_msg: &LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<bool> {
+        match self.primary.is_authorized_with_ctx(local_msg, token).await {
+            Ok(decision) => Ok(decision),
+            Err(e) => {
+                tracing::warn!(
+                    error = %e,
+                    fallback = %self.fallback.describe(),
+                    "access control failed, asking the fallback"
+                );
+                self.fallback.is_authorized_with_ctx(local_msg, token).await
+            }
+        }
+    }
+
+    /// Return the message and the completion of the primary AccessControl,
+    /// or of the fallback one when the primary one fails
+    async fn authorize_tracked(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<(LocalMessage, Completion)>> {
+        match self
+            .primary
+            .authorize_tracked(local_msg.clone(), token)
+            .await
+        {
+            Ok(authorized) => Ok(authorized),
+            Err(e) => {
+                tracing::warn!(
+                    error = %e,
+                    fallback = %self.fallback.describe(),
+                    "access control failed, asking the fallback"
+                );
+                self.fallback.authorize_tracked(local_msg, token).await
+            }
+        }
+    }
+
+    /// Trace the primary AccessControl, and the fallback one if the
+    /// primary one failed
+    async fn trace_decision(&self, local_msg: &LocalMessage) -> DecisionTrace {
+        let primary = self.primary.trace_decision(local_msg).await;
+        if !primary.outcome.is_failed() {
+            return DecisionTrace::new("Fallback", primary.outcome.clone(), vec![primary]);
+        }
+        let fallback = self.fallback.trace_decision(local_msg).await;
+        DecisionTrace::new(
+            "Fallback",
+            fallback.outcome.clone(),
+            vec![primary, fallback],
+        )
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "Fallback[{}, {}]",
+            self.primary.describe(),
+            self.fallback.describe()
+        )
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use crate::access_control::testing::{LocalMessageBuilder, MockAccessControl};
+    use crate::compat::future::poll_once;
+    use crate::errcode::{Kind, Origin};
+    use crate::{AllowAll, DenyAll, Error, Result};
+
+    use super::{AccessControl, FallbackAccessControl};
+
+    fn is_authorized(access_control: &impl AccessControl) -> Result<bool> {
+        poll_once(async {
+            access_control
+                .is_authorized(&LocalMessageBuilder::new().build())
+                .await
+        })
+    }
+
+    fn error() -> Result<bool> {
+        Err(Error::new_without_cause(Origin::Core, Kind::Invalid))
+    }
+
+    #[test]
+    fn test_fallback_primary_decides() {
+        for decision in [true, false] {
+            let (primary, fallback) = (
+                MockAccessControl::new([decision]),
+                MockAccessControl::new([true]),
+            );
+            let access_control = FallbackAccessControl::new(primary.clone(), fallback.clone());
+            assert_eq!(is_authorized(&access_control).ok(), Some(decision));
+            // A denial is not an error
+            assert_eq!((primary.calls(), fallback.calls()), (1, 0));
+        }
+    }
+
+    #[test]
+    fn test_fallback_primary_fails() {
+        let fallback = MockAccessControl::new([true]);
+        let access_control =
+            MockAccessControl::with_results([error()]).or_on_error(fallback.clone());
+        assert_eq!(is_authorized(&access_control).ok(), Some(true));
+        assert_eq!(fallback.calls(), 1);
+
+        // Errors of the fallback are returned
+        let access_control = FallbackAccessControl::new(
+            MockAccessControl::with_results([error()]),
+            MockAccessControl::with_results([error()]),
+        );
+        assert!(is_authorized(&access_control).is_err());
+    }
+
+    #[test]
+    fn test_fallback_describe() {
+        assert_eq!(
+            AllowAll.or_on_error(DenyAll).describe(),
+            "Fallback[AllowAll, DenyAll]"
+        );
+    }
+}
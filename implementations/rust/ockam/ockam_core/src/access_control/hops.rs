@@ -0,0 +1,70 @@
+use crate::access_control::AccessControl;
+use crate::compat::boxed::Box;
+use crate::compat::{format, string::String};
+use crate::{async_trait, LocalMessage, Result};
+
+/// Denies messages whose onward route has more than a maximum number of hops
+///
+/// Every address of the onward route counts as a hop, including the one of
+/// the worker receiving the message. This bounds how far a message can be
+/// routed on, e.g. around a loop of forwarders.
+#[derive(Debug, Clone, Copy)]
+pub struct MaxHopsAccessControl {
+    max_hops: usize,
+}
+
+impl MaxHopsAccessControl {
+    /// Constructor
+    pub fn new(max_hops: usize) -> Self {
+        MaxHopsAccessControl { max_hops }
+    }
+}
+
+#[async_trait]
+impl AccessControl for MaxHopsAccessControl {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        let hops = local_msg.onward_route().iter().count();
+        Ok(hops <= self.max_hops)
+    }
+
+    fn describe(&self) -> String {
+        format!("MaxHops({})", self.max_hops)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use crate::compat::future::poll_once;
+    use crate::{route, LocalMessage, Result, Route, TransportMessage, TransportType};
+
+    use super::{AccessControl, MaxHopsAccessControl};
+
+    fn is_authorized(access_control: &MaxHopsAccessControl, onward: Route) -> Result<bool> {
+        poll_once(async {
+            let local_message =
+                LocalMessage::new(TransportMessage::v1(onward, route![], vec![]), vec![]);
+            access_control.is_authorized(&local_message).await
+        })
+    }
+
+    #[test]
+    fn test_max_hops() -> Result<()> {
+        let access_control = MaxHopsAccessControl::new(3);
+
+        assert!(is_authorized(&access_control, route!["a", "b"])?);
+        assert!(is_authorized(&access_control, route!["a", "b", "c"])?);
+        assert!(!is_authorized(&access_control, route!["a", "b", "c", "d"])?);
+        assert!(is_authorized(&access_control, route![])?);
+
+        // Hops with other transport types count too
+        let route = Route::new()
+            .append("a")
+            .append_t(TransportType::new(1), "127.0.0.1:4000")
+            .append("b")
+            .append("c")
+            .into();
+        assert!(!is_authorized(&access_control, route)?);
+        Ok(())
+    }
+}
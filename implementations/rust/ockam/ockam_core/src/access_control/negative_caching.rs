@@ -0,0 +1,316 @@
+use crate::access_control::{
+    AccessControl, CacheKey, CancellationToken, Completion, DecisionTrace,
+};
+use crate::compat::boxed::Box;
+use crate::compat::collections::BTreeMap;
+use crate::compat::sync::{Arc, RwLock};
+use crate::compat::{format, string::String};
+use crate::errcode::{Kind, Origin};
+use crate::{async_trait, Error, LocalMessage, Result};
+use core::fmt::{self, Debug};
+use core::time::Duration;
+
+/// Remembers the denials of an AccessControl, for a while
+///
+/// A message denied by the inner AccessControl gets its sender, the next
+/// address of its return route, denied without asking again until `ttl`
+/// has elapsed. Allowed messages are never remembered, so every message
+/// from a sender which is not denied is passed on to the inner
+/// AccessControl, as are messages without a sender.
+///
+/// At most `capacity` senders are remembered: when there are more, the
+/// denial expiring first is forgotten. Clones share the denials, so that a
+/// worker started again with a clone keeps denying the same senders.
+#[derive(Clone)]
+pub struct NegativeCachingAccessControl<A> {
+    inner: A,
+    ttl: Duration,
+    capacity: usize,
+    clock: Arc<dyn Fn() -> Duration + Send + Sync>,
+    /// When the denial of each sender expires
+    denied: Arc<RwLock<BTreeMap<CacheKey, Duration>>>,
+}
+
+impl<A: AccessControl> NegativeCachingAccessControl<A> {
+    /// Constructor, using the system time
+    #[cfg(feature = "std")]
+    pub fn new(inner: A, ttl: Duration, capacity: usize) -> Self {
+        Self::with_clock(inner, ttl, capacity, || {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+        })
+    }
+
+    /// Constructor, using `clock` for the current time since any fixed
+    /// point in time
+    pub fn with_clock(
+        inner: A,
+        ttl: Duration,
+        capacity: usize,
+        clock: impl Fn() -> Duration + Send + Sync + 'static,
+    ) -> Self {
+        NegativeCachingAccessControl {
+            inner,
+            ttl,
+            capacity,
+            clock: Arc::new(clock),
+            denied: Default::default(),
+        }
+    }
+
+    /// When the denial of `key` expires, if it was denied
+    fn denied_until(&self, key: &CacheKey) -> Result<Option<Duration>> {
+        Ok(self
+            .denied
+            .read()
+            .map_err(|_| Error::new_without_cause(Origin::Core, Kind::Internal))?
+            .get(key)
+            .copied())
+    }
+
+    /// Whether `key` is denied at `now`, forgetting its denial if expired
+    fn is_denied(&self, key: &CacheKey, now: Duration) -> Result<bool> {
+        match self.denied_until(key)? {
+            Some(expires) if expires > now => Ok(true),
+            Some(_) => {
+                self.denied
+                    .write()
+                    .map_err(|_| Error::new_without_cause(Origin::Core, Kind::Internal))?
+                    .remove(key);
+                Ok(false)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Remember that `key` was denied at `now`
+    fn deny(&self, key: CacheKey, now: Duration) -> Result<()> {
+        if self.capacity == 0 {
+            return Ok(());
+        }
+        let mut denied = self
+            .denied
+            .write()
+            .map_err(|_| Error::new_without_cause(Origin::Core, Kind::Internal))?;
+        if denied.len() >= self.capacity && !denied.contains_key(&key) {
+            denied.retain(|_, expires| *expires > now);
+            if denied.len() >= self.capacity {
+                let first = denied
+                    .iter()
+                    .min_by_key(|(_, expires)| **expires)
+                    .map(|(key, _)| key.clone());
+                if let Some(first) = first {
+                    denied.remove(&first);
+                }
+            }
+        }
+        denied.insert(key, now + self.ttl);
+        Ok(())
+    }
+}
+
+impl<A: Debug> Debug for NegativeCachingAccessControl<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NegativeCachingAccessControl")
+            .field("inner", &self.inner)
+            .field("ttl", &self.ttl)
+            .field("capacity", &self.capacity)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<A: AccessControl> AccessControl for NegativeCachingAccessControl<A> {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        self.is_authorized_with_ctx(local_msg, None).await
+    }
+
+    async fn is_authorized_with_ctx(
+        &self,
+        local_msg: &LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<bool> {
+        let key = match CacheKey::source(local_msg) {
+            Some(key) => key,
+            None => return self.inner.is_authorized_with_ctx(local_msg, token).await,
+        };
+        let now = (self.clock)();
+        if self.is_denied(&key, now)? {
+            return crate::deny();
+        }
+        let decision = self.inner.is_authorized_with_ctx(local_msg, token).await?;
+        if !decision {
+            self.deny(key, now)?;
+        }
+        Ok(decision)
+    }
+
+    /// Return the message as annotated by the inner AccessControl
+    async fn authorize(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<LocalMessage>> {
+        Ok(self
+            .authorize_tracked(local_msg, token)
+            .await?
+            .map(|(local_msg, _)| local_msg))
+    }
+
+    /// Return the message and completion of the inner AccessControl, unless
+    /// the sender is denied
+    async fn authorize_tracked(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<(LocalMessage, Completion)>> {
+        let key = match CacheKey::source(&local_msg) {
+            Some(key) => key,
+            None => return self.inner.authorize_tracked(local_msg, token).await,
+        };
+        let now = (self.clock)();
+        if self.is_denied(&key, now)? {
+            return Ok(None);
+        }
+        let authorized = self.inner.authorize_tracked(local_msg, token).await?;
+        if authorized.is_none() {
+            self.deny(key, now)?;
+        }
+        Ok(authorized)
+    }
+
+    /// Trace the inner AccessControl, unless the sender is denied, without
+    /// remembering a denial
+    async fn trace_decision(&self, local_msg: &LocalMessage) -> DecisionTrace {
+        if let Some(key) = CacheKey::source(local_msg) {
+            match self.denied_until(&key) {
+                Ok(Some(expires)) if expires > (self.clock)() => {
+                    return DecisionTrace::leaf("NegativeCaching(cached)", &Ok(false));
+                }
+                Ok(_) => {}
+                Err(e) => return DecisionTrace::leaf("NegativeCaching", &Err(e)),
+            }
+        }
+        let inner = self.inner.trace_decision(local_msg).await;
+        DecisionTrace::new("NegativeCaching", inner.outcome.clone(), vec![inner])
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "NegativeCaching(ttl {}s)[{}]",
+            self.ttl.as_secs(),
+            self.inner.describe()
+        )
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use crate::access_control::testing::{LocalMessageBuilder, MockAccessControl};
+    use crate::compat::future::poll_once;
+    use crate::compat::sync::Arc;
+    use crate::{LocalMessage, Result};
+    use core::sync::atomic::{AtomicU64, Ordering};
+    use core::time::Duration;
+
+    use super::{AccessControl, NegativeCachingAccessControl};
+
+    fn msg(source: &str) -> LocalMessage {
+        LocalMessageBuilder::new()
+            .source(source)
+            .destination("a")
+            .build()
+    }
+
+    fn is_authorized(
+        access_control: &impl AccessControl,
+        local_msg: &LocalMessage,
+    ) -> Result<bool> {
+        poll_once(async { access_control.is_authorized(local_msg).await })
+    }
+
+    /// An AccessControl with a clock which only moves when told to
+    fn negative_caching(
+        mock: &MockAccessControl,
+        capacity: usize,
+    ) -> (
+        NegativeCachingAccessControl<MockAccessControl>,
+        Arc<AtomicU64>,
+    ) {
+        let secs = Arc::new(AtomicU64::new(0));
+        let clock = secs.clone();
+        let access_control = NegativeCachingAccessControl::with_clock(
+            mock.clone(),
+            Duration::from_secs(10),
+            capacity,
+            move || Duration::from_secs(clock.load(Ordering::Relaxed)),
+        );
+        (access_control, secs)
+    }
+
+    #[test]
+    fn test_negative_caching_allows_are_not_cached() -> Result<()> {
+        let mock = MockAccessControl::new([true, true, false]);
+        let (access_control, _) = negative_caching(&mock, 8);
+
+        assert!(is_authorized(&access_control, &msg("alice"))?);
+        assert!(is_authorized(&access_control, &msg("alice"))?);
+        assert!(!is_authorized(&access_control, &msg("alice"))?);
+        assert_eq!(mock.calls(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_negative_caching_denies_until_expiry() -> Result<()> {
+        let mock = MockAccessControl::new([false, true, true]);
+        let (access_control, secs) = negative_caching(&mock, 8);
+
+        assert!(!is_authorized(&access_control, &msg("alice"))?);
+        secs.store(9, Ordering::Relaxed);
+        assert!(!is_authorized(&access_control, &msg("alice"))?);
+        assert_eq!(mock.calls(), 1);
+
+        // Other senders are still asked about
+        assert!(is_authorized(&access_control, &msg("bob"))?);
+        assert_eq!(mock.calls(), 2);
+
+        secs.store(10, Ordering::Relaxed);
+        assert!(is_authorized(&access_control, &msg("alice"))?);
+        assert_eq!(mock.calls(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_negative_caching_capacity() -> Result<()> {
+        let mock = MockAccessControl::new([false, false, false, true]);
+        let (access_control, secs) = negative_caching(&mock, 2);
+
+        assert!(!is_authorized(&access_control, &msg("alice"))?);
+        secs.store(1, Ordering::Relaxed);
+        assert!(!is_authorized(&access_control, &msg("bob"))?);
+        // Forgets alice, whose denial expires first
+        assert!(!is_authorized(&access_control, &msg("carol"))?);
+        assert_eq!(mock.calls(), 3);
+
+        assert!(!is_authorized(&access_control, &msg("bob"))?);
+        assert!(!is_authorized(&access_control, &msg("carol"))?);
+        assert_eq!(mock.calls(), 3);
+        assert!(is_authorized(&access_control, &msg("alice"))?);
+        assert_eq!(mock.calls(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_negative_caching_without_source() -> Result<()> {
+        let mock = MockAccessControl::new([false]);
+        let (access_control, _) = negative_caching(&mock, 8);
+        let msg = LocalMessageBuilder::new().destination("a").build();
+
+        assert!(!is_authorized(&access_control, &msg)?);
+        assert!(!is_authorized(&access_control, &msg)?);
+        assert_eq!(mock.calls(), 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,454 @@
+use crate::access_control::{
+    AccessControl, CancellationToken, Completion, DecisionTrace, TraceOutcome,
+};
+use crate::compat::boxed::Box;
+use crate::compat::{format, string::String};
+use crate::{async_trait, LocalMessage, Result};
+use core::convert::Infallible;
+use futures_util::future::{join, pending, select, BoxFuture, Either};
+use futures_util::pin_mut;
+
+/// Allows messages that are allowed by both AccessControls, asking them
+/// at the same time
+///
+/// Unlike [`AllAccessControl`](crate::AllAccessControl), which only asks the
+/// second AccessControl once the first allowed the message, both are asked
+/// right away. The first denial, or error, decides: the other AccessControl is
+/// cancelled, through the [`CancellationToken`] it was given, and dropped.
+/// This trades the work of the cancelled AccessControl for latency, when both
+/// are slow, e.g. ask other nodes.
+#[derive(Debug)]
+pub struct AllParallelAccessControl<F: AccessControl, S: AccessControl> {
+    first: F,
+    second: S,
+}
+
+impl<F: AccessControl, S: AccessControl> AllParallelAccessControl<F, S> {
+    /// Constructor
+    pub fn new(first: F, second: S) -> Self {
+        AllParallelAccessControl { first, second }
+    }
+}
+
+#[async_trait]
+impl<F: AccessControl, S: AccessControl> AccessControl for AllParallelAccessControl<F, S> {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        self.is_authorized_with_ctx(local_msg, None).await
+    }
+
+    async fn is_authorized_with_ctx(
+        &self,
+        local_msg: &LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<bool> {
+        let children = CancellationToken::new();
+        race(
+            self.first
+                .is_authorized_with_ctx(local_msg, Some(&children)),
+            self.second
+                .is_authorized_with_ctx(local_msg, Some(&children)),
+            &children,
+            token,
+            |result| !matches!(result, Ok(true)),
+            |_, other| other,
+        )
+        .await
+    }
+
+    /// Return the message unchanged, with the completions of both
+    /// AccessControls
+    async fn authorize_tracked(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<(LocalMessage, Completion)>> {
+        let children = CancellationToken::new();
+        race(
+            self.first
+                .authorize_tracked(local_msg.clone(), Some(&children)),
+            self.second
+                .authorize_tracked(local_msg.clone(), Some(&children)),
+            &children,
+            token,
+            |result| !matches!(result, Ok(Some(_))),
+            |done, other| match (done, other) {
+                (Ok(Some((local_msg, mut completion))), Ok(Some((_, other)))) => {
+                    completion.merge(other);
+                    Ok(Some((local_msg, completion)))
+                }
+                (_, other) => other,
+            },
+        )
+        .await
+    }
+
+    /// Trace both AccessControls, the first not allowing the message
+    /// deciding
+    async fn trace_decision(&self, local_msg: &LocalMessage) -> DecisionTrace {
+        let (first, second) = join(
+            self.first.trace_decision(local_msg),
+            self.second.trace_decision(local_msg),
+        )
+        .await;
+        let outcome = if first.outcome.is_allowed() {
+            second.outcome.clone()
+        } else {
+            first.outcome.clone()
+        };
+        DecisionTrace::new("AllParallel", outcome, vec![first, second])
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "AllParallel[{}, {}]",
+            self.first.describe(),
+            self.second.describe()
+        )
+    }
+}
+
+/// Allows messages that are allowed by either AccessControl, asking them
+/// at the same time
+///
+/// Unlike [`AnyAccessControl`](crate::AnyAccessControl), which only asks the
+/// second AccessControl once the first denied the message, both are asked
+/// right away. The first approval, or error, decides: the other AccessControl
+/// is cancelled, through the [`CancellationToken`] it was given, and dropped.
+/// When both would allow the message, the annotations of the quickest one win.
+#[derive(Debug)]
+pub struct AnyParallelAccessControl<F: AccessControl, S: AccessControl> {
+    first: F,
+    second: S,
+}
+
+impl<F: AccessControl, S: AccessControl> AnyParallelAccessControl<F, S> {
+    /// Constructor
+    pub fn new(first: F, second: S) -> Self {
+        AnyParallelAccessControl { first, second }
+    }
+}
+
+#[async_trait]
+impl<F: AccessControl, S: AccessControl> AccessControl for AnyParallelAccessControl<F, S> {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        self.is_authorized_with_ctx(local_msg, None).await
+    }
+
+    async fn is_authorized_with_ctx(
+        &self,
+        local_msg: &LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<bool> {
+        let children = CancellationToken::new();
+        race(
+            self.first
+                .is_authorized_with_ctx(local_msg, Some(&children)),
+            self.second
+                .is_authorized_with_ctx(local_msg, Some(&children)),
+            &children,
+            token,
+            |result| !matches!(result, Ok(false)),
+            |_, other| other,
+        )
+        .await
+    }
+
+    /// Return the message as annotated by the first AccessControl allowing it
+    async fn authorize(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<LocalMessage>> {
+        let children = CancellationToken::new();
+        race(
+            self.first.authorize(local_msg.clone(), Some(&children)),
+            self.second.authorize(local_msg, Some(&children)),
+            &children,
+            token,
+            |result| !matches!(result, Ok(None)),
+            |_, other| other,
+        )
+        .await
+    }
+
+    /// Return the message and completion of the first AccessControl
+    /// allowing it
+    async fn authorize_tracked(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<(LocalMessage, Completion)>> {
+        let children = CancellationToken::new();
+        race(
+            self.first
+                .authorize_tracked(local_msg.clone(), Some(&children)),
+            self.second.authorize_tracked(local_msg, Some(&children)),
+            &children,
+            token,
+            |result| !matches!(result, Ok(None)),
+            |_, other| other,
+        )
+        .await
+    }
+
+    /// Trace both AccessControls, the first not denying the message deciding
+    async fn trace_decision(&self, local_msg: &LocalMessage) -> DecisionTrace {
+        let (first, second) = join(
+            self.first.trace_decision(local_msg),
+            self.second.trace_decision(local_msg),
+        )
+        .await;
+        let outcome = if first.outcome == TraceOutcome::Denied {
+            second.outcome.clone()
+        } else {
+            first.outcome.clone()
+        };
+        DecisionTrace::new("AnyParallel", outcome, vec![first, second])
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "AnyParallel[{}, {}]",
+            self.first.describe(),
+            self.second.describe()
+        )
+    }
+}
+
+/// Poll `first` and `second` together, and return the first of their results
+/// which is `decisive`, or else the results of both, in the order they
+/// finished, `combine`d.
+///
+/// `children` is the token given to both. It is cancelled once a result
+/// decides, before the other future is dropped, and when `token` is cancelled,
+/// in which case both keep being polled until they give up.
+async fn race<T>(
+    first: BoxFuture<'_, Result<T>>,
+    second: BoxFuture<'_, Result<T>>,
+    children: &CancellationToken,
+    token: Option<&CancellationToken>,
+    decisive: fn(&Result<T>) -> bool,
+    combine: fn(Result<T>, Result<T>) -> Result<T>,
+) -> Result<T> {
+    let forward = async {
+        if let Some(token) = token {
+            token.cancelled().await;
+            children.cancel();
+        }
+        pending::<Infallible>().await
+    };
+    let evaluation = async {
+        let (result, other) = match select(first, second).await {
+            Either::Left(done) | Either::Right(done) => done,
+        };
+        if !decisive(&result) {
+            return combine(result, other.await);
+        }
+        children.cancel();
+        drop(other);
+        result
+    };
+    pin_mut!(forward, evaluation);
+    // Forward a cancellation of `token` before polling the children
+    match select(forward, evaluation).await {
+        Either::Left((never, _)) => match never {},
+        Either::Right((result, _)) => result,
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use crate::access_control::testing::{LocalMessageBuilder, MockAccessControl};
+    use crate::compat::boxed::Box;
+    use crate::compat::future::poll_once;
+    use crate::compat::string::String;
+    use crate::compat::sync::{Arc, Mutex};
+    use crate::errcode::{Kind, Origin};
+    use crate::{async_trait, Error, LocalInfo, LocalMessage, Result};
+
+    use super::{
+        AccessControl, AllParallelAccessControl, AnyParallelAccessControl, CancellationToken,
+    };
+
+    /// Never decides, and fails once its token is cancelled
+    #[derive(Debug, Clone, Default)]
+    struct Pending {
+        state: Arc<Mutex<PendingState>>,
+    }
+
+    #[derive(Debug, Default)]
+    struct PendingState {
+        token: Option<CancellationToken>,
+        dropped: bool,
+    }
+
+    /// Marks its [`Pending`] access control as dropped
+    struct Guard(Arc<Mutex<PendingState>>);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            self.0.lock().unwrap().dropped = true;
+        }
+    }
+
+    impl Pending {
+        /// Whether the evaluation, if it started, was dropped with its
+        /// token cancelled
+        fn cleaned_up(&self) -> bool {
+            let state = self.state.lock().unwrap();
+            match &state.token {
+                Some(token) => state.dropped && token.is_cancelled(),
+                None => !state.dropped,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl AccessControl for Pending {
+        async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+            self.is_authorized_with_ctx(local_msg, None).await
+        }
+
+        async fn is_authorized_with_ctx(
+            &self,
+            _local_msg: &LocalMessage,
+            token: Option<&CancellationToken>,
+        ) -> Result<bool> {
+            let _guard = Guard(self.state.clone());
+            let token = token.expect("a parallel access control passes a token");
+            self.state.lock().unwrap().token = Some(token.clone());
+            token.cancelled().await;
+            Err(Error::new_without_cause(Origin::Core, Kind::Cancelled))
+        }
+    }
+
+    fn is_authorized(access_control: &impl AccessControl) -> Result<bool> {
+        poll_once(async {
+            access_control
+                .is_authorized(&LocalMessageBuilder::new().build())
+                .await
+        })
+    }
+
+    #[test]
+    fn test_all_parallel() {
+        for (first, second, decision) in [
+            (true, true, true),
+            (true, false, false),
+            (false, true, false),
+            (false, false, false),
+        ] {
+            let access_control = AllParallelAccessControl::new(
+                MockAccessControl::new([first]),
+                MockAccessControl::new([second]),
+            );
+            assert_eq!(is_authorized(&access_control).ok(), Some(decision));
+        }
+    }
+
+    #[test]
+    fn test_any_parallel() {
+        for (first, second, decision) in [
+            (true, true, true),
+            (true, false, true),
+            (false, true, true),
+            (false, false, false),
+        ] {
+            let access_control = AnyParallelAccessControl::new(
+                MockAccessControl::new([first]),
+                MockAccessControl::new([second]),
+            );
+            assert_eq!(is_authorized(&access_control).ok(), Some(decision));
+        }
+    }
+
+    #[test]
+    fn test_decisive_cancellation() {
+        // A denial decides for All, whichever part denies
+        let pending = Pending::default();
+        let access_control =
+            AllParallelAccessControl::new(pending.clone(), MockAccessControl::new([false]));
+        assert_eq!(is_authorized(&access_control).ok(), Some(false));
+        assert!(pending.cleaned_up());
+
+        let pending = Pending::default();
+        let access_control =
+            AllParallelAccessControl::new(MockAccessControl::new([false]), pending.clone());
+        assert_eq!(is_authorized(&access_control).ok(), Some(false));
+        assert!(pending.cleaned_up());
+
+        // An approval decides for Any
+        let pending = Pending::default();
+        let access_control =
+            AnyParallelAccessControl::new(pending.clone(), MockAccessControl::new([true]));
+        assert_eq!(is_authorized(&access_control).ok(), Some(true));
+        assert!(pending.cleaned_up());
+
+        // An error decides for both
+        let pending = Pending::default();
+        let access_control = AnyParallelAccessControl::new(
+            MockAccessControl::with_results([Err(Error::new_without_cause(
+                Origin::Core,
+                Kind::Invalid,
+            ))]),
+            pending.clone(),
+        );
+        assert!(is_authorized(&access_control).is_err());
+        assert!(pending.cleaned_up());
+    }
+
+    #[test]
+    fn test_parent_cancellation() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let (first, second) = (Pending::default(), Pending::default());
+        let access_control = AllParallelAccessControl::new(first.clone(), second.clone());
+        let decision = poll_once(async {
+            access_control
+                .is_authorized_with_ctx(&LocalMessageBuilder::new().build(), Some(&token))
+                .await
+        });
+        assert!(decision.is_err());
+        assert!(first.cleaned_up() && second.cleaned_up());
+    }
+
+    /// Allows messages after attaching its identity
+    #[derive(Debug)]
+    struct Credential(&'static str);
+
+    #[async_trait]
+    impl AccessControl for Credential {
+        async fn is_authorized(&self, _local_msg: &LocalMessage) -> Result<bool> {
+            Ok(true)
+        }
+
+        async fn authorize(
+            &self,
+            mut local_msg: LocalMessage,
+            _token: Option<&CancellationToken>,
+        ) -> Result<Option<LocalMessage>> {
+            local_msg.append_local_info(LocalInfo::new(
+                String::from("identity"),
+                self.0.as_bytes().to_vec(),
+            ));
+            Ok(Some(local_msg))
+        }
+    }
+
+    #[test]
+    fn test_any_parallel_annotations() {
+        let pending = Pending::default();
+        let access_control = AnyParallelAccessControl::new(pending.clone(), Credential("bob"));
+        let local_msg = poll_once(async {
+            access_control
+                .authorize(LocalMessageBuilder::new().build(), None)
+                .await
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(local_msg.local_info().len(), 1);
+        assert_eq!(local_msg.local_info()[0].data(), b"bob");
+        assert!(pending.cleaned_up());
+    }
+}
@@ -0,0 +1,190 @@
+use crate::access_control::{AccessControl, CancellationToken, Completion, DecisionTrace};
+use crate::compat::boxed::Box;
+use crate::compat::sync::{Arc, RwLock};
+use crate::compat::{format, string::String};
+use crate::errcode::{Kind, Origin};
+use crate::{async_trait, Error, LocalMessage, Result};
+use core::fmt::{self, Debug};
+
+/// An AccessControl whose policy can be replaced while it is in use
+///
+/// The policy is built by a loader, e.g. a function reading a policy file,
+/// when the AccessControl is created and again on every
+/// [`reload`](Self::reload). Messages are authorized by the policy loaded
+/// last: a message being authorized while a reload happens finishes with
+/// the policy it started with.
+pub struct ReloadableAccessControl {
+    loader: Box<dyn Fn() -> Result<Arc<dyn AccessControl>> + Send + Sync>,
+    current: RwLock<Arc<dyn AccessControl>>,
+}
+
+impl ReloadableAccessControl {
+    /// Constructor, failing when `loader` can not load the first policy
+    pub fn new(
+        loader: impl Fn() -> Result<Arc<dyn AccessControl>> + Send + Sync + 'static,
+    ) -> Result<Self> {
+        let current = loader()?;
+        Ok(ReloadableAccessControl {
+            loader: Box::new(loader),
+            current: RwLock::new(current),
+        })
+    }
+
+    /// Load the policy again and use it for the next messages
+    ///
+    /// When the loader fails, the error is logged and returned, and the
+    /// current policy is kept.
+    pub fn reload(&self) -> Result<()> {
+        let policy = (self.loader)().map_err(|e| {
+            tracing::error!(error = %e, "failed to reload the access control policy");
+            e
+        })?;
+        *self
+            .current
+            .write()
+            .map_err(|_| Error::new_without_cause(Origin::Core, Kind::Internal))? = policy;
+        Ok(())
+    }
+
+    /// The policy in use
+    pub fn current(&self) -> Result<Arc<dyn AccessControl>> {
+        self.current
+            .read()
+            .map(|current| current.clone())
+            .map_err(|_| Error::new_without_cause(Origin::Core, Kind::Internal))
+    }
+}
+
+impl Debug for ReloadableAccessControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.current() {
+            Ok(current) => f
+                .debug_struct("ReloadableAccessControl")
+                .field("current", &current)
+                .finish_non_exhaustive(),
+            Err(_) => f
+                .debug_struct("ReloadableAccessControl")
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+#[async_trait]
+impl AccessControl for ReloadableAccessControl {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        self.current()?.is_authorized(local_msg).await
+    }
+
+    async fn is_authorized_with_ctx(
+        &self,
+        local_msg: &LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<bool> {
+        self.current()?
+            .is_authorized_with_ctx(local_msg, token)
+            .await
+    }
+
+    async fn authorize(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<LocalMessage>> {
+        self.current()?.authorize(local_msg, token).await
+    }
+
+    async fn authorize_tracked(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<(LocalMessage, Completion)>> {
+        self.current()?.authorize_tracked(local_msg, token).await
+    }
+
+    /// Trace the policy in use
+    async fn trace_decision(&self, local_msg: &LocalMessage) -> DecisionTrace {
+        let current = match self.current() {
+            Ok(current) => current,
+            Err(e) => return DecisionTrace::leaf("Reloadable", &Err(e)),
+        };
+        let policy = current.trace_decision(local_msg).await;
+        DecisionTrace::new("Reloadable", policy.outcome.clone(), vec![policy])
+    }
+
+    fn describe(&self) -> String {
+        match self.current() {
+            Ok(current) => format!("Reloadable[{}]", current.describe()),
+            Err(_) => String::from("Reloadable"),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use crate::access_control::testing::LocalMessageBuilder;
+    use crate::compat::future::poll_once;
+    use crate::compat::sync::{Arc, Mutex};
+    use crate::errcode::{Kind, Origin};
+    use crate::{AllowAll, DenyAll, Error, LocalMessage, Result};
+
+    use super::{AccessControl, ReloadableAccessControl};
+
+    fn is_authorized(
+        access_control: &impl AccessControl,
+        local_msg: &LocalMessage,
+    ) -> Result<bool> {
+        poll_once(async { access_control.is_authorized(local_msg).await })
+    }
+
+    type NextPolicy = Arc<Mutex<Option<Arc<dyn AccessControl>>>>;
+
+    /// An AccessControl loading the policies given to the returned handle,
+    /// or failing to load when it was given `None`
+    fn reloadable() -> (ReloadableAccessControl, NextPolicy) {
+        let next: NextPolicy = Arc::new(Mutex::new(Some(Arc::new(AllowAll))));
+        let policy = next.clone();
+        let access_control = ReloadableAccessControl::new(move || {
+            policy
+                .lock()
+                .unwrap()
+                .clone()
+                .ok_or_else(|| Error::new_without_cause(Origin::Core, Kind::Invalid))
+        })
+        .unwrap();
+        (access_control, next)
+    }
+
+    #[test]
+    fn test_reloadable_uses_reloaded_policy() -> Result<()> {
+        let msg = LocalMessageBuilder::new().destination("a").build();
+        let (access_control, next) = reloadable();
+        assert!(is_authorized(&access_control, &msg)?);
+
+        *next.lock().unwrap() = Some(Arc::new(DenyAll));
+        // Nothing changes until the policy is reloaded
+        assert!(is_authorized(&access_control, &msg)?);
+        access_control.reload()?;
+        assert!(!is_authorized(&access_control, &msg)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reloadable_keeps_policy_when_reload_fails() -> Result<()> {
+        let msg = LocalMessageBuilder::new().destination("a").build();
+        let (access_control, next) = reloadable();
+
+        *next.lock().unwrap() = None;
+        assert!(access_control.reload().is_err());
+        assert!(is_authorized(&access_control, &msg)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reloadable_fails_without_first_policy() {
+        let access_control = ReloadableAccessControl::new(|| {
+            Err(Error::new_without_cause(Origin::Core, Kind::Invalid))
+        });
+        assert!(access_control.is_err());
+    }
+}
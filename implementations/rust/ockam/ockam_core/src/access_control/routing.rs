@@ -0,0 +1,159 @@
+use crate::access_control::{AccessControl, CancellationToken, Completion, DecisionTrace};
+use crate::compat::boxed::Box;
+use crate::compat::collections::BTreeMap;
+use crate::compat::{format, string::String};
+use crate::{async_trait, Address, LocalMessage, Result};
+
+/// Delegates to an AccessControl chosen by the destination of the message
+///
+/// The destination is the next address of the message's onward route.
+/// Messages to destinations without an AccessControl of their own are
+/// handled by the default AccessControl, e.g. [`DenyAll`](crate::DenyAll).
+#[derive(Debug)]
+pub struct RoutingAccessControl {
+    destinations: BTreeMap<Address, Box<dyn AccessControl>>,
+    default: Box<dyn AccessControl>,
+}
+
+impl RoutingAccessControl {
+    /// Constructor
+    pub fn new(default: impl AccessControl) -> Self {
+        RoutingAccessControl {
+            destinations: BTreeMap::new(),
+            default: Box::new(default),
+        }
+    }
+
+    /// Use `access_control` for messages sent to `destination`
+    pub fn with_destination(
+        mut self,
+        destination: impl Into<Address>,
+        access_control: impl AccessControl,
+    ) -> Self {
+        self.destinations
+            .insert(destination.into(), Box::new(access_control));
+        self
+    }
+
+    fn select(&self, local_msg: &LocalMessage) -> &dyn AccessControl {
+        local_msg
+            .transport()
+            .onward_route
+            .next()
+            .ok()
+            .and_then(|destination| self.destinations.get(destination))
+            .unwrap_or(&self.default)
+            .as_ref()
+    }
+}
+
+#[async_trait]
+impl AccessControl for RoutingAccessControl {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        self.select(local_msg).is_authorized(local_msg).await
+    }
+
+    async fn is_authorized_with_ctx(
+        &self,
+        local_msg: &LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<bool> {
+        self.select(local_msg)
+            .is_authorized_with_ctx(local_msg, token)
+            .await
+    }
+
+    async fn authorize(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<LocalMessage>> {
+        self.select(&local_msg).authorize(local_msg, token).await
+    }
+
+    async fn authorize_tracked(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<(LocalMessage, Completion)>> {
+        self.select(&local_msg)
+            .authorize_tracked(local_msg, token)
+            .await
+    }
+
+    /// Trace the AccessControl of the destination of the message
+    async fn trace_decision(&self, local_msg: &LocalMessage) -> DecisionTrace {
+        let selected = self.select(local_msg).trace_decision(local_msg).await;
+        DecisionTrace::new("Routing", selected.outcome.clone(), vec![selected])
+    }
+
+    fn describe(&self) -> String {
+        let mut description = String::from("Routing[");
+        for (destination, access_control) in &self.destinations {
+            description.push_str(&format!("{destination}: {}, ", access_control.describe()));
+        }
+        description.push_str(&format!("*: {}]", self.default.describe()));
+        description
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use crate::compat::future::poll_once;
+    use crate::{route, AllowAll, DenyAll, LocalMessage, Result, Route, TransportMessage};
+
+    use super::{AccessControl, RoutingAccessControl};
+
+    fn is_authorized(access_control: &RoutingAccessControl, onward: Route) -> Result<bool> {
+        poll_once(async {
+            let local_message =
+                LocalMessage::new(TransportMessage::v1(onward, route![], vec![]), vec![]);
+            access_control.is_authorized(&local_message).await
+        })
+    }
+
+    #[test]
+    fn test_routing() {
+        let access_control = RoutingAccessControl::new(DenyAll)
+            .with_destination("allowed", AllowAll)
+            .with_destination("denied", DenyAll);
+
+        assert_eq!(
+            is_authorized(&access_control, route!["allowed"]).ok(),
+            crate::allow().ok()
+        );
+        assert_eq!(
+            is_authorized(&access_control, route!["allowed", "next"]).ok(),
+            crate::allow().ok()
+        );
+        assert_eq!(
+            is_authorized(&access_control, route!["denied"]).ok(),
+            crate::deny().ok()
+        );
+        // Only the next hop selects the access control
+        assert_eq!(
+            is_authorized(&access_control, route!["other", "allowed"]).ok(),
+            crate::deny().ok()
+        );
+        assert_eq!(
+            is_authorized(&access_control, route![]).ok(),
+            crate::deny().ok()
+        );
+    }
+
+    #[test]
+    fn test_routing_default() {
+        let access_control =
+            RoutingAccessControl::new(AllowAll).with_destination("denied", DenyAll);
+
+        assert_eq!(
+            is_authorized(&access_control, route!["other"]).ok(),
+            crate::allow().ok()
+        );
+        assert_eq!(
+            is_authorized(&access_control, route!["denied"]).ok(),
+            crate::deny().ok()
+        );
+    }
+}
@@ -0,0 +1,357 @@
+use crate::access_control::{AccessControl, DecisionTrace};
+use crate::compat::boxed::Box;
+use crate::compat::collections::BTreeMap;
+use crate::compat::sync::{Arc, RwLock};
+use crate::errcode::{Kind, Origin};
+use crate::{async_trait, Address, Decodable, Encodable, Error, LocalInfo, LocalMessage, Result};
+use core::fmt::{self, Debug};
+use serde::{Deserialize, Serialize};
+
+/// Sequence LocalInfo unique Identifier
+pub const SEQUENCE_IDENTIFIER: &str = "SEQUENCE_IDENTIFIER";
+
+/// The position of a message in the stream of messages of its sender,
+/// checked by [`SequenceAccessControl`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SequenceLocalInfo {
+    sequence: u64,
+}
+
+impl SequenceLocalInfo {
+    /// Constructor
+    pub fn new(sequence: u64) -> Self {
+        Self { sequence }
+    }
+
+    /// Sequence number
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Convert from LocalInfo
+    pub fn from_local_info(value: &LocalInfo) -> Result<Self> {
+        if value.type_identifier() != SEQUENCE_IDENTIFIER {
+            return Err(Error::new_without_cause(Origin::Core, Kind::Invalid));
+        }
+        SequenceLocalInfo::decode(value.data())
+            .map_err(|_| Error::new_without_cause(Origin::Core, Kind::Invalid))
+    }
+
+    /// Convert to LocalInfo
+    pub fn to_local_info(&self) -> Result<LocalInfo> {
+        Ok(LocalInfo::new(SEQUENCE_IDENTIFIER.into(), self.encode()?))
+    }
+
+    /// Find first such instance in LocalMessage
+    pub fn find_info(local_msg: &LocalMessage) -> Result<Self> {
+        match local_msg
+            .local_info()
+            .iter()
+            .find(|x| x.type_identifier() == SEQUENCE_IDENTIFIER)
+        {
+            Some(local_info) => Self::from_local_info(local_info),
+            None => Err(Error::new_without_cause(Origin::Core, Kind::Invalid)),
+        }
+    }
+}
+
+/// Numbers the messages of a sender, for the [`SequenceAccessControl`] of
+/// their receiver
+///
+/// Attach [`next`](Self::next) to each message, e.g. with
+/// `Context::send_in_sequence`. The numbers start at 1.
+#[derive(Debug, Default)]
+pub struct Sequencer {
+    last: RwLock<u64>,
+}
+
+impl Sequencer {
+    /// Constructor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The position of the next message
+    pub fn next(&self) -> Result<SequenceLocalInfo> {
+        let mut last = self
+            .last
+            .write()
+            .map_err(|_| Error::new_without_cause(Origin::Core, Kind::Internal))?;
+        *last += 1;
+        Ok(SequenceLocalInfo::new(*last))
+    }
+}
+
+/// What is remembered about one sender
+#[derive(Debug, Clone, Copy)]
+struct Seen {
+    /// Highest sequence number accepted
+    highest: u64,
+    /// Bit `n` is set when `highest - n` was accepted
+    window: u64,
+}
+
+/// Allows the messages of each sender in order
+///
+/// The sender of a message is the next address of its return route, and its
+/// position is the [`SequenceLocalInfo`] attached to it, see [`Sequencer`].
+/// [`LocalInfo`] does not cross transports, so senders number their messages
+/// for receivers on the same node. A message is allowed
+/// if its sequence number is higher than the highest one allowed from the same
+/// sender, so duplicates are denied and gaps are allowed. Messages without a
+/// sender or a sequence number are denied.
+///
+/// With a reorder window of `n`, a message up to `n` positions behind the
+/// highest sequence number is allowed too, once.
+///
+/// At most `max_senders` senders are remembered. Once that many are, the
+/// messages of new senders are denied until one is [forgotten](Self::forget),
+/// since forgetting a sender on its own would allow its old messages again.
+///
+/// Clones share the senders they remember, so that a worker started again
+/// with a clone doesn't allow the messages its previous instance saw.
+#[derive(Clone)]
+pub struct SequenceAccessControl {
+    max_senders: usize,
+    reorder_window: u8,
+    senders: Arc<RwLock<BTreeMap<Address, Seen>>>,
+}
+
+impl SequenceAccessControl {
+    /// Constructor
+    pub fn new(max_senders: usize) -> Self {
+        SequenceAccessControl {
+            max_senders,
+            reorder_window: 0,
+            senders: Default::default(),
+        }
+    }
+
+    /// Also allow the messages up to `window` positions behind the highest
+    /// sequence number of their sender, at most 63
+    pub fn with_reorder_window(mut self, window: u8) -> Self {
+        self.reorder_window = window.min(63);
+        self
+    }
+
+    /// Forget `sender`, e.g. once its channel is closed, so that another
+    /// sender may be remembered instead
+    pub fn forget(&self, sender: &Address) -> Result<()> {
+        self.senders
+            .write()
+            .map_err(|_| Error::new_without_cause(Origin::Core, Kind::Internal))?
+            .remove(sender);
+        Ok(())
+    }
+
+    /// What is remembered about a sender once `sequence` is allowed, given
+    /// what was before and how many senders are `known`, or `None` if it is
+    /// denied
+    fn advance(&self, seen: Option<&Seen>, known: usize, sequence: u64) -> Option<Seen> {
+        let seen = match seen {
+            Some(seen) => seen,
+            None if known >= self.max_senders => return None,
+            None => {
+                return Some(Seen {
+                    highest: sequence,
+                    window: 1,
+                })
+            }
+        };
+        if sequence > seen.highest {
+            let shift = sequence - seen.highest;
+            let window = if shift < 64 { seen.window << shift } else { 0 };
+            return Some(Seen {
+                highest: sequence,
+                window: window | 1,
+            });
+        }
+        let behind = seen.highest - sequence;
+        let bit = 1u64.checked_shl(behind as u32).unwrap_or(0);
+        if behind <= self.reorder_window as u64 && seen.window & bit == 0 {
+            return Some(Seen {
+                highest: seen.highest,
+                window: seen.window | bit,
+            });
+        }
+        None
+    }
+
+    /// Record `sequence` for `sender`, returning whether it is allowed
+    fn accept(&self, sender: &Address, sequence: u64) -> Result<bool> {
+        let mut senders = self
+            .senders
+            .write()
+            .map_err(|_| Error::new_without_cause(Origin::Core, Kind::Internal))?;
+        match self.advance(senders.get(sender), senders.len(), sequence) {
+            Some(seen) => {
+                senders.insert(sender.clone(), seen);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Whether `sequence` would be allowed for `sender`, without recording it
+    fn would_accept(&self, sender: &Address, sequence: u64) -> Result<bool> {
+        let senders = self
+            .senders
+            .read()
+            .map_err(|_| Error::new_without_cause(Origin::Core, Kind::Internal))?;
+        Ok(self
+            .advance(senders.get(sender), senders.len(), sequence)
+            .is_some())
+    }
+
+    /// The sender of `local_msg` and its sequence number, if it has both
+    fn position(local_msg: &LocalMessage) -> Option<(&Address, u64)> {
+        let sender = local_msg.transport().return_route.next().ok()?;
+        let sequence = SequenceLocalInfo::find_info(local_msg).ok()?.sequence();
+        Some((sender, sequence))
+    }
+}
+
+impl Debug for SequenceAccessControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SequenceAccessControl")
+            .field("max_senders", &self.max_senders)
+            .field("reorder_window", &self.reorder_window)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl AccessControl for SequenceAccessControl {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        match Self::position(local_msg) {
+            Some((sender, sequence)) => self.accept(sender, sequence),
+            None => crate::deny(),
+        }
+    }
+
+    /// Trace the decision without recording the sequence number
+    async fn trace_decision(&self, local_msg: &LocalMessage) -> DecisionTrace {
+        let decision = match Self::position(local_msg) {
+            Some((sender, sequence)) => self.would_accept(sender, sequence),
+            None => crate::deny(),
+        };
+        DecisionTrace::leaf(self.describe(), &decision)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use crate::access_control::testing::LocalMessageBuilder;
+    use crate::compat::future::poll_once;
+    use crate::compat::vec::Vec;
+
+    use super::{AccessControl, SequenceAccessControl, SequenceLocalInfo, Sequencer};
+
+    /// Decisions for the messages of `sender` with the given sequence numbers
+    fn decide(
+        access_control: &SequenceAccessControl,
+        sender: &str,
+        sequences: &[u64],
+    ) -> Vec<bool> {
+        sequences
+            .iter()
+            .map(|&sequence| {
+                let msg = LocalMessageBuilder::new()
+                    .source(sender)
+                    .local_info(SequenceLocalInfo::new(sequence).to_local_info().unwrap())
+                    .build();
+                poll_once(async { access_control.is_authorized(&msg).await }).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_in_order() {
+        let access_control = SequenceAccessControl::new(8);
+        assert_eq!(
+            decide(&access_control, "alice", &[1, 2, 3]),
+            [true, true, true]
+        );
+        // Each sender has its own sequence
+        assert_eq!(decide(&access_control, "bob", &[1, 2]), [true, true]);
+
+        // Messages without a sequence number are denied
+        let msg = LocalMessageBuilder::new().source("alice").build();
+        assert_eq!(
+            poll_once(async { access_control.is_authorized(&msg).await }).ok(),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_duplicate() {
+        let access_control = SequenceAccessControl::new(8);
+        assert_eq!(
+            decide(&access_control, "alice", &[1, 2, 2, 1, 3]),
+            [true, true, false, false, true]
+        );
+
+        let access_control = SequenceAccessControl::new(8).with_reorder_window(4);
+        assert_eq!(
+            decide(&access_control, "alice", &[1, 3, 2, 2, 3]),
+            [true, true, true, false, false]
+        );
+    }
+
+    #[test]
+    fn test_gap() {
+        let access_control = SequenceAccessControl::new(8);
+        assert_eq!(
+            decide(&access_control, "alice", &[1, 5, 3, 6]),
+            [true, true, false, true]
+        );
+
+        let access_control = SequenceAccessControl::new(8).with_reorder_window(2);
+        assert_eq!(
+            decide(&access_control, "alice", &[1, 5, 3, 4, 2, 100, 99, 36]),
+            [true, true, true, true, false, true, true, false]
+        );
+    }
+
+    #[test]
+    fn test_bounded_senders() {
+        let access_control = SequenceAccessControl::new(2);
+        assert_eq!(decide(&access_control, "alice", &[5]), [true]);
+        assert_eq!(decide(&access_control, "bob", &[5]), [true]);
+        // New senders can't make the others forgotten...
+        assert_eq!(decide(&access_control, "carol", &[1]), [false]);
+        assert_eq!(decide(&access_control, "alice", &[5, 6]), [false, true]);
+        assert_eq!(decide(&access_control, "bob", &[5]), [false]);
+
+        // ...until one is forgotten explicitly
+        access_control.forget(&"bob".into()).unwrap();
+        assert_eq!(decide(&access_control, "carol", &[1]), [true]);
+
+        let access_control = SequenceAccessControl::new(0);
+        assert_eq!(decide(&access_control, "alice", &[1]), [false]);
+    }
+
+    #[test]
+    fn test_trace_keeps_sequence() {
+        let access_control = SequenceAccessControl::new(1);
+        let msg = LocalMessageBuilder::new()
+            .source("alice")
+            .local_info(SequenceLocalInfo::new(1).to_local_info().unwrap())
+            .build();
+        for _ in 0..2 {
+            let trace = poll_once(async { Ok(access_control.trace_decision(&msg).await) });
+            assert!(trace.unwrap().outcome.is_allowed());
+        }
+        assert_eq!(decide(&access_control, "alice", &[1, 1]), [true, false]);
+    }
+
+    #[test]
+    fn test_sequencer() {
+        let sequencer = Sequencer::new();
+        let sequences: Vec<u64> = (0..3)
+            .map(|_| sequencer.next().unwrap().sequence())
+            .collect();
+        assert_eq!(sequences, [1, 2, 3]);
+    }
+}
@@ -0,0 +1,150 @@
+use crate::access_control::AccessControl;
+use crate::compat::boxed::Box;
+use crate::compat::vec::Vec;
+use crate::compat::{format, string::String};
+use crate::{async_trait, LocalMessage, Result};
+
+/// Allows messages by the name of the service they are finally sent to
+///
+/// The service is the last address of the onward route, which has to be a
+/// local address, i.e. the `/service/<NAME>` a multiaddr route ends with.
+/// Messages are allowed when that name matches one of the patterns: a
+/// pattern is either a name, or a glob where `*` stands for any sequence
+/// of characters and `?` for any single character, e.g. `forward_to_*`.
+/// Messages whose route doesn't end with a local address are denied.
+#[derive(Debug, Clone)]
+pub struct ServiceNameAccessControl {
+    patterns: Vec<String>,
+}
+
+impl ServiceNameAccessControl {
+    /// Constructor
+    pub fn new(patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        ServiceNameAccessControl {
+            patterns: patterns.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Whether messages to the service `name` are allowed
+    pub fn allows(&self, name: &str) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| glob_matches(pattern.as_bytes(), name.as_bytes()))
+    }
+}
+
+/// Whether `name` matches the glob `pattern`
+fn glob_matches(pattern: &[u8], name: &[u8]) -> bool {
+    // Where to resume after the last `*`: the pattern after it, and the
+    // part of the name it doesn't cover yet
+    let mut backtrack: Option<(usize, usize)> = None;
+    let (mut p, mut n) = (0, 0);
+    while n < name.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p + 1, n));
+                p += 1;
+            }
+            Some(c) if *c == b'?' || *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` cover one more character
+                Some((after_star, covered)) => {
+                    backtrack = Some((after_star, covered + 1));
+                    p = after_star;
+                    n = covered + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == b'*')
+}
+
+#[async_trait]
+impl AccessControl for ServiceNameAccessControl {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        match local_msg.onward_route().iter().last() {
+            Some(service) if service.is_local() => Ok(self.allows(service.address())),
+            _ => Ok(false),
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("ServiceName({})", self.patterns.join(", "))
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use crate::access_control::testing::LocalMessageBuilder;
+    use crate::compat::future::poll_once;
+    use crate::{route, Address, Route, TransportType};
+
+    use super::{glob_matches, AccessControl, ServiceNameAccessControl};
+
+    fn is_authorized(access_control: &ServiceNameAccessControl, route: Route) -> bool {
+        poll_once(async {
+            access_control
+                .is_authorized(&LocalMessageBuilder::new().onward_route(route).build())
+                .await
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        for (pattern, name, matches) in [
+            ("uppercase", "uppercase", true),
+            ("uppercase", "uppercase2", false),
+            ("forward_to_*", "forward_to_blue", true),
+            ("forward_to_*", "forward_to_", true),
+            ("forward_to_*", "forward_blue", false),
+            ("*_service", "vault_service", true),
+            ("*a*b*", "xxaxxbxx", true),
+            ("*a*b", "xxaxxbxxc", false),
+            ("echo?", "echo1", true),
+            ("echo?", "echo", false),
+            ("*", "", true),
+            ("", "", true),
+            ("", "a", false),
+        ] {
+            assert_eq!(
+                glob_matches(pattern.as_bytes(), name.as_bytes()),
+                matches,
+                "{pattern} {name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_service_name() {
+        let access_control = ServiceNameAccessControl::new(["uppercase", "forward_to_*"]);
+        // Exact match
+        assert!(is_authorized(&access_control, route!["front", "uppercase"]));
+        // Glob match
+        assert!(is_authorized(
+            &access_control,
+            route!["front", "hub", "forward_to_blue"]
+        ));
+        // Not an allowed service
+        assert!(!is_authorized(&access_control, route!["front", "echo"]));
+        // Only the last address is the service
+        assert!(!is_authorized(&access_control, route!["uppercase", "echo"]));
+        assert_eq!(
+            access_control.describe(),
+            "ServiceName(uppercase, forward_to_*)"
+        );
+    }
+
+    #[test]
+    fn test_service_name_without_service() {
+        let access_control = ServiceNameAccessControl::new(["*"]);
+        let tcp = Address::new(TransportType::new(1), "127.0.0.1:4000");
+        assert!(!is_authorized(&access_control, route!["front", tcp]));
+        assert!(!is_authorized(&access_control, route![]));
+    }
+}
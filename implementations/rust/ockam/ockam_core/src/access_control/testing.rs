@@ -0,0 +1,193 @@
+//! Helpers for testing [`AccessControl`] implementations.
+//!
+//! Enabled by the `test-utils` feature.
+
+use crate::access_control::AccessControl;
+use crate::compat::boxed::Box;
+use crate::compat::collections::VecDeque;
+use crate::compat::sync::{Arc, RwLock};
+use crate::compat::vec::Vec;
+use crate::errcode::{Kind, Origin};
+use crate::{
+    async_trait, Address, Error, LocalInfo, LocalMessage, Result, Route, TransportMessage,
+};
+use core::fmt::{self, Debug};
+
+/// Builds the [`LocalMessage`]s an AccessControl is asked about.
+#[derive(Debug, Clone)]
+pub struct LocalMessageBuilder {
+    onward_route: Route,
+    return_route: Route,
+    payload: Vec<u8>,
+    local_info: Vec<LocalInfo>,
+}
+
+impl Default for LocalMessageBuilder {
+    fn default() -> Self {
+        LocalMessageBuilder {
+            onward_route: Route::new().into(),
+            return_route: Route::new().into(),
+            payload: Vec::new(),
+            local_info: Vec::new(),
+        }
+    }
+}
+
+impl LocalMessageBuilder {
+    /// Builder for an empty message
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the address the message comes from, i.e. its return route
+    pub fn source(mut self, source: impl Into<Address>) -> Self {
+        self.return_route = source.into().into();
+        self
+    }
+
+    /// Set the address the message goes to, i.e. its onward route
+    pub fn destination(mut self, destination: impl Into<Address>) -> Self {
+        self.onward_route = destination.into().into();
+        self
+    }
+
+    /// Set the onward route of the message
+    pub fn onward_route(mut self, route: impl Into<Route>) -> Self {
+        self.onward_route = route.into();
+        self
+    }
+
+    /// Set the return route of the message
+    pub fn return_route(mut self, route: impl Into<Route>) -> Self {
+        self.return_route = route.into();
+        self
+    }
+
+    /// Set the payload of the message
+    pub fn payload(mut self, payload: impl Into<Vec<u8>>) -> Self {
+        self.payload = payload.into();
+        self
+    }
+
+    /// Add local info to the message
+    pub fn local_info(mut self, local_info: LocalInfo) -> Self {
+        self.local_info.push(local_info);
+        self
+    }
+
+    /// Build the message
+    pub fn build(self) -> LocalMessage {
+        LocalMessage::new(
+            TransportMessage::v1(self.onward_route, self.return_route, self.payload),
+            self.local_info,
+        )
+    }
+}
+
+struct Script {
+    results: VecDeque<Result<bool>>,
+    calls: usize,
+}
+
+/// An AccessControl returning a scripted sequence of results
+///
+/// Once the script is exhausted every message is denied. Clones share the
+/// script and the call count, so a clone kept by the test can inspect a
+/// mock moved into the AccessControl under test.
+#[derive(Clone)]
+pub struct MockAccessControl {
+    script: Arc<RwLock<Script>>,
+}
+
+impl MockAccessControl {
+    /// Return `decisions` in order
+    pub fn new(decisions: impl IntoIterator<Item = bool>) -> Self {
+        Self::with_results(decisions.into_iter().map(Ok))
+    }
+
+    /// Return `results`, which may contain errors, in order
+    pub fn with_results(results: impl IntoIterator<Item = Result<bool>>) -> Self {
+        MockAccessControl {
+            script: Arc::new(RwLock::new(Script {
+                results: results.into_iter().collect(),
+                calls: 0,
+            })),
+        }
+    }
+
+    /// How many times `is_authorized` has been called
+    pub fn calls(&self) -> usize {
+        self.with_script(|s| s.calls).unwrap_or_default()
+    }
+
+    fn with_script<R>(&self, f: impl FnOnce(&mut Script) -> R) -> Result<R> {
+        let mut script = self
+            .script
+            .write()
+            .map_err(|_| Error::new_without_cause(Origin::Core, Kind::Internal))?;
+        Ok(f(&mut script))
+    }
+}
+
+impl Debug for MockAccessControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockAccessControl")
+            .field("calls", &self.calls())
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl AccessControl for MockAccessControl {
+    async fn is_authorized(&self, _local_msg: &LocalMessage) -> Result<bool> {
+        self.with_script(|s| {
+            s.calls += 1;
+            s.results.pop_front().unwrap_or(Ok(false))
+        })?
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use crate::compat::boxed::Box;
+    use crate::compat::future::poll_once;
+    use crate::errcode::{Kind, Origin};
+    use crate::{route, Error, LocalInfo};
+
+    use super::{AccessControl, LocalMessageBuilder, MockAccessControl};
+
+    #[test]
+    fn test_builder() {
+        let msg = LocalMessageBuilder::new()
+            .source("alice")
+            .onward_route(route!["a", "b"])
+            .payload(vec![1, 2])
+            .local_info(LocalInfo::new("t".into(), vec![3]))
+            .build();
+        assert_eq!(msg.transport().onward_route, route!["a", "b"]);
+        assert_eq!(msg.transport().return_route, route!["alice"]);
+        assert_eq!(msg.transport().payload, vec![1, 2]);
+        assert_eq!(msg.local_info(), &[LocalInfo::new("t".into(), vec![3])]);
+    }
+
+    #[test]
+    fn test_mock() {
+        let mock = MockAccessControl::with_results([
+            Ok(true),
+            Err(Error::new_without_cause(Origin::Core, Kind::Invalid)),
+            Ok(false),
+        ]);
+        let observer = mock.clone();
+        let access_control: Box<dyn AccessControl> = Box::new(mock);
+        let msg = LocalMessageBuilder::new().build();
+        let decide = || poll_once(async { access_control.is_authorized(&msg).await });
+
+        assert_eq!(decide().ok(), Some(true));
+        assert!(decide().is_err());
+        assert_eq!(decide().ok(), Some(false));
+        // Exhausted
+        assert_eq!(decide().ok(), Some(false));
+        assert_eq!(observer.calls(), 4);
+    }
+}
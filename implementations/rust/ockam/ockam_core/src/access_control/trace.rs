@@ -0,0 +1,283 @@
+use crate::compat::string::{String, ToString};
+use crate::compat::vec::Vec;
+use crate::Result;
+use core::fmt;
+
+/// The outcome of an AccessControl in a [`DecisionTrace`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceOutcome {
+    /// The message was allowed
+    Allowed,
+    /// The message was denied
+    Denied,
+    /// The AccessControl failed with this error
+    Failed(String),
+}
+
+impl TraceOutcome {
+    /// Whether the message was allowed
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, TraceOutcome::Allowed)
+    }
+
+    /// Whether the AccessControl failed
+    pub fn is_failed(&self) -> bool {
+        matches!(self, TraceOutcome::Failed(_))
+    }
+}
+
+impl From<&Result<bool>> for TraceOutcome {
+    fn from(result: &Result<bool>) -> Self {
+        match result {
+            Ok(true) => TraceOutcome::Allowed,
+            Ok(false) => TraceOutcome::Denied,
+            Err(e) => TraceOutcome::Failed(e.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for TraceOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceOutcome::Allowed => f.write_str("allowed"),
+            TraceOutcome::Denied => f.write_str("denied"),
+            TraceOutcome::Failed(e) => write!(f, "failed: {e}"),
+        }
+    }
+}
+
+/// How an AccessControl decided about a message, with the traces of the
+/// AccessControls it is made of which were asked, see
+/// [`AccessControl::trace_decision`](crate::AccessControl::trace_decision)
+///
+/// Displayed as a tree, one AccessControl per line:
+///
+/// ```text
+/// All: denied
+///   AllowAll: allowed
+///   DenyAll: denied
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecisionTrace {
+    /// The AccessControl, e.g. `All` for an AccessControl made of others or
+    /// its [`describe`](crate::AccessControl::describe) for the others
+    pub name: String,
+    /// What the AccessControl decided
+    pub outcome: TraceOutcome,
+    /// The traces of the AccessControls it asked, in order
+    pub children: Vec<DecisionTrace>,
+}
+
+impl DecisionTrace {
+    /// Trace of an AccessControl made of others
+    pub fn new(
+        name: impl Into<String>,
+        outcome: TraceOutcome,
+        children: Vec<DecisionTrace>,
+    ) -> Self {
+        DecisionTrace {
+            name: name.into(),
+            outcome,
+            children,
+        }
+    }
+
+    /// Trace of an AccessControl which returned `result`
+    pub fn leaf(name: impl Into<String>, result: &Result<bool>) -> Self {
+        Self::new(name, result.into(), Vec::new())
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        writeln!(
+            f,
+            "{:indent$}{}: {}",
+            "",
+            self.name,
+            self.outcome,
+            indent = 2 * depth
+        )?;
+        for child in &self.children {
+            child.write(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DecisionTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use crate::access_control::testing::{LocalMessageBuilder, MockAccessControl};
+    use crate::compat::boxed::Box;
+    use crate::compat::future::poll_once;
+    use crate::compat::sync::Arc;
+    use crate::errcode::{Kind, Origin};
+    use crate::{
+        async_trait, AccessControl, AllAccessControl, AllParallelAccessControl, AllowAll,
+        AnyAccessControl, AnyParallelAccessControl, AuditMetadata, AuditSink, AuditedAccessControl,
+        BypassableAccessControl, CacheKey, CachingAccessControl, DenyAll, Error,
+        FallbackAccessControl, LocalMessage, NegativeCachingAccessControl, ReloadableAccessControl,
+        Result, RoutingAccessControl, UnanimousAccessControl,
+    };
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use core::time::Duration;
+
+    use super::{DecisionTrace, TraceOutcome};
+
+    fn msg() -> LocalMessage {
+        LocalMessageBuilder::new()
+            .source("alice")
+            .destination("api")
+            .build()
+    }
+
+    fn trace(access_control: &impl AccessControl) -> DecisionTrace {
+        poll_once(async { Ok(access_control.trace_decision(&msg()).await) }).unwrap()
+    }
+
+    fn is_authorized(access_control: &impl AccessControl) -> bool {
+        poll_once(async { access_control.is_authorized(&msg()).await }).unwrap()
+    }
+
+    /// Counts the decisions recorded
+    #[derive(Debug, Default)]
+    struct CountingSink {
+        records: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl AuditSink for CountingSink {
+        async fn record(&self, _allowed: bool, _metadata: &AuditMetadata) -> Result<()> {
+            self.records.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_trace_leaf() {
+        assert_eq!(trace(&AllowAll), DecisionTrace::leaf("AllowAll", &Ok(true)));
+        let failing = MockAccessControl::with_results([Err(Error::new_without_cause(
+            Origin::Core,
+            Kind::Invalid,
+        ))]);
+        assert!(trace(&failing).outcome.is_failed());
+    }
+
+    #[test]
+    fn test_trace_tree() {
+        let access_control = AllAccessControl::new(
+            AnyAccessControl::new(DenyAll, AllowAll),
+            UnanimousAccessControl::new().with(AllowAll).with(DenyAll),
+        );
+        assert_eq!(
+            trace(&access_control).to_string(),
+            "All: denied
+  Any: allowed
+    DenyAll: denied
+    AllowAll: allowed
+  Unanimous: denied
+    AllowAll: allowed
+    DenyAll: denied
+"
+        );
+
+        // Only the AccessControls asked are traced
+        let access_control = AllAccessControl::new(DenyAll, AllowAll);
+        assert_eq!(trace(&access_control).children.len(), 1);
+    }
+
+    #[test]
+    fn test_trace_fallback_and_routing() {
+        let failing = MockAccessControl::with_results([Err(Error::new_without_cause(
+            Origin::Core,
+            Kind::Invalid,
+        ))]);
+        let trace = trace(
+            &RoutingAccessControl::new(DenyAll)
+                .with_destination("api", FallbackAccessControl::new(failing, AllowAll)),
+        );
+        assert_eq!(trace.name, "Routing");
+        assert_eq!(trace.outcome, TraceOutcome::Allowed);
+        let fallback = &trace.children[0];
+        assert_eq!(fallback.name, "Fallback");
+        assert!(fallback.children[0].outcome.is_failed());
+        assert_eq!(fallback.children[1].outcome, TraceOutcome::Allowed);
+    }
+
+    #[test]
+    fn test_trace_wrappers() {
+        let access_control = ReloadableAccessControl::new(|| {
+            let policy: Arc<dyn AccessControl> = Arc::new(AllParallelAccessControl::new(
+                AllowAll,
+                AnyParallelAccessControl::new(DenyAll, AllowAll),
+            ));
+            Ok(policy)
+        })
+        .unwrap();
+        assert_eq!(
+            trace(&access_control).to_string(),
+            "Reloadable: allowed
+  AllParallel: allowed
+    AllowAll: allowed
+    AnyParallel: allowed
+      DenyAll: denied
+      AllowAll: allowed
+"
+        );
+
+        let inner = MockAccessControl::new([false, false]);
+        let access_control = BypassableAccessControl::new(CachingAccessControl::new(
+            inner.clone(),
+            CacheKey::destination,
+        ));
+        assert_eq!(
+            trace(&access_control).to_string(),
+            "Bypassable(off): denied\n  Caching: denied\n    MockAccessControl: denied\n"
+        );
+        // The decision made is cached
+        assert!(!is_authorized(&access_control));
+        assert_eq!(
+            trace(&access_control).to_string(),
+            "Bypassable(off): denied\n  Caching(cached): denied\n"
+        );
+        assert_eq!(inner.calls(), 2);
+
+        access_control.handle().set_bypassed(true);
+        assert_eq!(
+            trace(&access_control).to_string(),
+            "Bypassable(on): allowed\n"
+        );
+    }
+
+    #[test]
+    fn test_trace_keeps_state() {
+        // Neither decision traced is remembered, so the next ones come from
+        // the inner AccessControl
+        let access_control =
+            CachingAccessControl::new(MockAccessControl::new([true, false]), CacheKey::destination);
+        assert_eq!(trace(&access_control).outcome, TraceOutcome::Allowed);
+        assert!(!is_authorized(&access_control));
+
+        let access_control = NegativeCachingAccessControl::with_clock(
+            MockAccessControl::new([false, true]),
+            Duration::from_secs(10),
+            8,
+            || Duration::ZERO,
+        );
+        assert_eq!(trace(&access_control).outcome, TraceOutcome::Denied);
+        assert!(is_authorized(&access_control));
+
+        // Tracing lets no message through, so there is nothing to record
+        let sink = Arc::new(CountingSink::default());
+        let access_control = AuditedAccessControl::new(AllowAll, sink.clone());
+        assert_eq!(trace(&access_control).outcome, TraceOutcome::Allowed);
+        assert_eq!(sink.records.load(Ordering::Relaxed), 0);
+        assert!(is_authorized(&access_control));
+        assert_eq!(sink.records.load(Ordering::Relaxed), 1);
+    }
+}
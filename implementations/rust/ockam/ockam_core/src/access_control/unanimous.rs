@@ -0,0 +1,207 @@
+use crate::access_control::{
+    AccessControl, CancellationToken, Completion, DecisionTrace, TraceOutcome,
+};
+use crate::compat::boxed::Box;
+use crate::compat::vec::Vec;
+use crate::compat::{format, string::String};
+use crate::{async_trait, LocalMessage, Result};
+
+/// Allows messages that are allowed by every one of its AccessControls,
+/// failing when any of them fails
+///
+/// The AccessControls are asked in turn, after a denial too, until one fails.
+/// The message is allowed only when all of them allow it, and denied when one
+/// denies it and none fails. When one fails, its error is returned, even if
+/// another denied the message, so that errors are never mistaken for denials.
+///
+/// This is stricter than [`AllAccessControl`](crate::AllAccessControl), which
+/// stops at the first denial: an error of the AccessControls after it goes
+/// unnoticed there, and the message is merely denied. Without AccessControls,
+/// messages are denied.
+#[derive(Debug, Default)]
+pub struct UnanimousAccessControl {
+    access_controls: Vec<Box<dyn AccessControl>>,
+}
+
+impl UnanimousAccessControl {
+    /// Constructor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also require `access_control` to allow the messages
+    pub fn with(mut self, access_control: impl AccessControl) -> Self {
+        self.access_controls.push(Box::new(access_control));
+        self
+    }
+}
+
+#[async_trait]
+impl AccessControl for UnanimousAccessControl {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        self.is_authorized_with_ctx(local_msg, None).await
+    }
+
+    async fn is_authorized_with_ctx(
+        &self,
+        local_msg: &LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<bool> {
+        let mut decision = !self.access_controls.is_empty();
+        for access_control in &self.access_controls {
+            decision &= access_control
+                .is_authorized_with_ctx(local_msg, token)
+                .await?;
+        }
+        Ok(decision)
+    }
+
+    /// Return the message as annotated by all the AccessControls in turn
+    async fn authorize(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<LocalMessage>> {
+        Ok(self
+            .authorize_tracked(local_msg, token)
+            .await?
+            .map(|(local_msg, _)| local_msg))
+    }
+
+    /// Return the message as annotated by all the AccessControls in turn,
+    /// with the completions of all of them
+    async fn authorize_tracked(
+        &self,
+        mut local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<(LocalMessage, Completion)>> {
+        if self.access_controls.is_empty() {
+            return Ok(None);
+        }
+        let mut completion = Some(Completion::new());
+        for access_control in &self.access_controls {
+            match access_control
+                .authorize_tracked(local_msg.clone(), token)
+                .await?
+            {
+                Some((annotated, other)) => {
+                    local_msg = annotated;
+                    if let Some(completion) = &mut completion {
+                        completion.merge(other)
+                    }
+                }
+                // Run the hooks of the AccessControls which allowed it
+                None => completion = None,
+            }
+        }
+        Ok(completion.map(|completion| (local_msg, completion)))
+    }
+
+    /// Trace every AccessControl
+    async fn trace_decision(&self, local_msg: &LocalMessage) -> DecisionTrace {
+        let mut children = Vec::new();
+        for access_control in &self.access_controls {
+            children.push(access_control.trace_decision(local_msg).await);
+        }
+        let outcome = match children.iter().find(|child| child.outcome.is_failed()) {
+            Some(failed) => failed.outcome.clone(),
+            None if !children.is_empty()
+                && children.iter().all(|child| child.outcome.is_allowed()) =>
+            {
+                TraceOutcome::Allowed
+            }
+            None => TraceOutcome::Denied,
+        };
+        DecisionTrace::new("Unanimous", outcome, children)
+    }
+
+    fn describe(&self) -> String {
+        let access_controls: Vec<String> = self
+            .access_controls
+            .iter()
+            .map(|access_control| access_control.describe())
+            .collect();
+        format!("Unanimous[{}]", access_controls.join(", "))
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use crate::access_control::testing::{LocalMessageBuilder, MockAccessControl};
+    use crate::compat::future::poll_once;
+    use crate::errcode::{Kind, Origin};
+    use crate::{AllAccessControl, AllowAll, DenyAll, Error, Result};
+
+    use super::{AccessControl, UnanimousAccessControl};
+
+    fn is_authorized(access_control: &impl AccessControl) -> Result<bool> {
+        poll_once(async {
+            access_control
+                .is_authorized(&LocalMessageBuilder::new().build())
+                .await
+        })
+    }
+
+    fn error() -> Result<bool> {
+        Err(Error::new_without_cause(Origin::Core, Kind::Invalid))
+    }
+
+    #[test]
+    fn test_unanimous_allow() {
+        let (first, second) = (
+            MockAccessControl::new([true]),
+            MockAccessControl::new([true]),
+        );
+        let access_control = UnanimousAccessControl::new()
+            .with(first.clone())
+            .with(second.clone());
+        assert_eq!(is_authorized(&access_control).ok(), Some(true));
+        assert_eq!((first.calls(), second.calls()), (1, 1));
+
+        assert_eq!(
+            is_authorized(&UnanimousAccessControl::new()).ok(),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_unanimous_deny() {
+        // Every AccessControl is asked, even after a denial
+        let (first, second) = (
+            MockAccessControl::new([false]),
+            MockAccessControl::new([true]),
+        );
+        let access_control = UnanimousAccessControl::new()
+            .with(first.clone())
+            .with(second.clone());
+        assert_eq!(is_authorized(&access_control).ok(), Some(false));
+        assert_eq!((first.calls(), second.calls()), (1, 1));
+    }
+
+    #[test]
+    fn test_unanimous_error() {
+        let access_control = UnanimousAccessControl::new()
+            .with(MockAccessControl::new([false]))
+            .with(MockAccessControl::with_results([error()]));
+        assert!(is_authorized(&access_control).is_err());
+
+        let access_control = UnanimousAccessControl::new()
+            .with(MockAccessControl::with_results([error()]))
+            .with(MockAccessControl::new([true]));
+        assert!(is_authorized(&access_control).is_err());
+
+        // All merely denies
+        let access_control = AllAccessControl::new(
+            MockAccessControl::new([false]),
+            MockAccessControl::with_results([error()]),
+        );
+        assert_eq!(is_authorized(&access_control).ok(), Some(false));
+    }
+
+    #[test]
+    fn test_unanimous_describe() {
+        let access_control = UnanimousAccessControl::new().with(AllowAll).with(DenyAll);
+        assert_eq!(access_control.describe(), "Unanimous[AllowAll, DenyAll]");
+    }
+}
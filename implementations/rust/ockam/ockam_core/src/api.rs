@@ -6,6 +6,8 @@ use crate::compat::vec::Vec;
 use crate::errcode::{Kind, Origin};
 use crate::Result;
 use core::fmt::{self, Display, Formatter};
+use core::num::ParseIntError;
+use core::str::FromStr;
 use minicbor::encode::{self, Encoder, Write};
 use minicbor::{Decode, Decoder, Encode};
 use tinyvec::ArrayVec;
@@ -39,7 +41,12 @@ pub struct Request<'a> {
     /// how to handle unknown methods.
     #[n(3)] method: Option<Method>,
     /// Indicator if a request body is expected after this header.
-    #[n(4)] has_body: bool
+    #[n(4)] has_body: bool,
+    /// The W3C `traceparent` of the span the request was sent from, if any.
+    ///
+    /// It lets the receiving node continue the trace of the sender. Nodes
+    /// which don't know this field ignore it.
+    #[b(5)] trace_context: Option<Cow<'a, str>>
 }
 
 /// The response header.
@@ -158,7 +165,8 @@ pub enum Status {
     #[n(409)] Conflict,
     #[n(405)] MethodNotAllowed,
     #[n(500)] InternalServerError,
-    #[n(501)] NotImplemented
+    #[n(501)] NotImplemented,
+    #[n(503)] ServiceUnavailable
 }
 
 impl Display for Status {
@@ -173,6 +181,7 @@ impl Display for Status {
             Status::MethodNotAllowed => "405 MethodNotAllowed",
             Status::InternalServerError => "500 InternalServerError",
             Status::NotImplemented => "501 NotImplemented",
+            Status::ServiceUnavailable => "503 ServiceUnavailable",
         })
     }
 }
@@ -190,12 +199,27 @@ impl From<Id> for u32 {
     }
 }
 
+impl From<u32> for Id {
+    fn from(n: u32) -> Self {
+        Id(n)
+    }
+}
+
 impl Display for Id {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{:08x}", self.0)
     }
 }
 
+/// Parses the hexadecimal form produced by `Display`.
+impl FromStr for Id {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        u32::from_str_radix(s, 16).map(Id)
+    }
+}
+
 impl<'a> Request<'a> {
     pub fn new<P: Into<Cow<'a, str>>>(method: Method, path: P, has_body: bool) -> Self {
         Request {
@@ -205,6 +229,7 @@ impl<'a> Request<'a> {
             method: Some(method),
             path: path.into(),
             has_body,
+            trace_context: None,
         }
     }
 
@@ -254,6 +279,10 @@ impl<'a> Request<'a> {
     pub fn has_body(&self) -> bool {
         self.has_body
     }
+
+    pub fn trace_context(&self) -> Option<&str> {
+        self.trace_context.as_deref()
+    }
 }
 
 impl Response {
@@ -418,6 +447,11 @@ impl<'a, T> RequestBuilder<'a, T> {
         self
     }
 
+    pub fn trace_context<C: Into<Cow<'a, str>>>(mut self, c: C) -> Self {
+        self.header.trace_context = Some(c.into());
+        self
+    }
+
     pub fn header(&self) -> &Request<'a> {
         &self.header
     }
@@ -652,3 +686,25 @@ impl<C> Encode<C> for Cbor<'_> {
             .map_err(encode::Error::write)
     }
 }
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace_context() {
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let buf = Request::get("/node")
+            .trace_context(traceparent)
+            .to_vec()
+            .unwrap();
+        let req: Request = minicbor::decode(&buf).unwrap();
+        assert_eq!(req.trace_context(), Some(traceparent));
+
+        // Requests of senders which don't know the field don't have it
+        let buf = Request::get("/node").to_vec().unwrap();
+        let req: Request = minicbor::decode(&buf).unwrap();
+        assert_eq!(req.trace_context(), None);
+    }
+}
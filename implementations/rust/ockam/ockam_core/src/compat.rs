@@ -258,6 +258,11 @@ pub mod sync {
             Ok(self.0.lock())
         }
     }
+    impl<T: Default> Default for Mutex<T> {
+        fn default() -> Self {
+            Self::new(Default::default())
+        }
+    }
     impl<T> core::ops::Deref for Mutex<T> {
         type Target = spin::Mutex<T>;
         fn deref(&self) -> &spin::Mutex<T> {
@@ -1,4 +1,4 @@
-use crate::access_control::AccessControl;
+use crate::access_control::{AccessControl, CancellationToken, Completion};
 use crate::compat::rand::{distributions::Standard, prelude::Distribution, random, Rng};
 use crate::compat::{
     string::{String, ToString},
@@ -133,6 +133,52 @@ impl Mailboxes {
         }
     }
 
+    /// Return the given [`LocalMessage`], as annotated by the access
+    /// control of the [`Mailbox`] with the given [`Address`], if it is
+    /// authorized to be posted to these `Mailboxes`
+    ///
+    /// `token` is handed to the access control, see
+    /// [`AccessControl::is_authorized_with_ctx`].
+    pub async fn authorize(
+        &self,
+        msg_addr: &Address,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<LocalMessage>> {
+        if let Some(mailbox) = self.find_mailbox(msg_addr) {
+            mailbox.access_control.authorize(local_msg, token).await
+        } else {
+            warn!(
+                "Message for {} does not match any addresses for this destination",
+                msg_addr
+            );
+            Ok(None)
+        }
+    }
+
+    /// Like [`authorize`](Self::authorize), also returning the
+    /// [`Completion`] to drop once the message is handled, see
+    /// [`AccessControl::authorize_tracked`].
+    pub async fn authorize_tracked(
+        &self,
+        msg_addr: &Address,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<(LocalMessage, Completion)>> {
+        if let Some(mailbox) = self.find_mailbox(msg_addr) {
+            mailbox
+                .access_control
+                .authorize_tracked(local_msg, token)
+                .await
+        } else {
+            warn!(
+                "Message for {} does not match any addresses for this destination",
+                msg_addr
+            );
+            Ok(None)
+        }
+    }
+
     /// Return the [`AddressSet`] represented by these `Mailboxes`
     pub fn addresses(&self) -> AddressSet {
         let mut addresses = vec![self.main_mailbox.address.clone()];
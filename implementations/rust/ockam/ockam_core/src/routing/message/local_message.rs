@@ -1,4 +1,4 @@
-use crate::{compat::string::String, compat::vec::Vec, Message, TransportMessage};
+use crate::{compat::string::String, compat::vec::Vec, Address, Message, Route, TransportMessage};
 use serde::{Deserialize, Serialize};
 
 /// Contains metadata that will only be routed locally within the
@@ -86,6 +86,51 @@ impl LocalMessage {
     }
 }
 
+/// Accessors for [`AccessControl`](crate::access_control::AccessControl)
+/// implementations
+///
+/// Both addresses are `None` when their route is empty: a message sent
+/// without a return route has no source, and one which reached the end
+/// of its onward route has no destination.
+impl LocalMessage {
+    /// The address of the sender, i.e. the next address of the return route
+    pub fn source_addr(&self) -> Option<&Address> {
+        self.transport_message.return_route.next().ok()
+    }
+
+    /// The address of the recipient, i.e. the next address of the onward
+    /// route
+    pub fn destination_addr(&self) -> Option<&Address> {
+        self.transport_message.onward_route.next().ok()
+    }
+
+    /// The onward route, starting with the address of the recipient
+    pub fn onward_route(&self) -> &Route {
+        &self.transport_message.onward_route
+    }
+
+    /// The return route, starting with the address of the sender
+    pub fn return_route(&self) -> &Route {
+        &self.transport_message.return_route
+    }
+
+    /// The encoded payload
+    pub fn payload(&self) -> &[u8] {
+        &self.transport_message.payload
+    }
+
+    /// The first [`LocalInfo`] entry with the given type identifier, or
+    /// `None` when no worker of the node added one
+    ///
+    /// The crates adding local info decode it, e.g. `ockam_identity` for the
+    /// identity a message was received from over a secure channel.
+    pub fn find_local_info(&self, type_identifier: &str) -> Option<&LocalInfo> {
+        self.local_info
+            .iter()
+            .find(|x| x.type_identifier() == type_identifier)
+    }
+}
+
 impl LocalMessage {
     /// Append a new [`LocalInfo`] entry.
     pub fn append_local_info(&mut self, local_info: LocalInfo) {
@@ -115,3 +160,34 @@ impl LocalMessage {
         }
     }
 }
+
+#[cfg(feature = "alloc")]
+#[cfg(test)]
+mod tests {
+    use super::{LocalInfo, LocalMessage};
+    use crate::{route, Address, TransportMessage};
+
+    #[test]
+    fn accessors() {
+        let transport = TransportMessage::v1(route!["a", "b"], route!["alice"], vec![1, 2]);
+        let local_info = vec![
+            LocalInfo::new("t".into(), vec![3]),
+            LocalInfo::new("t".into(), vec![4]),
+        ];
+        let msg = LocalMessage::new(transport, local_info);
+        assert_eq!(msg.source_addr(), Some(&Address::from("alice")));
+        assert_eq!(msg.destination_addr(), Some(&Address::from("a")));
+        assert_eq!(msg.onward_route(), &route!["a", "b"]);
+        assert_eq!(msg.return_route(), &route!["alice"]);
+        assert_eq!(msg.payload(), &[1, 2]);
+        assert_eq!(
+            msg.find_local_info("t").map(LocalInfo::data),
+            Some(&[3][..])
+        );
+        assert_eq!(msg.find_local_info("u"), None);
+
+        let msg = LocalMessage::new(TransportMessage::v1(route![], route![], vec![]), vec![]);
+        assert_eq!(msg.source_addr(), None);
+        assert_eq!(msg.destination_addr(), None);
+    }
+}
@@ -9,7 +9,7 @@ use zeroize::Zeroize;
 /// This zero-sized type is meant to help catching type errors in cases where
 /// CBOR items structurally match various nominal types. It will end up as an
 /// unsigned integer in CBOR and decoding checks that the value is expected.
-#[derive(Clone, Copy, Default, PartialEq, Eq, Zeroize)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Zeroize)]
 pub struct TypeTag<const N: usize>;
 
 // Custom `Debug` impl to include the tag number.
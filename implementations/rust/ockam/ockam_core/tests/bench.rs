@@ -0,0 +1,150 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+use ockam_core::{
+    async_trait, AccessControl, AllAccessControl, AllParallelAccessControl, AnyAccessControl,
+    AnyParallelAccessControl, LocalMessage, Result, Route, TransportMessage,
+};
+
+const DELAY: Duration = Duration::from_millis(10);
+
+#[test]
+fn benchmark() {
+    let slow = |allow| Slow {
+        allow,
+        delay: DELAY,
+    };
+    let quick = |allow| Slow {
+        allow,
+        delay: DELAY / 10,
+    };
+
+    bench(
+        "all/both-allow: ",
+        AllAccessControl::new(slow(true), slow(true)),
+    );
+    bench(
+        "all-parallel/both-allow: ",
+        AllParallelAccessControl::new(slow(true), slow(true)),
+    );
+    bench(
+        "all/second-denies: ",
+        AllAccessControl::new(slow(true), quick(false)),
+    );
+    bench(
+        "all-parallel/second-denies: ",
+        AllParallelAccessControl::new(slow(true), quick(false)),
+    );
+
+    bench(
+        "any/second-allows: ",
+        AnyAccessControl::new(slow(false), slow(true)),
+    );
+    bench(
+        "any-parallel/second-allows: ",
+        AnyParallelAccessControl::new(slow(false), slow(true)),
+    );
+    bench(
+        "any/first-allows: ",
+        AnyAccessControl::new(quick(true), slow(false)),
+    );
+    bench(
+        "any-parallel/first-allows: ",
+        AnyParallelAccessControl::new(quick(true), slow(false)),
+    );
+}
+
+fn bench(label: &str, access_control: impl AccessControl) {
+    const ROUNDS: u32 = 20;
+    let local_msg = LocalMessage::new(
+        TransportMessage::v1(Route::new(), Route::new(), Vec::new()),
+        Vec::new(),
+    );
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        block_on(access_control.is_authorized(&local_msg)).unwrap();
+    }
+    eprintln!("{label} {:0.2?}", start.elapsed() / ROUNDS)
+}
+
+/// Decides after `delay`, like an access control asking another node
+#[derive(Debug)]
+struct Slow {
+    allow: bool,
+    delay: Duration,
+}
+
+#[async_trait]
+impl AccessControl for Slow {
+    async fn is_authorized(&self, _local_msg: &LocalMessage) -> Result<bool> {
+        Sleep::new(self.delay).await;
+        Ok(self.allow)
+    }
+}
+
+/// Completes once a thread slept for its duration
+struct Sleep {
+    duration: Duration,
+    state: Option<Arc<(AtomicBool, Mutex<Waker>)>>,
+}
+
+impl Sleep {
+    fn new(duration: Duration) -> Self {
+        Sleep {
+            duration,
+            state: None,
+        }
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let duration = self.duration;
+        let state = self.state.get_or_insert_with(|| {
+            let state = Arc::new((AtomicBool::new(false), Mutex::new(cx.waker().clone())));
+            let timer = state.clone();
+            thread::spawn(move || {
+                thread::sleep(duration);
+                timer.0.store(true, Ordering::Release);
+                timer.1.lock().unwrap().wake_by_ref();
+            });
+            state
+        });
+        if state.0.load(Ordering::Acquire) {
+            return Poll::Ready(());
+        }
+        *state.1.lock().unwrap() = cx.waker().clone();
+        if state.0.load(Ordering::Acquire) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark()
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
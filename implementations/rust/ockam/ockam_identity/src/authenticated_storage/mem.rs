@@ -1,4 +1,5 @@
 use super::AuthenticatedStorage;
+use core::fmt;
 use ockam_core::async_trait;
 use ockam_core::compat::{
     boxed::Box,
@@ -17,6 +18,12 @@ pub struct InMemoryStorage {
     map: Arc<RwLock<BTreeMap<String, Attributes>>>,
 }
 
+impl fmt::Debug for InMemoryStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "InMemoryStorage")
+    }
+}
+
 impl InMemoryStorage {
     /// Constructor
     pub fn new() -> Self {
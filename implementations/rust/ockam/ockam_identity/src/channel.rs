@@ -17,6 +17,7 @@ use crate::{Identity, IdentityVault};
 use core::time::Duration;
 use ockam_core::compat::sync::Arc;
 use ockam_core::{Address, AsyncTryClone, Result, Route};
+use ockam_node::WorkerBuilder;
 
 impl<V: IdentityVault> Identity<V> {
     pub async fn create_secure_channel_listener(
@@ -28,7 +29,9 @@ impl<V: IdentityVault> Identity<V> {
         let identity_clone = self.async_try_clone().await?;
         let storage_clone = storage.async_try_clone().await?;
         let listener = IdentityChannelListener::new(trust_policy, identity_clone, storage_clone);
-        self.ctx.start_worker(address.into(), listener).await?;
+        WorkerBuilder::without_access_control(address.into(), listener)
+            .start(&self.ctx)
+            .await?;
         Ok(())
     }
 
@@ -133,6 +136,8 @@ mod test {
 
         let local_info = IdentitySecureChannelLocalInfo::find_info(msg.local_message())?;
         assert_eq!(local_info.their_identity_id(), bob.identifier());
+        let local_info = msg.local_message().identity_info().unwrap();
+        assert_eq!(local_info.their_identity_id(), bob.identifier());
 
         assert_eq!("Hello, Alice!", msg.body());
 
@@ -1,2 +1,7 @@
 mod identity_access_control;
 pub use identity_access_control::*;
+
+#[cfg(feature = "regex")]
+mod regex_identity_access_control;
+#[cfg(feature = "regex")]
+pub use regex_identity_access_control::*;
@@ -1,6 +1,7 @@
 use crate::{IdentityIdentifier, IdentitySecureChannelLocalInfo};
 use ockam_core::access_control::AccessControl;
 use ockam_core::compat::vec::Vec;
+use ockam_core::compat::{format, string::String};
 use ockam_core::{async_trait, compat::boxed::Box};
 use ockam_core::{LocalMessage, Result};
 
@@ -59,4 +60,11 @@ impl AccessControl for IdentityIdAccessControl {
             Ok(false)
         }
     }
+
+    fn describe(&self) -> String {
+        format!(
+            "IdentityIdAccessControl({} identities)",
+            self.identity_ids.len()
+        )
+    }
 }
@@ -0,0 +1,94 @@
+use crate::IdentitySecureChannelLocalInfo;
+use ockam_core::access_control::AccessControl;
+use ockam_core::compat::string::String;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{async_trait, compat::boxed::Box};
+use ockam_core::{Error, LocalMessage, Result};
+use regex::Regex;
+
+/// Allows the messages received over a secure channel from an identity
+/// whose identifier, e.g. `P6c20e814b565...`, matches a regular expression
+///
+/// The whole identifier must match, as if the pattern started with `^` and
+/// ended with `$`. Messages which didn't come over a secure channel are
+/// denied.
+#[derive(Clone, Debug)]
+pub struct RegexIdentityAccessControl {
+    pattern: String,
+    regex: Regex,
+}
+
+impl RegexIdentityAccessControl {
+    /// Constructor, failing when `pattern` is not a valid regular expression
+    pub fn new(pattern: &str) -> Result<Self> {
+        let regex = Regex::new(&format!("^(?:{pattern})$"))
+            .map_err(|e| Error::new(Origin::Identity, Kind::Invalid, e))?;
+        Ok(Self {
+            pattern: pattern.into(),
+            regex,
+        })
+    }
+
+    /// The pattern given to [`new`](Self::new)
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+}
+
+#[async_trait]
+impl AccessControl for RegexIdentityAccessControl {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        match IdentitySecureChannelLocalInfo::find_info(local_msg) {
+            Ok(info) => Ok(self.regex.is_match(&info.their_identity_id().to_string())),
+            Err(_) => Ok(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IdentityIdentifier;
+    use ockam_core::compat::vec::Vec;
+    use ockam_core::{route, TransportMessage};
+
+    fn message(sender: Option<&str>) -> LocalMessage {
+        let transport = TransportMessage::v1(route!["a"], route![], Vec::new());
+        let local_info = match sender {
+            Some(id) => {
+                let id = IdentityIdentifier::try_from(id).unwrap();
+                IdentitySecureChannelLocalInfo::mark(Vec::new(), id).unwrap()
+            }
+            None => Vec::new(),
+        };
+        LocalMessage::new(transport, local_info)
+    }
+
+    #[tokio::test]
+    async fn matching_identity() {
+        let ac = RegexIdentityAccessControl::new("Pab[0-9a-f]+").unwrap();
+        assert!(ac.is_authorized(&message(Some("Pab12cd"))).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn non_matching_identity() {
+        let ac = RegexIdentityAccessControl::new("Pab[0-9a-f]+").unwrap();
+        assert!(!ac.is_authorized(&message(Some("Pcd12ab"))).await.unwrap());
+        // Only whole identifiers match
+        let ac = RegexIdentityAccessControl::new("ab").unwrap();
+        assert!(!ac.is_authorized(&message(Some("Pab12cd"))).await.unwrap());
+        let ac = RegexIdentityAccessControl::new("Pab|x").unwrap();
+        assert!(!ac.is_authorized(&message(Some("Pab12cd"))).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn no_identity() {
+        let ac = RegexIdentityAccessControl::new(".*").unwrap();
+        assert!(!ac.is_authorized(&message(None)).await.unwrap());
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        assert!(RegexIdentityAccessControl::new("P(ab").is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::authenticated_storage::AuthenticatedStorage;
+use crate::credential::{AttributesStorageUtils, CredentialLocalInfo};
 use crate::{
     EncryptorWorker, Identity, IdentityChannelMessage, IdentityError, IdentityIdentifier,
     IdentitySecureChannelLocalInfo, IdentityVault, PublicIdentity, SecureChannelTrustInfo,
@@ -21,7 +22,7 @@ use ockam_core::{
 };
 use ockam_key_exchange_core::NewKeyExchanger;
 use ockam_key_exchange_xx::XXNewKeyExchanger;
-use ockam_node::Context;
+use ockam_node::{Context, WorkerBuilder};
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 
@@ -120,7 +121,9 @@ impl<V: IdentityVault, S: AuthenticatedStorage> DecryptorWorker<V, S> {
             state: Some(state),
         };
 
-        ctx.start_worker(self_address.clone(), worker).await?;
+        WorkerBuilder::without_access_control(self_address.clone(), worker)
+            .start(ctx)
+            .await?;
 
         debug!(
             "Starting IdentitySecureChannel Initiator at remote: {}",
@@ -172,10 +175,11 @@ impl<V: IdentityVault, S: AuthenticatedStorage> DecryptorWorker<V, S> {
             state: Some(state),
         };
 
-        ctx.start_worker(
+        WorkerBuilder::without_access_control(
             vec![self_address.clone(), kex_callback_address.clone()],
             worker,
         )
+        .start(ctx)
         .await?;
 
         debug!(
@@ -194,8 +198,12 @@ impl<V: IdentityVault, S: AuthenticatedStorage> DecryptorWorker<V, S> {
             SecureChannelDecryptor::new_responder(responder, Some(kex_callback_address), vault)
                 .await?;
 
-        ctx.start_worker(vec![regular_responder_address.clone()], regular_decryptor)
-            .await?;
+        WorkerBuilder::without_access_control(
+            vec![regular_responder_address.clone()],
+            regular_decryptor,
+        )
+        .start(ctx)
+        .await?;
 
         onward_route.step()?;
         onward_route.modify().prepend(regular_responder_address);
@@ -335,7 +343,8 @@ impl<V: IdentityVault, S: AuthenticatedStorage> DecryptorWorker<V, S> {
                 state.channel.address(),
             );
 
-            ctx.start_worker(encryptor_address.clone(), encryptor)
+            WorkerBuilder::without_access_control(encryptor_address.clone(), encryptor)
+                .start(ctx)
                 .await?;
 
             info!(
@@ -433,7 +442,8 @@ impl<V: IdentityVault, S: AuthenticatedStorage> DecryptorWorker<V, S> {
                 state.local_secure_channel_address,
             );
 
-            ctx.start_worker(encryptor_address.clone(), encryptor)
+            WorkerBuilder::without_access_control(encryptor_address.clone(), encryptor)
+                .start(ctx)
                 .await?;
 
             info!(
@@ -500,6 +510,16 @@ impl<V: IdentityVault, S: AuthenticatedStorage> DecryptorWorker<V, S> {
         let local_info =
             IdentitySecureChannelLocalInfo::mark(local_info, state.their_identity_id.clone())?;
 
+        // Mark it with the expiry of the credential their identity presented,
+        // if any, for CredentialValidityAccessControl
+        let local_info =
+            match AttributesStorageUtils::get_expiry(&state.their_identity_id, &self.storage)
+                .await?
+            {
+                Some(expires) => CredentialLocalInfo::mark(local_info, expires)?,
+                None => CredentialLocalInfo::strip(local_info),
+            };
+
         let msg = LocalMessage::new(transport_msg, local_info);
 
         match ctx.forward(msg).await {
@@ -55,6 +55,22 @@ impl IdentitySecureChannelLocalInfo {
     }
 }
 
+/// Gives [`AccessControl`](ockam_core::AccessControl) implementations the
+/// identity a [`LocalMessage`] was received from
+pub trait LocalMessageIdentity {
+    /// The local info of the secure channel the message was decrypted by,
+    /// or `None` when it wasn't received over a secure channel, e.g. sent
+    /// by a worker of the node or received in plain text over a transport
+    fn identity_info(&self) -> Option<IdentitySecureChannelLocalInfo>;
+}
+
+impl LocalMessageIdentity for LocalMessage {
+    fn identity_info(&self) -> Option<IdentitySecureChannelLocalInfo> {
+        let local_info = self.find_local_info(IDENTITY_SECURE_CHANNEL_IDENTIFIER)?;
+        IdentitySecureChannelLocalInfo::from_local_info(local_info).ok()
+    }
+}
+
 impl IdentitySecureChannelLocalInfo {
     /// Mark a `LocalInfo` vector with `IdentitySecureChannLocalInfo`
     /// replacing any pre-existing entries
@@ -1,12 +1,14 @@
 #![allow(missing_docs)]
 
 mod identity;
+mod local_info;
 mod public_identity;
 mod storage_utils;
 mod worker;
 
 pub mod access_control;
 
+pub use local_info::*;
 pub use storage_utils::*;
 
 use crate::IdentityIdentifier;
@@ -298,6 +300,12 @@ impl From<Timestamp> for u64 {
     }
 }
 
+impl From<u64> for Timestamp {
+    fn from(t: u64) -> Self {
+        Timestamp(t)
+    }
+}
+
 /// A schema identifier allows discriminate sets of credential attributes.
 #[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cbor(transparent)]
@@ -1,2 +1,8 @@
 mod credential_access_control;
+mod credential_validity_access_control;
+mod handshake_gate_access_control;
+mod pinned_peer_access_control;
 pub use credential_access_control::*;
+pub use credential_validity_access_control::*;
+pub use handshake_gate_access_control::*;
+pub use pinned_peer_access_control::*;
@@ -7,6 +7,13 @@ use ockam_core::compat::{string::String, vec::Vec};
 use ockam_core::{async_trait, compat::boxed::Box};
 use ockam_core::{LocalMessage, Result};
 
+/// Allows the messages whose sender has all the required attributes.
+///
+/// The sender is the identity at the other end of the secure channel the
+/// message arrived over, and its attributes are those stored for it in the
+/// given storage, e.g. when it presented a credential. Messages which didn't
+/// arrive over a secure channel, and senders without attributes, are denied.
+#[doc(alias = "AttributeAccessControl")]
 #[derive(Clone)]
 pub struct CredentialAccessControl<S: AuthenticatedStorage> {
     required_attributes: Vec<(String, Vec<u8>)>,
@@ -63,3 +70,84 @@ impl<S: AuthenticatedStorage> AccessControl for CredentialAccessControl<S> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authenticated_storage::mem::InMemoryStorage;
+    use crate::credential::{Attributes, AttributesEntry, Timestamp};
+    use crate::IdentityIdentifier;
+    use ockam_core::compat::string::ToString;
+    use ockam_core::{route, TransportMessage};
+
+    async fn storage(id: &IdentityIdentifier, attrs: &[(&str, &str)]) -> InMemoryStorage {
+        let storage = InMemoryStorage::new();
+        let mut attributes = Attributes::new();
+        for (k, v) in attrs {
+            attributes.put(k, v.as_bytes());
+        }
+        let expires = Timestamp::from(u64::from(Timestamp::now().unwrap()) + 60);
+        AttributesStorageUtils::put_attributes(
+            id,
+            AttributesEntry::new(attributes, expires),
+            &storage,
+        )
+        .await
+        .unwrap();
+        storage
+    }
+
+    fn message(sender: Option<&IdentityIdentifier>) -> LocalMessage {
+        let transport = TransportMessage::v1(route!["a"], route![], Vec::new());
+        let local_info = match sender {
+            Some(id) => IdentitySecureChannelLocalInfo::mark(Vec::new(), id.clone()).unwrap(),
+            None => Vec::new(),
+        };
+        LocalMessage::new(transport, local_info)
+    }
+
+    fn required(attrs: &[(&str, &str)]) -> Vec<(String, Vec<u8>)> {
+        attrs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.as_bytes().to_vec()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn matching_attributes() {
+        let id = IdentityIdentifier::random();
+        let storage = storage(&id, &[("role", "member"), ("team", "blue")]).await;
+        let required = required(&[("role", "member"), ("team", "blue")]);
+        let ac = CredentialAccessControl::new(&required, storage);
+        assert!(ac.is_authorized(&message(Some(&id))).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn partially_matching_attributes() {
+        let id = IdentityIdentifier::random();
+        let storage = storage(&id, &[("role", "member"), ("team", "red")]).await;
+        let required = required(&[("role", "member"), ("team", "blue")]);
+        let ac = CredentialAccessControl::new(&required, storage);
+        assert!(!ac.is_authorized(&message(Some(&id))).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn missing_attribute() {
+        let id = IdentityIdentifier::random();
+        let storage = storage(&id, &[("role", "member")]).await;
+        let required = required(&[("role", "member"), ("team", "blue")]);
+        let ac = CredentialAccessControl::new(&required, storage);
+        assert!(!ac.is_authorized(&message(Some(&id))).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn unknown_identity() {
+        let id = IdentityIdentifier::random();
+        let storage = storage(&id, &[("role", "member")]).await;
+        let required = required(&[("role", "member")]);
+        let ac = CredentialAccessControl::new(&required, storage);
+        let unknown = IdentityIdentifier::random();
+        assert!(!ac.is_authorized(&message(Some(&unknown))).await.unwrap());
+        assert!(!ac.is_authorized(&message(None)).await.unwrap());
+    }
+}
@@ -0,0 +1,35 @@
+use crate::credential::{CredentialLocalInfo, Timestamp};
+use ockam_core::access_control::AccessControl;
+use ockam_core::{async_trait, compat::boxed::Box};
+use ockam_core::{LocalMessage, Result};
+
+/// Allows messages only while the credential presented with them is valid
+///
+/// The expiry of the credential is read from the [`CredentialLocalInfo`] of
+/// the message, which the secure channel attaches once the sender presented
+/// a credential over it, and compared to [`Timestamp::now`]. Messages without that
+/// local info, with an expired credential, or received when the current
+/// time is unknown are denied.
+#[derive(Debug, Clone, Default)]
+pub struct CredentialValidityAccessControl;
+
+impl CredentialValidityAccessControl {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl AccessControl for CredentialValidityAccessControl {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        let info = match CredentialLocalInfo::find_info(local_msg) {
+            Ok(info) => info,
+            Err(_) => return Ok(false), // No credential presented
+        };
+
+        match Timestamp::now() {
+            Some(now) => Ok(now < info.expires()),
+            None => Ok(false),
+        }
+    }
+}
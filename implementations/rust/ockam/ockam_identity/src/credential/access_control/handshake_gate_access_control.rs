@@ -0,0 +1,318 @@
+use crate::credential::CredentialLocalInfo;
+use core::fmt::{Debug, Formatter};
+use core::time::Duration;
+use ockam_core::access_control::{AccessControl, DecisionTrace};
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::compat::{format, string::String};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{async_trait, compat::boxed::Box};
+use ockam_core::{Address, Error, LocalMessage, Result};
+
+/// Allows the data messages of a sender only once it presented a credential
+///
+/// The sender is the next address of the return route, e.g. the secure
+/// channel the messages arrived over. A message carrying a valid credential,
+/// i.e. a [`CredentialLocalInfo`] which hasn't expired, is the handshake: it
+/// is allowed and opens the gate of its sender. The other messages of that
+/// sender are then allowed while its credential is valid and it isn't idle
+/// for `ttl` or longer; after that, a new credential has to be presented.
+/// The secure channel attaches the [`CredentialLocalInfo`] to the messages of
+/// a sender once it presented a credential over it.
+/// Messages without a credential are denied before the handshake, and so are
+/// those without a sender.
+///
+/// At most `capacity` open gates are kept: when there are more, the gates
+/// of the senders idle the longest are closed. Clones share the gates, so
+/// that a worker started again with a clone doesn't need new handshakes.
+#[derive(Clone)]
+pub struct HandshakeGateAccessControl {
+    ttl: Duration,
+    capacity: usize,
+    clock: Arc<dyn Fn() -> Duration + Send + Sync>,
+    gates: Arc<Mutex<BTreeMap<Address, Gate>>>,
+}
+
+/// Until when the messages of a sender are allowed
+#[derive(Debug, Clone, Copy)]
+struct Gate {
+    /// When the sender was last seen, plus the ttl
+    idle_until: Duration,
+    /// When its credential expires
+    credential_expires: Duration,
+}
+
+impl Gate {
+    fn is_open(&self, now: Duration) -> bool {
+        now < self.idle_until && now < self.credential_expires
+    }
+}
+
+impl HandshakeGateAccessControl {
+    /// Constructor, using the system time
+    #[cfg(feature = "std")]
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self::with_clock(ttl, capacity, || {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+        })
+    }
+
+    /// Constructor, using `clock` for the time since the Unix epoch, to which
+    /// the expiry of credentials is compared
+    pub fn with_clock(
+        ttl: Duration,
+        capacity: usize,
+        clock: impl Fn() -> Duration + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            ttl,
+            capacity,
+            clock: Arc::new(clock),
+            gates: Default::default(),
+        }
+    }
+
+    /// Open the gate of `sender` until its credential expires
+    fn open(
+        &self,
+        gates: &mut BTreeMap<Address, Gate>,
+        sender: Address,
+        gate: Gate,
+        now: Duration,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        if gates.len() >= self.capacity && !gates.contains_key(&sender) {
+            gates.retain(|_, gate| gate.is_open(now));
+            if gates.len() >= self.capacity {
+                let idlest = gates
+                    .iter()
+                    .min_by_key(|(_, gate)| gate.idle_until)
+                    .map(|(sender, _)| sender.clone());
+                if let Some(idlest) = idlest {
+                    gates.remove(&idlest);
+                }
+            }
+        }
+        gates.insert(sender, gate);
+    }
+}
+
+impl HandshakeGateAccessControl {
+    /// Whether `local_msg` is allowed at `now`, without opening or closing
+    /// gates
+    fn admits(
+        &self,
+        gates: &BTreeMap<Address, Gate>,
+        local_msg: &LocalMessage,
+        now: Duration,
+    ) -> bool {
+        if let Ok(info) = CredentialLocalInfo::find_info(local_msg) {
+            return now < Duration::from_secs(info.expires().into());
+        }
+        match local_msg.source_addr() {
+            Some(sender) => gates.get(sender).map_or(false, |gate| gate.is_open(now)),
+            None => false,
+        }
+    }
+}
+
+impl Debug for HandshakeGateAccessControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Handshake Gate Access Control")
+            .field("ttl", &self.ttl)
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl AccessControl for HandshakeGateAccessControl {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        let now = (self.clock)();
+        let sender = local_msg.source_addr().cloned();
+        let mut gates = self
+            .gates
+            .lock()
+            .map_err(|_| Error::new_without_cause(Origin::Identity, Kind::Internal))?;
+
+        if let Ok(info) = CredentialLocalInfo::find_info(local_msg) {
+            let credential_expires = Duration::from_secs(info.expires().into());
+            if now >= credential_expires {
+                return Ok(false); // Expired credential
+            }
+            if let Some(sender) = sender {
+                let gate = Gate {
+                    idle_until: now + self.ttl,
+                    credential_expires,
+                };
+                self.open(&mut gates, sender, gate, now);
+            }
+            return Ok(true);
+        }
+
+        let sender = match sender {
+            Some(sender) => sender,
+            None => return Ok(false), // No sender to have made the handshake
+        };
+        match gates.get_mut(&sender) {
+            Some(gate) if gate.is_open(now) => {
+                gate.idle_until = now + self.ttl;
+                Ok(true)
+            }
+            Some(_) => {
+                gates.remove(&sender);
+                Ok(false)
+            }
+            None => Ok(false), // No handshake yet
+        }
+    }
+
+    /// Trace the decision without opening, keeping open or closing gates
+    async fn trace_decision(&self, local_msg: &LocalMessage) -> DecisionTrace {
+        let now = (self.clock)();
+        let decision = self
+            .gates
+            .lock()
+            .map(|gates| self.admits(&gates, local_msg, now))
+            .map_err(|_| Error::new_without_cause(Origin::Identity, Kind::Internal));
+        DecisionTrace::leaf(self.describe(), &decision)
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "HandshakeGate(ttl {}s, {} senders)",
+            self.ttl.as_secs(),
+            self.capacity
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credential::Timestamp;
+    use core::sync::atomic::{AtomicU64, Ordering};
+    use ockam_core::compat::sync::Arc;
+    use ockam_core::compat::vec::Vec;
+    use ockam_core::{route, TransportMessage};
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    /// A gate whose clock, in seconds, is the returned handle
+    fn gate(capacity: usize) -> (HandshakeGateAccessControl, Arc<AtomicU64>) {
+        let now = Arc::new(AtomicU64::new(1000));
+        let clock = now.clone();
+        let ac = HandshakeGateAccessControl::with_clock(TTL, capacity, move || {
+            Duration::from_secs(clock.load(Ordering::SeqCst))
+        });
+        (ac, now)
+    }
+
+    /// A message of `sender`, with a credential expiring at `expires`
+    fn message(sender: &str, expires: Option<u64>) -> LocalMessage {
+        let transport = TransportMessage::v1(route!["a"], route![sender], Vec::new());
+        let local_info = match expires {
+            Some(expires) => {
+                CredentialLocalInfo::mark(Vec::new(), Timestamp::from(expires)).unwrap()
+            }
+            None => Vec::new(),
+        };
+        LocalMessage::new(transport, local_info)
+    }
+
+    #[tokio::test]
+    async fn deny_before_handshake() {
+        let (ac, _) = gate(10);
+        assert!(!ac.is_authorized(&message("alice", None)).await.unwrap());
+        // An expired credential is no handshake
+        assert!(!ac
+            .is_authorized(&message("alice", Some(1000)))
+            .await
+            .unwrap());
+        assert!(!ac.is_authorized(&message("alice", None)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn allow_after_handshake() {
+        let (ac, now) = gate(10);
+        assert!(ac
+            .is_authorized(&message("alice", Some(5000)))
+            .await
+            .unwrap());
+        assert!(ac.is_authorized(&message("alice", None)).await.unwrap());
+        // Every message keeps the gate open for another ttl
+        now.store(1050, Ordering::SeqCst);
+        assert!(ac.is_authorized(&message("alice", None)).await.unwrap());
+        now.store(1100, Ordering::SeqCst);
+        assert!(ac.is_authorized(&message("alice", None)).await.unwrap());
+        // Only for the sender which made the handshake
+        assert!(!ac.is_authorized(&message("bob", None)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn handshake_expiry() {
+        let (ac, now) = gate(10);
+        assert!(ac
+            .is_authorized(&message("alice", Some(5000)))
+            .await
+            .unwrap());
+        // Idle for the ttl
+        now.store(1060, Ordering::SeqCst);
+        assert!(!ac.is_authorized(&message("alice", None)).await.unwrap());
+
+        // Until the credential expires
+        assert!(ac
+            .is_authorized(&message("alice", Some(1100)))
+            .await
+            .unwrap());
+        now.store(1100, Ordering::SeqCst);
+        assert!(!ac.is_authorized(&message("alice", None)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn capacity() {
+        let (ac, now) = gate(2);
+        assert!(ac
+            .is_authorized(&message("alice", Some(5000)))
+            .await
+            .unwrap());
+        now.store(1001, Ordering::SeqCst);
+        assert!(ac.is_authorized(&message("bob", Some(5000))).await.unwrap());
+        now.store(1002, Ordering::SeqCst);
+        assert!(ac
+            .is_authorized(&message("carol", Some(5000)))
+            .await
+            .unwrap());
+        // The gate of the sender idle the longest was closed
+        assert!(!ac.is_authorized(&message("alice", None)).await.unwrap());
+        assert!(ac.is_authorized(&message("bob", None)).await.unwrap());
+        assert!(ac.is_authorized(&message("carol", None)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn trace_keeps_gates() {
+        let (ac, now) = gate(10);
+        let trace = ac.trace_decision(&message("alice", Some(5000))).await;
+        assert!(trace.outcome.is_allowed());
+        // Tracing the handshake didn't open the gate...
+        assert!(!ac.is_authorized(&message("alice", None)).await.unwrap());
+
+        // ...nor does tracing keep it open
+        assert!(ac
+            .is_authorized(&message("alice", Some(5000)))
+            .await
+            .unwrap());
+        now.store(1050, Ordering::SeqCst);
+        assert!(ac
+            .trace_decision(&message("alice", None))
+            .await
+            .outcome
+            .is_allowed());
+        now.store(1060, Ordering::SeqCst);
+        assert!(!ac.is_authorized(&message("alice", None)).await.unwrap());
+    }
+}
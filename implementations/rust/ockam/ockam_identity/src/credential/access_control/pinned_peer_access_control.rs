@@ -0,0 +1,121 @@
+use crate::authenticated_storage::AuthenticatedStorage;
+use crate::credential::AttributesStorageUtils;
+use crate::{IdentityIdentifier, IdentitySecureChannelLocalInfo};
+use core::fmt::{Debug, Formatter};
+use ockam_core::access_control::AccessControl;
+use ockam_core::{async_trait, compat::boxed::Box};
+use ockam_core::{LocalMessage, Result};
+
+/// Allows only the messages of one pinned peer, once it presented a credential.
+///
+/// The peer is the identity at the other end of the secure channel the
+/// message arrived over. It must be the expected identity, and have
+/// attributes stored for it in the given storage, as after a mutual credential
+/// exchange. A peer with a valid credential but another identity is denied, and
+/// so are messages which didn't arrive over a secure channel.
+#[derive(Clone)]
+pub struct PinnedPeerAccessControl<S: AuthenticatedStorage> {
+    expected: IdentityIdentifier,
+    storage: S,
+}
+
+impl<S: AuthenticatedStorage> PinnedPeerAccessControl<S> {
+    pub fn new(expected: IdentityIdentifier, storage: S) -> Self {
+        Self { expected, storage }
+    }
+}
+
+impl<S: AuthenticatedStorage> Debug for PinnedPeerAccessControl<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Pinned Peer Access Control")
+            .field("Expected identity", &self.expected)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<S: AuthenticatedStorage> AccessControl for PinnedPeerAccessControl<S> {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        let their_id = match IdentitySecureChannelLocalInfo::find_info(local_msg) {
+            Ok(info) => info.their_identity_id().clone(),
+            Err(_) => return Ok(false), // Not over a secure channel
+        };
+
+        if !bool::from(self.expected.ct_eq(&their_id)) {
+            return Ok(false); // Another peer
+        }
+
+        // The credential of the peer was checked when its attributes were stored
+        Ok(
+            AttributesStorageUtils::get_attributes(&their_id, &self.storage)
+                .await?
+                .is_some(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authenticated_storage::mem::InMemoryStorage;
+    use crate::credential::{Attributes, AttributesEntry, Timestamp};
+    use ockam_core::compat::vec::Vec;
+    use ockam_core::{route, TransportMessage};
+
+    async fn credential(id: &IdentityIdentifier, storage: &InMemoryStorage) {
+        let mut attributes = Attributes::new();
+        attributes.put("role", b"relay");
+        let expires = Timestamp::from(u64::from(Timestamp::now().unwrap()) + 60);
+        AttributesStorageUtils::put_attributes(
+            id,
+            AttributesEntry::new(attributes, expires),
+            storage,
+        )
+        .await
+        .unwrap();
+    }
+
+    fn message(sender: Option<&IdentityIdentifier>) -> LocalMessage {
+        let transport = TransportMessage::v1(route!["a"], route![], Vec::new());
+        let local_info = match sender {
+            Some(id) => IdentitySecureChannelLocalInfo::mark(Vec::new(), id.clone()).unwrap(),
+            None => Vec::new(),
+        };
+        LocalMessage::new(transport, local_info)
+    }
+
+    #[tokio::test]
+    async fn matching_peer() {
+        let id = IdentityIdentifier::random();
+        let storage = InMemoryStorage::new();
+        credential(&id, &storage).await;
+        let ac = PinnedPeerAccessControl::new(id.clone(), storage);
+        assert!(ac.is_authorized(&message(Some(&id))).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn other_peer_with_credential() {
+        let (pinned, other) = (IdentityIdentifier::random(), IdentityIdentifier::random());
+        let storage = InMemoryStorage::new();
+        credential(&pinned, &storage).await;
+        credential(&other, &storage).await;
+        let ac = PinnedPeerAccessControl::new(pinned, storage);
+        assert!(!ac.is_authorized(&message(Some(&other))).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn unauthenticated_message() {
+        let id = IdentityIdentifier::random();
+        let storage = InMemoryStorage::new();
+        credential(&id, &storage).await;
+        let ac = PinnedPeerAccessControl::new(id, storage);
+        assert!(!ac.is_authorized(&message(None)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn pinned_peer_without_credential() {
+        let id = IdentityIdentifier::random();
+        let ac = PinnedPeerAccessControl::new(id.clone(), InMemoryStorage::new());
+        assert!(!ac.is_authorized(&message(Some(&id))).await.unwrap());
+    }
+}
@@ -0,0 +1,82 @@
+use crate::credential::Timestamp;
+use crate::IdentityError;
+use ockam_core::compat::vec::Vec;
+use ockam_core::{Decodable, Encodable, LocalInfo, LocalMessage, Result};
+use serde::{Deserialize, Serialize};
+
+/// Credential LocalInfo unique Identifier
+pub const CREDENTIAL_IDENTIFIER: &str = "CREDENTIAL_IDENTIFIER";
+
+/// Credential LocalInfo used for LocalMessage
+///
+/// Carries the expiry of the credential presented by the sender of a message.
+#[derive(Serialize, Deserialize)]
+pub struct CredentialLocalInfo {
+    expires: u64,
+}
+
+impl CredentialLocalInfo {
+    pub fn new(expires: Timestamp) -> Self {
+        Self {
+            expires: expires.into(),
+        }
+    }
+
+    pub fn from_local_info(value: &LocalInfo) -> Result<Self> {
+        if value.type_identifier() != CREDENTIAL_IDENTIFIER {
+            return Err(IdentityError::InvalidLocalInfoType.into());
+        }
+
+        if let Ok(info) = CredentialLocalInfo::decode(value.data()) {
+            return Ok(info);
+        }
+
+        Err(IdentityError::InvalidLocalInfoType.into())
+    }
+
+    pub fn to_local_info(&self) -> Result<LocalInfo> {
+        Ok(LocalInfo::new(CREDENTIAL_IDENTIFIER.into(), self.encode()?))
+    }
+
+    pub fn find_info(local_msg: &LocalMessage) -> Result<Self> {
+        Self::find_info_from_list(local_msg.local_info())
+    }
+
+    pub fn find_info_from_list(local_info: &[LocalInfo]) -> Result<Self> {
+        if let Some(local_info) = local_info
+            .iter()
+            .find(|x| x.type_identifier() == CREDENTIAL_IDENTIFIER)
+        {
+            Self::from_local_info(local_info)
+        } else {
+            Err(IdentityError::InvalidLocalInfoType.into())
+        }
+    }
+}
+
+impl CredentialLocalInfo {
+    /// Expiry of the presented credential
+    pub fn expires(&self) -> Timestamp {
+        self.expires.into()
+    }
+}
+
+impl CredentialLocalInfo {
+    /// Mark a `LocalInfo` vector with `CredentialLocalInfo`
+    /// replacing any pre-existing entries
+    pub fn mark(local_info: Vec<LocalInfo>, expires: Timestamp) -> Result<Vec<LocalInfo>> {
+        // strip out any pre-existing CredentialLocalInfo
+        let mut local_info = Self::strip(local_info);
+
+        // mark the vector
+        local_info.push(Self::new(expires).to_local_info()?);
+
+        Ok(local_info)
+    }
+
+    /// Remove any `CredentialLocalInfo` from a `LocalInfo` vector
+    pub fn strip(mut local_info: Vec<LocalInfo>) -> Vec<LocalInfo> {
+        local_info.retain(|x| x.type_identifier() != CREDENTIAL_IDENTIFIER);
+        local_info
+    }
+}
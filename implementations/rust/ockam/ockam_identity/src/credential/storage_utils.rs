@@ -64,6 +64,25 @@ impl AttributesStorageUtils {
         Ok(Some(attrs))
     }
 
+    /// Return the expiry of the attributes attached to that Identity, i.e. of
+    /// the credential it presented, even if they expired
+    pub async fn get_expiry(
+        identity_id: &IdentityIdentifier,
+        authenticated_storage: &impl AuthenticatedStorage,
+    ) -> Result<Option<Timestamp>> {
+        let entry = match authenticated_storage
+            .get(&identity_id.to_string(), IdentityStateConst::ATTRIBUTES_KEY)
+            .await?
+        {
+            Some(e) => e,
+            None => return Ok(None),
+        };
+
+        let entry: AttributesEntry = minicbor::decode(&entry)?;
+
+        Ok(Some(entry.expires()))
+    }
+
     pub(crate) async fn put_attributes(
         sender: &IdentityIdentifier,
         entry: AttributesEntry<'_>,
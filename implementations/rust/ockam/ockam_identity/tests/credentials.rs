@@ -1,9 +1,13 @@
 use ockam_core::compat::{boxed::Box, sync::Arc};
 use ockam_core::{async_trait, Any};
-use ockam_core::{route, Result, Routed, Worker};
+use ockam_core::{route, AccessControl, LocalMessage, Result, Routed, TransportMessage, Worker};
 use ockam_identity::authenticated_storage::mem::InMemoryStorage;
-use ockam_identity::credential::access_control::CredentialAccessControl;
-use ockam_identity::credential::{AttributesStorageUtils, Credential};
+use ockam_identity::credential::access_control::{
+    CredentialAccessControl, CredentialValidityAccessControl, HandshakeGateAccessControl,
+};
+use ockam_identity::credential::{
+    AttributesStorageUtils, Credential, CredentialLocalInfo, Timestamp,
+};
 use ockam_identity::{Identity, TrustEveryonePolicy, TrustIdentifierPolicy};
 use ockam_node::{Context, WorkerBuilder};
 use ockam_vault::Vault;
@@ -223,3 +227,170 @@ async fn access_control(ctx: &mut Context) -> Result<()> {
 
     ctx.stop().await
 }
+
+#[ockam_macros::test]
+async fn access_control_with_credential_validity_over_channel(ctx: &mut Context) -> Result<()> {
+    let vault = Vault::create();
+
+    let authority = Identity::create(ctx, &vault).await?;
+
+    let server = Identity::create(ctx, &vault).await?;
+    let server_storage = InMemoryStorage::new();
+
+    server
+        .create_secure_channel_listener("listener", TrustEveryonePolicy, &server_storage)
+        .await?;
+
+    let authorities = vec![authority.to_public().await?];
+
+    server
+        .start_credentials_exchange_worker(
+            authorities,
+            "credential_exchange",
+            false,
+            server_storage.clone(),
+        )
+        .await?;
+
+    let client = Identity::create(ctx, &vault).await?;
+    let client_storage = InMemoryStorage::new();
+    let channel = client
+        .create_secure_channel(
+            route!["listener"],
+            TrustIdentifierPolicy::new(server.identifier().clone()),
+            &client_storage,
+        )
+        .await?;
+
+    let credential = Credential::builder(client.identifier().clone());
+    let credential = authority.issue_credential(credential).await?;
+    client.set_credential(Some(credential)).await;
+
+    let counter = Arc::new(AtomicI8::new(0));
+
+    let worker = CountingWorker {
+        msgs_count: counter.clone(),
+    };
+
+    WorkerBuilder::with_access_control(CredentialValidityAccessControl::new(), "counter", worker)
+        .start(ctx)
+        .await?;
+
+    // Denied until a credential is presented over the channel
+    ctx.send(route![channel.clone(), "counter"], "Hello".to_string())
+        .await?;
+    ctx.sleep(Duration::from_millis(100)).await;
+    assert_eq!(counter.load(Ordering::Relaxed), 0);
+
+    client
+        .present_credential(route![channel.clone(), "credential_exchange"])
+        .await?;
+
+    ctx.send(route![channel, "counter"], "Hello".to_string())
+        .await?;
+    ctx.sleep(Duration::from_millis(100)).await;
+    assert_eq!(counter.load(Ordering::Relaxed), 1);
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn access_control_with_handshake_gate_over_channel(ctx: &mut Context) -> Result<()> {
+    let vault = Vault::create();
+
+    let authority = Identity::create(ctx, &vault).await?;
+
+    let server = Identity::create(ctx, &vault).await?;
+    let server_storage = InMemoryStorage::new();
+
+    server
+        .create_secure_channel_listener("listener", TrustEveryonePolicy, &server_storage)
+        .await?;
+
+    let authorities = vec![authority.to_public().await?];
+
+    server
+        .start_credentials_exchange_worker(
+            authorities,
+            "credential_exchange",
+            false,
+            server_storage.clone(),
+        )
+        .await?;
+
+    let counter = Arc::new(AtomicI8::new(0));
+
+    let worker = CountingWorker {
+        msgs_count: counter.clone(),
+    };
+
+    let access_control = HandshakeGateAccessControl::new(Duration::from_secs(60), 10);
+    WorkerBuilder::with_access_control(access_control, "counter", worker)
+        .start(ctx)
+        .await?;
+
+    // Two clients, of which only the first presents its credential
+    let mut channels = vec![];
+    for presents in [true, false] {
+        let client = Identity::create(ctx, &vault).await?;
+        let client_storage = InMemoryStorage::new();
+        let channel = client
+            .create_secure_channel(
+                route!["listener"],
+                TrustIdentifierPolicy::new(server.identifier().clone()),
+                &client_storage,
+            )
+            .await?;
+
+        let credential = Credential::builder(client.identifier().clone());
+        let credential = authority.issue_credential(credential).await?;
+        client.set_credential(Some(credential)).await;
+
+        // Denied before the handshake
+        ctx.send(route![channel.clone(), "counter"], "Hello".to_string())
+            .await?;
+        ctx.sleep(Duration::from_millis(100)).await;
+        assert_eq!(counter.load(Ordering::Relaxed), 0);
+
+        if presents {
+            client
+                .present_credential(route![channel.clone(), "credential_exchange"])
+                .await?;
+        }
+        channels.push(channel);
+    }
+
+    for channel in channels {
+        ctx.send(route![channel, "counter"], "Hello".to_string())
+            .await?;
+    }
+    ctx.sleep(Duration::from_millis(100)).await;
+    assert_eq!(counter.load(Ordering::Relaxed), 1);
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn access_control_with_credential_validity(ctx: &mut Context) -> Result<()> {
+    let access_control = CredentialValidityAccessControl::new();
+    let now = u64::from(Timestamp::now().unwrap());
+
+    for (expires, allowed) in [
+        (Some(now + 3600), true),
+        (Some(now - 3600), false),
+        (Some(now), false),
+        (None, false),
+    ] {
+        let local_info = match expires {
+            Some(expires) => CredentialLocalInfo::mark(vec![], Timestamp::from(expires))?,
+            None => vec![],
+        };
+        let msg = LocalMessage::new(
+            TransportMessage::v1(route!["counter"], route![], vec![]),
+            local_info,
+        );
+        assert_eq!(access_control.is_authorized(&msg).await?, allowed);
+    }
+
+    ctx.stop().await
+}
@@ -0,0 +1,244 @@
+use super::proto::{DnsAddr, Node, Project, Secure, Service, Sni, Space, Tcp, Tls};
+use super::{Code, Error, MultiAddr, ProtoValue, Protocol};
+use alloc::borrow::Cow;
+
+/// Codes of the protocols a [`Tcp`] port may follow: `ip4`, `ip6` and `dnsaddr`.
+const HOSTS: [Code; 3] = [Code::new(4), Code::new(41), DnsAddr::CODE];
+
+/// Builds a [`MultiAddr`] one protocol at a time.
+///
+/// Errors are deferred to [`MultiAddrBuilder::build`], which also checks
+/// that every `tcp` port directly follows a host, every `tls` a `tcp`
+/// port and every `sni` a `tls`.
+///
+/// # Examples
+///
+/// ```
+/// # use ockam_multiaddr::MultiAddrBuilder;
+/// let addr = MultiAddrBuilder::new()
+///     .dnsaddr("localhost")
+///     .tcp(4000)
+///     .service("api")
+///     .build()
+///     .unwrap();
+/// assert_eq!(addr.to_string(), "/dnsaddr/localhost/tcp/4000/service/api");
+///
+/// assert!(MultiAddrBuilder::new().tcp(4000).build().is_err());
+/// ```
+#[derive(Debug, Default)]
+pub struct MultiAddrBuilder {
+    addr: MultiAddr,
+    error: Option<Error>,
+}
+
+impl MultiAddrBuilder {
+    /// Start from an empty address using the default registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start from an empty address with an explicit protocol codec registry.
+    pub fn with_registry(r: super::Registry) -> Self {
+        MultiAddrBuilder {
+            addr: MultiAddr::new(r),
+            error: None,
+        }
+    }
+
+    /// Append an IPv4 address.
+    #[cfg(feature = "std")]
+    pub fn ip4<T: Into<std::net::Ipv4Addr>>(self, ip: T) -> Self {
+        self.proto(super::proto::Ip4::new(ip))
+    }
+
+    /// Append an IPv6 address.
+    #[cfg(feature = "std")]
+    pub fn ip6<T: Into<std::net::Ipv6Addr>>(self, ip: T) -> Self {
+        self.proto(super::proto::Ip6::new(ip))
+    }
+
+    /// Append a host name.
+    pub fn dnsaddr<'a, S: Into<Cow<'a, str>>>(self, host: S) -> Self {
+        self.proto(DnsAddr::new(host))
+    }
+
+    /// Append a TCP port.
+    pub fn tcp(self, port: u16) -> Self {
+        self.proto(Tcp::new(port))
+    }
+
+    /// Append a TLS session over the preceding TCP port.
+    pub fn tls(self) -> Self {
+        self.proto(Tls::new())
+    }
+
+    /// Append the server name of the preceding TLS session.
+    pub fn sni<'a, S: Into<Cow<'a, str>>>(self, server_name: S) -> Self {
+        self.proto(Sni::new(server_name))
+    }
+
+    /// Append a service address.
+    pub fn service<'a, S: Into<Cow<'a, str>>>(self, name: S) -> Self {
+        self.proto(Service::new(name))
+    }
+
+    /// Append a node name.
+    pub fn node<'a, S: Into<Cow<'a, str>>>(self, name: S) -> Self {
+        self.proto(Node::new(name))
+    }
+
+    /// Append a project name.
+    pub fn project<'a, S: Into<Cow<'a, str>>>(self, name: S) -> Self {
+        self.proto(Project::new(name))
+    }
+
+    /// Append a space name.
+    pub fn space<'a, S: Into<Cow<'a, str>>>(self, name: S) -> Self {
+        self.proto(Space::new(name))
+    }
+
+    /// Append a secure channel address.
+    pub fn secure<'a, S: Into<Cow<'a, str>>>(self, name: S) -> Self {
+        self.proto(Secure::new(name))
+    }
+
+    /// Append any protocol.
+    pub fn proto<'a, P: Protocol<'a>>(mut self, p: P) -> Self {
+        if self.error.is_none() {
+            self.error = self.addr.push_back(p).err()
+        }
+        self
+    }
+
+    /// Append a protocol value, e.g. one taken from another address.
+    pub fn value(mut self, p: &ProtoValue) -> Self {
+        if self.error.is_none() {
+            self.error = self.addr.push_back_value(p).err()
+        }
+        self
+    }
+
+    /// Append all protocols of another address.
+    pub fn extend(mut self, other: &MultiAddr) -> Self {
+        if self.error.is_none() {
+            self.error = self.addr.try_extend(other.iter()).err()
+        }
+        self
+    }
+
+    /// Return the address, or the first error encountered building it.
+    pub fn build(self) -> Result<MultiAddr, Error> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        let mut prev = None;
+        for p in self.addr.iter() {
+            let (prefix, after, expected): (_, &[Code], _) = match p.code() {
+                Tcp::CODE => (Tcp::PREFIX, &HOSTS, "a host"),
+                Tls::CODE => (Tls::PREFIX, &[Tcp::CODE], "/tcp"),
+                Sni::CODE => (Sni::PREFIX, &[Tls::CODE], "/tls"),
+                _ => {
+                    prev = Some(p.code());
+                    continue;
+                }
+            };
+            if !prev.map(|c| after.contains(&c)).unwrap_or(false) {
+                return Err(Error::message(format_args!(
+                    "/{prefix} must follow {expected} in {}",
+                    self.addr
+                )));
+            }
+            prev = Some(p.code())
+        }
+        Ok(self.addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MultiAddrBuilder;
+    use crate::MultiAddr;
+    use core::str::FromStr;
+
+    #[test]
+    fn build() {
+        let addr = MultiAddrBuilder::new()
+            .ip4([127, 0, 0, 1])
+            .tcp(4000)
+            .secure("api")
+            .service("echo")
+            .build()
+            .unwrap();
+        assert_eq!(
+            addr,
+            MultiAddr::from_str("/ip4/127.0.0.1/tcp/4000/secure/api/service/echo").unwrap()
+        );
+
+        let node = MultiAddr::from_str("/ip6/::1/tcp/4000").unwrap();
+        let addr = MultiAddrBuilder::new()
+            .extend(&node)
+            .value(&MultiAddr::from_str("/service/x").unwrap().first().unwrap())
+            .build()
+            .unwrap();
+        assert_eq!(addr.to_string(), "/ip6/::1/tcp/4000/service/x");
+
+        assert!(MultiAddrBuilder::new().build().unwrap().is_empty());
+    }
+
+    #[test]
+    fn port_after_host() {
+        assert!(MultiAddrBuilder::new().tcp(1).build().is_err());
+        assert!(MultiAddrBuilder::new().service("a").tcp(1).build().is_err());
+        assert!(MultiAddrBuilder::new()
+            .dnsaddr("a")
+            .tcp(1)
+            .tcp(2)
+            .build()
+            .is_err());
+        assert!(MultiAddrBuilder::new()
+            .project("p")
+            .dnsaddr("a")
+            .tcp(1)
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn tls() {
+        let addr = MultiAddrBuilder::new()
+            .dnsaddr("relay.example.com")
+            .tcp(443)
+            .tls()
+            .sni("relay.example.com")
+            .service("api")
+            .build()
+            .unwrap();
+        assert_eq!(
+            addr.to_string(),
+            "/dnsaddr/relay.example.com/tcp/443/tls/sni/relay.example.com/service/api"
+        );
+        assert_eq!(MultiAddr::from_str(&addr.to_string()).unwrap(), addr);
+        assert!(MultiAddrBuilder::new()
+            .ip4([127, 0, 0, 1])
+            .tcp(443)
+            .tls()
+            .build()
+            .is_ok());
+
+        assert!(MultiAddrBuilder::new().dnsaddr("a").tls().build().is_err());
+        assert!(MultiAddrBuilder::new()
+            .dnsaddr("a")
+            .tcp(1)
+            .sni("a")
+            .build()
+            .is_err());
+        assert!(MultiAddrBuilder::new()
+            .dnsaddr("a")
+            .tcp(1)
+            .tls()
+            .tls()
+            .build()
+            .is_err());
+        assert!(MultiAddr::from_str("/tls/x").is_err());
+    }
+}
@@ -6,11 +6,13 @@
 //! - [`Protocol`]: A type that can be read from and written to strings and bytes.
 //! - [`Codec`]: A type that understands protocols.
 //! - [`ProtoValue`]: A section of a MultiAddr.
+//! - [`MultiAddrBuilder`]: Builds a MultiAddr one protocol at a time.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 extern crate alloc;
 
+mod builder;
 mod error;
 mod registry;
 
@@ -26,6 +28,7 @@ use core::str::FromStr;
 use once_cell::race::OnceBox;
 use tinyvec::{Array, ArrayVec, TinyVec};
 
+pub use builder::MultiAddrBuilder;
 pub use error::Error;
 pub use registry::{Registry, RegistryBuilder};
 
@@ -557,6 +560,53 @@ impl MultiAddr {
         let b = MultiAddr::default().try_with(iter).expect("valid address");
         (a, b)
     }
+
+    /// Return the normalized form of this address.
+    ///
+    /// Addresses routing to the same place but written differently have
+    /// the same normalized form:
+    ///
+    /// - DNS names are lowercased and lose their trailing dot,
+    /// - `/dnsaddr/localhost` becomes `/ip4/127.0.0.1`, unless the name may
+    ///   be the one a `/tls` session verifies, i.e. without an `/sni`,
+    /// - IPv4-mapped IPv6 addresses become IPv4 addresses.
+    ///
+    /// All the other protocol values are kept as they are, in order.
+    pub fn canonicalize(&self) -> MultiAddr {
+        let mut ma = MultiAddr::new(self.reg.clone());
+        #[cfg(feature = "std")]
+        let server_name = {
+            let has = |c| self.iter().any(|p| p.code() == c);
+            has(proto::Tls::CODE) && !has(proto::Sni::CODE)
+        };
+        for p in self.iter() {
+            match p.code() {
+                proto::DnsAddr::CODE => {
+                    let dns = p.cast::<proto::DnsAddr>().expect("valid dnsaddr");
+                    let name = dns.trim_end_matches('.').to_ascii_lowercase();
+                    #[cfg(feature = "std")]
+                    if name == "localhost" && !server_name {
+                        let ip = proto::Ip4::new(std::net::Ipv4Addr::LOCALHOST);
+                        ma.push_back(ip).expect("known protocol");
+                        continue;
+                    }
+                    ma.push_back(proto::DnsAddr::new(name))
+                        .expect("known protocol")
+                }
+                #[cfg(feature = "std")]
+                proto::Ip6::CODE => {
+                    let ip = p.cast::<proto::Ip6>().expect("valid ip6");
+                    match ip.to_ipv4_mapped() {
+                        Some(ip) => ma.push_back(proto::Ip4::new(ip)),
+                        None => ma.push_back(ip),
+                    }
+                    .expect("known protocol")
+                }
+                _ => ma.push_back_value(&p).expect("known protocol"),
+            }
+        }
+        ma
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
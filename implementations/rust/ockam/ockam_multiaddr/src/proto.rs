@@ -153,6 +153,51 @@ impl Protocol<'_> for Tcp {
     }
 }
 
+/// A TLS session over the preceding transport, e.g. `/tcp/443/tls`.
+///
+/// It has no value. The server name to request and verify may follow as
+/// an [`Sni`], e.g. `/tcp/443/tls/sni/relay.example.com`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tls;
+
+impl Tls {
+    pub fn new() -> Self {
+        Tls
+    }
+}
+
+impl Protocol<'_> for Tls {
+    const CODE: Code = Code::new(448);
+    const PREFIX: &'static str = "tls";
+
+    fn read_str(input: Checked<&str>) -> Result<Self, Error> {
+        if input.is_empty() {
+            Ok(Tls)
+        } else {
+            Err(Error::message("/tls has no value"))
+        }
+    }
+
+    fn read_bytes(input: Checked<&[u8]>) -> Result<Self, Error> {
+        if input.is_empty() {
+            Ok(Tls)
+        } else {
+            Err(Error::message("/tls has no value"))
+        }
+    }
+
+    fn write_str(&self, f: &mut fmt::Formatter) -> Result<(), Error> {
+        write!(f, "/{}", Self::PREFIX)?;
+        Ok(())
+    }
+
+    fn write_bytes(&self, buf: &mut dyn Buffer) {
+        let mut b = encode::u32_buffer();
+        let uvi = encode::u32(Self::CODE.into(), &mut b);
+        buf.extend_with(uvi)
+    }
+}
+
 macro_rules! gen_str_proto {
     ($t:ident, $c:literal, $p:literal) => {
         #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -209,3 +254,4 @@ gen_str_proto!(Node, 72526, "node");
 gen_str_proto!(Project, 82526, "project");
 gen_str_proto!(Space, 92526, "space");
 gen_str_proto!(Secure, 99526, "secure");
+gen_str_proto!(Sni, 449, "sni");
@@ -1,5 +1,7 @@
 use core::fmt;
-use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp};
+use ockam_multiaddr::proto::{
+    DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Sni, Space, Tcp, Tls,
+};
 use ockam_multiaddr::{Code, Match, MultiAddr, Protocol};
 use quickcheck::{quickcheck, Arbitrary, Gen};
 use rand::distributions::{Alphanumeric, DistString};
@@ -132,6 +134,14 @@ quickcheck! {
                         addr.push_back(Space::new("space")).unwrap();
                         prot.push_back(Space::CODE);
                     }
+                    Tls::CODE => {
+                        addr.push_back(Tls::new()).unwrap();
+                        prot.push_back(Tls::CODE);
+                    }
+                    Sni::CODE => {
+                        addr.push_back(Sni::new("localhost")).unwrap();
+                        prot.push_back(Sni::CODE);
+                    }
                     _ => unreachable!()
                 }
             }
@@ -141,6 +151,43 @@ quickcheck! {
         }
         true
     }
+
+    fn canonicalize_idempotent(a: Addr) -> bool {
+        let c = a.0.canonicalize();
+        c.iter().count() == a.0.iter().count() && c == c.canonicalize()
+    }
+}
+
+#[test]
+fn canonicalize() {
+    for (input, canonical) in [
+        ("/dnsaddr/localhost/tcp/4000", "/ip4/127.0.0.1/tcp/4000"),
+        ("/dnsaddr/LocalHost./tcp/4000", "/ip4/127.0.0.1/tcp/4000"),
+        // The name is verified by the TLS session
+        (
+            "/dnsaddr/localhost/tcp/443/tls",
+            "/dnsaddr/localhost/tcp/443/tls",
+        ),
+        (
+            "/dnsaddr/localhost/tcp/443/tls/sni/localhost",
+            "/ip4/127.0.0.1/tcp/443/tls/sni/localhost",
+        ),
+        ("/ip4/127.0.0.1/tcp/4000", "/ip4/127.0.0.1/tcp/4000"),
+        ("/ip6/::ffff:127.0.0.1/tcp/4000", "/ip4/127.0.0.1/tcp/4000"),
+        ("/ip6/::1/tcp/4000", "/ip6/::1/tcp/4000"),
+        (
+            "/dnsaddr/Relay.Example.COM./tcp/443/tls/sni/relay.example.com",
+            "/dnsaddr/relay.example.com/tcp/443/tls/sni/relay.example.com",
+        ),
+        // Only DNS names are case insensitive
+        (
+            "/node/Blue/service/Forward_To_Blue",
+            "/node/Blue/service/Forward_To_Blue",
+        ),
+    ] {
+        let ma = MultiAddr::from_str(input).unwrap();
+        assert_eq!(ma.canonicalize().to_string(), canonical, "{input}");
+    }
 }
 
 const PROTOS: &[Code] = &[
@@ -153,6 +200,8 @@ const PROTOS: &[Code] = &[
     Node::CODE,
     Project::CODE,
     Space::CODE,
+    Tls::CODE,
+    Sni::CODE,
 ];
 
 impl Arbitrary for Addr {
@@ -169,6 +218,8 @@ impl Arbitrary for Addr {
                 Project::CODE => a.push_back(Project::new(gen_string())).unwrap(),
                 Space::CODE => a.push_back(Space::new(gen_string())).unwrap(),
                 Node::CODE => a.push_back(Node::new(gen_string())).unwrap(),
+                Tls::CODE => a.push_back(Tls::new()).unwrap(),
+                Sni::CODE => a.push_back(Sni::new(gen_hostname())).unwrap(),
                 _ => unreachable!(),
             }
         }
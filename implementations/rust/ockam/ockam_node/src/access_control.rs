@@ -1,5 +1,21 @@
+#[cfg(feature = "std")]
+use crate::tokio::time::timeout;
 use crate::ExternalLocalInfo;
+#[cfg(feature = "std")]
+use core::time::Duration;
 use ockam_core::access_control::AccessControl;
+#[cfg(all(feature = "std", unix))]
+use ockam_core::access_control::ReloadableAccessControl;
+#[cfg(feature = "std")]
+use ockam_core::access_control::{
+    CacheKey, CancellationToken, Completion, DecisionTrace, TraceOutcome,
+};
+#[cfg(feature = "std")]
+use ockam_core::compat::sync::Arc;
+#[cfg(feature = "std")]
+use ockam_core::compat::{collections::BTreeMap, string::String, sync::RwLock};
+#[cfg(all(feature = "std", unix))]
+use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{allow, LocalMessage, Result};
 use ockam_core::{
     async_trait,
@@ -53,3 +69,516 @@ impl AccessControl for AllowedTransport {
         allow()
     }
 }
+
+/// Denies messages that arrived over any of a set of transport types
+///
+/// Messages which only ever travelled inside this node, or which were
+/// decrypted by a secure channel, carry no transport type and are allowed.
+/// For example, denying TCP lets a worker accept messages through a secure
+/// channel while refusing plaintext TCP traffic. See [`AllowedTransport`]
+/// for the allow-list counterpart.
+#[derive(Debug)]
+pub struct TransportTypeAccessControl {
+    denied_transport: Vec<TransportType>,
+}
+
+impl TransportTypeAccessControl {
+    /// Constructor
+    pub fn deny(denied_transport: Vec<TransportType>) -> Self {
+        Self { denied_transport }
+    }
+
+    /// Constructor
+    pub fn deny_single(denied_transport: TransportType) -> Self {
+        Self {
+            denied_transport: vec![denied_transport],
+        }
+    }
+}
+
+#[async_trait]
+impl AccessControl for TransportTypeAccessControl {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        let denied = ExternalLocalInfo::find_all(local_msg)?
+            .into_iter()
+            .any(|x| self.denied_transport.contains(&x.transport_type()));
+        Ok(!denied)
+    }
+}
+
+/// What [`BudgetedAccessControl`] decides for the messages its
+/// AccessControl can't decide on within the budget
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetFallback {
+    /// Always this decision, e.g. `false` to deny
+    Constant(bool),
+    /// The last decision made within the budget for the sender of the
+    /// message, the next address of its return route, or `default` for
+    /// senders without one. At most `capacity` senders are remembered.
+    LastDecision {
+        /// Decision for senders without a remembered one
+        default: bool,
+        /// How many senders to remember
+        capacity: usize,
+    },
+}
+
+/// Gives an AccessControl a time budget for each decision
+///
+/// When the inner AccessControl decides within the budget, its decision, or
+/// error, is returned. Otherwise its evaluation is dropped and the decision
+/// comes from the [`BudgetFallback`], so that a slow AccessControl, e.g. one
+/// asking another node, delays messages by at most the budget. Unlike a plain
+/// timeout, which could only deny or fail, the fallback can be the last
+/// decision made in time for the same sender, allowing the senders which were
+/// allowed before. Messages allowed by the fallback come without the
+/// annotations and the [`Completion`] of the inner AccessControl. Fallbacks
+/// are logged, unless turned off with [`without_logging`](Self::without_logging).
+///
+/// The budget is measured with the timers of the node's runtime. Clones
+/// share the last decisions, so that a worker started again with a clone
+/// falls back on the decisions made for its previous instance.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct BudgetedAccessControl<A: AccessControl> {
+    inner: A,
+    budget: Duration,
+    fallback: BudgetFallback,
+    logging: bool,
+    /// The last decision made within the budget for each sender
+    decisions: Arc<RwLock<BTreeMap<CacheKey, bool>>>,
+}
+
+#[cfg(feature = "std")]
+impl<A: AccessControl> BudgetedAccessControl<A> {
+    /// Constructor
+    pub fn new(inner: A, budget: Duration, fallback: BudgetFallback) -> Self {
+        BudgetedAccessControl {
+            inner,
+            budget,
+            fallback,
+            logging: true,
+            decisions: Default::default(),
+        }
+    }
+
+    /// Don't log the fallbacks
+    pub fn without_logging(mut self) -> Self {
+        self.logging = false;
+        self
+    }
+
+    /// Remember `decision` for the sender of `local_msg`
+    fn remember(&self, local_msg: &LocalMessage, decision: bool) {
+        let capacity = match self.fallback {
+            BudgetFallback::LastDecision { capacity, .. } if capacity > 0 => capacity,
+            _ => return,
+        };
+        let key = match CacheKey::source(local_msg) {
+            Some(key) => key,
+            None => return,
+        };
+        if let Ok(mut decisions) = self.decisions.write() {
+            if decisions.len() >= capacity && !decisions.contains_key(&key) {
+                let first = decisions.keys().next().cloned();
+                if let Some(first) = first {
+                    decisions.remove(&first);
+                }
+            }
+            decisions.insert(key, decision);
+        }
+    }
+
+    /// The decision of the fallback for `local_msg`
+    fn fallback_decision(&self, local_msg: &LocalMessage) -> bool {
+        match self.fallback {
+            BudgetFallback::Constant(decision) => decision,
+            BudgetFallback::LastDecision { default, .. } => CacheKey::source(local_msg)
+                .and_then(|key| self.decisions.read().ok()?.get(&key).copied())
+                .unwrap_or(default),
+        }
+    }
+
+    /// The decision of the fallback for `local_msg`, logged
+    fn fallback(&self, local_msg: &LocalMessage) -> bool {
+        let decision = self.fallback_decision(local_msg);
+        if self.logging {
+            tracing::warn!(
+                source = ?local_msg.source_addr(),
+                budget = ?self.budget,
+                decision,
+                "access control over budget, using the fallback decision"
+            );
+        }
+        decision
+    }
+}
+
+#[cfg(feature = "std")]
+#[async_trait]
+impl<A: AccessControl> AccessControl for BudgetedAccessControl<A> {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        self.is_authorized_with_ctx(local_msg, None).await
+    }
+
+    async fn is_authorized_with_ctx(
+        &self,
+        local_msg: &LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<bool> {
+        let evaluation = self.inner.is_authorized_with_ctx(local_msg, token);
+        match timeout(self.budget, evaluation).await {
+            Ok(result) => {
+                if let Ok(decision) = result {
+                    self.remember(local_msg, decision);
+                }
+                result
+            }
+            Err(_) => Ok(self.fallback(local_msg)),
+        }
+    }
+
+    /// Return the message as annotated by the inner AccessControl, when it
+    /// decides within the budget
+    async fn authorize(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<LocalMessage>> {
+        Ok(self
+            .authorize_tracked(local_msg, token)
+            .await?
+            .map(|(local_msg, _)| local_msg))
+    }
+
+    /// Return the message and completion of the inner AccessControl when it
+    /// decides within the budget, and the message unchanged when the fallback
+    /// allows it
+    async fn authorize_tracked(
+        &self,
+        local_msg: LocalMessage,
+        token: Option<&CancellationToken>,
+    ) -> Result<Option<(LocalMessage, Completion)>> {
+        let evaluation = self.inner.authorize_tracked(local_msg.clone(), token);
+        match timeout(self.budget, evaluation).await {
+            Ok(result) => {
+                if let Ok(authorized) = &result {
+                    self.remember(&local_msg, authorized.is_some());
+                }
+                result
+            }
+            Err(_) => Ok(self
+                .fallback(&local_msg)
+                .then(|| (local_msg, Completion::new()))),
+        }
+    }
+
+    /// Trace the inner AccessControl, or show it over the budget, without
+    /// remembering the decision for the fallback
+    async fn trace_decision(&self, local_msg: &LocalMessage) -> DecisionTrace {
+        let inner = match timeout(self.budget, self.inner.trace_decision(local_msg)).await {
+            Ok(inner) => inner,
+            Err(_) => {
+                let over_budget = format!("over the budget of {}ms", self.budget.as_millis());
+                let inner = DecisionTrace::new(
+                    self.inner.describe(),
+                    TraceOutcome::Failed(over_budget),
+                    Vec::new(),
+                );
+                let outcome = if self.fallback_decision(local_msg) {
+                    TraceOutcome::Allowed
+                } else {
+                    TraceOutcome::Denied
+                };
+                return DecisionTrace::new("Budgeted", outcome, vec![inner]);
+            }
+        };
+        DecisionTrace::new("Budgeted", inner.outcome.clone(), vec![inner])
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "Budgeted({}ms)[{}]",
+            self.budget.as_millis(),
+            self.inner.describe()
+        )
+    }
+}
+
+/// Reload `access_control` whenever the process receives SIGHUP
+///
+/// The reloads run on the runtime of `ctx`, for as long as it runs. As with
+/// [`ReloadableAccessControl::reload`], a policy which fails to load is
+/// logged and the current one is kept.
+#[cfg(all(feature = "std", unix))]
+pub fn reload_on_sighup(
+    ctx: &crate::Context,
+    access_control: Arc<ReloadableAccessControl>,
+) -> Result<()> {
+    use crate::tokio::signal::unix::{signal, SignalKind};
+
+    let _runtime = ctx.runtime().enter();
+    let mut hangups = signal(SignalKind::hangup())
+        .map_err(|e| ockam_core::Error::new(Origin::Node, Kind::Io, e))?;
+    ctx.runtime().spawn(async move {
+        while hangups.recv().await.is_some() {
+            tracing::info!("received SIGHUP, reloading the access control policy");
+            let _ = access_control.reload();
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ExternalLocalInfo;
+    use ockam_core::compat::future::poll_once;
+    use ockam_core::{route, LocalInfo, LocalMessage, Result, TransportMessage, TransportType};
+
+    #[cfg(unix)]
+    use super::{reload_on_sighup, ReloadableAccessControl};
+    use super::{
+        AccessControl, BudgetFallback, BudgetedAccessControl, TraceOutcome,
+        TransportTypeAccessControl,
+    };
+    #[cfg(unix)]
+    use crate::NodeBuilder;
+    #[cfg(unix)]
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use core::time::Duration;
+    #[cfg(unix)]
+    use ockam_core::{compat::sync::Arc, AllowAll};
+
+    const TCP: TransportType = TransportType::new(1);
+    const UDP: TransportType = TransportType::new(2);
+
+    fn is_authorized(
+        access_control: &TransportTypeAccessControl,
+        local_info: Vec<LocalInfo>,
+    ) -> Result<bool> {
+        poll_once(async {
+            let local_message = LocalMessage::new(
+                TransportMessage::v1(route!["worker"], route![], vec![]),
+                local_info,
+            );
+            access_control.is_authorized(&local_message).await
+        })
+    }
+
+    #[test]
+    fn test_transport_type() -> Result<()> {
+        let access_control = TransportTypeAccessControl::deny_single(TCP);
+        let tcp = ExternalLocalInfo::new(TCP).to_local_info()?;
+        let udp = ExternalLocalInfo::new(UDP).to_local_info()?;
+
+        assert!(!is_authorized(&access_control, vec![tcp.clone()])?);
+        assert!(is_authorized(&access_control, vec![udp.clone()])?);
+        assert!(!is_authorized(&access_control, vec![udp, tcp])?);
+        // Local or decrypted by a secure channel
+        assert!(is_authorized(&access_control, vec![])?);
+        Ok(())
+    }
+
+    /// Makes each decision of its script after the given delay
+    #[derive(Debug)]
+    struct Scripted(std::sync::Mutex<Vec<(u64, bool)>>);
+
+    impl Scripted {
+        /// Decisions in order, with their delay in milliseconds
+        fn new(script: impl IntoIterator<Item = (u64, bool)>) -> Self {
+            let mut script: Vec<_> = script.into_iter().collect();
+            script.reverse();
+            Scripted(std::sync::Mutex::new(script))
+        }
+    }
+
+    #[ockam_core::async_trait]
+    impl AccessControl for Scripted {
+        async fn is_authorized(&self, _local_msg: &LocalMessage) -> Result<bool> {
+            let (delay, decision) = self.0.lock().unwrap().pop().expect("a decision");
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            Ok(decision)
+        }
+    }
+
+    /// Decisions of `access_control` for a message from each of `sources`
+    fn budgeted_decisions(
+        access_control: &BudgetedAccessControl<Scripted>,
+        sources: &[&str],
+    ) -> Vec<bool> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        sources
+            .iter()
+            .map(|source| {
+                let local_message = LocalMessage::new(
+                    TransportMessage::v1(route!["worker"], route![*source], vec![]),
+                    vec![],
+                );
+                runtime
+                    .block_on(access_control.is_authorized(&local_message))
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_budgeted_in_budget() {
+        let access_control = BudgetedAccessControl::new(
+            Scripted::new([(0, true), (0, false)]),
+            Duration::from_secs(10),
+            BudgetFallback::Constant(true),
+        );
+        assert_eq!(
+            budgeted_decisions(&access_control, &["a", "a"]),
+            [true, false]
+        );
+    }
+
+    #[test]
+    fn test_budgeted_constant_fallback() {
+        let access_control = BudgetedAccessControl::new(
+            Scripted::new([(0, true), (10_000, true)]),
+            Duration::from_millis(50),
+            BudgetFallback::Constant(false),
+        )
+        .without_logging();
+        assert_eq!(
+            budgeted_decisions(&access_control, &["a", "a"]),
+            [true, false]
+        );
+    }
+
+    #[test]
+    fn test_budgeted_last_decision_fallback() {
+        let access_control = BudgetedAccessControl::new(
+            Scripted::new([
+                (0, true),
+                (0, false),
+                (10_000, false),
+                (10_000, false),
+                (10_000, true),
+            ]),
+            Duration::from_millis(50),
+            BudgetFallback::LastDecision {
+                default: false,
+                capacity: 10,
+            },
+        );
+        // a was allowed and b denied in time, c never decided on
+        assert_eq!(
+            budgeted_decisions(&access_control, &["a", "b", "a", "b", "c"]),
+            [true, false, true, false, false]
+        );
+    }
+
+    #[test]
+    fn test_budgeted_trace_keeps_last_decision() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let access_control = BudgetedAccessControl::new(
+            Scripted::new([(0, true), (10_000, true)]),
+            Duration::from_millis(50),
+            BudgetFallback::LastDecision {
+                default: false,
+                capacity: 10,
+            },
+        )
+        .without_logging();
+        let local_message = LocalMessage::new(
+            TransportMessage::v1(route!["worker"], route!["a"], vec![]),
+            vec![],
+        );
+        let trace = runtime.block_on(access_control.trace_decision(&local_message));
+        assert_eq!(trace.outcome, TraceOutcome::Allowed);
+        // The decision traced in time is not the last decision of a
+        assert_eq!(budgeted_decisions(&access_control, &["a"]), [false]);
+    }
+
+    #[test]
+    fn test_budgeted_completion_and_trace() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let local_message = LocalMessage::new(
+            TransportMessage::v1(route!["worker"], route!["a"], vec![]),
+            vec![],
+        );
+        let a = "a".into();
+
+        // The completion of the inner access control is kept...
+        let limit = ockam_core::ConcurrencyLimitAccessControl::new(1, 10);
+        let access_control = BudgetedAccessControl::new(
+            limit.clone(),
+            Duration::from_secs(10),
+            BudgetFallback::Constant(false),
+        );
+        let (_, completion) = runtime
+            .block_on(access_control.authorize_tracked(local_message.clone(), None))
+            .unwrap()
+            .unwrap();
+        assert_eq!(limit.in_flight(&a).unwrap(), 1);
+        drop(completion);
+        assert_eq!(limit.in_flight(&a).unwrap(), 0);
+        let trace = runtime.block_on(access_control.trace_decision(&local_message));
+        assert_eq!(
+            trace.to_string(),
+            "Budgeted: allowed\n  ConcurrencyLimit(1 per sender): allowed\n"
+        );
+
+        // ...and the fallback decides over the budget
+        let access_control = BudgetedAccessControl::new(
+            Scripted::new([(10_000, false), (10_000, false)]),
+            Duration::from_millis(50),
+            BudgetFallback::Constant(true),
+        )
+        .without_logging();
+        assert!(runtime
+            .block_on(access_control.authorize_tracked(local_message.clone(), None))
+            .unwrap()
+            .is_some());
+        let trace = runtime.block_on(access_control.trace_decision(&local_message));
+        assert_eq!(
+            trace.to_string(),
+            "Budgeted: allowed\n  Scripted: failed: over the budget of 50ms\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_reload_on_sighup() -> Result<()> {
+        let loads = Arc::new(AtomicUsize::new(0));
+        let counter = loads.clone();
+        let access_control = Arc::new(ReloadableAccessControl::new(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            let policy: Arc<dyn AccessControl> = Arc::new(AllowAll);
+            Ok(policy)
+        })?);
+
+        let (mut ctx, mut executor) = NodeBuilder::without_access_control().build();
+        executor
+            .execute(async move {
+                reload_on_sighup(&ctx, access_control)?;
+                std::process::Command::new("kill")
+                    .args(["-HUP", &std::process::id().to_string()])
+                    .status()
+                    .unwrap();
+                for _ in 0..100 {
+                    if loads.load(Ordering::SeqCst) > 1 {
+                        break;
+                    }
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+                ctx.stop().await?;
+                assert_eq!(loads.load(Ordering::SeqCst), 2);
+                Result::<()>::Ok(())
+            })
+            .unwrap()
+    }
+}
@@ -0,0 +1,52 @@
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::Mutex;
+use ockam_core::Address;
+
+/// How many messages the access control of a worker allowed and denied
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AuthorizationCount {
+    /// Messages the access control let through
+    pub allowed: u64,
+    /// Messages the access control dropped
+    pub denied: u64,
+}
+
+/// Authorization decisions taken for the messages received by the workers
+/// of a node, by worker address
+///
+/// Every [`Context`](crate::Context) of a node shares the same stats, and
+/// records each decision of its access control there. A worker's counts are
+/// forgotten once its context is dropped.
+#[derive(Default)]
+pub(crate) struct AuthorizationStats {
+    counts: Mutex<BTreeMap<Address, AuthorizationCount>>,
+}
+
+impl AuthorizationStats {
+    /// Record a decision for a message received by `address`
+    pub(crate) fn record(&self, address: &Address, allowed: bool) {
+        if let Ok(mut counts) = self.counts.lock() {
+            let count = counts.entry(address.clone()).or_default();
+            if allowed {
+                count.allowed += 1;
+            } else {
+                count.denied += 1;
+            }
+        }
+    }
+
+    /// Forget the decisions recorded for `address`
+    pub(crate) fn forget(&self, address: &Address) {
+        if let Ok(mut counts) = self.counts.lock() {
+            counts.remove(address);
+        }
+    }
+
+    /// The counts of all the workers which received messages
+    pub(crate) fn snapshot(&self) -> BTreeMap<Address, AuthorizationCount> {
+        self.counts
+            .lock()
+            .map(|counts| counts.clone())
+            .unwrap_or_default()
+    }
+}
@@ -1,24 +1,29 @@
 use crate::async_drop::AsyncDrop;
+use crate::authorization_stats::AuthorizationStats;
 use crate::channel_types::{message_channel, small_channel, SmallReceiver, SmallSender};
+use crate::default_access_control::DefaultAccessControl;
 use crate::tokio::{self, runtime::Handle, time::timeout};
+use crate::worker_access_controls::WorkerAccessControls;
 use crate::{
     error::*,
     parser,
     relay::{CtrlSignal, ProcessorRelay, RelayMessage},
     router::SenderPair,
-    Cancel, NodeMessage, ShutdownType, WorkerBuilder,
+    AuthorizationCount, Cancel, NodeMessage, ShutdownType, WorkerBuilder,
 };
 use core::{
     sync::atomic::{AtomicUsize, Ordering},
     time::Duration,
 };
-use ockam_core::compat::{boxed::Box, string::String, sync::Arc, vec::Vec};
+use ockam_core::compat::{boxed::Box, collections::BTreeMap, string::String, sync::Arc, vec::Vec};
 use ockam_core::{
     errcode::{Kind, Origin},
     Address, AddressSet, AllowAll, AsyncTryClone, Error, LocalMessage, Mailbox, Mailboxes, Message,
     Processor, Result, Route, TransportMessage, TransportType, Worker,
 };
-use ockam_core::{AccessControl, LocalInfo};
+use ockam_core::{
+    AccessControl, CancellationToken, Completion, DecisionTrace, LocalInfo, Sequencer,
+};
 
 /// A default timeout in seconds
 pub const DEFAULT_TIMEOUT: u64 = 30;
@@ -56,10 +61,24 @@ pub struct Context {
     receiver: SmallReceiver<RelayMessage>,
     async_drop_sender: Option<AsyncDropSender>,
     mailbox_count: Arc<AtomicUsize>,
+    /// Cancelled once this context stops receiving messages
+    cancellation: CancellationToken,
+    /// Shared by all the contexts of the node
+    authorization_stats: Arc<AuthorizationStats>,
+    /// Shared by all the contexts of the node
+    default_access_control: Arc<DefaultAccessControl>,
+    /// Whether the default access control of the node applies to the
+    /// messages of this context, see [`WorkerBuilder`]
+    uses_default_access_control: bool,
+    /// Shared by all the contexts of the node
+    worker_access_controls: Arc<WorkerAccessControls>,
 }
 
 impl Drop for Context {
     fn drop(&mut self) {
+        self.cancellation.cancel();
+        self.authorization_stats.forget(&self.address());
+        self.worker_access_controls.forget(&self.mailboxes);
         if let Some(sender) = self.async_drop_sender.take() {
             trace!("De-allocated detached context {}", self.address());
             if let Err(e) = sender.send(self.address()) {
@@ -92,10 +111,127 @@ impl Context {
         &self.sender
     }
 
-    /// Wait for the next message from the mailbox
-    pub(crate) async fn receiver_next(&mut self) -> Result<Option<RelayMessage>> {
+    /// Return the token cancelled once this context stops receiving
+    /// messages, handed to the access controls of its mailboxes
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    /// Return the stats shared by all the contexts of the node
+    pub(crate) fn shared_authorization_stats(&self) -> Arc<AuthorizationStats> {
+        self.authorization_stats.clone()
+    }
+
+    /// Return the default access control shared by all the contexts of the node
+    pub(crate) fn shared_default_access_control(&self) -> Arc<DefaultAccessControl> {
+        self.default_access_control.clone()
+    }
+
+    /// Return the access controls of the workers shared by all the contexts
+    /// of the node
+    pub(crate) fn shared_worker_access_controls(&self) -> Arc<WorkerAccessControls> {
+        self.worker_access_controls.clone()
+    }
+
+    /// Make the default access control of the node apply, or not, to the
+    /// messages of this context
+    pub(crate) fn set_uses_default_access_control(&mut self, uses: bool) {
+        self.uses_default_access_control = uses
+    }
+
+    /// Set the access control which the workers of the node apply to their
+    /// messages before their own
+    ///
+    /// It applies to the messages received from then on by the workers of
+    /// the node, except those started with an explicit [`AllowAll`], e.g.
+    /// [`WorkerBuilder::without_access_control`], or with
+    /// [`WorkerBuilder::with_mailboxes`], which opt out. Transports and
+    /// secure channels start their workers that way, so that a default
+    /// only applies to the workers their messages are delivered to.
+    /// Detached contexts and processors don't apply it either.
+    pub fn set_default_access_control(&self, access_control: impl AccessControl) {
+        self.default_access_control
+            .set(Some(Arc::new(access_control)))
+    }
+
+    /// Remove the default access control of the node
+    pub fn clear_default_access_control(&self) {
+        self.default_access_control.set(None)
+    }
+
+    /// Return the default access control of the node, if any
+    pub fn default_access_control(&self) -> Option<Arc<dyn AccessControl>> {
+        self.default_access_control.get()
+    }
+
+    /// Return how many messages the access control of each worker of the
+    /// node allowed and denied, by primary address
+    pub fn authorization_stats(&self) -> BTreeMap<Address, AuthorizationCount> {
+        self.authorization_stats.snapshot()
+    }
+
+    /// Describe the policy applied to the messages received at `address`,
+    /// see [`AccessControl::describe`]
+    ///
+    /// When the default access control of the node applies to the worker,
+    /// it is described first, as in `All[<default>, <worker's own>]`. Returns
+    /// `None` when no worker of the node has this address.
+    pub fn describe_access_control(&self, address: &Address) -> Option<String> {
+        let worker = self.worker_access_controls.get(address)?;
+        let own = worker.access_control.describe();
+        let default = if worker.uses_default {
+            self.default_access_control.get()
+        } else {
+            None
+        };
+        Some(match default {
+            Some(default) => format!("All[{}, {own}]", default.describe()),
+            None => own,
+        })
+    }
+
+    /// Trace how the worker at `address` would decide to receive
+    /// `local_msg`, see [`AccessControl::trace_decision`]
+    ///
+    /// When the default access control of the node applies to the worker,
+    /// the trace is that of `All[<default>, <worker's own>]`, as for
+    /// [`describe_access_control`](Self::describe_access_control). Returns
+    /// `None` when no worker of the node has this address.
+    pub async fn trace_access_control(
+        &self,
+        address: &Address,
+        local_msg: &LocalMessage,
+    ) -> Option<DecisionTrace> {
+        let worker = self.worker_access_controls.get(address)?;
+        let default = if worker.uses_default {
+            self.default_access_control.get()
+        } else {
+            None
+        };
+        let default = match default {
+            Some(default) => default.trace_decision(local_msg).await,
+            None => return Some(worker.access_control.trace_decision(local_msg).await),
+        };
+        if !default.outcome.is_allowed() {
+            return Some(DecisionTrace::new(
+                "All",
+                default.outcome.clone(),
+                vec![default],
+            ));
+        }
+        let own = worker.access_control.trace_decision(local_msg).await;
+        Some(DecisionTrace::new(
+            "All",
+            own.outcome.clone(),
+            vec![default, own],
+        ))
+    }
+
+    /// Wait for the next message from the mailbox, returned with the
+    /// [`Completion`] to drop once it is handled
+    pub(crate) async fn receiver_next(&mut self) -> Result<Option<(RelayMessage, Completion)>> {
         loop {
-            let relay_msg = if let Some(msg) = self.receiver.recv().await.map(|msg| {
+            let mut relay_msg = if let Some(msg) = self.receiver.recv().await.map(|msg| {
                 trace!("{}: received new message!", self.address());
 
                 // First we update the mailbox fill metrics
@@ -108,16 +244,56 @@ impl Context {
                 return Ok(None);
             };
 
-            if !self
+            // The default access control of the node comes first
+            let mut completion = Completion::new();
+            let default_access_control = if self.uses_default_access_control {
+                self.default_access_control.get()
+            } else {
+                None
+            };
+            if let Some(access_control) = default_access_control {
+                let authorized = access_control
+                    .authorize_tracked(relay_msg.local_msg, Some(&self.cancellation))
+                    .await?;
+                match authorized {
+                    Some((local_msg, default_completion)) => {
+                        relay_msg.local_msg = local_msg;
+                        completion = default_completion;
+                    }
+                    None => {
+                        self.authorization_stats.record(&self.address(), false);
+                        warn!(
+                            "Message for {} did not pass the default access control of the node",
+                            relay_msg.addr
+                        );
+                        continue;
+                    }
+                }
+            }
+
+            // The access control may annotate the message for the worker
+            let authorized = self
                 .mailboxes
-                .is_authorized(&relay_msg.addr, &relay_msg.local_msg)
-                .await?
-            {
-                warn!("Message for {} did not pass access control", relay_msg.addr);
-                continue;
+                .authorize_tracked(
+                    &relay_msg.addr,
+                    relay_msg.local_msg,
+                    Some(&self.cancellation),
+                )
+                .await?;
+            self.authorization_stats
+                .record(&self.address(), authorized.is_some());
+            match authorized {
+                Some((local_msg, mailbox_completion)) => {
+                    relay_msg.local_msg = local_msg;
+                    completion.merge(mailbox_completion);
+                }
+                None => {
+                    warn!("Message for {} did not pass access control", relay_msg.addr);
+                    continue;
+                }
             }
 
-            return Ok(Some(relay_msg));
+            return Ok(Some((relay_msg, completion)));
         }
     }
 }
@@ -135,9 +311,13 @@ impl Context {
         sender: SmallSender<NodeMessage>,
         mailboxes: Mailboxes,
         async_drop_sender: Option<AsyncDropSender>,
+        authorization_stats: Arc<AuthorizationStats>,
+        default_access_control: Arc<DefaultAccessControl>,
+        worker_access_controls: Arc<WorkerAccessControls>,
     ) -> (Self, SenderPair, SmallReceiver<CtrlSignal>) {
         let (mailbox_tx, receiver) = message_channel();
         let (ctrl_tx, ctrl_rx) = small_channel();
+        let cancellation = CancellationToken::new();
         (
             Self {
                 rt,
@@ -146,10 +326,16 @@ impl Context {
                 receiver,
                 async_drop_sender,
                 mailbox_count: Arc::new(0.into()),
+                cancellation: cancellation.clone(),
+                authorization_stats,
+                default_access_control,
+                uses_default_access_control: false,
+                worker_access_controls,
             },
             SenderPair {
                 msgs: mailbox_tx,
                 ctrl: ctrl_tx,
+                cancellation,
             },
             ctrl_rx,
         )
@@ -227,6 +413,9 @@ impl Context {
             self.sender.clone(),
             mailboxes,
             Some(drop_sender),
+            self.authorization_stats.clone(),
+            self.default_access_control.clone(),
+            self.worker_access_controls.clone(),
         );
 
         // Create a "detached relay" and register it with the router
@@ -313,8 +502,15 @@ impl Context {
         let main_mailbox = Mailbox::new(addr, Arc::new(AllowAll)); // TODO FIXME
         let mailboxes = Mailboxes::new(main_mailbox, vec![]);
 
-        let (ctx, senders, ctrl_rx) =
-            Context::new(self.rt.clone(), self.sender.clone(), mailboxes, None);
+        let (ctx, senders, ctrl_rx) = Context::new(
+            self.rt.clone(),
+            self.sender.clone(),
+            mailboxes,
+            None,
+            self.authorization_stats.clone(),
+            self.default_access_control.clone(),
+            self.worker_access_controls.clone(),
+        );
 
         // Initialise the processor relay with the ctrl receiver
         ProcessorRelay::<P>::init(&self.rt, processor, ctx, ctrl_rx);
@@ -529,6 +725,25 @@ impl Context {
             .await
     }
 
+    /// Send a message to an address or via a fully-qualified route,
+    /// numbered by `sequencer` for the
+    /// [`SequenceAccessControl`](ockam_core::SequenceAccessControl) of the
+    /// receiver
+    pub async fn send_in_sequence<R, M>(
+        &self,
+        route: R,
+        msg: M,
+        sequencer: &Sequencer,
+    ) -> Result<()>
+    where
+        R: Into<Route>,
+        M: Message + Send + 'static,
+    {
+        let local_info = sequencer.next()?.to_local_info()?;
+        self.send_with_local_info(route, msg, vec![local_info])
+            .await
+    }
+
     /// Send a message to an address or via a fully-qualified route
     ///
     /// Routes can be constructed from a set of [`Address`]es, or via
@@ -768,6 +983,24 @@ impl Context {
         self.register_impl(type_, addr.into()).await
     }
 
+    /// Deliver the messages for local addresses that no worker uses to
+    /// the worker at the given address
+    ///
+    /// Their onward route is left unchanged. Only one fallback worker can
+    /// be set per node, and it is unset when that worker stops.
+    pub async fn set_fallback_address<A: Into<Address>>(&self, addr: A) -> Result<()> {
+        let (msg, mut rx) = NodeMessage::set_fallback(addr.into());
+        self.sender
+            .send(msg)
+            .await
+            .map_err(NodeError::from_send_err)?;
+
+        rx.recv()
+            .await
+            .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??;
+        Ok(())
+    }
+
     /// Send a shutdown acknowledgement to the router
     pub(crate) async fn send_stop_ack(&self) -> Result<()> {
         self.sender
@@ -805,7 +1038,8 @@ impl Context {
     /// has woken it.
     async fn next_from_mailbox<M: Message>(&mut self) -> Result<(M, LocalMessage, Address)> {
         loop {
-            let msg = self
+            // The message is handled once received
+            let (msg, _completion) = self
                 .receiver_next()
                 .await?
                 .ok_or_else(|| NodeError::Data.not_found())?;
@@ -0,0 +1,28 @@
+use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::AccessControl;
+
+/// Access control which the workers of a node apply before their own
+///
+/// Every [`Context`](crate::Context) of a node shares it, see
+/// [`Context::set_default_access_control`](crate::Context::set_default_access_control).
+#[derive(Default)]
+pub(crate) struct DefaultAccessControl {
+    access_control: RwLock<Option<Arc<dyn AccessControl>>>,
+}
+
+impl DefaultAccessControl {
+    /// Replace the default, or remove it with `None`
+    pub(crate) fn set(&self, access_control: Option<Arc<dyn AccessControl>>) {
+        if let Ok(mut current) = self.access_control.write() {
+            *current = access_control;
+        }
+    }
+
+    /// The current default, if any
+    pub(crate) fn get(&self) -> Option<Arc<dyn AccessControl>> {
+        self.access_control
+            .read()
+            .ok()
+            .and_then(|current| current.clone())
+    }
+}
@@ -45,8 +45,10 @@ pub mod access_control;
 pub mod api;
 
 mod async_drop;
+mod authorization_stats;
 mod cancel;
 mod context;
+mod default_access_control;
 mod delayed;
 mod error;
 mod executor;
@@ -56,8 +58,10 @@ mod node;
 mod parser;
 mod relay;
 mod router;
+mod worker_access_controls;
 mod worker_builder;
 
+pub use authorization_stats::AuthorizationCount;
 pub use cancel::*;
 pub use context::*;
 pub use delayed::*;
@@ -48,6 +48,8 @@ pub enum NodeMessage {
     SetReady(Address),
     /// Check whether an address has been marked as "ready"
     CheckReady(Address, SmallSender<NodeReplyResult>),
+    /// Set the worker receiving messages for unknown local addresses
+    SetFallback(Address, SmallSender<NodeReplyResult>),
 }
 
 impl fmt::Display for NodeMessage {
@@ -66,6 +68,7 @@ impl fmt::Display for NodeMessage {
             NodeMessage::Router(_, _, _) => write!(f, "Router"),
             NodeMessage::SetReady(_) => write!(f, "SetReady"),
             NodeMessage::CheckReady(_, _) => write!(f, "CheckReady"),
+            NodeMessage::SetFallback(_, _) => write!(f, "SetFallback"),
         }
     }
 }
@@ -131,6 +134,12 @@ impl NodeMessage {
         (Self::StopWorker(address, detached, tx), rx)
     }
 
+    /// Create a set fallback message and reply receiver
+    pub fn set_fallback(address: Address) -> (Self, SmallReceiver<NodeReplyResult>) {
+        let (tx, rx) = small_channel();
+        (Self::SetFallback(address, tx), rx)
+    }
+
     /// Create a stop node message
     pub fn stop_node(tt: ShutdownType) -> (Self, SmallReceiver<NodeReplyResult>) {
         let (tx, rx) = small_channel();
@@ -248,6 +257,11 @@ impl RouterReply {
         Err(NodeError::RouterState(RouterReason::Duplicate).already_exists())
     }
 
+    /// Return [NodeError::FallbackExists]
+    pub fn fallback_exists() -> NodeReplyResult {
+        Err(NodeError::RouterState(RouterReason::DuplicateFallback).already_exists())
+    }
+
     /// Return [NodeReply::Rejected(reason)]
     pub fn node_rejected(reason: NodeReason) -> NodeReplyResult {
         Err(NodeError::NodeState(reason).conflict())
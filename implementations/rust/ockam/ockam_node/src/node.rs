@@ -75,6 +75,9 @@ where
             exe.sender(),
             Mailboxes::new(Mailbox::new(addr, Arc::new(self.access_control)), vec![]),
             None,
+            Default::default(),
+            Default::default(),
+            Default::default(),
         );
 
         // Register this mailbox handle with the executor
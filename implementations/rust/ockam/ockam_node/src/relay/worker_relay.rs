@@ -65,8 +65,9 @@ where
     /// Report errors as they occur, and signal whether the loop should
     /// continue running or not
     async fn recv_message(&mut self) -> Result<bool> {
-        let relay_msg = match self.ctx.receiver_next().await? {
-            Some(msg) => msg,
+        // Dropped once the worker is done with the message
+        let (relay_msg, _completion) = match self.ctx.receiver_next().await? {
+            Some(next) => next,
             None => {
                 trace!("No more messages for worker {}", self.ctx.address());
                 return Ok(false);
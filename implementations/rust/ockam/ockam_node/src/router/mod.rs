@@ -20,13 +20,15 @@ use crate::{
     NodeMessage, NodeReplyResult, RouterReply, ShutdownType,
 };
 use ockam_core::compat::{collections::BTreeMap, sync::Arc};
-use ockam_core::{Address, Result, TransportType};
+use ockam_core::{Address, CancellationToken, Result, TransportType};
 
 /// A pair of senders to a worker relay
 #[derive(Debug)]
 pub struct SenderPair {
     pub msgs: MessageSender<RelayMessage>,
     pub ctrl: SmallSender<CtrlSignal>,
+    /// Cancelled when the worker is asked to stop
+    pub cancellation: CancellationToken,
 }
 
 /// A combined address type and local worker router
@@ -38,6 +40,9 @@ pub struct SenderPair {
 /// External routing is supported only after a plugin component
 /// registers itself with this router.  Only one router can be
 /// registered per address type.
+///
+/// Messages for local addresses that no worker uses are delivered to
+/// the fallback worker, if one is set.
 pub struct Router {
     /// Keep track of some additional router state information
     state: RouterState,
@@ -45,6 +50,8 @@ pub struct Router {
     map: InternalMap,
     /// Externally registered router components
     external: BTreeMap<TransportType, Address>,
+    /// Worker receiving the messages for unknown local addresses
+    fallback: Option<Address>,
     /// Receiver for messages from node
     receiver: Option<RouterReceiver<NodeMessage>>,
 }
@@ -69,6 +76,7 @@ impl Router {
             state: RouterState::new(sender),
             map: InternalMap::default(),
             external: BTreeMap::new(),
+            fallback: None,
             receiver: Some(receiver),
         }
     }
@@ -92,6 +100,7 @@ impl Router {
                 addr.clone().into(),
                 senders.msgs,
                 senders.ctrl,
+                senders.cancellation,
                 Arc::new(0.into()), // don't track for app worker (yet?)
                 AddressMeta {
                     processor: false,
@@ -171,6 +180,28 @@ impl Router {
                 .await
                 .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?,
 
+            // Successful fallback registration command
+            SetFallback(addr, reply) if self.fallback.is_none() => {
+                trace!("Setting fallback worker {}", addr);
+
+                let msg = match self.map.addr_map.get(&addr) {
+                    Some(_) => {
+                        self.fallback = Some(addr);
+                        RouterReply::ok()
+                    }
+                    None => RouterReply::no_such_address(addr),
+                };
+                reply
+                    .send(msg)
+                    .await
+                    .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?
+            }
+            // Rejected fallback registration command
+            SetFallback(_, reply) => reply
+                .send(RouterReply::fallback_exists())
+                .await
+                .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?,
+
             //// ==! Basic worker control
             StartWorker {
                 addrs,
@@ -221,6 +252,7 @@ impl Router {
             StopAck(addr) if self.state.running() => {
                 trace!("Received shutdown ACK for address {}", addr);
                 if let Some(rec) = self.map.internal.remove(&addr) {
+                    utils::clear_fallback(&mut self.fallback, rec.address_set());
                     rec.address_set().iter().for_each(|addr| {
                         self.map.addr_map.remove(addr);
                     });
@@ -12,7 +12,7 @@ use ockam_core::{
         sync::Arc,
         vec::Vec,
     },
-    Address, AddressSet, Result,
+    Address, AddressSet, CancellationToken, Result,
 };
 
 /// Address states and associated logic
@@ -165,6 +165,7 @@ pub struct AddressRecord {
     address_set: AddressSet,
     sender: Option<MessageSender<RelayMessage>>,
     ctrl_tx: SmallSender<CtrlSignal>,
+    cancellation: CancellationToken,
     state: AddressState,
     ready: ReadyState,
     meta: AddressMeta,
@@ -180,11 +181,13 @@ impl AddressRecord {
     }
     pub fn sender_drop(&mut self) {
         self.sender = None;
+        self.cancellation.cancel();
     }
     pub fn new(
         address_set: AddressSet,
         sender: MessageSender<RelayMessage>,
         ctrl_tx: SmallSender<CtrlSignal>,
+        cancellation: CancellationToken,
         msg_count: Arc<AtomicUsize>,
         meta: AddressMeta,
     ) -> Self {
@@ -192,6 +195,7 @@ impl AddressRecord {
             address_set,
             sender: Some(sender),
             ctrl_tx,
+            cancellation,
             state: AddressState::Running,
             ready: ReadyState::Initialising(vec![]),
             msg_count,
@@ -213,6 +217,7 @@ impl AddressRecord {
         } else {
             self.sender = None;
         }
+        self.cancellation.cancel();
         self.state = AddressState::Stopping;
         Ok(())
     }
@@ -31,12 +31,17 @@ async fn start(
 
     debug!("Starting new processor '{}'", &addr);
 
-    let SenderPair { msgs, ctrl } = senders;
+    let SenderPair {
+        msgs,
+        ctrl,
+        cancellation,
+    } = senders;
 
     let record = AddressRecord::new(
         addr.clone().into(),
         msgs,
         ctrl,
+        cancellation,
         // We don't keep track of the mailbox count for processors
         // because, while they are able to send and receive messages
         // via their mailbox, most likely this metric is going to be
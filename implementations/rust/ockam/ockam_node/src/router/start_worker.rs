@@ -38,7 +38,11 @@ async fn start(
 
     debug!("Starting new worker '{}'", addrs.first());
 
-    let SenderPair { msgs, ctrl } = senders;
+    let SenderPair {
+        msgs,
+        ctrl,
+        cancellation,
+    } = senders;
 
     // Create an address record and insert it into the internal map
 
@@ -46,6 +50,7 @@ async fn start(
         addrs.clone(),
         msgs,
         ctrl,
+        cancellation,
         metrics,
         AddressMeta {
             processor: false,
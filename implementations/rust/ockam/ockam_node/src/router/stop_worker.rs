@@ -1,4 +1,4 @@
-use super::Router;
+use super::{utils, Router};
 use crate::channel_types::SmallSender;
 use crate::{
     error::{NodeError, NodeReason},
@@ -42,6 +42,7 @@ pub(super) async fn exec(
     };
 
     // Remove all secondary addresses
+    utils::clear_fallback(&mut router.fallback, record.address_set());
     for addr in record.address_set().iter() {
         router.map.addr_map.remove(addr);
     }
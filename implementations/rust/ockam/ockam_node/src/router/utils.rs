@@ -4,7 +4,7 @@ use crate::{
     error::{NodeError, NodeReason, WorkerReason},
     NodeReplyResult, RouterReply,
 };
-use ockam_core::{Address, Result, TransportType};
+use ockam_core::{Address, AddressSet, Result, TransportType};
 
 /// Receive an address and resolve it to a sender
 ///
@@ -21,6 +21,9 @@ pub(super) async fn resolve(
 
     let primary_address = if let Some(p) = router.map.addr_map.get(addr) {
         p.clone()
+    } else if let Some(fallback) = router.fallback.clone().filter(|_| !wrap) {
+        trace!("{} using fallback worker '{}'", base, fallback);
+        return resolve_fallback(router, &fallback, reply).await;
     } else {
         trace!("{} FAILED; no such worker", base);
         reply
@@ -51,6 +54,45 @@ pub(super) async fn resolve(
     Ok(())
 }
 
+/// Resolve the fallback worker, in place of an unknown local address
+///
+/// The message is then posted to the fallback worker's address, so that
+/// its access control applies, while the onward route is left unchanged.
+async fn resolve_fallback(
+    router: &mut Router,
+    fallback: &Address,
+    reply: &SmallSender<NodeReplyResult>,
+) -> Result<()> {
+    let record = router
+        .map
+        .addr_map
+        .get(fallback)
+        .and_then(|p| router.map.internal.get(p));
+    match record {
+        Some(record) if record.check() => {
+            record.increment_msg_count();
+            reply.send(RouterReply::sender(
+                fallback.clone(),
+                record.sender(),
+                false,
+            ))
+        }
+        Some(_) => reply.send(RouterReply::worker_rejected(WorkerReason::Shutdown)),
+        None => reply.send(RouterReply::no_such_address(fallback.clone())),
+    }
+    .await
+    .map_err(NodeError::from_send_err)?;
+    Ok(())
+}
+
+/// Unset the fallback worker if it uses one of these addresses
+pub(super) fn clear_fallback(fallback: &mut Option<Address>, addrs: &AddressSet) {
+    if fallback.as_ref().map_or(false, |f| addrs.contains(f)) {
+        trace!("Clearing fallback worker");
+        *fallback = None;
+    }
+}
+
 pub(super) fn router_addr(router: &mut Router, tt: TransportType) -> Result<Address> {
     router
         .external
@@ -8,7 +8,7 @@ use ockam_core::compat::{
     sync::Arc,
 };
 use ockam_core::{async_trait, Address, Any, Decodable, Message, LOCAL};
-use ockam_core::{route, Processor, Result, Routed, Worker};
+use ockam_core::{route, LocalMessage, Processor, Result, Routed, TransportMessage, Worker};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicI8, AtomicU32};
 use tokio::time::sleep;
@@ -560,3 +560,347 @@ async fn starting_worker_with_dup_address_should_fail(ctx: &mut Context) -> Resu
     assert!(ctx.start_worker("dummy_worker", DummyWorker).await.is_err());
     ctx.stop().await
 }
+
+#[ockam_macros::test(crate = "crate")]
+async fn fallback_worker_receives_unknown_addresses(ctx: &mut Context) -> Result<()> {
+    ctx.start_worker("fallback", DummyWorker).await?;
+    assert!(ctx.send("unknown", "hello".to_string()).await.is_err());
+
+    ctx.set_fallback_address("fallback").await?;
+    ctx.send("unknown", "hello".to_string()).await?;
+    assert_eq!(ctx.receive::<String>().await?.take().body(), "hello");
+
+    // Only one fallback worker per node, unset when it stops
+    ctx.start_worker("second", DummyWorker).await?;
+    assert!(ctx.set_fallback_address("second").await.is_err());
+    ctx.stop_worker("fallback").await?;
+    assert!(ctx.send("unknown", "hello".to_string()).await.is_err());
+    ctx.set_fallback_address("second").await?;
+    assert!(ctx.set_fallback_address("nobody").await.is_err());
+    ctx.stop().await
+}
+
+/// Access control waiting for its token to be cancelled, then denying
+#[derive(Debug)]
+struct StuckAccessControl {
+    cancelled: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl ockam_core::AccessControl for StuckAccessControl {
+    async fn is_authorized(&self, _local_msg: &LocalMessage) -> Result<bool> {
+        ockam_core::deny()
+    }
+
+    async fn is_authorized_with_ctx(
+        &self,
+        _local_msg: &LocalMessage,
+        token: Option<&ockam_core::CancellationToken>,
+    ) -> Result<bool> {
+        if let Some(token) = token {
+            token.cancelled().await;
+            self.cancelled.store(true, Ordering::Relaxed);
+        }
+        ockam_core::deny()
+    }
+}
+
+#[ockam_macros::test(crate = "crate")]
+async fn stopping_worker_cancels_pending_authorization(ctx: &mut Context) -> Result<()> {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let access_control = StuckAccessControl {
+        cancelled: cancelled.clone(),
+    };
+    crate::WorkerBuilder::with_access_control(access_control, "stuck", DummyWorker)
+        .start(ctx)
+        .await?;
+    ctx.send("stuck", "hello".to_string()).await?;
+    sleep(Duration::from_millis(100)).await;
+    assert!(!cancelled.load(Ordering::Relaxed));
+
+    ctx.stop_worker("stuck").await?;
+    sleep(Duration::from_millis(100)).await;
+    assert!(cancelled.load(Ordering::Relaxed));
+    ctx.stop().await
+}
+
+#[ockam_macros::test(crate = "crate")]
+async fn access_control_decisions_are_counted(ctx: &mut Context) -> Result<()> {
+    ctx.start_worker("allowed", DummyWorker).await?;
+    crate::WorkerBuilder::with_access_control(ockam_core::DenyAll, "denied", DummyWorker)
+        .start(ctx)
+        .await?;
+
+    for _ in 0..2 {
+        let reply: String = ctx.send_and_receive("allowed", "hello".to_string()).await?;
+        assert_eq!(reply, "hello");
+        ctx.send("denied", "hello".to_string()).await?;
+    }
+    sleep(Duration::from_millis(100)).await;
+
+    let stats = ctx.authorization_stats();
+    let allowed = stats[&Address::from_string("allowed")];
+    assert_eq!((allowed.allowed, allowed.denied), (2, 0));
+    let denied = stats[&Address::from_string("denied")];
+    assert_eq!((denied.allowed, denied.denied), (0, 2));
+
+    ctx.stop_worker("denied").await?;
+    sleep(Duration::from_millis(100)).await;
+    assert!(!ctx
+        .authorization_stats()
+        .contains_key(&Address::from_string("denied")));
+    ctx.stop().await
+}
+
+#[ockam_macros::test(crate = "crate")]
+async fn sequence_of_sent_messages(ctx: &mut Context) -> Result<()> {
+    crate::WorkerBuilder::with_access_control(
+        ockam_core::SequenceAccessControl::new(8),
+        "ordered",
+        DummyWorker,
+    )
+    .start(ctx)
+    .await?;
+
+    let sequencer = ockam_core::Sequencer::new();
+    for _ in 0..2 {
+        ctx.send_in_sequence("ordered", "hello".to_string(), &sequencer)
+            .await?;
+        assert_eq!(ctx.receive::<String>().await?.take().body(), "hello");
+    }
+    // A message sent again with the first sequence number is denied, as
+    // is a message without one
+    let replayed = ockam_core::SequenceLocalInfo::new(1).to_local_info()?;
+    ctx.send_with_local_info("ordered", "replayed".to_string(), vec![replayed])
+        .await?;
+    ctx.send("ordered", "unnumbered".to_string()).await?;
+    sleep(Duration::from_millis(100)).await;
+
+    let stats = ctx.authorization_stats()[&Address::from_string("ordered")];
+    assert_eq!((stats.allowed, stats.denied), (2, 2));
+    ctx.stop().await
+}
+
+#[ockam_macros::test(crate = "crate")]
+async fn sequence_survives_worker_restart(ctx: &mut Context) -> Result<()> {
+    let access_control = ockam_core::SequenceAccessControl::new(8);
+    let sequencer = ockam_core::Sequencer::new();
+    crate::WorkerBuilder::with_access_control(access_control.clone(), "ordered", DummyWorker)
+        .start(ctx)
+        .await?;
+    ctx.send_in_sequence("ordered", "hello".to_string(), &sequencer)
+        .await?;
+    assert_eq!(ctx.receive::<String>().await?.take().body(), "hello");
+    ctx.stop_worker("ordered").await?;
+    sleep(Duration::from_millis(100)).await;
+
+    // The worker started again with a clone remembers the first message
+    crate::WorkerBuilder::with_access_control(access_control, "ordered", DummyWorker)
+        .start(ctx)
+        .await?;
+    let replayed = ockam_core::SequenceLocalInfo::new(1).to_local_info()?;
+    ctx.send_with_local_info("ordered", "replayed".to_string(), vec![replayed])
+        .await?;
+    ctx.send_in_sequence("ordered", "again".to_string(), &sequencer)
+        .await?;
+    assert_eq!(ctx.receive::<String>().await?.take().body(), "again");
+
+    let stats = ctx.authorization_stats()[&Address::from_string("ordered")];
+    assert_eq!((stats.allowed, stats.denied), (1, 1));
+    ctx.stop().await
+}
+
+/// Replies to messages after a while
+struct SlowWorker;
+
+#[async_trait]
+impl Worker for SlowWorker {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Self::Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        sleep(Duration::from_millis(300)).await;
+        ctx.send(msg.return_route(), msg.body()).await
+    }
+}
+
+#[ockam_macros::test(crate = "crate")]
+async fn concurrency_limit_covers_handling(ctx: &mut Context) -> Result<()> {
+    // One message of each sender at a time among both workers
+    let limit = ockam_core::ConcurrencyLimitAccessControl::new(1, 10);
+    for address in ["slow1", "slow2"] {
+        crate::WorkerBuilder::with_access_control(limit.clone(), address, SlowWorker)
+            .start(ctx)
+            .await?;
+    }
+
+    ctx.send("slow1", "first".to_string()).await?;
+    sleep(Duration::from_millis(100)).await;
+    ctx.send("slow2", "denied".to_string()).await?;
+    assert_eq!(ctx.receive::<String>().await?.take().body(), "first");
+
+    // The slot is free once slow1 handled the message
+    ctx.send("slow2", "second".to_string()).await?;
+    assert_eq!(ctx.receive::<String>().await?.take().body(), "second");
+    ctx.stop().await
+}
+
+/// Counts the messages allowed and denied
+#[derive(Debug, Default)]
+struct CountingSink {
+    allowed: AtomicU32,
+    denied: AtomicU32,
+}
+
+#[async_trait]
+impl ockam_core::AuditSink for CountingSink {
+    async fn record(&self, allowed: bool, _metadata: &ockam_core::AuditMetadata) -> Result<()> {
+        let count = if allowed { &self.allowed } else { &self.denied };
+        count.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+#[ockam_macros::test(crate = "crate")]
+async fn concurrency_limit_within_audit_covers_handling(ctx: &mut Context) -> Result<()> {
+    // One message of each sender at a time among both workers
+    let limit = ockam_core::ConcurrencyLimitAccessControl::new(1, 10);
+    let sink = Arc::new(CountingSink::default());
+    for address in ["audited1", "audited2"] {
+        let access_control = ockam_core::AuditedAccessControl::new(limit.clone(), sink.clone());
+        crate::WorkerBuilder::with_access_control(access_control, address, SlowWorker)
+            .start(ctx)
+            .await?;
+    }
+
+    ctx.send("audited1", "first".to_string()).await?;
+    sleep(Duration::from_millis(100)).await;
+    ctx.send("audited2", "denied".to_string()).await?;
+    assert_eq!(ctx.receive::<String>().await?.take().body(), "first");
+
+    ctx.send("audited2", "second".to_string()).await?;
+    assert_eq!(ctx.receive::<String>().await?.take().body(), "second");
+    assert_eq!(sink.allowed.load(Ordering::Relaxed), 2);
+    assert_eq!(sink.denied.load(Ordering::Relaxed), 1);
+    ctx.stop().await
+}
+
+#[ockam_macros::test(crate = "crate")]
+async fn default_access_control_applies_to_workers(ctx: &mut Context) -> Result<()> {
+    ctx.set_default_access_control(ockam_core::DenyAll);
+    ctx.start_worker("covered", DummyWorker).await?;
+    crate::WorkerBuilder::with_access_control(ockam_core::AllowAll, "opted_out", DummyWorker)
+        .start(ctx)
+        .await?;
+
+    // The reply goes to a detached context, which the default doesn't cover
+    let reply: String = ctx
+        .send_and_receive("opted_out", "hello".to_string())
+        .await?;
+    assert_eq!(reply, "hello");
+    ctx.send("covered", "hello".to_string()).await?;
+    sleep(Duration::from_millis(100)).await;
+    let covered = ctx.authorization_stats()[&Address::from_string("covered")];
+    assert_eq!((covered.allowed, covered.denied), (0, 1));
+
+    ctx.clear_default_access_control();
+    assert!(ctx.default_access_control().is_none());
+    let reply: String = ctx.send_and_receive("covered", "hello".to_string()).await?;
+    assert_eq!(reply, "hello");
+    ctx.stop().await
+}
+
+#[ockam_macros::test(crate = "crate")]
+async fn describe_worker_access_control(ctx: &mut Context) -> Result<()> {
+    let access_control = ockam_core::AnyAccessControl::new(
+        ockam_core::DenyAll,
+        ockam_core::MaxHopsAccessControl::new(4),
+    );
+    crate::WorkerBuilder::with_access_control(access_control, "described", DummyWorker)
+        .start(ctx)
+        .await?;
+    let described = Address::from_string("described");
+    assert_eq!(
+        ctx.describe_access_control(&described).as_deref(),
+        Some("Any[DenyAll, MaxHops(4)]")
+    );
+
+    // The default of the node comes first
+    ctx.set_default_access_control(ockam_core::AllowAll);
+    assert_eq!(
+        ctx.describe_access_control(&described).as_deref(),
+        Some("All[AllowAll, Any[DenyAll, MaxHops(4)]]")
+    );
+    ctx.clear_default_access_control();
+
+    ctx.stop_worker(described.clone()).await?;
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(ctx.describe_access_control(&described), None);
+    assert_eq!(ctx.describe_access_control(&"unknown".into()), None);
+    ctx.stop().await
+}
+
+#[ockam_macros::test(crate = "crate")]
+async fn trace_worker_access_control(ctx: &mut Context) -> Result<()> {
+    let access_control = ockam_core::AnyAccessControl::new(
+        ockam_core::DenyAll,
+        ockam_core::MaxHopsAccessControl::new(4),
+    );
+    crate::WorkerBuilder::with_access_control(access_control, "traced", DummyWorker)
+        .start(ctx)
+        .await?;
+    let traced = Address::from_string("traced");
+    let local_msg = LocalMessage::new(
+        TransportMessage::v1(route!["traced"], route!["sender"], vec![]),
+        vec![],
+    );
+    let trace = ctx.trace_access_control(&traced, &local_msg).await.unwrap();
+    assert_eq!(
+        trace.to_string(),
+        "Any: allowed\n  DenyAll: denied\n  MaxHops(4): allowed\n"
+    );
+
+    // The default of the node comes first, and denies before the worker's own
+    ctx.set_default_access_control(ockam_core::DenyAll);
+    let trace = ctx.trace_access_control(&traced, &local_msg).await.unwrap();
+    assert_eq!(trace.to_string(), "All: denied\n  DenyAll: denied\n");
+    ctx.clear_default_access_control();
+
+    assert!(ctx
+        .trace_access_control(&"unknown".into(), &local_msg)
+        .await
+        .is_none());
+    ctx.stop().await
+}
+
+#[ockam_macros::test(crate = "crate")]
+async fn trace_reloadable_worker_access_control(ctx: &mut Context) -> Result<()> {
+    let access_control = ockam_core::ReloadableAccessControl::new(|| {
+        let policy: Arc<dyn ockam_core::AccessControl> =
+            Arc::new(ockam_core::AnyAccessControl::new(
+                ockam_core::DenyAll,
+                ockam_core::MaxHopsAccessControl::new(4),
+            ));
+        Ok(policy)
+    })?;
+    crate::WorkerBuilder::with_access_control(access_control, "reloadable", DummyWorker)
+        .start(ctx)
+        .await?;
+    let local_msg = LocalMessage::new(
+        TransportMessage::v1(route!["reloadable"], route!["sender"], vec![]),
+        vec![],
+    );
+    let trace = ctx
+        .trace_access_control(&"reloadable".into(), &local_msg)
+        .await
+        .unwrap();
+    assert_eq!(
+        trace.to_string(),
+        "Reloadable: allowed\n  Any: allowed\n    DenyAll: denied\n    MaxHops(4): allowed\n"
+    );
+    ctx.stop().await
+}
@@ -0,0 +1,57 @@
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::{AccessControl, Address, Mailboxes};
+
+/// The access control of a worker mailbox, and whether the default access
+/// control of the node comes before it
+#[derive(Clone)]
+pub(crate) struct WorkerAccessControl {
+    pub(crate) access_control: Arc<dyn AccessControl>,
+    pub(crate) uses_default: bool,
+}
+
+/// Access controls of the mailboxes of the workers of a node, by address
+///
+/// Every [`Context`](crate::Context) of a node shares them. The mailboxes
+/// of a worker are registered when it starts, and forgotten once its context
+/// is dropped.
+#[derive(Default)]
+pub(crate) struct WorkerAccessControls {
+    access_controls: Mutex<BTreeMap<Address, WorkerAccessControl>>,
+}
+
+impl WorkerAccessControls {
+    /// Register the mailboxes of a worker
+    pub(crate) fn register(&self, mailboxes: &Mailboxes, uses_default: bool) {
+        if let Ok(mut access_controls) = self.access_controls.lock() {
+            let all =
+                core::iter::once(mailboxes.main_mailbox()).chain(mailboxes.additional_mailboxes());
+            for mailbox in all {
+                access_controls.insert(
+                    mailbox.address().clone(),
+                    WorkerAccessControl {
+                        access_control: mailbox.access_control().clone(),
+                        uses_default,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Forget the mailboxes of a worker
+    pub(crate) fn forget(&self, mailboxes: &Mailboxes) {
+        if let Ok(mut access_controls) = self.access_controls.lock() {
+            for address in mailboxes.addresses() {
+                access_controls.remove(&address);
+            }
+        }
+    }
+
+    /// The access control of the mailbox at `address`, if a worker has it
+    pub(crate) fn get(&self, address: &Address) -> Option<WorkerAccessControl> {
+        self.access_controls
+            .lock()
+            .ok()
+            .and_then(|access_controls| access_controls.get(address).cloned())
+    }
+}
@@ -1,5 +1,6 @@
 use crate::error::{NodeError, NodeReason};
 use crate::{relay::WorkerRelay, Context, NodeMessage};
+use core::any::TypeId;
 use ockam_core::compat::sync::Arc;
 use ockam_core::{
     errcode::{Kind, Origin},
@@ -17,9 +18,15 @@ use ockam_core::{
 ///
 /// Varying use-cases should use the builder API to customise the
 /// underlying worker that is created.
+///
+/// The worker applies the default access control of the node, if any, see
+/// [`Context::set_default_access_control()`], before its own. Workers
+/// started with an explicit [`AllowAll`], or with [`Mailboxes`] of their
+/// own, opt out of it.
 pub struct WorkerBuilder<W> {
     mailboxes: Mailboxes,
     worker: W,
+    uses_default_access_control: bool,
 }
 
 impl<M, W> WorkerBuilder<W>
@@ -27,14 +34,19 @@ where
     M: Message + Send + 'static,
     W: Worker<Context = Context, Message = M>,
 {
-    /// Create a worker with `AllowAll` access control
+    /// Create a worker with `AllowAll` access control, which doesn't apply
+    /// the default access control of the node either
     pub fn without_access_control<AS>(address_set: AS, worker: W) -> Self
     where
         AS: Into<AddressSet>,
     {
         let mailboxes = Mailboxes::from_address_set(address_set.into(), Arc::new(AllowAll));
 
-        Self { mailboxes, worker }
+        Self {
+            mailboxes,
+            worker,
+            uses_default_access_control: false,
+        }
     }
 
     /// Create a worker which inherits access control from the given context
@@ -56,10 +68,17 @@ where
 
         let mailboxes = Mailboxes::from_address_set(address_set, access_control);
 
-        Self { mailboxes, worker }
+        Self {
+            mailboxes,
+            worker,
+            uses_default_access_control: true,
+        }
     }
 
     /// Create a worker which uses the given access control
+    ///
+    /// With [`AllowAll`] the worker doesn't apply the default access control
+    /// of the node either.
     pub fn with_access_control<A, AC>(access_control: AC, address: A, worker: W) -> Self
     where
         A: Into<Address>,
@@ -67,13 +86,24 @@ where
     {
         let mailboxes = Mailboxes::main(address.into(), Arc::new(access_control));
 
-        Self { mailboxes, worker }
+        Self {
+            mailboxes,
+            worker,
+            uses_default_access_control: TypeId::of::<AC>() != TypeId::of::<AllowAll>(),
+        }
     }
 
     /// Create a worker which uses the access control from the given
     /// [`Mailboxes`]
+    ///
+    /// The access control of each mailbox is the one given, the worker
+    /// doesn't apply the default access control of the node.
     pub fn with_mailboxes(mailboxes: Mailboxes, worker: W) -> Self {
-        Self { mailboxes, worker }
+        Self {
+            mailboxes,
+            worker,
+            uses_default_access_control: false,
+        }
     }
 
     /// Consume this builder and start a new Ockam [`Worker`] from the given context
@@ -89,12 +119,19 @@ where
         let main_address = mailboxes.main_address().clone();
 
         // Pass it to the context
-        let (ctx, sender, ctrl_rx) = Context::new(
+        let (mut ctx, sender, ctrl_rx) = Context::new(
             context.runtime().clone(),
             context.sender().clone(),
             mailboxes,
             None,
+            context.shared_authorization_stats(),
+            context.shared_default_access_control(),
+            context.shared_worker_access_controls(),
         );
+        ctx.set_uses_default_access_control(self.uses_default_access_control);
+        context
+            .shared_worker_access_controls()
+            .register(ctx.mailboxes(), self.uses_default_access_control);
 
         // Then initialise the worker message relay
         WorkerRelay::<W, M>::init(context.runtime(), self.worker, ctx, ctrl_rx);
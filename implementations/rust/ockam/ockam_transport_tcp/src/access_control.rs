@@ -0,0 +1,236 @@
+use crate::TcpLocalInfo;
+use core::fmt::{self, Display, Formatter};
+use core::str::FromStr;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::net::IpAddr;
+use ockam_core::compat::vec::Vec;
+use ockam_core::compat::{format, string::String};
+use ockam_core::{async_trait, AccessControl, LocalMessage, Result};
+use ockam_transport_core::TransportError;
+
+/// A block of IPv4 or IPv6 addresses, e.g. `10.0.0.0/8` or `2001:db8::/32`
+///
+/// A bare address stands for the block of that single address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpCidr {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpCidr {
+    /// Constructor, failing when `prefix_len` is longer than the address
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Result<Self> {
+        let max = match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        if prefix_len > max {
+            return Err(TransportError::InvalidAddress.into());
+        }
+        Ok(Self { addr, prefix_len })
+    }
+
+    /// Whether `ip` is in this block
+    ///
+    /// IPv4 addresses are never in IPv6 blocks and conversely, except for
+    /// IPv4-mapped IPv6 addresses, e.g. `::ffff:10.0.0.1`, which are handled
+    /// as the IPv4 address they map.
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(*ip, IpAddr::V4),
+            IpAddr::V4(_) => *ip,
+        };
+        match (self.addr, ip) {
+            (IpAddr::V4(block), IpAddr::V4(ip)) => {
+                prefix_matches(&block.octets(), &ip.octets(), self.prefix_len)
+            }
+            (IpAddr::V6(block), IpAddr::V6(ip)) => {
+                prefix_matches(&block.octets(), &ip.octets(), self.prefix_len)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Whether the first `len` bits of `a` and `b` are the same
+fn prefix_matches(a: &[u8], b: &[u8], len: u8) -> bool {
+    let (bytes, bits) = ((len / 8) as usize, len % 8);
+    if a[..bytes] != b[..bytes] {
+        return false;
+    }
+    if bits == 0 {
+        return true;
+    }
+    let mask = !(0xffu8 >> bits);
+    a[bytes] & mask == b[bytes] & mask
+}
+
+impl FromStr for IpCidr {
+    type Err = ockam_core::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, len)) => (addr, Some(len)),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr.parse().map_err(|_| TransportError::InvalidAddress)?;
+        let prefix_len = match (prefix_len, addr) {
+            (Some(len), _) => len.parse().map_err(|_| TransportError::InvalidAddress)?,
+            (None, IpAddr::V4(_)) => 32,
+            (None, IpAddr::V6(_)) => 128,
+        };
+        Self::new(addr, prefix_len)
+    }
+}
+
+impl Display for IpCidr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+/// Allows messages by the IP address of the TCP peer they were received from
+///
+/// The address is read from the [`TcpLocalInfo`] of the message. Addresses in
+/// one of the `deny` blocks are denied. Other addresses are allowed when they
+/// are in one of the `allow` blocks, or when there are none. Messages without
+/// that local info, e.g. sent by a worker of the node or received over another
+/// transport, are denied unless [`allow_without_ip`](Self::allow_without_ip)
+/// says otherwise.
+///
+/// Only the workers receiving the messages of a TCP connection see its local
+/// info: a secure channel replaces it with its own, so that the messages it
+/// decrypted are checked by identity instead.
+#[derive(Debug, Clone)]
+pub struct IpFilterAccessControl {
+    allow: Vec<IpCidr>,
+    deny: Vec<IpCidr>,
+    without_ip: bool,
+}
+
+impl IpFilterAccessControl {
+    /// Constructor
+    pub fn new(allow: impl Into<Vec<IpCidr>>, deny: impl Into<Vec<IpCidr>>) -> Self {
+        Self {
+            allow: allow.into(),
+            deny: deny.into(),
+            without_ip: false,
+        }
+    }
+
+    /// Whether to allow the messages which don't carry an IP address
+    pub fn allow_without_ip(mut self, allow: bool) -> Self {
+        self.without_ip = allow;
+        self
+    }
+
+    /// Whether messages from `ip` are allowed
+    pub fn allows(&self, ip: &IpAddr) -> bool {
+        if self.deny.iter().any(|block| block.contains(ip)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|block| block.contains(ip))
+    }
+}
+
+#[async_trait]
+impl AccessControl for IpFilterAccessControl {
+    async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool> {
+        match TcpLocalInfo::find_info(local_msg) {
+            Ok(info) => Ok(self.allows(&info.peer().ip())),
+            Err(_) => Ok(self.without_ip),
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "IpFilter(allow {} blocks, deny {} blocks)",
+            self.allow.len(),
+            self.deny.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::compat::net::SocketAddr;
+    use ockam_core::{route, TransportMessage};
+
+    fn cidr(s: &str) -> IpCidr {
+        s.parse().unwrap()
+    }
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    fn message(peer: Option<&str>) -> LocalMessage {
+        let transport = TransportMessage::v1(route!["a"], route![], Vec::new());
+        let local_info = match peer {
+            Some(peer) => {
+                let peer: SocketAddr = peer.parse().unwrap();
+                vec![TcpLocalInfo::new(peer).to_local_info().unwrap()]
+            }
+            None => Vec::new(),
+        };
+        LocalMessage::new(transport, local_info)
+    }
+
+    #[test]
+    fn cidr_contains() {
+        let v4 = cidr("10.1.0.0/16");
+        assert!(v4.contains(&ip("10.1.200.3")));
+        assert!(!v4.contains(&ip("10.2.0.1")));
+        assert!(v4.contains(&ip("::ffff:10.1.0.1")));
+        assert!(!v4.contains(&ip("2001:db8::1")));
+
+        let v6 = cidr("2001:db8:8000::/33");
+        assert!(v6.contains(&ip("2001:db8:ffff::1")));
+        assert!(!v6.contains(&ip("2001:db8:7fff::1")));
+        assert!(!v6.contains(&ip("10.1.0.1")));
+
+        assert!(cidr("0.0.0.0/0").contains(&ip("192.0.2.7")));
+        assert!(cidr("::/0").contains(&ip("2001:db8::1")));
+        assert!(cidr("192.0.2.7").contains(&ip("192.0.2.7")));
+        assert!(!cidr("192.0.2.7").contains(&ip("192.0.2.8")));
+        for s in ["10.0.0.0/33", "::/129", "10.0.0.0/", "10.0.0/8", "host/8"] {
+            assert!(s.parse::<IpCidr>().is_err(), "{s}");
+        }
+        assert_eq!(cidr("2001:db8::/32").to_string(), "2001:db8::/32");
+    }
+
+    async fn allows(ac: &IpFilterAccessControl, peer: Option<&str>) -> bool {
+        ac.is_authorized(&message(peer)).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn ip_filter() {
+        let ac = IpFilterAccessControl::new(
+            [cidr("10.0.0.0/8"), cidr("2001:db8::/32")],
+            [cidr("10.6.6.0/24")],
+        );
+        // In range
+        assert!(allows(&ac, Some("10.1.2.3:4000")).await);
+        assert!(allows(&ac, Some("[2001:db8::7]:4000")).await);
+        // Out of range, or denied
+        assert!(!allows(&ac, Some("192.0.2.1:4000")).await);
+        assert!(!allows(&ac, Some("[2001:db9::7]:4000")).await);
+        assert!(!allows(&ac, Some("10.6.6.6:4000")).await);
+    }
+
+    #[tokio::test]
+    async fn ip_filter_deny_only() {
+        let ac = IpFilterAccessControl::new([], [cidr("192.0.2.0/24")]);
+        assert!(allows(&ac, Some("198.51.100.1:4000")).await);
+        assert!(!allows(&ac, Some("192.0.2.1:4000")).await);
+    }
+
+    #[tokio::test]
+    async fn ip_filter_without_ip() {
+        let ac = IpFilterAccessControl::new([cidr("0.0.0.0/0")], []);
+        assert!(!allows(&ac, None).await);
+        let ac = ac.allow_without_ip(true);
+        assert!(allows(&ac, None).await);
+    }
+}
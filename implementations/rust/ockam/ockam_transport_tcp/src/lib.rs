@@ -33,8 +33,12 @@ pub(crate) use portal::*;
 pub(crate) use router::*;
 pub(crate) use workers::*;
 
+mod access_control;
+mod local_info;
 mod transport;
 
+pub use access_control::*;
+pub use local_info::*;
 pub use transport::*;
 
 use ockam_core::compat::net::SocketAddr;
@@ -0,0 +1,64 @@
+use ockam_core::compat::net::SocketAddr;
+use ockam_core::{
+    errcode::{Kind, Origin},
+    Decodable, Encodable, Error, LocalInfo, LocalMessage, Result,
+};
+use serde::{Deserialize, Serialize};
+
+/// TCP LocalInfo unique Identifier
+pub const TCP_LOCAL_INFO_IDENTIFIER: &str = "TCP_LOCAL_INFO_IDENTIFIER";
+
+/// Marks the messages received over a TCP connection with the address of
+/// the peer at the other end of it
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TcpLocalInfo {
+    peer: SocketAddr,
+}
+
+impl TcpLocalInfo {
+    /// Convert from LocalInfo
+    pub fn from_local_info(value: &LocalInfo) -> Result<Self> {
+        if value.type_identifier() != TCP_LOCAL_INFO_IDENTIFIER {
+            return Err(Error::new_without_cause(Origin::Transport, Kind::Invalid));
+        }
+
+        if let Ok(info) = TcpLocalInfo::decode(value.data()) {
+            return Ok(info);
+        }
+
+        Err(Error::new_without_cause(Origin::Transport, Kind::Invalid))
+    }
+
+    /// Convert to LocalInfo
+    pub fn to_local_info(&self) -> Result<LocalInfo> {
+        Ok(LocalInfo::new(
+            TCP_LOCAL_INFO_IDENTIFIER.into(),
+            self.encode()?,
+        ))
+    }
+
+    /// Find first such instance in LocalMessage
+    pub fn find_info(local_msg: &LocalMessage) -> Result<Self> {
+        if let Some(local_info) = local_msg
+            .local_info()
+            .iter()
+            .find(|x| x.type_identifier() == TCP_LOCAL_INFO_IDENTIFIER)
+        {
+            Self::from_local_info(local_info)
+        } else {
+            Err(Error::new_without_cause(Origin::Transport, Kind::Invalid))
+        }
+    }
+}
+
+impl TcpLocalInfo {
+    /// Constructor
+    pub fn new(peer: SocketAddr) -> Self {
+        Self { peer }
+    }
+
+    /// Address of the peer the message was received from
+    pub fn peer(&self) -> SocketAddr {
+        self.peer
+    }
+}
@@ -2,6 +2,7 @@ use crate::{
     parse_socket_addr, TcpInletListenProcessor, TcpListenProcessor, TcpRouterRequest,
     TcpRouterResponse, WorkerPair, TCP,
 };
+use core::time::Duration;
 use ockam_core::compat::net::{SocketAddr, ToSocketAddrs};
 use ockam_core::{async_trait, compat::boxed::Box, AccessControl};
 use ockam_core::{Address, AsyncTryClone, Result, Route};
@@ -46,13 +47,18 @@ impl TcpRouterHandle {
     }
 
     /// Establish an outgoing TCP connection on an existing transport
-    pub async fn connect<S: AsRef<str>>(&self, peer: S) -> Result<Address> {
+    pub async fn connect<S: AsRef<str>>(
+        &self,
+        peer: S,
+        keepalive: Option<Duration>,
+    ) -> Result<Address> {
         let response = self
             .ctx
             .send_and_receive(
                 self.api_addr.clone(),
                 TcpRouterRequest::Connect {
                     peer: peer.as_ref().to_string(),
+                    keepalive,
                 },
             )
             .await?;
@@ -93,12 +99,17 @@ impl TcpRouterHandle {
                 .map(|x| Address::from_string(format!("{}#{}", TCP, x))),
         );
         let self_addr = pair.tx_addr();
+        let internal_addr = pair.internal_addr();
 
         let mut child_ctx = self.ctx.new_detached(Address::random_local()).await?;
         child_ctx
             .send(
                 self.api_addr.clone(),
-                TcpRouterRequest::Register { accepts, self_addr },
+                TcpRouterRequest::Register {
+                    accepts,
+                    self_addr,
+                    internal_addr,
+                },
             )
             .await?;
 
@@ -1,3 +1,4 @@
+use core::time::Duration;
 use ockam_core::{Address, Message, Result};
 use serde::{Deserialize, Serialize};
 
@@ -9,9 +10,15 @@ pub enum TcpRouterRequest {
         accepts: Vec<Address>,
         /// The clients own worker bus address.
         self_addr: Address,
+        /// The address of the client receiving its control messages.
+        internal_addr: Address,
+    },
+    /// Connect, enabling TCP keepalive on the connection when given,
+    /// also when it already exists
+    Connect {
+        peer: String,
+        keepalive: Option<Duration>,
     },
-    /// Connect
-    Connect { peer: String },
     /// Connect
     Disconnect { peer: String },
     /// Unregister (usually, after disconnection)
@@ -1,8 +1,11 @@
-use crate::{TcpRouterHandle, TcpRouterRequest, TcpRouterResponse, TcpSendWorker, TCP};
+use crate::{
+    TcpRouterHandle, TcpRouterRequest, TcpRouterResponse, TcpSendWorker, TcpSendWorkerMsg, TCP,
+};
 use core::ops::Deref;
+use core::time::Duration;
 use ockam_core::{async_trait, Any};
 use ockam_core::{Address, Decodable, LocalMessage, Result, Routed, Worker};
-use ockam_node::Context;
+use ockam_node::{Context, WorkerBuilder};
 use ockam_transport_core::TransportError;
 use std::collections::BTreeMap;
 use tracing::{debug, error, trace};
@@ -20,6 +23,8 @@ pub(crate) struct TcpRouter {
     main_addr: Address,
     api_addr: Address,
     map: BTreeMap<Address, Address>,
+    /// The internal address of each connection worker, by its own address
+    internal_addrs: BTreeMap<Address, Address>,
     allow_auto_connection: bool,
 }
 
@@ -37,12 +42,14 @@ impl TcpRouter {
             main_addr: main_addr.clone(),
             api_addr: api_addr.clone(),
             map: BTreeMap::new(),
+            internal_addrs: BTreeMap::new(),
             allow_auto_connection: true,
         };
 
         let handle = router.create_self_handle().await?;
 
-        ctx.start_worker(vec![main_addr.clone(), api_addr], router)
+        WorkerBuilder::without_access_control(vec![main_addr.clone(), api_addr], router)
+            .start(ctx)
             .await?;
         trace!("Registering TCP router for type = {}", TCP);
         ctx.register(TCP, main_addr).await?;
@@ -61,7 +68,12 @@ impl TcpRouter {
 impl TcpRouter {
     /// Handle any [`TcpRouterRequest::Register`] messages received by
     /// this node's worker
-    async fn handle_register(&mut self, accepts: Vec<Address>, self_addr: Address) -> Result<()> {
+    async fn handle_register(
+        &mut self,
+        accepts: Vec<Address>,
+        self_addr: Address,
+        internal_addr: Address,
+    ) -> Result<()> {
         if let Some(f) = accepts.first().cloned() {
             trace!("TCP registration request: {} => {}", f, self_addr);
         } else {
@@ -82,6 +94,7 @@ impl TcpRouter {
         for accept in accepts {
             self.map.insert(accept.clone(), self_addr.clone());
         }
+        self.internal_addrs.insert(self_addr, internal_addr);
 
         Ok(())
     }
@@ -92,6 +105,7 @@ impl TcpRouter {
         trace!("TCP unregistration request: {}", &self_addr);
 
         self.map.retain(|_, self_addr_i| self_addr_i != &self_addr);
+        self.internal_addrs.remove(&self_addr);
 
         Ok(())
     }
@@ -104,10 +118,22 @@ impl TcpRouter {
     /// This handler starts a `(TcpSendWorker, TcpRecvProcessor)` pair
     /// that open and manage a connection to the given peer and
     /// finally register the given peer with this `TcpRouter`.
-    async fn handle_connect(&mut self, peer: String) -> Result<Address> {
+    async fn handle_connect(
+        &mut self,
+        peer: String,
+        keepalive: Option<Duration>,
+    ) -> Result<Address> {
         // Resolve peer address
         let (peer_addr, hostnames) = TcpRouterHandle::resolve_peer(peer)?;
 
+        if let Some(keepalive) = keepalive {
+            let tcp_address = Address::new(TCP, peer_addr.to_string());
+            if let Some(self_addr) = self.map.get(&tcp_address).cloned() {
+                self.enable_keepalive(&self_addr, keepalive).await?;
+                return Ok(self_addr);
+            }
+        }
+
         // Start a new `WorkerPair` for the given peer containing a
         // `TcpSendWorker` and `TcpRecvprocessor`
         let router_handle = self.create_self_handle().await?;
@@ -122,11 +148,29 @@ impl TcpRouter {
         accepts.extend(hostnames.iter().map(|x| Address::new(TCP, x)));
         let self_addr = pair.tx_addr();
 
-        self.handle_register(accepts, self_addr.clone()).await?;
+        self.handle_register(accepts, self_addr.clone(), pair.internal_addr())
+            .await?;
+        if let Some(keepalive) = keepalive {
+            self.enable_keepalive(&self_addr, keepalive).await?;
+        }
 
         Ok(self_addr)
     }
 
+    /// Ask the connection worker at `self_addr` to enable TCP keepalive
+    async fn enable_keepalive(&self, self_addr: &Address, keepalive: Duration) -> Result<()> {
+        let internal_addr = self
+            .internal_addrs
+            .get(self_addr)
+            .ok_or(TransportError::PeerNotFound)?;
+        self.ctx
+            .send(
+                internal_addr.clone(),
+                TcpSendWorkerMsg::Keepalive(keepalive),
+            )
+            .await
+    }
+
     /// Handle any [`TcpRouterRequest::Disconnect`] messages received by this
     /// nodes worker
     async fn handle_disconnect(&mut self, peer: String) -> Result<()> {
@@ -199,7 +243,7 @@ impl TcpRouter {
 
         // No existing connection
         if self.allow_auto_connection {
-            self.handle_connect(peer).await
+            self.handle_connect(peer, None).await
         } else {
             error!(
                 "Failed to resolve route, no existing connection to peer: {}",
@@ -229,8 +273,14 @@ impl Worker for TcpRouter {
         } else if msg_addr == self.api_addr {
             let msg = TcpRouterRequest::decode(msg.payload())?;
             match msg {
-                TcpRouterRequest::Register { accepts, self_addr } => {
-                    let res = self.handle_register(accepts, self_addr).await;
+                TcpRouterRequest::Register {
+                    accepts,
+                    self_addr,
+                    internal_addr,
+                } => {
+                    let res = self
+                        .handle_register(accepts, self_addr, internal_addr)
+                        .await;
 
                     ctx.send(return_route, TcpRouterResponse::Register(res))
                         .await?;
@@ -241,8 +291,8 @@ impl Worker for TcpRouter {
                     ctx.send(return_route, TcpRouterResponse::Unregister(res))
                         .await?;
                 }
-                TcpRouterRequest::Connect { peer } => {
-                    let res = self.handle_connect(peer).await;
+                TcpRouterRequest::Connect { peer, keepalive } => {
+                    let res = self.handle_connect(peer, keepalive).await;
 
                     ctx.send(return_route, TcpRouterResponse::Connect(res))
                         .await?;
@@ -1,3 +1,4 @@
+use core::time::Duration;
 use ockam_core::access_control::AccessControl;
 use ockam_core::compat::{boxed::Box, net::SocketAddr};
 use ockam_core::{Address, AllowAll, AsyncTryClone, Result, Route};
@@ -84,7 +85,23 @@ impl TcpTransport {
     /// # Ok(()) }
     /// ```
     pub async fn connect<S: AsRef<str>>(&self, peer: S) -> Result<Address> {
-        self.router_handle.connect(peer.as_ref()).await
+        self.router_handle.connect(peer.as_ref(), None).await
+    }
+
+    /// Establish an outgoing TCP connection like [`TcpTransport::connect`],
+    /// with TCP keepalive enabled.
+    ///
+    /// The operating system probes the peer once the connection has been
+    /// idle for `keepalive`, which keeps NAT mappings alive and detects dead
+    /// peers. An existing connection to `peer` gets keepalive enabled too.
+    pub async fn connect_with_keepalive<S: AsRef<str>>(
+        &self,
+        peer: S,
+        keepalive: Duration,
+    ) -> Result<Address> {
+        self.router_handle
+            .connect(peer.as_ref(), Some(keepalive))
+            .await
     }
 
     /// Disconnect from peer
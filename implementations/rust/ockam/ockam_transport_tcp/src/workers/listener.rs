@@ -1,7 +1,7 @@
 use crate::{TcpRouterHandle, TcpSendWorker};
 use ockam_core::{async_trait, compat::net::SocketAddr, AsyncTryClone};
 use ockam_core::{Address, Processor, Result};
-use ockam_node::Context;
+use ockam_node::{Context, WorkerBuilder};
 use ockam_transport_core::TransportError;
 use tokio::net::TcpListener;
 use tracing::{debug, trace};
@@ -69,8 +69,12 @@ impl Processor for TcpListenProcessor {
             "starting tcp connection worker"
         };
 
-        ctx.start_worker(vec![pair.tx_addr(), worker.internal_addr().clone()], worker)
-            .await?;
+        WorkerBuilder::without_access_control(
+            vec![pair.tx_addr(), worker.internal_addr().clone()],
+            worker,
+        )
+        .start(ctx)
+        .await?;
 
         Ok(true)
     }
@@ -1,5 +1,6 @@
-use crate::{TcpSendWorkerMsg, TCP};
+use crate::{TcpLocalInfo, TcpSendWorkerMsg, TCP};
 use ockam_core::async_trait;
+use ockam_core::compat::net::SocketAddr;
 use ockam_core::{Address, Decodable, LocalMessage, Processor, Result, TransportMessage};
 use ockam_node::{Context, ExternalLocalInfo};
 use ockam_transport_core::TransportError;
@@ -16,16 +17,18 @@ use tracing::{error, info, trace};
 /// the node message system.
 pub(crate) struct TcpRecvProcessor {
     rx: OwnedReadHalf,
+    peer: SocketAddr,
     peer_addr: Address,
     sender_internal_address: Address,
 }
 
 impl TcpRecvProcessor {
     /// Create a new `TcpRecvProcessor`
-    pub fn new(rx: OwnedReadHalf, peer_addr: Address, sender_internal_address: Address) -> Self {
+    pub fn new(rx: OwnedReadHalf, peer: SocketAddr, sender_internal_address: Address) -> Self {
         Self {
             rx,
-            peer_addr,
+            peer,
+            peer_addr: format!("{}#{}", TCP, peer).into(),
             sender_internal_address,
         }
     }
@@ -102,12 +105,14 @@ impl Processor for TcpRecvProcessor {
         trace!("Message onward route: {}", msg.onward_route);
         trace!("Message return route: {}", msg.return_route);
 
-        // Mark that message originates from some other node
-        let local_info = ExternalLocalInfo::new(TCP).to_local_info()?;
+        // Mark that message originates from some other node, at the peer
+        let local_info = vec![
+            ExternalLocalInfo::new(TCP).to_local_info()?,
+            TcpLocalInfo::new(self.peer).to_local_info()?,
+        ];
 
         // Forward the message to the next hop in the route
-        ctx.forward(LocalMessage::new(msg, vec![local_info]))
-            .await?;
+        ctx.forward(LocalMessage::new(msg, local_info)).await?;
 
         Ok(true)
     }
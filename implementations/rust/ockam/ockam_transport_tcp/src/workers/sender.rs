@@ -2,9 +2,10 @@ use crate::{TcpRecvProcessor, TcpRouterHandle};
 use core::time::Duration;
 use ockam_core::{async_trait, compat::net::SocketAddr, route, Any, Decodable, LocalMessage};
 use ockam_core::{Address, Encodable, Message, Result, Routed, TransportMessage, Worker};
-use ockam_node::{Context, DelayedEvent};
+use ockam_node::{Context, DelayedEvent, WorkerBuilder};
 use ockam_transport_core::TransportError;
 use serde::{Deserialize, Serialize};
+use socket2::{SockRef, TcpKeepalive};
 use tokio::io::AsyncWriteExt;
 use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::TcpStream;
@@ -16,6 +17,7 @@ pub(crate) struct WorkerPair {
     hostnames: Vec<String>,
     peer: SocketAddr,
     tx_addr: Address,
+    internal_addr: Address,
 }
 
 impl WorkerPair {
@@ -33,12 +35,21 @@ impl WorkerPair {
     pub fn tx_addr(&self) -> Address {
         self.tx_addr.clone()
     }
+
+    /// Return a clone of the [`Address`] receiving the
+    /// [`TcpSendWorkerMsg`] of the connection
+    pub fn internal_addr(&self) -> Address {
+        self.internal_addr.clone()
+    }
 }
 
 #[derive(Serialize, Deserialize, Message, Clone)]
 pub(crate) enum TcpSendWorkerMsg {
     Heartbeat,
     ConnectionClosed,
+    /// Enable TCP keepalive on the connection, probing the peer after
+    /// it has been idle for this long
+    Keepalive(Duration),
 }
 
 /// A TCP sending message worker
@@ -103,19 +114,16 @@ impl TcpSendWorker {
     ) -> Result<(Self, WorkerPair)> {
         let tx_addr = Address::random_local();
         let int_addr = Address::random_local();
-        let sender = TcpSendWorker::new(
-            router_handle,
-            stream,
-            peer,
-            int_addr.clone(),
-            DelayedEvent::create(ctx, int_addr.clone(), TcpSendWorkerMsg::Heartbeat).await?,
-        );
+        let heartbeat =
+            DelayedEvent::create(ctx, int_addr.clone(), TcpSendWorkerMsg::Heartbeat).await?;
+        let sender = TcpSendWorker::new(router_handle, stream, peer, int_addr.clone(), heartbeat);
         Ok((
             sender,
             WorkerPair {
                 hostnames,
                 peer,
                 tx_addr,
+                internal_addr: int_addr,
             },
         ))
     }
@@ -131,8 +139,12 @@ impl TcpSendWorker {
     ) -> Result<WorkerPair> {
         trace!("Creating new TCP worker pair");
         let (worker, pair) = Self::new_pair(ctx, router_handle, stream, peer, hostnames).await?;
-        ctx.start_worker(vec![pair.tx_addr(), worker.internal_addr().clone()], worker)
-            .await?;
+        WorkerBuilder::without_access_control(
+            vec![pair.tx_addr(), worker.internal_addr().clone()],
+            worker,
+        )
+        .start(ctx)
+        .await?;
         Ok(pair)
     }
 
@@ -185,11 +197,7 @@ impl Worker for TcpSendWorker {
         let rx = self.rx.take().ok_or(TransportError::GenericIo)?;
 
         let rx_addr = Address::random_local();
-        let receiver = TcpRecvProcessor::new(
-            rx,
-            format!("{}#{}", crate::TCP, self.peer).into(),
-            self.internal_addr.clone(),
-        );
+        let receiver = TcpRecvProcessor::new(rx, self.peer, self.internal_addr.clone());
         ctx.start_processor(rx_addr.clone(), receiver).await?;
 
         self.rx_addr = Some(rx_addr);
@@ -248,6 +256,13 @@ impl Worker for TcpSendWorker {
 
                     return Ok(());
                 }
+                TcpSendWorkerMsg::Keepalive(time) => {
+                    let keepalive = TcpKeepalive::new().with_time(time);
+                    match SockRef::from(tx.as_ref()).set_tcp_keepalive(&keepalive) {
+                        Ok(()) => debug!(?time, "Enabled keepalive for peer {}", self.peer),
+                        Err(e) => warn!(%e, "Failed to enable keepalive for peer {}", self.peer),
+                    }
+                }
             }
         } else {
             let mut msg = LocalMessage::decode(msg.payload())?.into_transport_message();
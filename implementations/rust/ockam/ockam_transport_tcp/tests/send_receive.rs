@@ -1,8 +1,9 @@
+use core::time::Duration;
 use ockam_core::compat::rand::{self, Rng};
 use ockam_core::{route, Address, Result, Routed, Worker};
-use ockam_node::Context;
+use ockam_node::{Context, WorkerBuilder};
 
-use ockam_transport_tcp::{TcpTransport, TCP};
+use ockam_transport_tcp::{IpFilterAccessControl, TcpTransport, TCP};
 
 #[ockam_macros::test]
 async fn send_receive(ctx: &mut Context) -> Result<()> {
@@ -32,6 +33,50 @@ async fn send_receive(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn ip_filter(ctx: &mut Context) -> Result<()> {
+    let loopback = "127.0.0.0/8".parse()?;
+    WorkerBuilder::with_access_control(
+        IpFilterAccessControl::new([loopback], []),
+        "allowed",
+        Echoer,
+    )
+    .start(ctx)
+    .await?;
+    WorkerBuilder::with_access_control(
+        IpFilterAccessControl::new([], [loopback]),
+        "denied",
+        Echoer,
+    )
+    .start(ctx)
+    .await?;
+
+    let transport = TcpTransport::create(ctx).await?;
+    let listener_address = transport.listen("127.0.0.1:0").await?.to_string();
+    let mut child_ctx = ctx.new_detached(Address::random_local()).await?;
+
+    let r = route![(TCP, listener_address.clone()), "allowed"];
+    child_ctx.send(r, "hello".to_string()).await?;
+    let reply = child_ctx.receive::<String>().await?.take().body();
+    assert_eq!(reply, "hello");
+
+    let r = route![(TCP, listener_address), "denied"];
+    child_ctx.send(r, "hello".to_string()).await?;
+    let reply = child_ctx
+        .receive_duration_timeout::<String>(Duration::from_millis(500))
+        .await;
+    assert!(
+        reply.is_err(),
+        "the message from 127.0.0.1 should be denied"
+    );
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
 pub struct Echoer;
 
 #[ockam_core::worker]